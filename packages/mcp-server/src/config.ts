@@ -53,3 +53,49 @@ export function getConfig(): BridgeConfig {
 export function buildWebSocketURL(host: string, port: number): string {
    return `ws://${host}:${port}`;
 }
+
+/**
+ * How MCP clients connect to this server.
+ *
+ * - `stdio`: a single client talks over stdin/stdout (the default)
+ * - `websocket`: any number of clients connect to a WebSocket server, one MCP
+ *   message per text frame
+ */
+export type TransportConfig =
+   | { type: 'stdio' }
+   | { type: 'websocket'; host: string; port: number };
+
+/** Default port for the MCP WebSocket transport, below the bridge's port range. */
+export const DEFAULT_TRANSPORT_PORT = 9220;
+
+/**
+ * Gets the MCP transport configuration from environment variables.
+ *
+ * - MCP_TRANSPORT: 'stdio' (default) or 'websocket'
+ * - MCP_TRANSPORT_HOST: interface to listen on for 'websocket' (default '127.0.0.1')
+ * - MCP_TRANSPORT_PORT: port to listen on for 'websocket' (default 9220)
+ *
+ * @throws If MCP_TRANSPORT names an unknown transport or the port is invalid
+ */
+export function getTransportConfig(): TransportConfig {
+   // eslint-disable-next-line no-process-env
+   const { MCP_TRANSPORT, MCP_TRANSPORT_HOST, MCP_TRANSPORT_PORT } = process.env;
+
+   const type = (MCP_TRANSPORT || 'stdio').toLowerCase();
+
+   if (type === 'stdio') {
+      return { type: 'stdio' };
+   }
+
+   if (type !== 'websocket') {
+      throw new Error(`Unknown MCP_TRANSPORT "${MCP_TRANSPORT}", expected "stdio" or "websocket"`);
+   }
+
+   const port = MCP_TRANSPORT_PORT ? Number(MCP_TRANSPORT_PORT) : DEFAULT_TRANSPORT_PORT;
+
+   if (!Number.isInteger(port) || port < 0 || port > 65535) {
+      throw new Error(`Invalid MCP_TRANSPORT_PORT "${MCP_TRANSPORT_PORT}"`);
+   }
+
+   return { type: 'websocket', host: MCP_TRANSPORT_HOST || '127.0.0.1', port };
+}
@@ -0,0 +1,28 @@
+/**
+ * Correlation IDs for tool calls.
+ *
+ * Every MCP tool call gets an ID that's carried through async work without being
+ * passed around explicitly. Requests the call sends to the plugin derive their IDs
+ * from it, and the plugin uses those as execution IDs, so the tool call, the plugin
+ * request and any script execution can be found in logs by searching for one ID.
+ */
+import { AsyncLocalStorage } from 'async_hooks';
+import { randomUUID } from 'crypto';
+
+const storage = new AsyncLocalStorage<string>();
+
+/**
+ * Runs `fn` with a new correlation ID, returned by `getCorrelationId` inside it.
+ */
+export function withCorrelationId<T>(fn: (correlationId: string) => Promise<T>): Promise<T> {
+   const correlationId = `call_${randomUUID().slice(0, 8)}`;
+
+   return storage.run(correlationId, () => { return fn(correlationId); });
+}
+
+/**
+ * Gets the correlation ID of the tool call currently running, if any.
+ */
+export function getCorrelationId(): string | undefined {
+   return storage.getStore();
+}
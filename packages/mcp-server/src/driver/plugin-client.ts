@@ -2,6 +2,7 @@ import WebSocket from 'ws';
 import { EventEmitter } from 'events';
 
 import { buildWebSocketURL, getDefaultHost, getDefaultPort } from '../config.js';
+import { getCorrelationId } from '../correlation.js';
 
 
 interface PluginCommand {
@@ -15,6 +16,7 @@ export interface PluginResponse {
    success: boolean;
    data?: unknown;
    error?: string;
+   correlationId?: string;
    windowContext?: {
       windowLabel: string;
       totalWindows: number;
@@ -185,8 +187,13 @@ export class PluginClient extends EventEmitter {
          throw new Error('Not connected to plugin');
       }
 
-      // Generate unique ID for this request
-      const id = `req_${Date.now()}_${Math.random().toString(36).substr(2, 9)}`;
+      // Generate unique ID for this request, prefixed with the tool call's ID when
+      // there is one so the plugin's logs can be matched to the call
+      const suffix = `${Date.now()}_${Math.random().toString(36).substr(2, 9)}`;
+
+      const correlationId = getCorrelationId();
+
+      const id = correlationId ? `${correlationId}_${suffix}` : `req_${suffix}`;
 
       const commandWithId = { ...command, id };
 
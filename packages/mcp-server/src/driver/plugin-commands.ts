@@ -245,6 +245,65 @@ export async function getBackendState(options: {
    }
 }
 
+export const ListCommandsSchema = z.object({
+   appIdentifier: z.union([ z.string(), z.number() ]).optional().describe(
+      'App port or bundle ID to target. Defaults to the only connected app or the default app if multiple are connected.'
+   ),
+});
+
+/**
+ * Lists the app's own Tauri commands, as registered with the plugin's builder.
+ */
+export async function listCommands(appIdentifier?: string | number): Promise<string> {
+   try {
+      const client = await ensureSessionAndConnect(appIdentifier);
+
+      const response = await client.sendCommand({
+         command: 'introspect_commands',
+      });
+
+      if (!response.success) {
+         throw new Error(response.error || 'Unknown error');
+      }
+
+      return JSON.stringify(response.data);
+   } catch(error: unknown) {
+      const message = error instanceof Error ? error.message : String(error);
+
+      throw new Error(`Failed to list commands: ${message}`);
+   }
+}
+
+export const ListBridgeCommandsSchema = z.object({
+   appIdentifier: z.union([ z.string(), z.number() ]).optional().describe(
+      'App port or bundle ID to target. Defaults to the only connected app or the default app if multiple are connected.'
+   ),
+});
+
+/**
+ * Lists the commands the MCP Bridge plugin accepts over its WebSocket, with a JSON
+ * Schema for each command's arguments.
+ */
+export async function listBridgeCommands(appIdentifier?: string | number): Promise<string> {
+   try {
+      const client = await ensureSessionAndConnect(appIdentifier);
+
+      const response = await client.sendCommand({
+         command: 'list_bridge_commands',
+      });
+
+      if (!response.success) {
+         throw new Error(response.error || 'Unknown error');
+      }
+
+      return JSON.stringify(response.data);
+   } catch(error: unknown) {
+      const message = error instanceof Error ? error.message : String(error);
+
+      throw new Error(`Failed to list bridge commands: ${message}`);
+   }
+}
+
 // ============================================================================
 // Window Management
 // ============================================================================
@@ -326,11 +385,44 @@ export async function resizeWindow(options: {
    }
 }
 
+/**
+ * Focuses, shows, or hides a window.
+ *
+ * @param command - The plugin command to send
+ * @param windowId - Window label to target (defaults to "main")
+ * @param appIdentifier - App port or bundle ID to target
+ * @returns JSON string with the window's visibility and focus afterwards
+ */
+async function setWindowVisibility(
+   command: 'focus_window' | 'show_window' | 'hide_window',
+   windowId?: string,
+   appIdentifier?: string | number
+): Promise<string> {
+   try {
+      const client = await ensureSessionAndConnect(appIdentifier);
+
+      const response = await client.sendCommand({
+         command,
+         args: { windowLabel: windowId },
+      });
+
+      if (!response.success) {
+         throw new Error(response.error || 'Unknown error');
+      }
+
+      return JSON.stringify(response.data);
+   } catch(error: unknown) {
+      const message = error instanceof Error ? error.message : String(error);
+
+      throw new Error(`Failed to ${command.replace('_window', '')} window: ${message}`);
+   }
+}
+
 export const ManageWindowSchema = z.object({
-   action: z.enum([ 'list', 'info', 'resize' ])
-      .describe('Action: "list" all windows, get "info" for one window, or "resize" a window'),
+   action: z.enum([ 'list', 'info', 'resize', 'focus', 'show', 'hide' ])
+      .describe('Action: "list" all windows, get "info" for one window, "resize" a window, or "focus", "show" or "hide" a window'),
    windowId: z.string().optional()
-      .describe('Window label to target (defaults to "main"). Required for "info", optional for "resize"'),
+      .describe('Window label to target (defaults to "main"). Required for "info", optional for the other actions'),
    width: z.number().int().positive().optional()
       .describe('Width in pixels (required for "resize" action)'),
    height: z.number().int().positive().optional()
@@ -349,12 +441,15 @@ export const ManageWindowSchema = z.object({
  * - `list`: List all open webview windows with their labels, titles, URLs, and state
  * - `info`: Get detailed info for a window (size, position, title, focus, visibility)
  * - `resize`: Resize a window to specified dimensions
+ * - `focus`: Bring a window to the front and focus it, showing it first if hidden
+ * - `show`: Show a hidden window
+ * - `hide`: Hide a window without closing it
  *
  * @param options - Action and parameters
  * @returns JSON string with the result
  */
 export async function manageWindow(options: {
-   action: 'list' | 'info' | 'resize';
+   action: 'list' | 'info' | 'resize' | 'focus' | 'show' | 'hide';
    windowId?: string;
    width?: number;
    height?: number;
@@ -397,6 +492,18 @@ export async function manageWindow(options: {
          return resizeWindow({ width, height, windowId, logical, appIdentifier });
       }
 
+      case 'focus': {
+         return setWindowVisibility('focus_window', windowId, appIdentifier);
+      }
+
+      case 'show': {
+         return setWindowVisibility('show_window', windowId, appIdentifier);
+      }
+
+      case 'hide': {
+         return setWindowVisibility('hide_window', windowId, appIdentifier);
+      }
+
       default: {
          throw new Error(`Unknown action: ${action}`);
       }
@@ -1,3 +1,4 @@
+import { AsyncLocalStorage } from 'node:async_hooks';
 import { z } from 'zod';
 
 import { getDefaultHost, getDefaultPort } from '../config.js';
@@ -55,33 +56,82 @@ export interface SessionInfo {
 // AppDiscovery instance - recreated when host changes
 let appDiscovery: AppDiscovery | null = null;
 
-// Track multiple concurrent sessions keyed by port
-const activeSessions = new Map<number, SessionInfo>();
+/**
+ * The apps one MCP client is connected to.
+ *
+ * Every WebSocket connection gets its own, so clients can't see or stop each other's
+ * sessions. The stdio client uses the shared state.
+ */
+export interface SessionState {
+
+   /** Sessions keyed by port. */
+   sessions: Map<number, SessionInfo>;
+
+   /** Port of the default app (most recently connected). */
+   defaultPort: number | null;
+}
+
+/**
+ * Create the session state for a new MCP client.
+ */
+export function createSessionState(): SessionState {
+   return { sessions: new Map<number, SessionInfo>(), defaultPort: null };
+}
+
+// State used outside of runWithSessionState, i.e. by the stdio client
+const sharedState = createSessionState();
+
+const sessionStorage = new AsyncLocalStorage<SessionState>();
+
+/**
+ * Get the session state of the client whose request is being handled.
+ */
+function currentState(): SessionState {
+   return sessionStorage.getStore() ?? sharedState;
+}
 
-// Track the default app (most recently connected)
-let defaultPort: number | null = null;
+/**
+ * Run `fn`, and everything it starts asynchronously, with `state` as the session state.
+ */
+export function runWithSessionState<T>(state: SessionState, fn: () => T): T {
+   return sessionStorage.run(state, fn);
+}
+
+/**
+ * Disconnect every app of a client that went away.
+ */
+export function closeSessionState(state: SessionState): void {
+   for (const session of state.sessions.values()) {
+      session.client.disconnect();
+   }
+
+   state.sessions.clear();
+   state.defaultPort = null;
+}
 
 /**
  * Check if any session is currently active.
  * @returns true if at least one session exists
  */
 export function hasActiveSession(): boolean {
-   return activeSessions.size > 0;
+   return currentState().sessions.size > 0;
 }
 
 /**
  * Get a specific session by port.
  */
 export function getSession(port: number): SessionInfo | null {
-   return activeSessions.get(port) ?? null;
+   return currentState().sessions.get(port) ?? null;
 }
 
 /**
  * Get the default session (most recently connected).
  */
 export function getDefaultSession(): SessionInfo | null {
-   if (defaultPort !== null && activeSessions.has(defaultPort)) {
-      const session = activeSessions.get(defaultPort);
+   const state = currentState();
+
+   if (state.defaultPort !== null && state.sessions.has(state.defaultPort)) {
+      const session = state.sessions.get(state.defaultPort);
 
       return session ?? null;
    }
@@ -92,7 +142,7 @@ export function getDefaultSession(): SessionInfo | null {
  * Get all active sessions.
  */
 export function getAllSessions(): SessionInfo[] {
-   return Array.from(activeSessions.values());
+   return Array.from(currentState().sessions.values());
 }
 
 function getAppDiscovery(host: string): AppDiscovery {
@@ -108,15 +158,17 @@ function getAppDiscovery(host: string): AppDiscovery {
  * Returns the appropriate session based on the routing logic.
  */
 export function resolveTargetApp(portOrIdentifier?: string | number): SessionInfo {
-   if (activeSessions.size === 0) {
+   const state = currentState();
+
+   if (state.sessions.size === 0) {
       throw new Error(
          'No active session. Call tauri_driver_session with action "start" first to connect to a Tauri app.'
       );
    }
 
    // Single app - return it
-   if (activeSessions.size === 1) {
-      const session = activeSessions.values().next().value;
+   if (state.sessions.size === 1) {
+      const session = state.sessions.values().next().value;
 
       if (!session) {
          throw new Error('Session state inconsistent');
@@ -131,8 +183,8 @@ export function resolveTargetApp(portOrIdentifier?: string | number): SessionInf
          ? portOrIdentifier
          : parseInt(String(portOrIdentifier), 10);
 
-      if (!isNaN(port) && activeSessions.has(port)) {
-         const session = activeSessions.get(port);
+      if (!isNaN(port) && state.sessions.has(port)) {
+         const session = state.sessions.get(port);
 
          if (session) {
             return session;
@@ -140,7 +192,7 @@ export function resolveTargetApp(portOrIdentifier?: string | number): SessionInf
       }
 
       // Try identifier match
-      for (const session of activeSessions.values()) {
+      for (const session of state.sessions.values()) {
          if (session.identifier === String(portOrIdentifier)) {
             return session;
          }
@@ -150,8 +202,8 @@ export function resolveTargetApp(portOrIdentifier?: string | number): SessionInf
    }
 
    // Use default app
-   if (defaultPort !== null && activeSessions.has(defaultPort)) {
-      const session = activeSessions.get(defaultPort);
+   if (state.defaultPort !== null && state.sessions.has(state.defaultPort)) {
+      const session = state.sessions.get(state.defaultPort);
 
       if (session) {
          return session;
@@ -165,9 +217,11 @@ export function resolveTargetApp(portOrIdentifier?: string | number): SessionInf
  * Format error message when app not found.
  */
 function formatAppNotFoundError(identifier: string | number): string {
-   const appList = Array.from(activeSessions.values())
+   const state = currentState();
+
+   const appList = Array.from(state.sessions.values())
       .map((session) => {
-         const isDefault = session.port === defaultPort;
+         const isDefault = session.port === state.defaultPort;
 
          const defaultMarker = isDefault ? ' [DEFAULT]' : '';
 
@@ -187,23 +241,27 @@ function formatAppNotFoundError(identifier: string | number): string {
  * Selects the oldest remaining session (first in insertion order).
  */
 function promoteNextDefault(): void {
-   if (activeSessions.size > 0) {
+   const state = currentState();
+
+   if (state.sessions.size > 0) {
       // Get first session (oldest)
-      const firstSession = activeSessions.values().next().value;
+      const firstSession = state.sessions.values().next().value;
 
       if (firstSession) {
-         defaultPort = firstSession.port;
-         sessionLogger.info(`Promoted port ${defaultPort} as new default app`);
+         state.defaultPort = firstSession.port;
+         sessionLogger.info(`Promoted port ${state.defaultPort} as new default app`);
       } else {
-         defaultPort = null;
+         state.defaultPort = null;
       }
    } else {
-      defaultPort = null;
+      state.defaultPort = null;
    }
 }
 
 async function handleStatusAction(): Promise<string> {
-   if (activeSessions.size === 0) {
+   const state = currentState();
+
+   if (state.sessions.size === 0) {
       return JSON.stringify({
          connected: false,
          app: null,
@@ -213,8 +271,8 @@ async function handleStatusAction(): Promise<string> {
       });
    }
 
-   if (activeSessions.size === 1) {
-      const session = activeSessions.values().next().value;
+   if (state.sessions.size === 1) {
+      const session = state.sessions.values().next().value;
 
       if (!session) {
          return JSON.stringify({
@@ -235,13 +293,13 @@ async function handleStatusAction(): Promise<string> {
       });
    }
 
-   const apps = Array.from(activeSessions.values()).map((session) => {
+   const apps = Array.from(state.sessions.values()).map((session) => {
       return {
          name: session.name,
          identifier: session.identifier,
          host: session.host,
          port: session.port,
-         isDefault: session.port === defaultPort,
+         isDefault: session.port === state.defaultPort,
       };
    });
 
@@ -249,16 +307,18 @@ async function handleStatusAction(): Promise<string> {
       connected: true,
       apps,
       totalCount: apps.length,
-      defaultPort,
+      defaultPort: state.defaultPort,
    });
 }
 
 async function handleStartAction(host?: string, port?: number): Promise<string> {
+   const state = currentState();
+
    const configuredHost = host ?? getDefaultHost();
 
    const configuredPort = port ?? getDefaultPort();
 
-   if (activeSessions.has(configuredPort)) {
+   if (state.sessions.has(configuredPort)) {
       return `Already connected to app on port ${configuredPort}`;
    }
 
@@ -304,6 +364,8 @@ async function handleStartAction(host?: string, port?: number): Promise<string>
 
    const identifier = await fetchAppIdentifier(client);
 
+   const protocolWarning = await checkProtocolVersion(client);
+
    const sessionInfo: SessionInfo = {
       name: connectedSession.name,
       identifier,
@@ -313,24 +375,33 @@ async function handleStartAction(host?: string, port?: number): Promise<string>
       connected: true,
    };
 
-   activeSessions.set(connectedSession.port, sessionInfo);
-   defaultPort = connectedSession.port;
+   state.sessions.set(connectedSession.port, sessionInfo);
+   state.defaultPort = connectedSession.port;
 
    sessionLogger.info(
       `Session started: ${sessionInfo.name} (${sessionInfo.host}:${sessionInfo.port}) [DEFAULT]`
    );
 
-   return `Session started with app: ${sessionInfo.name} (${sessionInfo.host}:${sessionInfo.port}) [DEFAULT]`;
+   const started = `Session started with app: ${sessionInfo.name} (${sessionInfo.host}:${sessionInfo.port}) [DEFAULT]`;
+
+   if (protocolWarning) {
+      sessionLogger.warn(protocolWarning);
+      return `${started}\nWarning: ${protocolWarning}`;
+   }
+
+   return started;
 }
 
 async function handleStopAction(appIdentifier?: string | number): Promise<string> {
+   const state = currentState();
+
    if (appIdentifier !== undefined) {
       const session = resolveTargetApp(appIdentifier);
 
       session.client.disconnect();
-      activeSessions.delete(session.port);
+      state.sessions.delete(session.port);
 
-      if (session.port === defaultPort) {
+      if (session.port === state.defaultPort) {
          promoteNextDefault();
       }
 
@@ -338,12 +409,12 @@ async function handleStopAction(appIdentifier?: string | number): Promise<string
       return `Session stopped: ${session.name} (${session.host}:${session.port})`;
    }
 
-   for (const session of activeSessions.values()) {
+   for (const session of state.sessions.values()) {
       session.client.disconnect();
    }
 
-   activeSessions.clear();
-   defaultPort = null;
+   state.sessions.clear();
+   state.defaultPort = null;
 
    if (appDiscovery) {
       await appDiscovery.disconnectAll();
@@ -375,6 +446,41 @@ async function tryConnect(host: string, port: number): Promise<{ name: string; h
    };
 }
 
+/**
+ * Bridge protocol version this server speaks, compared against the plugin's `ping`.
+ */
+export const BRIDGE_PROTOCOL_VERSION = 1;
+
+/**
+ * Ping the plugin and compare its protocol version with this server's.
+ * Must be called after a PluginClient is connected.
+ *
+ * @param client - The PluginClient to query
+ * @returns A warning describing the mismatch, or null if the versions match or the
+ *          plugin predates `ping`
+ */
+async function checkProtocolVersion(client: PluginClient): Promise<string | null> {
+   try {
+      const response = await client.sendCommand({ command: 'ping' });
+
+      if (!response.success || !response.data) {
+         return null;
+      }
+
+      const pong = response.data as { plugin_version?: string; protocol_version?: number };
+
+      if (pong.protocol_version === undefined || pong.protocol_version === BRIDGE_PROTOCOL_VERSION) {
+         return null;
+      }
+
+      return `Plugin version ${pong.plugin_version ?? 'unknown'} speaks bridge protocol ${pong.protocol_version}, `
+         + `but this server speaks ${BRIDGE_PROTOCOL_VERSION}. Update the one that's older.`;
+   } catch{
+      // Older plugins don't know the command
+      return null;
+   }
+}
+
 /**
  * Fetch the app identifier from the backend state.
  * Must be called after a PluginClient is connected.
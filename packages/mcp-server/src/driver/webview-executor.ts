@@ -354,6 +354,49 @@ export interface CaptureScreenshotOptions {
    appIdentifier?: string | number;
 }
 
+export interface CaptureElementScreenshotOptions {
+   selector: string;
+   windowId?: string;
+   appIdentifier?: string | number;
+}
+
+/**
+ * Capture a PNG screenshot of a single element, cropped by the plugin to its bounding box.
+ *
+ * @param options - Element selector and optional windowId and appIdentifier
+ * @returns Screenshot result with image content and the crop rect used
+ */
+export async function captureElementScreenshot(options: CaptureElementScreenshotOptions): Promise<ScreenshotResult> {
+   const { selector, windowId, appIdentifier } = options;
+
+   await ensureReady();
+
+   const session = resolveTargetApp(appIdentifier);
+
+   const response = await session.client.sendCommand({
+      command: 'take_element_screenshot',
+      args: { selector, windowLabel: windowId },
+   }, 15000);
+
+   const data = response.data as {
+      image?: string;
+      rect?: { x: number; y: number; width: number; height: number };
+      clipped?: boolean;
+   } | undefined;
+
+   if (!response.success || !data?.image || !data.rect) {
+      throw new Error(response.error || 'Element screenshot returned invalid data');
+   }
+
+   const { x, y, width, height } = data.rect;
+
+   let method = `element screenshot of "${selector}" (crop ${width}x${height} at ${x},${y}`;
+
+   method += data.clipped ? ', clipped to the viewport)' : ')';
+
+   return buildScreenshotResult(`data:image/png;base64,${data.image}`, method, response.windowContext);
+}
+
 /**
  * Prepares the html2canvas script for screenshot capture.
  * Tries to use the script manager for persistence, falls back to inline injection.
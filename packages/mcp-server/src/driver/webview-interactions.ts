@@ -5,6 +5,7 @@ import {
    executeInWebview,
    executeInWebviewWithContext,
    captureScreenshot,
+   captureElementScreenshot,
    getConsoleLogs as getConsoleLogsFromCapture,
    ScreenshotResult,
 } from './webview-executor.js';
@@ -49,6 +50,8 @@ export const ScreenshotSchema = WindowTargetSchema.extend({
    format: z.enum([ 'png', 'jpeg' ]).optional().default('png').describe('Image format'),
    quality: z.number().min(0).max(100).optional().describe('JPEG quality (0-100, only for jpeg format)'),
    filePath: z.string().optional().describe('File path to save the screenshot to instead of returning as base64'),
+   selector: z.string().optional()
+      .describe('CSS selector of an element to capture instead of the whole viewport (always PNG; the element must be at least partly visible)'),
 });
 
 export const KeyboardSchema = WindowTargetSchema.extend({
@@ -182,6 +185,7 @@ export interface ScreenshotOptions {
    format?: 'png' | 'jpeg';
    windowId?: string;
    filePath?: string;
+   selector?: string;
    appIdentifier?: string | number;
 }
 
@@ -191,10 +195,15 @@ export interface ScreenshotFileResult {
 }
 
 export async function screenshot(options: ScreenshotOptions = {}): Promise<ScreenshotResult | ScreenshotFileResult> {
-   const { quality, format = 'png', windowId, filePath, appIdentifier } = options;
+   const { quality, windowId, filePath, selector, appIdentifier } = options;
+
+   // Element screenshots are cropped by the plugin and always PNG
+   const format = selector ? 'png' : options.format ?? 'png';
 
    // Use the native screenshot function from webview-executor
-   const result = await captureScreenshot({ format, quality, windowId, appIdentifier });
+   const result = selector
+      ? await captureElementScreenshot({ selector, windowId, appIdentifier })
+      : await captureScreenshot({ format, quality, windowId, appIdentifier });
 
    // If filePath is provided, write to file instead of returning base64
    if (filePath) {
@@ -6,16 +6,22 @@ import {
    ListToolsRequestSchema,
    ListPromptsRequestSchema,
    GetPromptRequestSchema,
+   ListResourcesRequestSchema,
+   ReadResourceRequestSchema,
 } from '@modelcontextprotocol/sdk/types.js';
 import { zodToJsonSchema } from 'zod-to-json-schema';
 import { readFileSync } from 'fs';
 import { fileURLToPath } from 'url';
 import { dirname, join } from 'path';
 
-// Import the single source of truth for all tools and prompts
+// Import the single source of truth for all tools, prompts, and resources
 import { TOOLS, TOOL_MAP, ToolResult, ToolContent } from './tools-registry.js';
 import { PROMPTS, PROMPT_MAP } from './prompts-registry.js';
+import { RESOURCES, RESOURCE_MAP } from './resources-registry.js';
 import { createMcpLogger } from './logger.js';
+import { getTransportConfig } from './config.js';
+import { withCorrelationId } from './correlation.js';
+import { listenWebSocket } from './websocket-transport.js';
 
 /* eslint-disable no-process-exit */
 
@@ -28,52 +34,6 @@ const VERSION = packageJson.version as string;
 
 const serverLogger = createMcpLogger('SERVER');
 
-// Initialize server
-const server = new Server(
-   {
-      name: 'mcp-server-tauri',
-      version: VERSION,
-   },
-   {
-      capabilities: {
-         tools: {},
-         prompts: {},
-      },
-   }
-);
-
-// Handle connection errors gracefully - don't crash on broken pipe
-server.onerror = (error) => {
-   // Ignore broken pipe errors - they happen when the client disconnects
-   const message = error instanceof Error ? error.message : String(error);
-
-   if (message.includes('broken pipe') || message.includes('EPIPE')) {
-      // Client disconnected, exit gracefully
-      process.exit(0);
-   }
-   // For other errors, log to stderr (will be captured by MCP client)
-   serverLogger.error(message);
-};
-
-// Handle connection close - exit gracefully
-server.onclose = () => {
-   process.exit(0);
-};
-
-// Tool list handler - generated from registry
-server.setRequestHandler(ListToolsRequestSchema, async () => {
-   return {
-      tools: TOOLS.map((tool) => {
-         return {
-            name: tool.name,
-            description: tool.description,
-            inputSchema: zodToJsonSchema(tool.schema) as Record<string, unknown>,
-            annotations: tool.annotations,
-         };
-      }),
-   };
-});
-
 /**
  * Convert a ToolResult to MCP content array.
  * Handles string (legacy), single content, and content arrays.
@@ -104,62 +64,168 @@ function contentToMcp(content: ToolContent): { type: string; text?: string; data
    return { type: 'image', data: content.data, mimeType: content.mimeType };
 }
 
-// Tool call handler - generated from registry
-server.setRequestHandler(CallToolRequestSchema, async (request) => {
-   try {
-      const tool = TOOL_MAP.get(request.params.name);
+/**
+ * Creates an MCP server with every tool, prompt and resource handler registered.
+ *
+ * Each transport connection needs its own server, since the server tracks the
+ * protocol state of one client.
+ */
+function createServer(): Server {
+   const server = new Server(
+      {
+         name: 'mcp-server-tauri',
+         version: VERSION,
+      },
+      {
+         capabilities: {
+            tools: {},
+            prompts: {},
+            resources: {},
+         },
+      }
+   );
+
+   // Tool list handler - generated from registry
+   server.setRequestHandler(ListToolsRequestSchema, async () => {
+      return {
+         tools: TOOLS.map((tool) => {
+            return {
+               name: tool.name,
+               description: tool.description,
+               inputSchema: zodToJsonSchema(tool.schema) as Record<string, unknown>,
+               annotations: tool.annotations,
+            };
+         }),
+      };
+   });
+
+   // Tool call handler - generated from registry
+   // Each call runs with its own correlation ID, which prefixes the IDs of the
+   // plugin requests it makes and is included in error results
+   server.setRequestHandler(CallToolRequestSchema, async (request) => {
+      return withCorrelationId(async (correlationId) => {
+         try {
+            const tool = TOOL_MAP.get(request.params.name);
+
+            if (!tool) {
+               throw new Error(`Unknown tool: ${request.params.name}`);
+            }
+
+            const output = await tool.handler(request.params.arguments);
+
+            return { content: toolResultToContent(output) };
+         } catch(error: unknown) {
+            const message = error instanceof Error ? error.message : String(error);
+
+            serverLogger.warn(`${request.params.name} [${correlationId}] failed: ${message}`);
+
+            return {
+               content: [ { type: 'text', text: `Error: ${message} (correlation ID: ${correlationId})` } ],
+               isError: true,
+            };
+         }
+      });
+   });
+
+   // Prompt list handler - generated from registry
+   server.setRequestHandler(ListPromptsRequestSchema, async () => {
+      return {
+         prompts: PROMPTS.map((prompt) => {
+            return {
+               name: prompt.name,
+               description: prompt.description,
+               arguments: prompt.arguments,
+            };
+         }),
+      };
+   });
+
+   // Get prompt handler - returns prompt messages for a specific prompt
+   server.setRequestHandler(GetPromptRequestSchema, async (request) => {
+      const prompt = PROMPT_MAP.get(request.params.name);
 
-      if (!tool) {
-         throw new Error(`Unknown tool: ${request.params.name}`);
+      if (!prompt) {
+         throw new Error(`Unknown prompt: ${request.params.name}`);
       }
 
-      const output = await tool.handler(request.params.arguments);
+      const args = (request.params.arguments || {}) as Record<string, string>;
 
-      return { content: toolResultToContent(output) };
-   } catch(error: unknown) {
-      const message = error instanceof Error ? error.message : String(error);
+      return {
+         description: prompt.description,
+         messages: prompt.handler(args),
+      };
+   });
 
+   // Resource list handler - generated from registry
+   server.setRequestHandler(ListResourcesRequestSchema, async () => {
       return {
-         content: [ { type: 'text', text: `Error: ${message}` } ],
-         isError: true,
+         resources: RESOURCES.map((resource) => {
+            return {
+               uri: resource.uri,
+               name: resource.name,
+               description: resource.description,
+               mimeType: resource.mimeType,
+            };
+         }),
       };
-   }
-});
+   });
 
-// Prompt list handler - generated from registry
-server.setRequestHandler(ListPromptsRequestSchema, async () => {
-   return {
-      prompts: PROMPTS.map((prompt) => {
-         return {
-            name: prompt.name,
-            description: prompt.description,
-            arguments: prompt.arguments,
-         };
-      }),
-   };
-});
+   // Read resource handler - returns the current contents of a resource
+   server.setRequestHandler(ReadResourceRequestSchema, async (request) => {
+      const resource = RESOURCE_MAP.get(request.params.uri);
 
-// Get prompt handler - returns prompt messages for a specific prompt
-server.setRequestHandler(GetPromptRequestSchema, async (request) => {
-   const prompt = PROMPT_MAP.get(request.params.name);
+      if (!resource) {
+         throw new Error(`Unknown resource: ${request.params.uri}`);
+      }
+
+      return {
+         contents: [
+            {
+               uri: resource.uri,
+               mimeType: resource.mimeType,
+               text: await resource.handler(),
+            },
+         ],
+      };
+   });
+
+   return server;
+}
 
-   if (!prompt) {
-      throw new Error(`Unknown prompt: ${request.params.name}`);
+// Start server
+async function main(): Promise<void> {
+   const transportConfig = getTransportConfig();
+
+   if (transportConfig.type === 'websocket') {
+      // No stdout protocol to protect here, so startup failures can be reported
+      await listenWebSocket(transportConfig.host, transportConfig.port, createServer).catch((error: unknown) => {
+         serverLogger.error('Failed to start WebSocket transport:', error);
+         throw error;
+      });
+      return;
    }
 
-   const args = (request.params.arguments || {}) as Record<string, string>;
+   const stdioServer = createServer();
 
-   return {
-      description: prompt.description,
-      messages: prompt.handler(args),
+   // Handle connection errors gracefully - don't crash on broken pipe
+   stdioServer.onerror = (error) => {
+      // Ignore broken pipe errors - they happen when the client disconnects
+      const message = error instanceof Error ? error.message : String(error);
+
+      if (message.includes('broken pipe') || message.includes('EPIPE')) {
+         // Client disconnected, exit gracefully
+         process.exit(0);
+      }
+      // For other errors, log to stderr (will be captured by MCP client)
+      serverLogger.error(message);
    };
-});
 
-// Start server
-async function main(): Promise<void> {
-   const transport = new StdioServerTransport();
+   // Handle connection close - exit gracefully
+   stdioServer.onclose = () => {
+      process.exit(0);
+   };
 
-   await server.connect(transport);
+   await stdioServer.connect(new StdioServerTransport());
    // Don't log to stderr - it interferes with MCP protocol
 }
 
@@ -0,0 +1,52 @@
+/**
+ * Single source of truth for all MCP resource definitions
+ * Resources are read-only data that clients can discover, read, and cache
+ */
+
+import { ensureSessionAndConnect } from './driver/plugin-client.js';
+
+export interface ResourceDefinition {
+   uri: string;
+   name: string;
+   description: string;
+   mimeType: string;
+   handler: () => Promise<string>;
+}
+
+/**
+ * Reads the connected app's metadata and the bridge's supported commands.
+ */
+async function readAppConfig(): Promise<string> {
+   const client = await ensureSessionAndConnect();
+
+   const response = await client.sendCommand({
+      command: 'get_app_config',
+   });
+
+   if (!response.success) {
+      throw new Error(response.error || 'Unknown error');
+   }
+
+   return JSON.stringify(response.data, null, 2);
+}
+
+/**
+ * Complete list of all available resources
+ */
+export const RESOURCES: ResourceDefinition[] = [
+   {
+      uri: 'app://config',
+      name: 'Tauri App Config',
+      description:
+         'Name, identifier, and version of the connected Tauri app, the MCP Bridge plugin ' +
+         'version, and the commands the bridge supports. Requires active tauri_driver_session. ' +
+         'Use the plugin version or bridge_commands to check which features are available.',
+      mimeType: 'application/json',
+      handler: readAppConfig,
+   },
+];
+
+/**
+ * Create a Map for fast resource lookup by URI
+ */
+export const RESOURCE_MAP = new Map(RESOURCES.map((resource) => { return [ resource.uri, resource ]; }));
@@ -13,10 +13,10 @@ import { readLogs, ReadLogsSchema } from './monitor/logs.js';
 import {
    executeIPCCommand,
    manageIPCMonitoring, getIPCEvents, emitTestEvent, getBackendState,
-   manageWindow,
+   listCommands, listBridgeCommands, manageWindow,
    ExecuteIPCCommandSchema,
    ManageIPCMonitoringSchema, GetIPCEventsSchema, EmitTestEventSchema,
-   GetBackendStateSchema, ManageWindowSchema,
+   GetBackendStateSchema, ListCommandsSchema, ListBridgeCommandsSchema, ManageWindowSchema,
 } from './driver/plugin-commands.js';
 import {
    interact, screenshot, keyboard, waitFor, getStyles,
@@ -323,6 +323,7 @@ export const TOOLS: ToolDefinition[] = [
       description:
          '[Tauri Apps Only] Screenshot a running Tauri app\'s webview. ' +
          'Requires active tauri_driver_session. Captures only visible viewport. ' +
+         'Pass selector to capture a single element, cropped to its bounding box. ' +
          MULTI_APP_DESC + ' ' +
          'For browser screenshots, use Chrome DevTools MCP instead. ' +
          'For Electron apps, this will NOT work.',
@@ -341,6 +342,7 @@ export const TOOLS: ToolDefinition[] = [
             format: parsed.format,
             windowId: parsed.windowId,
             filePath: parsed.filePath,
+            selector: parsed.selector,
             appIdentifier: parsed.appIdentifier,
          });
 
@@ -583,6 +585,48 @@ export const TOOLS: ToolDefinition[] = [
       },
    },
 
+   {
+      name: 'tauri_ipc_list_commands',
+      description:
+         '[Tauri Apps Only] List the app\'s own Tauri commands (names, and descriptions and ' +
+         'argument names when provided) to call with tauri_ipc_execute_command. ' +
+         'Only commands the app registered with the MCP Bridge plugin\'s Builder are listed. ' +
+         'Requires active tauri_driver_session.',
+      category: TOOL_CATEGORIES.IPC_PLUGIN,
+      schema: ListCommandsSchema,
+      annotations: {
+         title: 'List Tauri Commands',
+         readOnlyHint: true,
+         openWorldHint: false,
+      },
+      handler: async (args) => {
+         const parsed = ListCommandsSchema.parse(args);
+
+         return await listCommands(parsed.appIdentifier);
+      },
+   },
+
+   {
+      name: 'tauri_list_bridge_commands',
+      description:
+         '[Tauri Apps Only] List the commands the MCP Bridge plugin accepts, with a ' +
+         'description and a JSON Schema of the arguments for each. Useful for checking what ' +
+         'the connected plugin version supports. ' +
+         'Requires active tauri_driver_session.',
+      category: TOOL_CATEGORIES.IPC_PLUGIN,
+      schema: ListBridgeCommandsSchema,
+      annotations: {
+         title: 'List Bridge Commands',
+         readOnlyHint: true,
+         openWorldHint: false,
+      },
+      handler: async (args) => {
+         const parsed = ListBridgeCommandsSchema.parse(args);
+
+         return await listBridgeCommands(parsed.appIdentifier);
+      },
+   },
+
    // Window Management Tools
    {
       name: 'tauri_manage_window',
@@ -591,6 +635,8 @@ export const TOOLS: ToolDefinition[] = [
          '"list" - List all windows with labels, titles, URLs, and state. ' +
          '"info" - Get detailed info for a window (size, position, title, focus, visibility). ' +
          '"resize" - Resize a window (requires width/height, uses logical pixels by default). ' +
+         '"focus", "show", "hide" - Bring a window to the front, show it, or hide it; ' +
+         'returns the window\'s visibility and focus afterwards. ' +
          'Requires active tauri_driver_session. ' +
          'For browser windows, use Chrome DevTools MCP instead.',
       category: TOOL_CATEGORIES.UI_AUTOMATION,
@@ -11,6 +11,8 @@ export interface WindowInfo {
    url?: string;
    focused: boolean;
    visible: boolean;
+   width?: number;
+   height?: number;
    isMain: boolean;
 }
 
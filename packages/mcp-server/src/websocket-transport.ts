@@ -0,0 +1,136 @@
+import WebSocket, { WebSocketServer } from 'ws';
+import type { Server } from '@modelcontextprotocol/sdk/server/index.js';
+import type { Transport } from '@modelcontextprotocol/sdk/shared/transport.js';
+import { JSONRPCMessage, JSONRPCMessageSchema } from '@modelcontextprotocol/sdk/types.js';
+
+import { closeSessionState, createSessionState, runWithSessionState, SessionState } from './driver/session-manager.js';
+import { createMcpLogger } from './logger.js';
+
+const logger = createMcpLogger('WS_TRANSPORT');
+
+/**
+ * MCP transport over a single WebSocket connection.
+ *
+ * Each text frame carries exactly one JSON-RPC message, the same messages the
+ * stdio transport writes one per line. Messages are handled with the connection's own
+ * driver session state, so the apps one client connects to are never used or stopped
+ * by another, and they are disconnected when the connection closes.
+ */
+export class WebSocketServerTransport implements Transport {
+   public onclose?: () => void;
+   public onerror?: (error: Error) => void;
+   public onmessage?: (message: JSONRPCMessage) => void;
+
+   private _socket: WebSocket;
+   private _sessionState: SessionState;
+   private _started = false;
+
+   public constructor(socket: WebSocket, sessionState: SessionState = createSessionState()) {
+      this._socket = socket;
+      this._sessionState = sessionState;
+   }
+
+   public async start(): Promise<void> {
+      if (this._started) {
+         throw new Error('WebSocketServerTransport already started');
+      }
+      this._started = true;
+
+      this._socket.on('message', (data, isBinary) => {
+         if (isBinary) {
+            this.onerror?.(new Error('Binary frames are not supported, send JSON-RPC messages as text'));
+            return;
+         }
+
+         let message: JSONRPCMessage;
+
+         try {
+            message = JSONRPCMessageSchema.parse(JSON.parse(data.toString()));
+         } catch(error: unknown) {
+            this.onerror?.(error instanceof Error ? error : new Error(String(error)));
+            return;
+         }
+
+         runWithSessionState(this._sessionState, () => {
+            this.onmessage?.(message);
+         });
+      });
+
+      this._socket.on('error', (error) => {
+         this.onerror?.(error);
+      });
+
+      this._socket.on('close', () => {
+         closeSessionState(this._sessionState);
+         this.onclose?.();
+      });
+   }
+
+   public async send(message: JSONRPCMessage): Promise<void> {
+      if (this._socket.readyState !== WebSocket.OPEN) {
+         throw new Error('WebSocket is not open');
+      }
+
+      await new Promise<void>((resolve, reject) => {
+         this._socket.send(JSON.stringify(message), (error) => {
+            if (error) {
+               reject(error);
+            } else {
+               resolve();
+            }
+         });
+      });
+   }
+
+   public async close(): Promise<void> {
+      this._socket.close();
+   }
+}
+
+/**
+ * Listens for MCP clients over WebSocket.
+ *
+ * Every connection gets its own server from `createServer`, so protocol state
+ * such as the `initialize` handshake is kept per client, and its own driver sessions.
+ *
+ * @param host Interface to listen on
+ * @param port Port to listen on
+ * @param createServer Creates the MCP server for one connection
+ */
+export async function listenWebSocket(
+   host: string,
+   port: number,
+   createServer: () => Server
+): Promise<WebSocketServer> {
+   const wss = new WebSocketServer({ host, port });
+
+   wss.on('connection', (socket, request) => {
+      const remote = request.socket.remoteAddress;
+
+      const server = createServer();
+
+      server.onerror = (error) => {
+         logger.error(`Client ${remote}:`, error instanceof Error ? error.message : String(error));
+      };
+
+      server.onclose = () => {
+         logger.info(`Client ${remote} disconnected`);
+      };
+
+      server.connect(new WebSocketServerTransport(socket)).catch((error: unknown) => {
+         logger.error(`Failed to connect client ${remote}:`, error);
+         socket.close();
+      });
+
+      logger.info(`Client ${remote} connected`);
+   });
+
+   await new Promise<void>((resolve, reject) => {
+      wss.once('listening', resolve);
+      wss.once('error', reject);
+   });
+
+   logger.info(`Listening on ws://${host}:${port}`);
+
+   return wss;
+}
@@ -156,6 +156,37 @@ describe('Multi-Window Support E2E Tests', () => {
       }, TIMEOUT);
    });
 
+   describe('Window Visibility', () => {
+      it('should hide and show the main window', async () => {
+         const hidden = JSON.parse(await manageWindow({ action: 'hide' }));
+
+         expect(hidden.window_label).toBe('main');
+         expect(hidden.visible).toBe(false);
+
+         const shown = JSON.parse(await manageWindow({ action: 'show', windowId: 'main' }));
+
+         expect(shown.window_label).toBe('main');
+         expect(shown.visible).toBe(true);
+      }, TIMEOUT);
+
+      it('should focus the main window, showing it if hidden', async () => {
+         await manageWindow({ action: 'hide' });
+
+         const result = JSON.parse(await manageWindow({ action: 'focus' }));
+
+         expect(result.window_label).toBe('main');
+         expect(result.visible).toBe(true);
+         expect(result).toHaveProperty('focused');
+         expect(result).toHaveProperty('minimized', false);
+      }, TIMEOUT);
+
+      it('should return error for non-existent window', async () => {
+         await expect(manageWindow({ action: 'focus', windowId: 'non-existent-window' }))
+            .rejects
+            .toThrow(/Failed to focus window/);
+      }, TIMEOUT);
+   });
+
    describe('Window Info', () => {
       it('should get detailed info for main window', async () => {
          const result = await manageWindow({ action: 'info' });
@@ -7,6 +7,8 @@ import {
    emitTestEvent,
    getBackendState,
    executeIPCCommand,
+   listCommands,
+   listBridgeCommands,
 } from '../../src/driver/plugin-commands';
 import { manageDriverSession } from '../../src/driver/session-manager';
 import { getTestAppPort } from '../test-utils';
@@ -106,4 +108,45 @@ describe('MCP Bridge Plugin E2E Tests', () => {
       // Check if it succeeded or returned expected structure
       expect(parsed).toBeTruthy();
    }, TIMEOUT);
+
+   it('should list the app commands registered with the plugin', async () => {
+      const result = await listCommands();
+
+      const parsed = JSON.parse(result);
+
+      expect(parsed.count).toBe(3);
+      expect(parsed.note).toBeUndefined();
+      expect(parsed.commands.map((c: { name: string }) => { return c.name; }))
+         .toEqual([ 'greet', 'add_numbers', 'get_config' ]);
+      expect(parsed.commands[1]).toEqual({
+         name: 'add_numbers',
+         description: 'Adds two numbers',
+         args: [ 'a', 'b' ],
+      });
+      expect(parsed.commands[2]).toEqual({ name: 'get_config' });
+   }, TIMEOUT);
+
+   it('should list the bridge commands with their argument schemas', async () => {
+      const result = await listBridgeCommands();
+
+      const parsed = JSON.parse(result);
+
+      expect(parsed.count).toBe(parsed.commands.length);
+
+      const names = parsed.commands.map((c: { name: string }) => { return c.name; });
+
+      expect(names).toContain('ping');
+      expect(names).toContain('list_bridge_commands');
+      expect(new Set(names).size).toBe(names.length);
+
+      for (const command of parsed.commands) {
+         expect(command.description).toBeTruthy();
+         expect(command.args_schema.type).toBe('object');
+      }
+
+      const executeJs = parsed.commands.find((c: { name: string }) => { return c.name === 'execute_js'; });
+
+      expect(executeJs.args_schema.required).toEqual([ 'script' ]);
+      expect(executeJs.args_schema.properties.timeoutMs.type).toBe('integer');
+   }, TIMEOUT);
 });
@@ -8,6 +8,7 @@ import {
    getStyles,
    executeJavaScript,
 } from '../../src/driver/webview-interactions';
+import { executeInWebview } from '../../src/driver/webview-executor';
 import { getTestAppPort } from '../test-utils';
 
 /**
@@ -115,6 +116,33 @@ describe('Webview Interactions E2E Tests', () => {
             expect(() => { return Buffer.from(data, 'base64'); }).not.toThrow();
          }
       }, TIMEOUT);
+
+      it('should take a PNG screenshot of a single element', async () => {
+         const result = await screenshot({ selector: '#greet-input' });
+
+         if (!('content' in result)) {
+            throw new Error('Expected ScreenshotResult with content');
+         }
+
+         type ContentItem = { type: string; text?: string; data?: string; mimeType?: string };
+         const textContent = result.content.find((c: ContentItem) => { return c.type === 'text'; }) as ContentItem | undefined;
+
+         const imageContent = result.content.find((c: ContentItem) => { return c.type === 'image'; }) as ContentItem | undefined;
+
+         expect(textContent?.text).toContain('element screenshot of "#greet-input"');
+         expect(imageContent?.mimeType).toBe('image/png');
+
+         const png = Buffer.from(imageContent?.data ?? '', 'base64');
+
+         // PNG signature, then the IHDR chunk holding the width and height
+         expect(png.subarray(1, 4).toString('ascii')).toBe('PNG');
+         expect(png.readUInt32BE(16)).toBeGreaterThan(0);
+         expect(png.readUInt32BE(20)).toBeGreaterThan(0);
+      }, TIMEOUT);
+
+      it('should fail to screenshot an element that does not exist', async () => {
+         await expect(screenshot({ selector: '#does-not-exist' })).rejects.toThrow();
+      }, TIMEOUT);
    });
 
    describe('Keyboard Interactions', () => {
@@ -237,6 +265,27 @@ describe('Webview Interactions E2E Tests', () => {
 
          expect(result).toContain('8');
       }, TIMEOUT);
+
+      it('should return a canvas blob as base64 PNG', async () => {
+         const result = await executeInWebview(`
+            const canvas = document.createElement('canvas');
+            canvas.width = 2;
+            canvas.height = 2;
+            canvas.getContext('2d').fillRect(0, 0, 1, 1);
+            return await new Promise((resolve) => { canvas.toBlob(resolve, 'image/png'); });
+         `);
+
+         const parsed = JSON.parse(result);
+
+         expect(parsed.__binary).toBe(true);
+         expect(parsed.encoding).toBe('base64');
+         expect(parsed.mime).toBe('image/png');
+
+         // PNG signature
+         const bytes = Buffer.from(parsed.data, 'base64');
+
+         expect(bytes.subarray(0, 8).toString('hex')).toBe('89504e470d0a1a0a');
+      }, TIMEOUT);
    });
 
    describe('Focus and Keyboard Management', () => {
@@ -1,6 +1,12 @@
 import { describe, it, expect, beforeEach, afterEach, vi } from 'vitest';
 
-import { getDefaultHost, getDefaultPort, getConfig, buildWebSocketURL } from '../../src/config.js';
+import {
+   getDefaultHost,
+   getDefaultPort,
+   getConfig,
+   buildWebSocketURL,
+   getTransportConfig,
+} from '../../src/config.js';
 
 describe('config', () => {
    const originalEnv = process.env;
@@ -12,6 +18,9 @@ describe('config', () => {
       delete process.env.MCP_BRIDGE_HOST;
       delete process.env.MCP_BRIDGE_PORT;
       delete process.env.TAURI_DEV_HOST;
+      delete process.env.MCP_TRANSPORT;
+      delete process.env.MCP_TRANSPORT_HOST;
+      delete process.env.MCP_TRANSPORT_PORT;
    });
 
    afterEach(() => {
@@ -94,4 +103,36 @@ describe('config', () => {
          expect(buildWebSocketURL('0.0.0.0', 9223)).toBe('ws://0.0.0.0:9223');
       });
    });
+   describe('getTransportConfig', () => {
+      it('returns stdio by default', () => {
+         expect(getTransportConfig()).toEqual({ type: 'stdio' });
+      });
+
+      it('returns websocket defaults', () => {
+         process.env.MCP_TRANSPORT = 'websocket';
+
+         expect(getTransportConfig()).toEqual({ type: 'websocket', host: '127.0.0.1', port: 9220 });
+      });
+
+      it('returns websocket config from environment', () => {
+         process.env.MCP_TRANSPORT = 'WebSocket';
+         process.env.MCP_TRANSPORT_HOST = '0.0.0.0';
+         process.env.MCP_TRANSPORT_PORT = '4000';
+
+         expect(getTransportConfig()).toEqual({ type: 'websocket', host: '0.0.0.0', port: 4000 });
+      });
+
+      it('throws for an unknown transport', () => {
+         process.env.MCP_TRANSPORT = 'http';
+
+         expect(() => { return getTransportConfig(); }).toThrow('Unknown MCP_TRANSPORT');
+      });
+
+      it('throws for an invalid port', () => {
+         process.env.MCP_TRANSPORT = 'websocket';
+         process.env.MCP_TRANSPORT_PORT = 'abc';
+
+         expect(() => { return getTransportConfig(); }).toThrow('Invalid MCP_TRANSPORT_PORT');
+      });
+   });
 });
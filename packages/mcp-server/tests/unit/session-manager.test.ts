@@ -93,4 +93,48 @@ describe('Session Manager Unit Tests', () => {
          expect(portField.description).toContain('9223');
       });
    });
+
+   describe('Session state', () => {
+      it('should keep the sessions of each state apart', async () => {
+         const {
+            createSessionState,
+            runWithSessionState,
+            closeSessionState,
+            hasActiveSession,
+            resolveTargetApp,
+         } = await import('../../src/driver/session-manager');
+
+         const { PluginClient } = await import('../../src/driver/plugin-client');
+
+         const client = new PluginClient('localhost', 9300);
+
+         const disconnect = vi.spyOn(client, 'disconnect').mockImplementation(() => { return undefined; });
+
+         const first = createSessionState();
+
+         const second = createSessionState();
+
+         first.sessions.set(9300, { name: 'test-app', identifier: null, host: 'localhost', port: 9300, client, connected: true });
+         first.defaultPort = 9300;
+
+         // The state follows the client's request across awaits
+         await runWithSessionState(first, async () => {
+            await Promise.resolve();
+            expect(hasActiveSession()).toBe(true);
+            expect(resolveTargetApp().port).toBe(9300);
+         });
+
+         runWithSessionState(second, () => {
+            expect(hasActiveSession()).toBe(false);
+            expect(() => { return resolveTargetApp(); }).toThrow(/No active session/);
+         });
+         expect(hasActiveSession()).toBe(false);
+
+         closeSessionState(first);
+
+         expect(disconnect).toHaveBeenCalledOnce();
+         expect(first.sessions.size).toBe(0);
+         expect(first.defaultPort).toBeNull();
+      });
+   });
 });
@@ -7,6 +7,80 @@ fn main() {
         "start_ipc_monitor",
         "stop_ipc_monitor",
         "get_ipc_events",
+        "take_screenshot",
+        "cancel_execution",
+        "wait_for_selector",
+        "click_element",
+        "set_input_value",
+        "get_dom_snapshot",
+        "navigate",
+        "get_server_address",
+        "get_console_logs",
+        "clear_console_logs",
+        "get_page_errors",
+        "query_elements",
+        "get_local_storage",
+        "set_local_storage",
+        "get_cookies",
+        "set_cookie",
+        "batch_execute",
+        "scroll_into_view",
+        "scroll_to",
+        "get_page_metrics",
+        "get_execution_metrics",
+        "execute_js_file",
+        "get_computed_style",
+        "type_text",
+        "press_key",
+        "introspect_commands",
+        "invoke_command",
+        "get_app_config",
+        "reload",
+        "focus_window",
+        "show_window",
+        "hide_window",
+        "take_element_screenshot",
+        "get_accessibility_tree",
+        "get_window_size",
+        "set_window_size",
+        "ping",
+        "get_network_log",
+        "clear_network_log",
+        "mock_request",
+        "unmock_request",
+        "clear_mocks",
+        "hover_element",
+        "get_attribute",
+        "set_attribute",
+        "select_option",
+        "drag_and_drop",
+        "get_html",
+        "wait_for_function",
+        "focus_element",
+        "blur_element",
+        "list_bridge_commands",
+        "element_state",
+        "get_selection",
+        "select_text",
+        "read_clipboard",
+        "write_clipboard",
+        "get_metrics",
+        "wait_for_idle",
+        "get_title",
+        "set_title",
+        "get_url",
+        "go_back",
+        "go_forward",
+        "disable_animations",
+        "enable_animations",
+        "get_scroll",
+        "set_scroll",
+        "validate_js",
+        "clear_context",
+        "get_page_info",
+        "set_file_input",
+        "diff_snapshots",
+        "get_bounding_rects",
     ])
     .build();
 }
@@ -36,9 +36,12 @@
 
    bridgeLogger = createMcpLogger('BRIDGE');
 
+   // Maximum number of console entries kept; the oldest are dropped first
+   var MAX_CONSOLE_LOGS = 1000;
+
    // Initialize console capture so logs are captured from app startup
    function initConsoleCapture() {
-      var args, message;
+      var nextSeq = 1;
 
       if (window.__MCP_CONSOLE_LOGS__) {
          return; // Already initialized
@@ -52,26 +55,45 @@
 
       window.__MCP_CONSOLE_LOGS__ = [];
 
+      function serializeArg(arg) {
+         if (arg instanceof Error) {
+            return arg.stack || String(arg);
+         }
+         if (typeof arg === 'object' && arg !== null) {
+            try {
+               return JSON.stringify(arg);
+            } catch(e) {
+               return String(arg);
+            }
+         }
+         return String(arg);
+      }
+
       function captureLog(level, origFn) {
          return function() {
-            args = Array.prototype.slice.call(arguments);
+            var args = Array.prototype.slice.call(arguments),
+                logs = window.__MCP_CONSOLE_LOGS__,
+                serialized;
 
             try {
-               message = args
-                  .map(function(a) {
-                     return typeof a === 'object' ? JSON.stringify(a) : String(a);
-                  })
-                  .join(' ');
+               serialized = args.map(serializeArg);
+
+               // Entries are numbered so readers can ask for everything after a cursor
+               logs.push({
+                  seq: nextSeq++,
+                  level: level,
+                  message: serialized.join(' '),
+                  args: serialized,
+                  timestamp: Date.now(),
+               });
+
+               if (logs.length > MAX_CONSOLE_LOGS) {
+                  logs.splice(0, logs.length - MAX_CONSOLE_LOGS);
+               }
             } catch(e) {
-               message = args.map(String).join(' ');
+               // Capture is best-effort; never break the app's own logging
             }
 
-            window.__MCP_CONSOLE_LOGS__.push({
-               level: level,
-               message: message,
-               timestamp: Date.now(),
-            });
-
             origFn.apply(console, args);
          };
       }
@@ -85,69 +107,410 @@
       bridgeLogger.info('Console capture initialized');
    }
 
-   // Wait for Tauri API to be available
-   function waitForTauri(callback) {
-      if (window.__TAURI__ && window.__TAURI__.core && window.__TAURI__.core.invoke) {
-         // eslint-disable-next-line callback-return
-         callback();
-      } else {
-         setTimeout(function() {
-            waitForTauri(callback);
-         }, 50);
+   // Capture console output right away, before the Tauri API is ready
+   initConsoleCapture();
+
+   // Maximum number of page errors kept; the oldest are dropped first
+   var MAX_PAGE_ERRORS = 200;
+
+   // Capture unhandled JS errors and promise rejections while preserving default
+   // behavior. Entries are buffered for `get_page_errors` and also logged.
+   function initErrorCapture() {
+      var nextSeq = 1;
+
+      if (window.__MCP_PAGE_ERRORS__) {
+         return; // Already initialized
       }
+
+      window.__MCP_PAGE_ERRORS__ = [];
+
+      function recordError(entry) {
+         var errors = window.__MCP_PAGE_ERRORS__;
+
+         entry.seq = nextSeq++;
+         entry.timestamp = Date.now();
+         errors.push(entry);
+
+         if (errors.length > MAX_PAGE_ERRORS) {
+            errors.splice(0, errors.length - MAX_PAGE_ERRORS);
+         }
+      }
+
+      window.addEventListener('error', function(event) {
+         var message, error;
+
+         try {
+            // Resource load failures (e.g. a broken <img>) also fire here, without a message
+            if (!event.message && !event.error) {
+               return;
+            }
+
+            error = event.error;
+            message = event.message || 'Unhandled error';
+
+            recordError({
+               type: 'error',
+               message: message,
+               stack: error && typeof error.stack === 'string' ? error.stack : null,
+               source: event.filename || null,
+               line: typeof event.lineno === 'number' ? event.lineno : null,
+               column: typeof event.colno === 'number' ? event.colno : null,
+            });
+
+            bridgeLogger.tag('UNHANDLED_ERROR', message
+               + (event.filename ? ' at ' + event.filename : '')
+               + (typeof event.lineno === 'number' ? ':' + event.lineno : ''));
+         } catch(e) {
+            // Best-effort capture; do not interfere with default handling
+         }
+      });
+
+      window.addEventListener('unhandledrejection', function(event) {
+         var reason, reasonMessage;
+
+         try {
+            reason = event.reason;
+
+            if (reason && typeof reason === 'object') {
+               if (reason instanceof Error && reason.message) {
+                  reasonMessage = reason.message;
+               } else {
+                  try {
+                     reasonMessage = JSON.stringify(reason);
+                  } catch(e) {
+                     reasonMessage = String(reason);
+                  }
+               }
+            } else {
+               reasonMessage = String(reason);
+            }
+
+            recordError({
+               type: 'unhandledrejection',
+               message: reasonMessage,
+               stack: reason && typeof reason.stack === 'string' ? reason.stack : null,
+               source: null,
+               line: null,
+               column: null,
+            });
+
+            bridgeLogger.tag('UNHANDLED_REJECTION', reasonMessage);
+         } catch(e) {
+            // Best-effort capture; do not interfere with default handling
+         }
+      });
    }
 
-   waitForTauri(function() {
-      bridgeLogger.info('Tauri API available, initializing bridge');
+   // Capture errors right away too, so failures during startup aren't missed
+   initErrorCapture();
 
-      // Initialize console capture immediately so logs are captured from the start
-      initConsoleCapture();
+   // Maximum number of network requests kept; the oldest are dropped first
+   var MAX_NETWORK_ENTRIES = 500;
 
-      // Capture unhandled JS errors and promise rejections while preserving
-      // default behavior
-      if (!window.__MCP_UNHANDLED_ERRORS_CAPTURED__) {
-         window.__MCP_UNHANDLED_ERRORS_CAPTURED__ = true;
+   // Tauri's own IPC travels over fetch too, but isn't app traffic
+   var IPC_URL = /^(ipc:|https?:\/\/ipc\.localhost\b)/;
 
-         window.addEventListener('error', function(event) {
-            var message, source, line;
+   // Record fetch and XMLHttpRequest calls for `get_network_log`. Entries are recorded
+   // when a request completes. Bodies are only kept when the plugin sets
+   // `__MCP_NETWORK_BODY_LIMIT__`, and are cut off at that many characters. fetch calls
+   // matching a rule set by `mock_request` get the rule's response instead. Requests in
+   // flight are counted in `__MCP_NETWORK_ACTIVITY__` for `wait_for_idle`.
+   function initNetworkCapture() {
+      var nextSeq = 1,
+          origFetch, origOpen, origSend;
 
-            try {
-               message = event.message || 'Unhandled error';
-               source = event.filename ? ' at ' + event.filename : '';
-               line = typeof event.lineno === 'number' ? ':' + event.lineno : '';
+      if (window.__MCP_NETWORK_LOG__) {
+         return; // Already initialized
+      }
+
+      window.__MCP_NETWORK_LOG__ = [];
+      window.__MCP_NETWORK_ACTIVITY__ = { pending: 0, last_activity: Date.now() };
+
+      function requestStarted() {
+         var activity = window.__MCP_NETWORK_ACTIVITY__;
+
+         activity.pending++;
+         activity.last_activity = Date.now();
+      }
+
+      function requestEnded() {
+         var activity = window.__MCP_NETWORK_ACTIVITY__;
+
+         activity.pending = Math.max(0, activity.pending - 1);
+         activity.last_activity = Date.now();
+      }
+
+      function bodyLimit() {
+         var limit = window.__MCP_NETWORK_BODY_LIMIT__;
+
+         return typeof limit === 'number' && limit > 0 ? limit : 0;
+      }
+
+      function clipBody(text) {
+         var limit = bodyLimit();
+
+         if (typeof text !== 'string' || !limit) {
+            return null;
+         }
+         return text.length > limit ? text.slice(0, limit) : text;
+      }
+
+      function bodySize(body) {
+         if (body === null || body === undefined) {
+            return 0;
+         }
+         if (typeof body === 'string') {
+            return body.length;
+         }
+         if (typeof Blob !== 'undefined' && body instanceof Blob) {
+            return body.size;
+         }
+         if (body instanceof ArrayBuffer || ArrayBuffer.isView(body)) {
+            return body.byteLength;
+         }
+         if (typeof URLSearchParams !== 'undefined' && body instanceof URLSearchParams) {
+            return body.toString().length;
+         }
+         // FormData and streams can't be measured without reading them
+         return null;
+      }
+
+      function absoluteUrl(url) {
+         try {
+            return new URL(String(url), window.location.href).href;
+         } catch(e) {
+            return String(url);
+         }
+      }
+
+      function contentLength(value) {
+         return value === null || value === '' || isNaN(Number(value)) ? null : Number(value);
+      }
 
-               bridgeLogger.tag('UNHANDLED_ERROR', message + source + line);
+      function record(entry) {
+         var log = window.__MCP_NETWORK_LOG__;
+
+         entry.seq = nextSeq++;
+         log.push(entry);
+
+         if (log.length > MAX_NETWORK_ENTRIES) {
+            log.splice(0, log.length - MAX_NETWORK_ENTRIES);
+         }
+      }
+
+      // Replaces the mock rules. Returns the IDs of rules whose pattern isn't a valid
+      // regular expression, which are left out.
+      window.__MCP_SET_NETWORK_MOCKS__ = function(rules) {
+         var compiled = [],
+             invalid = [];
+
+         rules.forEach(function(rule) {
+            try {
+               compiled.push({
+                  id: rule.id,
+                  method: rule.method,
+                  regex: new RegExp(rule.pattern),
+                  status: rule.status,
+                  headers: rule.headers,
+                  body: rule.body,
+               });
             } catch(e) {
-               // Best-effort logging; do not interfere with default handling
+               invalid.push(rule.id);
             }
          });
 
-         window.addEventListener('unhandledrejection', function(event) {
-            var reason, reasonMessage;
+         window.__MCP_NETWORK_MOCKS__ = compiled;
+         return invalid;
+      };
+
+      function findMock(method, url) {
+         var mocks = window.__MCP_NETWORK_MOCKS__ || [],
+             i;
+
+         for (i = 0; i < mocks.length; i++) {
+            if ((!mocks[i].method || mocks[i].method === method) && mocks[i].regex.test(url)) {
+               return mocks[i];
+            }
+         }
+         return null;
+      }
+
+      function mockResponse(mock) {
+         // Responses with these statuses can't have a body
+         var noBody = mock.status === 204 || mock.status === 205 || mock.status === 304;
+
+         return new Response(noBody ? null : mock.body, { status: mock.status, headers: mock.headers });
+      }
+
+      if (typeof window.fetch === 'function') {
+         origFetch = window.fetch;
+
+         window.fetch = function(input, init) {
+            var start = Date.now(),
+                entry = null,
+                mock = null,
+                promise, request;
 
             try {
-               reason = event.reason;
+               request = typeof Request !== 'undefined' && input instanceof Request ? input : null;
+               entry = {
+                  type: 'fetch',
+                  method: String((init && init.method) || (request && request.method) || 'GET').toUpperCase(),
+                  url: absoluteUrl(request ? request.url : input),
+                  status: null,
+                  duration_ms: null,
+                  request_size: bodySize(init && init.body),
+                  response_size: null,
+                  timestamp: start,
+               };
+
+               if (IPC_URL.test(entry.url)) {
+                  entry = null;
+               } else {
+                  mock = findMock(entry.method, entry.url);
+                  if (mock) {
+                     entry.mock_id = mock.id;
+                  }
+                  if (bodyLimit()) {
+                     entry.request_body = clipBody(init && init.body);
+                  }
+               }
+            } catch(e) {
+               entry = null;
+               mock = null;
+            }
+
+            promise = mock ? Promise.resolve(mockResponse(mock)) : origFetch.apply(window, arguments);
 
-               if (reason && typeof reason === 'object') {
-                  if (reason instanceof Error && reason.message) {
-                     reasonMessage = reason.message;
-                  } else {
+            if (!entry) {
+               return promise;
+            }
+            requestStarted();
+
+            return promise.then(function(response) {
+               requestEnded();
+               try {
+                  entry.status = response.status;
+                  entry.duration_ms = Date.now() - start;
+                  entry.response_size = contentLength(response.headers.get('content-length'));
+
+                  if (bodyLimit()) {
+                     // Read from a copy so the app's own read isn't affected
+                     response.clone().text().then(function(text) {
+                        entry.response_body = clipBody(text);
+                        if (entry.response_size === null) {
+                           entry.response_size = text.length;
+                        }
+                     }).catch(function() {
+                        // The body couldn't be read as text
+                     });
+                  }
+                  record(entry);
+               } catch(e) {
+                  // Best-effort capture; never break the app's requests
+               }
+               return response;
+            }, function(error) {
+               requestEnded();
+               try {
+                  entry.duration_ms = Date.now() - start;
+                  entry.error = (error && error.message) || String(error);
+                  record(entry);
+               } catch(e) {
+                  // Best-effort capture; never break the app's requests
+               }
+               throw error;
+            });
+         };
+      }
+
+      if (typeof XMLHttpRequest !== 'undefined') {
+         origOpen = XMLHttpRequest.prototype.open;
+         origSend = XMLHttpRequest.prototype.send;
+
+         XMLHttpRequest.prototype.open = function(method, url) {
+            try {
+               this.__mcpNetwork = { method: String(method).toUpperCase(), url: absoluteUrl(url) };
+            } catch(e) {
+               this.__mcpNetwork = null;
+            }
+            return origOpen.apply(this, arguments);
+         };
+
+         XMLHttpRequest.prototype.send = function(body) {
+            var xhr = this,
+                info = xhr.__mcpNetwork,
+                start = Date.now(),
+                entry;
+
+            try {
+               if (info && !IPC_URL.test(info.url)) {
+                  entry = {
+                     type: 'xhr',
+                     method: info.method,
+                     url: info.url,
+                     status: null,
+                     duration_ms: null,
+                     request_size: bodySize(body),
+                     response_size: null,
+                     timestamp: start,
+                  };
+                  if (bodyLimit()) {
+                     entry.request_body = clipBody(body);
+                  }
+
+                  requestStarted();
+                  xhr.addEventListener('loadend', function() {
+                     var text;
+
+                     requestEnded();
                      try {
-                        reasonMessage = JSON.stringify(reason);
+                        entry.duration_ms = Date.now() - start;
+                        entry.status = xhr.status || null;
+                        if (!xhr.status) {
+                           entry.error = 'Request failed or was aborted';
+                        }
+
+                        text = xhr.responseType === '' || xhr.responseType === 'text' ? xhr.responseText : null;
+                        entry.response_size = contentLength(xhr.getResponseHeader('content-length'));
+                        if (entry.response_size === null && text !== null) {
+                           entry.response_size = text.length;
+                        }
+                        if (bodyLimit()) {
+                           entry.response_body = clipBody(text);
+                        }
+                        record(entry);
                      } catch(e) {
-                        reasonMessage = String(reason);
+                        // Best-effort capture; never break the app's requests
                      }
-                  }
-               } else {
-                  reasonMessage = String(reason);
+                  });
                }
-
-               bridgeLogger.tag('UNHANDLED_REJECTION', reasonMessage);
             } catch(e) {
-               // Best-effort logging; do not interfere with default handling
+               // Best-effort capture; never break the app's requests
             }
-         });
+
+            return origSend.apply(this, arguments);
+         };
       }
+   }
+
+   // Wrap fetch and XMLHttpRequest before the app's code runs
+   initNetworkCapture();
+
+   // Wait for Tauri API to be available
+   function waitForTauri(callback) {
+      if (window.__TAURI__ && window.__TAURI__.core && window.__TAURI__.core.invoke) {
+         // eslint-disable-next-line callback-return
+         callback();
+      } else {
+         setTimeout(function() {
+            waitForTauri(callback);
+         }, 50);
+      }
+   }
+
+   waitForTauri(function() {
+      bridgeLogger.info('Tauri API available, initializing bridge');
 
       // Listen for execution requests from eval() contexts
       window.addEventListener('__mcp_exec_request', async function(event) {
@@ -0,0 +1,329 @@
+//! Accessibility tree approximated from the DOM.
+
+use super::execute_js::run_script;
+use super::list_windows::target_window;
+use super::script_executor::ScriptExecutor;
+use serde_json::Value;
+use tauri::{command, Runtime, State, WebviewWindow};
+
+/// Default number of accessibility tree levels below the root.
+pub const DEFAULT_ACCESSIBILITY_MAX_DEPTH: u32 = 30;
+
+/// Default number of nodes in the accessibility tree.
+pub const DEFAULT_ACCESSIBILITY_MAX_NODES: u32 = 2000;
+
+/// Upper bound for `max_nodes`, so a single call can't serialize a whole huge page.
+pub const MAX_ACCESSIBILITY_NODES: u32 = 20000;
+
+/// Builds the accessibility tree under `selector`.
+///
+/// Expects `selector`, `maxDepth` and `maxNodes` to be defined before it. Elements
+/// without a role are left out and their children attached to the nearest ancestor
+/// that has one. Hidden elements (`hidden`, `aria-hidden`, `display: none` or
+/// `visibility: hidden`) are skipped with their subtree.
+const ACCESSIBILITY_TREE_SCRIPT: &str = r#"
+const root = selector === null ? document.body : document.querySelector(selector);
+if (root === null) {
+    throw new Error('No element matches selector ' + JSON.stringify(selector));
+}
+
+const NAME_FROM_CONTENT = new Set([
+    'button', 'cell', 'checkbox', 'columnheader', 'gridcell', 'heading', 'link', 'menuitem',
+    'menuitemcheckbox', 'menuitemradio', 'option', 'radio', 'row', 'rowheader', 'switch',
+    'tab', 'tooltip', 'treeitem'
+]);
+const LANDMARK_SCOPES = 'article, aside, main, nav, section';
+const MAX_NAME_LENGTH = 200;
+
+let nodeCount = 0;
+let truncated = false;
+
+function collapse(text) {
+    return (text || '').replace(/\s+/g, ' ').trim().slice(0, MAX_NAME_LENGTH);
+}
+
+function isHidden(el) {
+    if (el.hidden || el.getAttribute('aria-hidden') === 'true') return true;
+    const style = getComputedStyle(el);
+    return style.display === 'none' || style.visibility === 'hidden';
+}
+
+function inputRole(el) {
+    const type = (el.getAttribute('type') || 'text').toLowerCase();
+    switch (type) {
+        case 'button': case 'image': case 'reset': case 'submit': return 'button';
+        case 'checkbox': return 'checkbox';
+        case 'radio': return 'radio';
+        case 'range': return 'slider';
+        case 'number': return 'spinbutton';
+        case 'search': return el.list ? 'combobox' : 'searchbox';
+        case 'hidden': return null;
+        default: return el.list ? 'combobox' : 'textbox';
+    }
+}
+
+function implicitRole(el) {
+    const tag = el.tagName.toLowerCase();
+    switch (tag) {
+        case 'a': case 'area': return el.hasAttribute('href') ? 'link' : null;
+        case 'article': return 'article';
+        case 'aside': return 'complementary';
+        case 'button': return 'button';
+        case 'dialog': return 'dialog';
+        case 'details': return 'group';
+        case 'fieldset': return 'group';
+        case 'figure': return 'figure';
+        case 'footer': return el.closest(LANDMARK_SCOPES) ? null : 'contentinfo';
+        case 'form': return 'form';
+        case 'h1': case 'h2': case 'h3': case 'h4': case 'h5': case 'h6': return 'heading';
+        case 'header': return el.closest(LANDMARK_SCOPES) ? null : 'banner';
+        case 'hr': return 'separator';
+        case 'img': return el.getAttribute('alt') === '' ? null : 'img';
+        case 'input': return inputRole(el);
+        case 'li': return 'listitem';
+        case 'main': return 'main';
+        case 'meter': return 'meter';
+        case 'nav': return 'navigation';
+        case 'ol': case 'ul': case 'menu': return 'list';
+        case 'option': return 'option';
+        case 'output': return 'status';
+        case 'p': return 'paragraph';
+        case 'progress': return 'progressbar';
+        case 'section': return el.hasAttribute('aria-label') || el.hasAttribute('aria-labelledby') ? 'region' : null;
+        case 'select': return el.multiple || el.size > 1 ? 'listbox' : 'combobox';
+        case 'summary': return 'button';
+        case 'table': return 'table';
+        case 'tbody': case 'thead': case 'tfoot': return 'rowgroup';
+        case 'td': return 'cell';
+        case 'textarea': return 'textbox';
+        case 'th': return el.getAttribute('scope') === 'row' ? 'rowheader' : 'columnheader';
+        case 'tr': return 'row';
+        default: return null;
+    }
+}
+
+function roleOf(el) {
+    const explicit = (el.getAttribute('role') || '').trim().split(/\s+/)[0];
+    if (explicit === 'none' || explicit === 'presentation') return null;
+    return explicit || implicitRole(el);
+}
+
+function nameOf(el, role) {
+    const labelledBy = el.getAttribute('aria-labelledby');
+    if (labelledBy) {
+        const text = labelledBy.split(/\s+/)
+            .map(function(id) { const ref = document.getElementById(id); return ref ? ref.textContent : ''; })
+            .join(' ');
+        if (collapse(text)) return collapse(text);
+    }
+    const ariaLabel = collapse(el.getAttribute('aria-label'));
+    if (ariaLabel) return ariaLabel;
+
+    const tag = el.tagName.toLowerCase();
+    if ((tag === 'input' || tag === 'textarea' || tag === 'select') && el.labels && el.labels.length > 0) {
+        return collapse(Array.from(el.labels).map(function(label) { return label.textContent; }).join(' '));
+    }
+    if (tag === 'img' || (tag === 'input' && el.type === 'image')) {
+        const alt = collapse(el.getAttribute('alt'));
+        if (alt) return alt;
+    }
+    if (tag === 'input' && (el.type === 'button' || el.type === 'submit' || el.type === 'reset')) {
+        return collapse(el.value) || (el.type === 'submit' ? 'Submit' : el.type === 'reset' ? 'Reset' : '');
+    }
+    const captions = { fieldset: 'legend', table: 'caption', figure: 'figcaption' };
+    if (captions[tag]) {
+        const caption = el.querySelector(captions[tag]);
+        if (caption) return collapse(caption.textContent);
+    }
+    if (NAME_FROM_CONTENT.has(role)) {
+        const text = collapse(el.textContent);
+        if (text) return text;
+    }
+    return collapse(el.getAttribute('title') || el.getAttribute('placeholder'));
+}
+
+function valueOf(el, role) {
+    const tag = el.tagName.toLowerCase();
+    const valueText = el.getAttribute('aria-valuetext') || el.getAttribute('aria-valuenow');
+    if (valueText !== null) return valueText;
+    if (tag === 'select') {
+        return Array.from(el.selectedOptions).map(function(o) { return o.textContent.trim(); }).join(', ');
+    }
+    if (tag === 'textarea' || tag === 'progress' || tag === 'meter') return String(el.value);
+    if (tag === 'input' && role !== 'checkbox' && role !== 'radio' && role !== 'button') {
+        return el.type === 'password' ? '•'.repeat(el.value.length) : el.value;
+    }
+    return null;
+}
+
+function statesOf(el, role) {
+    const states = {};
+    const aria = function(name) { return el.getAttribute('aria-' + name); };
+    // Tri-state attributes keep "mixed" as a string
+    const triState = function(value) { return value === 'true' ? true : value === 'false' ? false : value; };
+
+    if (role === 'checkbox' || role === 'radio' || role === 'switch' || aria('checked') !== null) {
+        states.checked = aria('checked') !== null ? triState(aria('checked')) : !!el.checked;
+    }
+    if (el.disabled || aria('disabled') === 'true') states.disabled = true;
+    if (aria('expanded') !== null) states.expanded = aria('expanded') === 'true';
+    else if (el.tagName === 'DETAILS') states.expanded = el.open;
+    if (aria('selected') !== null) states.selected = aria('selected') === 'true';
+    else if (el.tagName === 'OPTION') states.selected = el.selected;
+    if (aria('pressed') !== null) states.pressed = triState(aria('pressed'));
+    if (el.required || aria('required') === 'true') states.required = true;
+    if (el.readOnly || aria('readonly') === 'true') states.readonly = true;
+    if (aria('invalid') === 'true') states.invalid = true;
+    if (document.activeElement === el) states.focused = true;
+    return states;
+}
+
+function describe(el, role) {
+    const node = { role: role, tag: el.tagName.toLowerCase() };
+    const name = nameOf(el, role);
+    if (name) node.name = name;
+    const value = valueOf(el, role);
+    if (value !== null && value !== '') node.value = value;
+    if (role === 'heading') {
+        node.level = Number(el.getAttribute('aria-level')) || Number(el.tagName.slice(1)) || 2;
+    }
+    const states = statesOf(el, role);
+    if (Object.keys(states).length > 0) node.states = states;
+    return node;
+}
+
+// Appends the accessible nodes under `el` to `out`, `depth` levels below the root
+function collect(el, depth, out) {
+    for (const child of el.childNodes) {
+        if (nodeCount >= maxNodes) {
+            truncated = true;
+            return;
+        }
+        if (child.nodeType === Node.TEXT_NODE) {
+            const text = collapse(child.textContent);
+            if (text) {
+                nodeCount++;
+                out.push({ role: 'text', name: text });
+            }
+        } else if (child.nodeType === Node.ELEMENT_NODE) {
+            visit(child, depth, out);
+        }
+    }
+}
+
+function visit(el, depth, out) {
+    const tag = el.tagName.toLowerCase();
+    if (tag === 'script' || tag === 'style' || tag === 'template' || tag === 'noscript' || isHidden(el)) {
+        return;
+    }
+    const role = roleOf(el);
+    if (role === null) {
+        collect(el, depth, out);
+        return;
+    }
+
+    nodeCount++;
+    const node = describe(el, role);
+    out.push(node);
+    // A name taken from the content already says what the text children would
+    const leaf = node.name !== undefined && NAME_FROM_CONTENT.has(role) && el.children.length === 0;
+    if (leaf || el.childNodes.length === 0) return;
+    if (depth >= maxDepth) {
+        node.truncated = true;
+        truncated = true;
+        return;
+    }
+    const children = [];
+    collect(el, depth + 1, children);
+    if (children.length > 0) node.children = children;
+}
+
+const tree = [];
+let rootNode;
+if (root === document.body) {
+    collect(root, 0, tree);
+    rootNode = { role: 'document', name: collapse(document.title), children: tree };
+} else {
+    visit(root, 0, tree);
+    rootNode = tree.length === 1 && roleOf(root) !== null
+        ? tree[0]
+        : { role: 'generic', tag: root.tagName.toLowerCase(), children: tree };
+}
+
+({ tree: rootNode, node_count: nodeCount, truncated: truncated })
+"#;
+
+/// Returns the accessibility tree of the page, or of the subtree matching a selector.
+///
+/// Each node has the role, accessible name, value and state that assistive technology
+/// would see, so tests can find controls by what they are rather than by markup.
+///
+/// # Approximation
+///
+/// The tree is computed from the DOM rather than read from the platform accessibility
+/// API, which Tauri doesn't expose. It follows the ARIA roles and the main naming
+/// rules (`aria-labelledby`, `aria-label`, `<label>`, `alt`, captions, content,
+/// `title`), but it's not a full implementation of the specifications:
+///
+/// - Implicit roles cover common elements only; custom elements and less common ones
+///   are treated as generic containers unless they have a `role`.
+/// - Names from content use the text of the whole subtree, including text that's
+///   visually hidden with CSS other than `display` or `visibility`.
+/// - CSS generated content (`::before`, `::after`) and shadow DOM aren't included.
+/// - Browser heuristics, like presentational tables, aren't applied.
+///
+/// # Arguments
+///
+/// * `window` - The window to inspect
+/// * `selector` - CSS selector of the subtree root (defaults to `document.body`)
+/// * `max_depth` - How many levels below the root to include (defaults to 30). Nodes
+///   cut off at the limit have `truncated: true`.
+/// * `max_nodes` - Maximum number of nodes to include (defaults to 2000, at most
+///   20000)
+/// * `window_label` - Label of the window to target (defaults to the calling window)
+/// * `executor` - Script executor state
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the tree was built
+///   - `data`: `{ tree, node_count, truncated }` where each node is `{ role, tag,
+///     name?, value?, level?, states?, children? }` and text is `{ role: "text", name }`
+///   - `error`: Error message (if nothing matched the selector)
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const result = await invoke('plugin:mcp-bridge|get_accessibility_tree', {
+///   selector: 'form',
+///   maxDepth: 5
+/// });
+/// console.log(result.data.tree.children);
+/// ```
+#[command]
+pub async fn get_accessibility_tree<R: Runtime>(
+    window: WebviewWindow<R>,
+    selector: Option<String>,
+    max_depth: Option<u32>,
+    max_nodes: Option<u32>,
+    window_label: Option<String>,
+    executor: State<'_, ScriptExecutor>,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    let selector_js = serde_json::to_string(&selector).map_err(|e| e.to_string())?;
+    let max_depth = max_depth.unwrap_or(DEFAULT_ACCESSIBILITY_MAX_DEPTH);
+    let max_nodes = max_nodes
+        .unwrap_or(DEFAULT_ACCESSIBILITY_MAX_NODES)
+        .min(MAX_ACCESSIBILITY_NODES);
+    let script = format!(
+        "const selector = {selector_js};\nconst maxDepth = {max_depth};\nconst maxNodes = {max_nodes};\n{ACCESSIBILITY_TREE_SCRIPT}"
+    );
+
+    run_script(window, script, None, None, None, None, None, executor).await
+}
@@ -0,0 +1,156 @@
+//! Turning CSS animations and transitions off for stable screenshots and timing.
+
+use super::execute_js::run_script;
+use super::list_windows::target_window;
+use super::script_executor::ScriptExecutor;
+use serde_json::Value;
+use tauri::{command, Runtime, State, WebviewWindow};
+
+/// Adds the stylesheet that disables animations, unless the page already has it, and
+/// evaluates to `{ disabled, changed }`.
+///
+/// Also used as an init script, which runs before the document has a root element, so
+/// the stylesheet is added as soon as one appears. It goes directly in `<html>` rather
+/// than `<head>`, which doesn't exist that early either.
+pub(crate) const DISABLE_ANIMATIONS_SCRIPT: &str = r#"
+(function() {
+    const id = '__mcp_disable_animations__';
+    if (document.getElementById(id)) {
+        return { disabled: true, changed: false };
+    }
+    const style = document.createElement('style');
+    style.id = id;
+    style.textContent = '*, *::before, *::after {'
+        + ' animation-duration: 0s !important;'
+        + ' animation-delay: 0s !important;'
+        + ' animation-iteration-count: 1 !important;'
+        + ' transition-duration: 0s !important;'
+        + ' transition-delay: 0s !important;'
+        + ' scroll-behavior: auto !important;'
+        + ' }';
+    if (document.documentElement) {
+        document.documentElement.appendChild(style);
+    } else {
+        const observer = new MutationObserver(function() {
+            if (document.documentElement) {
+                observer.disconnect();
+                document.documentElement.appendChild(style);
+            }
+        });
+        observer.observe(document, { childList: true });
+    }
+    return { disabled: true, changed: true };
+})()
+"#;
+
+/// Removes the stylesheet added by [`DISABLE_ANIMATIONS_SCRIPT`], evaluating to
+/// `{ disabled, changed }`.
+const ENABLE_ANIMATIONS_SCRIPT: &str = r#"
+(function() {
+    const style = document.getElementById('__mcp_disable_animations__');
+    if (style) {
+        style.remove();
+    }
+    return { disabled: false, changed: !!style };
+})()
+"#;
+
+/// Disables CSS animations, transitions and smooth scrolling in the page.
+///
+/// Adds a stylesheet that sets animation and transition durations and delays to zero,
+/// so running ones jump to their end state, and scrolling happens at once. Animations
+/// started from script through the Web Animations API aren't affected. The stylesheet
+/// is lost when the page reloads or navigates, unless the plugin is configured with
+/// `disable_animations`.
+///
+/// # Arguments
+///
+/// * `window` - The window to disable animations in
+/// * `window_label` - Label of the window to target (defaults to the calling window)
+/// * `executor` - Script executor state
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether animations were disabled
+///   - `data`: `{ disabled: true, changed }`, where `changed` is false if they
+///     already were
+///   - `error`: Error message (if failed)
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// await invoke('plugin:mcp-bridge|disable_animations');
+/// ```
+#[command]
+pub async fn disable_animations<R: Runtime>(
+    window: WebviewWindow<R>,
+    window_label: Option<String>,
+    executor: State<'_, ScriptExecutor>,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    run_script(
+        window,
+        DISABLE_ANIMATIONS_SCRIPT.to_string(),
+        None,
+        None,
+        None,
+        None,
+        None,
+        executor,
+    )
+    .await
+}
+
+/// Enables animations again after [`disable_animations`].
+///
+/// # Arguments
+///
+/// * `window` - The window to enable animations in
+/// * `window_label` - Label of the window to target (defaults to the calling window)
+/// * `executor` - Script executor state
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether animations were enabled
+///   - `data`: `{ disabled: false, changed }`, where `changed` is false if they
+///     weren't disabled
+///   - `error`: Error message (if failed)
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// await invoke('plugin:mcp-bridge|enable_animations');
+/// ```
+#[command]
+pub async fn enable_animations<R: Runtime>(
+    window: WebviewWindow<R>,
+    window_label: Option<String>,
+    executor: State<'_, ScriptExecutor>,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    run_script(
+        window,
+        ENABLE_ANIMATIONS_SCRIPT.to_string(),
+        None,
+        None,
+        None,
+        None,
+        None,
+        executor,
+    )
+    .await
+}
@@ -0,0 +1,84 @@
+//! App and bridge metadata for feature detection.
+
+use super::introspect_commands::CommandCatalog;
+use crate::websocket::WEBSOCKET_COMMANDS;
+use serde_json::Value;
+use tauri::{command, AppHandle, Runtime, State};
+
+/// Returns the app's identity and what the bridge supports.
+///
+/// Unlike `get_backend_state`, this only holds metadata that doesn't change while the
+/// app runs, so clients can read it once and cache it. The MCP server exposes it as the
+/// `app://config` resource.
+///
+/// # Arguments
+///
+/// * `app` - The Tauri application handle
+/// * `catalog` - The app commands registered with the plugin
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `app`: `{ name, identifier, version }` from the app's Tauri config
+///   - `plugin`: `{ name, version }` of this plugin, to feature-detect against
+///   - `tauri`: `{ version }` of the Tauri runtime
+///   - `bridge_commands`: Commands the bridge's WebSocket server accepts
+///   - `app_commands`: Names of the app's own commands, if it registered them with
+///     `Builder::with_commands` or `Builder::command`
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const config = await invoke('plugin:mcp-bridge|get_app_config');
+/// if (config.bridge_commands.includes('type_text')) {
+///   // The bridge can send keyboard events
+/// }
+/// ```
+#[command]
+pub async fn get_app_config<R: Runtime>(
+    app: AppHandle<R>,
+    catalog: State<'_, CommandCatalog>,
+) -> Result<Value, String> {
+    let config = app.config();
+    let app_commands: Vec<&str> = catalog.0.iter().map(|info| info.name.as_str()).collect();
+
+    Ok(serde_json::json!({
+        "app": {
+            "name": config.product_name.clone().unwrap_or_else(|| "Unknown".to_string()),
+            "identifier": config.identifier.clone(),
+            "version": config.version.clone().unwrap_or_else(|| "0.0.0".to_string()),
+        },
+        "plugin": {
+            "name": env!("CARGO_PKG_NAME"),
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+        "tauri": {
+            "version": tauri::VERSION,
+        },
+        "bridge_commands": WEBSOCKET_COMMANDS,
+        "app_commands": app_commands,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bridge_commands_match_websocket_dispatch() {
+        let source = include_str!("../websocket.rs");
+        let mut dispatched: Vec<&str> = source
+            .split("cmd_name == \"")
+            .skip(1)
+            .filter_map(|rest| rest.split('"').next())
+            .collect();
+        dispatched.sort_unstable();
+        dispatched.dedup();
+
+        let mut listed = WEBSOCKET_COMMANDS.to_vec();
+        listed.sort_unstable();
+        assert_eq!(listed, dispatched);
+    }
+}
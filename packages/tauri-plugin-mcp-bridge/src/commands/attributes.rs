@@ -0,0 +1,309 @@
+//! Reading and changing single element attributes.
+
+use super::execute_js::run_script;
+use super::handles::ElementTarget;
+use super::list_windows::target_window;
+use super::script_executor::ScriptExecutor;
+use serde_json::Value;
+use tauri::{command, Runtime, State, WebviewWindow};
+
+/// Finds the element matching `selector`, failing when there is none.
+///
+/// Expects `selector` to be defined before it.
+const FIND_ELEMENT_JS: &str = r#"
+const el = document.querySelector(selector);
+if (el === null) {
+    throw new Error('No element matches selector ' + JSON.stringify(selector));
+}
+"#;
+
+/// Reads attribute `name` of `el`.
+///
+/// Expects `el` and `name` to be defined before it.
+const READ_ATTRIBUTE_JS: &str = r#"
+({ name: name, value: el.getAttribute(name), present: el.hasAttribute(name) })
+"#;
+
+/// Sets attribute `name` of `el` to `value`, or removes it when `value` is null.
+///
+/// Expects `el`, `name` and `value` to be defined before it.
+const WRITE_ATTRIBUTE_JS: &str = r#"
+if (value === null) {
+    el.removeAttribute(name);
+} else {
+    el.setAttribute(name, value);
+}
+"#;
+
+/// Checks that `name` can be used as an HTML attribute name.
+///
+/// `setAttribute` throws a terse `InvalidCharacterError` for these, so they're caught
+/// here with a clearer message.
+fn validate_attribute_name(name: &str) -> Result<(), String> {
+    if name.is_empty()
+        || name.chars().any(|c| {
+            c.is_whitespace() || c.is_control() || matches!(c, '"' | '\'' | '>' | '/' | '=')
+        })
+    {
+        return Err(format!("Invalid attribute name '{name}'"));
+    }
+    Ok(())
+}
+
+/// Works out the string to set an attribute to, or `None` to remove it.
+///
+/// `true` and `false` are for boolean attributes like `disabled` or `hidden`: `true`
+/// sets the attribute to an empty string (present) and `false` removes it. Numbers are
+/// set as their decimal text.
+fn attribute_value(value: Option<Value>, remove: bool) -> Result<Option<String>, String> {
+    match (value, remove) {
+        (Some(value), true) if !value.is_null() => {
+            Err("Pass either value or remove, not both".to_string())
+        }
+        (_, true) => Ok(None),
+        (None | Some(Value::Null), false) => {
+            Err("Missing value argument (pass remove: true to remove the attribute)".to_string())
+        }
+        (Some(Value::String(text)), false) => Ok(Some(text)),
+        (Some(Value::Bool(true)), false) => Ok(Some(String::new())),
+        (Some(Value::Bool(false)), false) => Ok(None),
+        (Some(Value::Number(number)), false) => Ok(Some(number.to_string())),
+        (Some(_), false) => Err("Attribute value must be a string, number or boolean".to_string()),
+    }
+}
+
+/// Reads one attribute of the element matching `selector`.
+///
+/// `present` tells a boolean attribute like `disabled` (present with an empty value)
+/// apart from a missing one (`value: null`). `data-*` attributes are read by their full
+/// attribute name, like `data-user-id`, not the `dataset` key.
+///
+/// # Arguments
+///
+/// * `window` - The window containing the element
+/// * `selector` - CSS selector of the element
+/// * `handle` - Handle of the element from an earlier `execute_js` result, in place of
+///   `selector`
+/// * `by` - How `selector` locates the element: `"css"` (the default), `"xpath"`,
+///   `"text"`, `"id"` or `"name"`
+/// * `name` - Attribute name, like `href`, `aria-expanded` or `data-state`
+/// * `window_label` - Label of the window to target (defaults to the calling window)
+/// * `executor` - Script executor state
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the attribute was read
+///   - `data`: `{ name, value, present }` where `value` is `null` if the attribute is
+///     missing
+///   - `error`: Error message (if nothing matched or the name is invalid)
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const expanded = await invoke('plugin:mcp-bridge|get_attribute', {
+///   selector: '#menu-button',
+///   name: 'aria-expanded'
+/// });
+/// console.log(expanded.data.value); // "true"
+/// ```
+#[command]
+pub async fn get_attribute<R: Runtime>(
+    window: WebviewWindow<R>,
+    selector: Option<String>,
+    handle: Option<String>,
+    by: Option<String>,
+    name: String,
+    window_label: Option<String>,
+    executor: State<'_, ScriptExecutor>,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    let target = match ElementTarget::new(selector, handle, by) {
+        Ok(target) => target,
+        Err(error) => {
+            return Ok(serde_json::json!({
+                "success": false,
+                "error": error
+            }))
+        }
+    };
+
+    if let Err(error) = validate_attribute_name(&name) {
+        return Ok(serde_json::json!({
+            "success": false,
+            "error": error
+        }));
+    }
+
+    let selector_js = serde_json::to_string(&target.selector()).map_err(|e| e.to_string())?;
+    let name_js = serde_json::to_string(&name).map_err(|e| e.to_string())?;
+    let script = format!(
+        "const selector = {selector_js};\nconst name = {name_js};\n{FIND_ELEMENT_JS}{READ_ATTRIBUTE_JS}"
+    );
+
+    run_script(
+        window,
+        target.wrap(script),
+        None,
+        None,
+        None,
+        None,
+        None,
+        executor,
+    )
+    .await
+}
+
+/// Sets or removes one attribute of the element matching `selector`.
+///
+/// For boolean attributes like `disabled`, `hidden` or `checked`, pass `true` to add the
+/// attribute and `false` to remove it. `data-*` attributes are set by their full
+/// attribute name, like `data-state`, and show up in the element's `dataset` as usual.
+///
+/// Setting an attribute doesn't always change the live state: the `value` and `checked`
+/// attributes are only a form field's default, which stops applying once the field has
+/// been edited. Use `set_input_value` to change what a field holds.
+///
+/// # Arguments
+///
+/// * `window` - The window containing the element
+/// * `selector` - CSS selector of the element
+/// * `handle` - Handle of the element from an earlier `execute_js` result, in place of
+///   `selector`
+/// * `by` - How `selector` locates the element: `"css"` (the default), `"xpath"`,
+///   `"text"`, `"id"` or `"name"`
+/// * `name` - Attribute name
+/// * `value` - New value: a string, a number, or a boolean for boolean attributes
+/// * `remove` - Remove the attribute instead (defaults to false)
+/// * `window_label` - Label of the window to target (defaults to the calling window)
+/// * `executor` - Script executor state
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the attribute was changed
+///   - `data`: `{ name, value, present }` with the attribute after the change
+///   - `error`: Error message (if nothing matched, or the name or value is invalid)
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// await invoke('plugin:mcp-bridge|set_attribute', {
+///   selector: '#submit',
+///   name: 'disabled',
+///   value: false
+/// });
+/// await invoke('plugin:mcp-bridge|set_attribute', {
+///   selector: '.panel',
+///   name: 'data-state',
+///   value: 'open'
+/// });
+/// ```
+#[command]
+#[allow(clippy::too_many_arguments)]
+pub async fn set_attribute<R: Runtime>(
+    window: WebviewWindow<R>,
+    selector: Option<String>,
+    handle: Option<String>,
+    by: Option<String>,
+    name: String,
+    value: Option<Value>,
+    remove: Option<bool>,
+    window_label: Option<String>,
+    executor: State<'_, ScriptExecutor>,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    let target = match ElementTarget::new(selector, handle, by) {
+        Ok(target) => target,
+        Err(error) => {
+            return Ok(serde_json::json!({
+                "success": false,
+                "error": error
+            }))
+        }
+    };
+
+    let value = match validate_attribute_name(&name)
+        .and_then(|_| attribute_value(value, remove.unwrap_or(false)))
+    {
+        Ok(value) => value,
+        Err(error) => {
+            return Ok(serde_json::json!({
+                "success": false,
+                "error": error
+            }))
+        }
+    };
+
+    let selector_js = serde_json::to_string(&target.selector()).map_err(|e| e.to_string())?;
+    let name_js = serde_json::to_string(&name).map_err(|e| e.to_string())?;
+    let value_js = serde_json::to_string(&value).map_err(|e| e.to_string())?;
+    let script = format!(
+        "const selector = {selector_js};\nconst name = {name_js};\nconst value = {value_js};\n{FIND_ELEMENT_JS}{WRITE_ATTRIBUTE_JS}{READ_ATTRIBUTE_JS}"
+    );
+
+    run_script(
+        window,
+        target.wrap(script),
+        None,
+        None,
+        None,
+        None,
+        None,
+        executor,
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_attribute_names() {
+        assert!(validate_attribute_name("href").is_ok());
+        assert!(validate_attribute_name("data-user-id").is_ok());
+        assert!(validate_attribute_name("aria-expanded").is_ok());
+        assert!(validate_attribute_name("xlink:href").is_ok());
+        assert!(validate_attribute_name("").is_err());
+        assert!(validate_attribute_name("data id").is_err());
+        assert!(validate_attribute_name("a=b").is_err());
+    }
+
+    #[test]
+    fn test_boolean_values_add_or_remove_the_attribute() {
+        assert_eq!(
+            attribute_value(Some(Value::Bool(true)), false),
+            Ok(Some(String::new()))
+        );
+        assert_eq!(attribute_value(Some(Value::Bool(false)), false), Ok(None));
+    }
+
+    #[test]
+    fn test_attribute_values() {
+        assert_eq!(
+            attribute_value(Some(Value::from("open")), false),
+            Ok(Some("open".to_string()))
+        );
+        assert_eq!(
+            attribute_value(Some(Value::from(3)), false),
+            Ok(Some("3".to_string()))
+        );
+        assert_eq!(attribute_value(None, true), Ok(None));
+        assert!(attribute_value(None, false).is_err());
+        assert!(attribute_value(Some(Value::from("x")), true).is_err());
+        assert!(attribute_value(Some(serde_json::json!({ "a": 1 })), false).is_err());
+    }
+}
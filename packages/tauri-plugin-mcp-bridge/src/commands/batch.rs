@@ -0,0 +1,169 @@
+//! Running several scripts in one round trip.
+
+use super::execute_js::{capture_script, run_script};
+use super::list_windows::target_window;
+use super::script_executor::ScriptExecutor;
+use serde_json::Value;
+use tauri::{command, Runtime, State, WebviewWindow};
+use uuid::Uuid;
+
+/// Builds one script body that runs `scripts` in order and evaluates to their results.
+///
+/// Each script sits in its own `try` block inside the shared function body, so `var`
+/// declarations are visible to later scripts while `let` and `const` stay local. The
+/// value of a script's last expression is captured into `{prefix}_value`.
+fn batch_script(prefix: &str, scripts: &[String], stop_on_error: bool) -> String {
+    let mut body = format!(
+        r#"const {prefix}_results = [];
+let {prefix}_value;
+let {prefix}_stopped = false;
+function {prefix}_ok(value) {{
+    return {{
+        success: true,
+        data: value === undefined ? null : value,
+        result_type: value === null ? 'null' : typeof value
+    }};
+}}
+function {prefix}_fail(error) {{
+    const envelope = {{
+        success: false,
+        error: (error && error.message) || String(error),
+        error_kind: 'runtime_error'
+    }};
+    if (error && typeof error.stack === 'string') envelope.error_stack = error.stack;
+    return envelope;
+}}
+"#
+    );
+
+    for (index, script) in scripts.iter().enumerate() {
+        let captured = capture_script(script, &format!("{prefix}_value"));
+        body.push_str(&format!(
+            r#"// Script {index}
+if ({prefix}_stopped) {{
+    {prefix}_results.push({{ success: false, skipped: true, error: 'Skipped after an earlier script failed' }});
+}} else {{
+    try {{
+        {prefix}_value = undefined;
+{captured}
+        {prefix}_results.push({prefix}_ok({prefix}_value));
+    }} catch ({prefix}_error) {{
+        {prefix}_results.push({prefix}_fail({prefix}_error));
+        {prefix}_stopped = {stop_on_error};
+    }}
+}}
+"#
+        ));
+    }
+
+    body.push_str(&format!("{prefix}_results"));
+    body
+}
+
+/// Runs several scripts in the webview with a single execution round trip.
+///
+/// The scripts run in order inside one injected function, and all their results come
+/// back together, which saves a round trip per script on slow connections.
+///
+/// # Shared scope
+///
+/// - `var` declarations and function declarations from one script are visible to the
+///   scripts after it. `let`, `const` and `class` declarations stay local to their
+///   script. Properties set on `window` are shared as usual.
+/// - Each script's result is the value of its last expression statement, like
+///   `execute_js`. A top-level `return` is not allowed, since it would end the whole
+///   batch.
+/// - `await` works in every script, and the next script starts once it settles.
+///
+/// # Arguments
+///
+/// * `window` - The window to run the scripts in
+/// * `scripts` - JavaScript snippets to run, in order
+/// * `stop_on_error` - Skip the remaining scripts after one throws (defaults to false)
+/// * `timeout_ms` - Maximum time to wait for the whole batch in milliseconds (defaults
+///   to 5000)
+/// * `window_label` - Label of the window to target (defaults to the calling window)
+/// * `state` - Script executor state
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the batch ran (individual scripts may still have failed)
+///   - `data`: One envelope per script, in order: `{ success, data, result_type }` or
+///     `{ success: false, error, error_stack, error_kind }`. Scripts skipped by `stop_on_error`
+///     have `skipped: true`.
+///   - `error`: Error message (if the batch as a whole failed or timed out)
+///   - `error_kind`: `"blocked"` if a script was rejected by the app's script rules, in
+///     which case none of them ran, `index` is the rejected script and `rule` the rule,
+///     as in `execute_js`
+///   - `exec_id`: The ID of the execution
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const result = await invoke('plugin:mcp-bridge|batch_execute', {
+///   scripts: ['var items = document.querySelectorAll("li")', 'items.length'],
+///   stopOnError: true
+/// });
+/// console.log(result.data[1].data); // Number of list items
+/// ```
+#[command]
+pub async fn batch_execute<R: Runtime>(
+    window: WebviewWindow<R>,
+    scripts: Vec<String>,
+    stop_on_error: Option<bool>,
+    timeout_ms: Option<u64>,
+    window_label: Option<String>,
+    state: State<'_, ScriptExecutor>,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    // Every script is checked on its own, since the combined batch is run unchecked
+    for (index, script) in scripts.iter().enumerate() {
+        if let Err(blocked) = state.script_policy.check(script) {
+            let mut result = blocked.to_result(&Uuid::new_v4().to_string());
+            result["index"] = serde_json::json!(index);
+            return Ok(result);
+        }
+    }
+
+    if scripts.is_empty() {
+        return Ok(serde_json::json!({
+            "success": true,
+            "data": []
+        }));
+    }
+
+    let prefix = format!("__mcp_batch_{}", &Uuid::new_v4().simple().to_string()[..12]);
+    let script = batch_script(&prefix, &scripts, stop_on_error.unwrap_or(false));
+
+    let mut result = run_script(window, script, timeout_ms, None, None, None, None, state).await?;
+    if result.get("success").and_then(|v| v.as_bool()) != Some(true) {
+        return Ok(result);
+    }
+
+    // Anything but one envelope per script means a script returned early
+    let complete = result
+        .get("data")
+        .and_then(|data| data.as_array())
+        .is_some_and(|results| results.len() == scripts.len());
+    if !complete {
+        result["success"] = Value::Bool(false);
+        result["data"] = Value::Null;
+        result["error"] = Value::from(
+            "A script in the batch used a top-level `return`, which ends the whole batch. \
+             End the script with an expression to produce its result instead.",
+        );
+    }
+    // The batch's own result type is always "object", so it says nothing useful
+    if let Some(result) = result.as_object_mut() {
+        result.remove("result_type");
+    }
+
+    Ok(result)
+}
@@ -0,0 +1,1328 @@
+//! Registry of the bridge's own WebSocket commands.
+//!
+//! Each command the WebSocket server dispatches is described here with its arguments,
+//! so clients can discover the bridge's surface at runtime with
+//! `list_bridge_commands`. Tests check the registry against the dispatcher, so a
+//! command or argument can't be added without describing it.
+
+use serde_json::{Map, Value};
+use tauri::command;
+
+/// JSON type of a command argument.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArgType {
+    String,
+    Integer,
+    Number,
+    Boolean,
+    Object,
+    Array,
+    /// Any JSON value
+    Any,
+}
+
+impl ArgType {
+    /// The JSON Schema `type` keyword for this type, if it has one.
+    fn schema_type(self) -> Option<&'static str> {
+        match self {
+            ArgType::String => Some("string"),
+            ArgType::Integer => Some("integer"),
+            ArgType::Number => Some("number"),
+            ArgType::Boolean => Some("boolean"),
+            ArgType::Object => Some("object"),
+            ArgType::Array => Some("array"),
+            ArgType::Any => None,
+        }
+    }
+}
+
+/// One argument of a bridge command, as sent in the request's `args` (camelCase).
+#[derive(Debug, Clone, Copy)]
+pub struct BridgeArg {
+    pub name: &'static str,
+    pub arg_type: ArgType,
+    pub description: &'static str,
+    pub required: bool,
+    /// The only values accepted, if limited
+    pub values: &'static [&'static str],
+}
+
+impl BridgeArg {
+    /// Marks the argument as required.
+    const fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    /// Limits the argument to the given values.
+    const fn one_of(mut self, values: &'static [&'static str]) -> Self {
+        self.values = values;
+        self
+    }
+}
+
+/// Describes an optional argument.
+const fn arg(name: &'static str, arg_type: ArgType, description: &'static str) -> BridgeArg {
+    BridgeArg {
+        name,
+        arg_type,
+        description,
+        required: false,
+        values: &[],
+    }
+}
+
+/// One command of the bridge's WebSocket server.
+#[derive(Debug, Clone, Copy)]
+pub struct BridgeCommand {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub args: &'static [BridgeArg],
+}
+
+impl BridgeCommand {
+    /// JSON Schema of the command's `args` object.
+    pub fn args_schema(&self) -> Value {
+        let mut properties = Map::new();
+        for arg in self.args {
+            let mut schema = Map::new();
+            if let Some(schema_type) = arg.arg_type.schema_type() {
+                schema.insert("type".to_string(), Value::from(schema_type));
+            }
+            if !arg.values.is_empty() {
+                schema.insert("enum".to_string(), Value::from(arg.values.to_vec()));
+            }
+            schema.insert("description".to_string(), Value::from(arg.description));
+            properties.insert(arg.name.to_string(), Value::Object(schema));
+        }
+        let required: Vec<&str> = self
+            .args
+            .iter()
+            .filter(|arg| arg.required)
+            .map(|arg| arg.name)
+            .collect();
+
+        serde_json::json!({
+            "type": "object",
+            "properties": properties,
+            "required": required
+        })
+    }
+}
+
+const WINDOW_LABEL: BridgeArg = arg(
+    "windowLabel",
+    ArgType::String,
+    "Label of the window to target (defaults to \"main\")",
+);
+const TIMEOUT_MS: BridgeArg = arg(
+    "timeoutMs",
+    ArgType::Integer,
+    "How long to wait in milliseconds (defaults to 5000)",
+);
+const SELECTOR: BridgeArg = arg("selector", ArgType::String, "CSS selector of the element");
+const HANDLE: BridgeArg = arg(
+    "handle",
+    ArgType::String,
+    "Handle of the element from an earlier execute_js result, in place of selector",
+);
+const BY: BridgeArg = arg(
+    "by",
+    ArgType::String,
+    "How selector locates the element (defaults to \"css\")",
+)
+.one_of(&["css", "xpath", "text", "id", "name"]);
+const NTH: BridgeArg = arg(
+    "nth",
+    ArgType::Integer,
+    "Which match to use when the selector matches several, counting from 0",
+);
+const STRICT: BridgeArg = arg(
+    "strict",
+    ArgType::Boolean,
+    "Whether several matches without nth are an error (defaults to true)",
+);
+const SCROLL_CONTAINER: BridgeArg = arg(
+    "selector",
+    ArgType::String,
+    "Selector of the scrollable element (defaults to the document)",
+);
+const FRAME_SELECTOR: BridgeArg = arg(
+    "frameSelector",
+    ArgType::String,
+    "CSS selector of a same-origin <iframe> to work inside (defaults to the page)",
+);
+const WAIT_UNTIL: BridgeArg = arg(
+    "waitUntil",
+    ArgType::String,
+    "Page load stage to wait for (defaults to \"load\")",
+)
+.one_of(&["load", "domcontentloaded", "networkidle"]);
+const SINCE: BridgeArg = arg(
+    "since",
+    ArgType::Integer,
+    "Only return entries after this timestamp, in milliseconds since the epoch",
+);
+const SUBSCRIPTION_ID: BridgeArg = arg(
+    "subscriptionId",
+    ArgType::String,
+    "ID returned when subscribing",
+)
+.required();
+const STORAGE: BridgeArg = arg(
+    "storage",
+    ArgType::String,
+    "Which storage to use (defaults to \"local\")",
+)
+.one_of(&["local", "session"]);
+
+/// Every command the bridge's WebSocket server accepts.
+pub const BRIDGE_COMMANDS: &[BridgeCommand] = &[
+    BridgeCommand {
+        name: "invoke_tauri",
+        description: "Calls one of the plugin's Tauri commands by its full name, like \
+                      plugin:mcp-bridge|get_backend_state",
+        args: &[
+            arg("command", ArgType::String, "Full name of the command").required(),
+            arg("args", ArgType::Object, "Arguments for the command"),
+        ],
+    },
+    BridgeCommand {
+        name: "list_windows",
+        description: "Lists the open webview windows with their labels, titles and URLs",
+        args: &[],
+    },
+    BridgeCommand {
+        name: "get_window_info",
+        description: "Returns a window's size, position, title, focus and visibility",
+        args: &[arg(
+            "windowId",
+            ArgType::String,
+            "Label of the window (defaults to \"main\")",
+        )],
+    },
+    BridgeCommand {
+        name: "execute_js",
+        description: "Runs JavaScript in a webview and returns the value of its last \
+                      expression",
+        args: &[
+            arg("script", ArgType::String, "JavaScript code to run").required(),
+            arg(
+                "args",
+                ArgType::Any,
+                "Value available to the script as $args",
+            ),
+            arg(
+                "context",
+                ArgType::String,
+                "Name of a context whose state is shared with other scripts as $context",
+            ),
+            TIMEOUT_MS,
+            arg(
+                "execId",
+                ArgType::String,
+                "Execution ID, to cancel the execution with cancel_execution (defaults to \
+                 the request ID)",
+            ),
+            arg(
+                "isolated",
+                ArgType::Boolean,
+                "Run the script in an isolated realm with pristine globals",
+            ),
+            arg(
+                "decodeBinary",
+                ArgType::Boolean,
+                "Return binary results as byte values instead of base64",
+            ),
+            FRAME_SELECTOR,
+            arg(
+                "resultPath",
+                ArgType::String,
+                "Dotted or JSONPath-style path of the part of the result to return",
+            ),
+            arg(
+                "retry",
+                ArgType::Object,
+                "Run the script again when it fails: { attempts, delay_ms, on }",
+            ),
+            arg(
+                "waitForResult",
+                ArgType::Boolean,
+                "Wait for the script's result (defaults to true)",
+            ),
+            arg(
+                "waitReadyState",
+                ArgType::String,
+                "Wait for the document to reach this ready state before running the script",
+            )
+            .one_of(&["interactive", "complete"]),
+            arg(
+                "captureConsole",
+                ArgType::Boolean,
+                "Return the console lines the script logs as `console` (defaults to false)",
+            ),
+            arg(
+                "encoding",
+                ArgType::String,
+                "How the result is sent from the webview (defaults to json)",
+            )
+            .one_of(&["json", "msgpack"]),
+            WINDOW_LABEL,
+        ],
+    },
+    BridgeCommand {
+        name: "cancel_execution",
+        description: "Cancels a running execute_js call",
+        args: &[
+            arg("execId", ArgType::String, "ID of the execution").required(),
+            WINDOW_LABEL,
+        ],
+    },
+    BridgeCommand {
+        name: "clear_context",
+        description: "Resets a named context of execute_js",
+        args: &[
+            arg("context", ArgType::String, "Name of the context").required(),
+            WINDOW_LABEL,
+        ],
+    },
+    BridgeCommand {
+        name: "capture_native_screenshot",
+        description: "Captures a window's content with the platform's screenshot API",
+        args: &[
+            arg("format", ArgType::String, "Image format (defaults to \"png\")")
+                .one_of(&["png", "jpeg"]),
+            arg("quality", ArgType::Integer, "JPEG quality from 0 to 100"),
+            WINDOW_LABEL,
+        ],
+    },
+    BridgeCommand {
+        name: "take_screenshot",
+        description: "Captures the visible page as an image",
+        args: &[WINDOW_LABEL],
+    },
+    BridgeCommand {
+        name: "wait_for_selector",
+        description: "Waits until an element matching a selector reaches a state",
+        args: &[
+            arg("selector", ArgType::String, "CSS selector of the element").required(),
+            BY,
+            TIMEOUT_MS,
+            arg("state", ArgType::String, "State to wait for (defaults to \"visible\")")
+                .one_of(&["attached", "visible", "hidden"]),
+            FRAME_SELECTOR,
+            WINDOW_LABEL,
+        ],
+    },
+    BridgeCommand {
+        name: "click_element",
+        description: "Clicks an element with a full pointer and mouse event sequence",
+        args: &[
+            SELECTOR,
+            HANDLE,
+            BY,
+            NTH,
+            STRICT,
+            WINDOW_LABEL,
+        ],
+    },
+    BridgeCommand {
+        name: "hover_element",
+        description: "Moves the pointer onto an element, dispatching hover events",
+        args: &[
+            SELECTOR,
+            HANDLE,
+            BY,
+            NTH,
+            STRICT,
+            WINDOW_LABEL,
+        ],
+    },
+    BridgeCommand {
+        name: "focus_element",
+        description: "Focuses an element, dispatching focus events",
+        args: &[
+            SELECTOR,
+            HANDLE,
+            BY,
+            arg(
+                "preventScroll",
+                ArgType::Boolean,
+                "Don't scroll the element into view",
+            ),
+            NTH,
+            STRICT,
+            WINDOW_LABEL,
+        ],
+    },
+    BridgeCommand {
+        name: "blur_element",
+        description: "Removes focus from an element, dispatching blur events",
+        args: &[
+            arg(
+                "selector",
+                ArgType::String,
+                "CSS selector of the element (defaults to the focused element)",
+            ),
+            WINDOW_LABEL,
+        ],
+    },
+    BridgeCommand {
+        name: "resize_window",
+        description: "Resizes a window",
+        args: &[
+            arg("width", ArgType::Integer, "New width").required(),
+            arg("height", ArgType::Integer, "New height").required(),
+            arg(
+                "windowId",
+                ArgType::String,
+                "Label of the window (defaults to \"main\")",
+            ),
+            arg(
+                "logical",
+                ArgType::Boolean,
+                "Sizes are in logical rather than physical pixels (defaults to true)",
+            ),
+        ],
+    },
+    BridgeCommand {
+        name: "register_script",
+        description: "Registers a script that is injected into every page load",
+        args: &[
+            arg("id", ArgType::String, "Unique ID of the script").required(),
+            arg("type", ArgType::String, "Whether content is code or a URL")
+                .one_of(&["inline", "url"])
+                .required(),
+            arg("content", ArgType::String, "JavaScript code or script URL").required(),
+            WINDOW_LABEL,
+        ],
+    },
+    BridgeCommand {
+        name: "remove_script",
+        description: "Removes a registered script",
+        args: &[
+            arg("id", ArgType::String, "ID of the script").required(),
+            WINDOW_LABEL,
+        ],
+    },
+    BridgeCommand {
+        name: "clear_scripts",
+        description: "Removes every registered script",
+        args: &[WINDOW_LABEL],
+    },
+    BridgeCommand {
+        name: "get_scripts",
+        description: "Lists the registered scripts",
+        args: &[],
+    },
+    BridgeCommand {
+        name: "get_app_config",
+        description: "Returns the app's identity, the plugin and Tauri versions, and the \
+                      commands the bridge accepts",
+        args: &[],
+    },
+    BridgeCommand {
+        name: "reload",
+        description: "Reloads the page and waits for it to load",
+        args: &[
+            arg(
+                "ignoreCache",
+                ArgType::Boolean,
+                "Refresh cached resources before reloading (defaults to false)",
+            ),
+            WAIT_UNTIL,
+            TIMEOUT_MS,
+            WINDOW_LABEL,
+        ],
+    },
+    BridgeCommand {
+        name: "get_url",
+        description: "Returns the page's current URL, split into its parts, and the history length",
+        args: &[WINDOW_LABEL],
+    },
+    BridgeCommand {
+        name: "get_page_info",
+        description: "Returns the page's title, URL, language, description, Open Graph tags, \
+                      favicon and ready state",
+        args: &[WINDOW_LABEL],
+    },
+    BridgeCommand {
+        name: "go_back",
+        description: "Goes back one entry in the window's history and waits for the page",
+        args: &[WAIT_UNTIL, TIMEOUT_MS, WINDOW_LABEL],
+    },
+    BridgeCommand {
+        name: "go_forward",
+        description: "Goes forward one entry in the window's history and waits for the page",
+        args: &[WAIT_UNTIL, TIMEOUT_MS, WINDOW_LABEL],
+    },
+    BridgeCommand {
+        name: "disable_animations",
+        description: "Disables CSS animations, transitions and smooth scrolling in the page",
+        args: &[WINDOW_LABEL],
+    },
+    BridgeCommand {
+        name: "enable_animations",
+        description: "Enables animations again after disable_animations",
+        args: &[WINDOW_LABEL],
+    },
+    BridgeCommand {
+        name: "set_input_value",
+        description: "Sets the value of an <input>, <textarea> or <select> so frameworks \
+                      see the change",
+        args: &[
+            SELECTOR,
+            HANDLE,
+            BY,
+            arg("value", ArgType::String, "The value to set").required(),
+            NTH,
+            STRICT,
+            WINDOW_LABEL,
+        ],
+    },
+    BridgeCommand {
+        name: "set_file_input",
+        description: "Chooses files from the app's upload directory in an <input type=\"file\">",
+        args: &[
+            SELECTOR,
+            HANDLE,
+            BY,
+            arg(
+                "paths",
+                ArgType::Array,
+                "Paths of the files, relative to the upload directory",
+            )
+            .required(),
+            WINDOW_LABEL,
+        ],
+    },
+    BridgeCommand {
+        name: "get_dom_snapshot",
+        description: "Returns a structured snapshot of the DOM",
+        args: &[
+            arg(
+                "selector",
+                ArgType::String,
+                "CSS selector of the subtree root (defaults to the whole document)",
+            ),
+            arg(
+                "maxDepth",
+                ArgType::Integer,
+                "How many levels below the root to include (unlimited by default)",
+            ),
+            arg(
+                "includeScripts",
+                ArgType::Boolean,
+                "Keep the contents of <script> elements (stripped by default)",
+            ),
+            WINDOW_LABEL,
+        ],
+    },
+    BridgeCommand {
+        name: "diff_snapshots",
+        description: "Lists the nodes added, removed and changed between two DOM snapshots",
+        args: &[
+            arg(
+                "before",
+                ArgType::String,
+                "snapshot_id of the earlier get_dom_snapshot result",
+            )
+            .required(),
+            arg(
+                "after",
+                ArgType::String,
+                "snapshot_id of the later get_dom_snapshot result",
+            )
+            .required(),
+        ],
+    },
+    BridgeCommand {
+        name: "navigate",
+        description: "Loads a URL in a window and waits for it to load",
+        args: &[
+            arg("url", ArgType::String, "URL to load").required(),
+            WAIT_UNTIL,
+            TIMEOUT_MS,
+            WINDOW_LABEL,
+        ],
+    },
+    BridgeCommand {
+        name: "get_server_address",
+        description: "Returns the address the bridge's WebSocket server listens on",
+        args: &[],
+    },
+    BridgeCommand {
+        name: "get_console_logs",
+        description: "Returns captured console messages",
+        args: &[
+            arg("level", ArgType::String, "Only return entries of this level")
+                .one_of(&["log", "debug", "info", "warn", "error"]),
+            SINCE,
+            WINDOW_LABEL,
+        ],
+    },
+    BridgeCommand {
+        name: "clear_console_logs",
+        description: "Clears captured console messages",
+        args: &[WINDOW_LABEL],
+    },
+    BridgeCommand {
+        name: "get_page_errors",
+        description: "Returns uncaught errors and unhandled promise rejections",
+        args: &[SINCE, WINDOW_LABEL],
+    },
+    BridgeCommand {
+        name: "query_elements",
+        description: "Lists the elements matching a selector with their text, attributes \
+                      and position",
+        args: &[
+            arg("selector", ArgType::String, "CSS selector to match").required(),
+            BY,
+            arg(
+                "limit",
+                ArgType::Integer,
+                "Maximum number of elements to return (defaults to 100)",
+            ),
+            FRAME_SELECTOR,
+            WINDOW_LABEL,
+        ],
+    },
+    BridgeCommand {
+        name: "get_bounding_rects",
+        description: "Returns the bounding rectangles of the elements matching several \
+                      selectors, measured at the same moment",
+        args: &[
+            arg("selectors", ArgType::Array, "CSS selectors of the elements").required(),
+            arg(
+                "limit",
+                ArgType::Integer,
+                "Maximum number of elements to measure per selector (defaults to 100)",
+            ),
+            FRAME_SELECTOR,
+            WINDOW_LABEL,
+        ],
+    },
+    BridgeCommand {
+        name: "element_state",
+        description: "Reports whether an element exists, is visible, enabled, focused and \
+                      in the viewport, and how many elements match",
+        args: &[
+            SELECTOR,
+            HANDLE,
+            BY,
+            FRAME_SELECTOR,
+            WINDOW_LABEL,
+        ],
+    },
+    BridgeCommand {
+        name: "get_selection",
+        description: "Returns the selected text, where the selection starts and ends, and \
+                      its bounding box",
+        args: &[WINDOW_LABEL],
+    },
+    BridgeCommand {
+        name: "select_text",
+        description: "Selects text inside an element or text field by character offsets",
+        args: &[
+            arg(
+                "selector",
+                ArgType::String,
+                "CSS selector of the element to select text in",
+            )
+            .required(),
+            arg(
+                "start",
+                ArgType::Integer,
+                "Offset of the first selected character (defaults to 0)",
+            ),
+            arg(
+                "end",
+                ArgType::Integer,
+                "Offset just past the last selected character (defaults to the end)",
+            ),
+            WINDOW_LABEL,
+        ],
+    },
+    BridgeCommand {
+        name: "get_title",
+        description: "Returns the document title and the window title",
+        args: &[WINDOW_LABEL],
+    },
+    BridgeCommand {
+        name: "set_title",
+        description: "Sets the document title, and optionally the window title too",
+        args: &[
+            arg("title", ArgType::String, "The new title").required(),
+            arg(
+                "syncWindow",
+                ArgType::Boolean,
+                "Also set the native window title (defaults to false)",
+            ),
+            WINDOW_LABEL,
+        ],
+    },
+    BridgeCommand {
+        name: "read_clipboard",
+        description: "Reads the clipboard's text, through the Tauri clipboard plugin if the \
+                      app allows it, or the web Clipboard API",
+        args: &[WINDOW_LABEL],
+    },
+    BridgeCommand {
+        name: "write_clipboard",
+        description: "Puts text on the clipboard, through the Tauri clipboard plugin if the \
+                      app allows it, or the web Clipboard API",
+        args: &[
+            arg("text", ArgType::String, "Text to put on the clipboard").required(),
+            arg(
+                "mimeType",
+                ArgType::String,
+                "MIME type of the content (defaults to text/plain)",
+            )
+            .one_of(&["text/plain"]),
+            WINDOW_LABEL,
+        ],
+    },
+    BridgeCommand {
+        name: "get_local_storage",
+        description: "Reads localStorage or sessionStorage, one key or all of them",
+        args: &[
+            arg("key", ArgType::String, "Key to read (defaults to every key)"),
+            STORAGE,
+            arg(
+                "parseJson",
+                ArgType::Boolean,
+                "Parse values that hold JSON (defaults to false)",
+            ),
+            WINDOW_LABEL,
+        ],
+    },
+    BridgeCommand {
+        name: "set_local_storage",
+        description: "Writes or removes a localStorage or sessionStorage key, or clears it",
+        args: &[
+            arg(
+                "key",
+                ArgType::String,
+                "Key to set, or to remove when clear is set",
+            ),
+            arg(
+                "value",
+                ArgType::Any,
+                "Value to store; non-strings are stored as JSON (required unless clear is set)",
+            ),
+            arg(
+                "clear",
+                ArgType::Boolean,
+                "Remove key instead, or every key when key is omitted",
+            ),
+            STORAGE,
+            WINDOW_LABEL,
+        ],
+    },
+    BridgeCommand {
+        name: "get_cookies",
+        description: "Returns the cookies of the window's current page",
+        args: &[WINDOW_LABEL],
+    },
+    BridgeCommand {
+        name: "set_cookie",
+        description: "Sets a cookie for the window's current page",
+        args: &[
+            arg(
+                "cookie",
+                ArgType::Object,
+                "The cookie: { name, value, domain, path, expires, httpOnly, secure, \
+                 sameSite }",
+            )
+            .required(),
+            WINDOW_LABEL,
+        ],
+    },
+    BridgeCommand {
+        name: "batch_execute",
+        description: "Runs several scripts in one round trip and returns each result",
+        args: &[
+            arg("scripts", ArgType::Array, "JavaScript snippets to run in order").required(),
+            arg(
+                "stopOnError",
+                ArgType::Boolean,
+                "Skip the remaining scripts after a failure (defaults to false)",
+            ),
+            TIMEOUT_MS,
+            WINDOW_LABEL,
+        ],
+    },
+    BridgeCommand {
+        name: "validate_js",
+        description: "Checks a script for syntax errors without running it",
+        args: &[
+            arg("script", ArgType::String, "JavaScript code to check").required(),
+            WINDOW_LABEL,
+        ],
+    },
+    BridgeCommand {
+        name: "scroll_into_view",
+        description: "Scrolls an element into view",
+        args: &[
+            SELECTOR,
+            HANDLE,
+            BY,
+            arg(
+                "block",
+                ArgType::String,
+                "Vertical alignment (defaults to \"start\")",
+            )
+            .one_of(&["start", "center", "end", "nearest"]),
+            arg("inline", ArgType::String, "Horizontal alignment")
+                .one_of(&["start", "center", "end", "nearest"]),
+            WINDOW_LABEL,
+        ],
+    },
+    BridgeCommand {
+        name: "scroll_to",
+        description: "Scrolls the page to a position or to an element",
+        args: &[
+            arg("x", ArgType::Number, "Horizontal scroll position in CSS pixels"),
+            arg("y", ArgType::Number, "Vertical scroll position in CSS pixels"),
+            arg(
+                "selector",
+                ArgType::String,
+                "CSS selector of an element to scroll to, instead of x and y",
+            ),
+            WINDOW_LABEL,
+        ],
+    },
+    BridgeCommand {
+        name: "get_scroll",
+        description: "Returns the scroll position of the page or of a scrollable element",
+        args: &[SCROLL_CONTAINER, HANDLE, BY, WINDOW_LABEL],
+    },
+    BridgeCommand {
+        name: "set_scroll",
+        description: "Scrolls the page or a scrollable element and waits for the scroll event",
+        args: &[
+            arg("top", ArgType::Number, "Vertical scroll position in CSS pixels"),
+            arg("left", ArgType::Number, "Horizontal scroll position in CSS pixels"),
+            SCROLL_CONTAINER,
+            HANDLE,
+            BY,
+            WINDOW_LABEL,
+        ],
+    },
+    BridgeCommand {
+        name: "get_page_metrics",
+        description: "Returns page load timing, memory use and DOM size",
+        args: &[WINDOW_LABEL],
+    },
+    BridgeCommand {
+        name: "get_execution_metrics",
+        description: "Returns how many script executions are running and queued",
+        args: &[],
+    },
+    BridgeCommand {
+        name: "get_metrics",
+        description: "Returns call counts, error and timeout counts, p50/p95 durations and \
+                      in-flight calls of each command",
+        args: &[],
+    },
+    BridgeCommand {
+        name: "execute_js_file",
+        description: "Runs a script file from the app's script directory",
+        args: &[
+            arg(
+                "path",
+                ArgType::String,
+                "Path of the script, relative to the script directory",
+            )
+            .required(),
+            arg(
+                "args",
+                ArgType::Object,
+                "Values available to the script as __args",
+            ),
+            TIMEOUT_MS,
+            arg("execId", ArgType::String, "Execution ID, to cancel the execution"),
+            WINDOW_LABEL,
+        ],
+    },
+    BridgeCommand {
+        name: "get_computed_style",
+        description: "Returns the computed CSS of an element",
+        args: &[
+            SELECTOR.required(),
+            arg(
+                "properties",
+                ArgType::Array,
+                "CSS properties to read (defaults to common layout and text properties)",
+            ),
+            WINDOW_LABEL,
+        ],
+    },
+    BridgeCommand {
+        name: "type_text",
+        description: "Types text into an element with real keyboard events",
+        args: &[
+            SELECTOR.required(),
+            arg("text", ArgType::String, "The text to type").required(),
+            arg(
+                "clear",
+                ArgType::Boolean,
+                "Empty the field before typing (defaults to false)",
+            ),
+            arg(
+                "delayMs",
+                ArgType::Integer,
+                "Pause between characters in milliseconds (defaults to 0)",
+            ),
+            NTH,
+            STRICT,
+            WINDOW_LABEL,
+        ],
+    },
+    BridgeCommand {
+        name: "press_key",
+        description: "Presses a key, optionally with modifiers held down",
+        args: &[
+            arg(
+                "key",
+                ArgType::String,
+                "A single character or a key name like Enter or ArrowDown",
+            )
+            .required(),
+            arg(
+                "modifiers",
+                ArgType::Array,
+                "Modifier keys to hold: Control, Shift, Alt or Meta",
+            ),
+            arg(
+                "selector",
+                ArgType::String,
+                "CSS selector of the element to press the key on (defaults to the \
+                 focused element)",
+            ),
+            WINDOW_LABEL,
+        ],
+    },
+    BridgeCommand {
+        name: "introspect_commands",
+        description: "Lists the app's own Tauri commands, as registered with the plugin",
+        args: &[],
+    },
+    BridgeCommand {
+        name: "invoke_command",
+        description: "Calls one of the app's own Tauri commands from the webview",
+        args: &[
+            arg("command", ArgType::String, "Name of the command").required(),
+            arg("args", ArgType::Object, "Arguments for the command"),
+            TIMEOUT_MS,
+            WINDOW_LABEL,
+        ],
+    },
+    BridgeCommand {
+        name: "focus_window",
+        description: "Brings a window to the front and focuses it",
+        args: &[WINDOW_LABEL],
+    },
+    BridgeCommand {
+        name: "show_window",
+        description: "Shows a hidden window",
+        args: &[WINDOW_LABEL],
+    },
+    BridgeCommand {
+        name: "hide_window",
+        description: "Hides a window",
+        args: &[WINDOW_LABEL],
+    },
+    BridgeCommand {
+        name: "take_element_screenshot",
+        description: "Captures one element as an image",
+        args: &[SELECTOR.required(), WINDOW_LABEL],
+    },
+    BridgeCommand {
+        name: "get_accessibility_tree",
+        description: "Returns the accessibility tree of the page or an element",
+        args: &[
+            arg(
+                "selector",
+                ArgType::String,
+                "CSS selector of the subtree root (defaults to the body)",
+            ),
+            arg(
+                "maxDepth",
+                ArgType::Integer,
+                "How many levels below the root to include (defaults to 30)",
+            ),
+            arg(
+                "maxNodes",
+                ArgType::Integer,
+                "Maximum number of nodes to include (defaults to 2000)",
+            ),
+            WINDOW_LABEL,
+        ],
+    },
+    BridgeCommand {
+        name: "get_window_size",
+        description: "Returns a window's inner size in physical and logical pixels",
+        args: &[WINDOW_LABEL],
+    },
+    BridgeCommand {
+        name: "set_window_size",
+        description: "Sets a window's inner size and waits for the page to see it",
+        args: &[
+            arg("width", ArgType::Integer, "New width").required(),
+            arg("height", ArgType::Integer, "New height").required(),
+            arg(
+                "logical",
+                ArgType::Boolean,
+                "Sizes are in logical rather than physical pixels (defaults to true)",
+            ),
+            TIMEOUT_MS,
+            WINDOW_LABEL,
+        ],
+    },
+    BridgeCommand {
+        name: "ping",
+        description: "Checks that the bridge is up and returns its protocol version",
+        args: &[],
+    },
+    BridgeCommand {
+        name: "subscribe_dom_mutations",
+        description: "Pushes batches of DOM changes to this connection",
+        args: &[
+            arg(
+                "selector",
+                ArgType::String,
+                "CSS selector of the element to observe (defaults to the whole document)",
+            ),
+            arg(
+                "batchIntervalMs",
+                ArgType::Integer,
+                "How long to collect changes before sending them, in milliseconds (defaults to 100)",
+            ),
+            arg(
+                "maxRecords",
+                ArgType::Integer,
+                "Maximum number of changes per batch (defaults to 500)",
+            ),
+            WINDOW_LABEL,
+        ],
+    },
+    BridgeCommand {
+        name: "unsubscribe_dom_mutations",
+        description: "Stops a DOM mutation subscription",
+        args: &[SUBSCRIPTION_ID],
+    },
+    BridgeCommand {
+        name: "subscribe_event",
+        description: "Pushes every emission of an app event to this connection",
+        args: &[arg("event", ArgType::String, "Name of the event").required()],
+    },
+    BridgeCommand {
+        name: "unsubscribe_event",
+        description: "Stops an event subscription",
+        args: &[SUBSCRIPTION_ID],
+    },
+    BridgeCommand {
+        name: "get_network_log",
+        description: "Returns captured fetch and XMLHttpRequest requests",
+        args: &[SINCE, WINDOW_LABEL],
+    },
+    BridgeCommand {
+        name: "clear_network_log",
+        description: "Clears captured requests",
+        args: &[WINDOW_LABEL],
+    },
+    BridgeCommand {
+        name: "mock_request",
+        description: "Answers matching fetch requests with a canned response",
+        args: &[
+            arg("url", ArgType::String, "URL pattern to match").required(),
+            arg(
+                "urlMatch",
+                ArgType::String,
+                "How url is matched (defaults to \"glob\")",
+            )
+            .one_of(&["glob", "regex"]),
+            arg(
+                "method",
+                ArgType::String,
+                "Only mock requests with this HTTP method",
+            ),
+            arg(
+                "status",
+                ArgType::Integer,
+                "Response status (defaults to 200)",
+            ),
+            arg("headers", ArgType::Object, "Response headers"),
+            arg(
+                "body",
+                ArgType::Any,
+                "Response body; non-strings are sent as JSON",
+            ),
+        ],
+    },
+    BridgeCommand {
+        name: "unmock_request",
+        description: "Removes a mock rule",
+        args: &[arg("mockId", ArgType::String, "ID returned by mock_request").required()],
+    },
+    BridgeCommand {
+        name: "clear_mocks",
+        description: "Removes every mock rule",
+        args: &[],
+    },
+    BridgeCommand {
+        name: "get_attribute",
+        description: "Reads one attribute of an element",
+        args: &[
+            SELECTOR,
+            HANDLE,
+            BY,
+            arg("name", ArgType::String, "Attribute name").required(),
+            WINDOW_LABEL,
+        ],
+    },
+    BridgeCommand {
+        name: "set_attribute",
+        description: "Sets or removes one attribute of an element",
+        args: &[
+            SELECTOR,
+            HANDLE,
+            BY,
+            arg("name", ArgType::String, "Attribute name").required(),
+            arg(
+                "value",
+                ArgType::Any,
+                "New value: a string, a number, or a boolean for boolean attributes",
+            ),
+            arg("remove", ArgType::Boolean, "Remove the attribute instead"),
+            WINDOW_LABEL,
+        ],
+    },
+    BridgeCommand {
+        name: "select_option",
+        description: "Selects options of a <select> by value, label or index",
+        args: &[
+            arg(
+                "selector",
+                ArgType::String,
+                "CSS selector of the <select> element",
+            )
+            .required(),
+            arg(
+                "value",
+                ArgType::Any,
+                "Option value, or an array of them for multiple selects",
+            ),
+            arg(
+                "label",
+                ArgType::Any,
+                "Option label, or an array of them for multiple selects",
+            ),
+            arg(
+                "index",
+                ArgType::Any,
+                "Option index, or an array of them for multiple selects",
+            ),
+            NTH,
+            STRICT,
+            WINDOW_LABEL,
+        ],
+    },
+    BridgeCommand {
+        name: "drag_and_drop",
+        description: "Drags one element or position onto another",
+        args: &[
+            arg("sourceSelector", ArgType::String, "CSS selector of the element to drag"),
+            arg(
+                "targetSelector",
+                ArgType::String,
+                "CSS selector of the element to drop on",
+            ),
+            arg(
+                "sourcePosition",
+                ArgType::Object,
+                "Viewport position { x, y } to drag from, instead of sourceSelector",
+            ),
+            arg(
+                "targetPosition",
+                ArgType::Object,
+                "Viewport position { x, y } to drop at, instead of targetSelector",
+            ),
+            arg(
+                "kind",
+                ArgType::String,
+                "Drag events to dispatch (defaults to \"html5\")",
+            )
+            .one_of(&["html5", "pointer"]),
+            arg(
+                "sourceNth",
+                ArgType::Integer,
+                "Which match of sourceSelector to drag when it matches several, counting from 0",
+            ),
+            arg(
+                "targetNth",
+                ArgType::Integer,
+                "Which match of targetSelector to drop on when it matches several, counting \
+                 from 0",
+            ),
+            arg(
+                "strict",
+                ArgType::Boolean,
+                "Whether a selector matching several elements without its nth is an error \
+                 (defaults to true)",
+            ),
+            WINDOW_LABEL,
+        ],
+    },
+    BridgeCommand {
+        name: "get_html",
+        description: "Returns the page's current HTML",
+        args: &[
+            arg(
+                "pretty",
+                ArgType::Boolean,
+                "Reformat the HTML with consistent indentation (defaults to false)",
+            ),
+            WINDOW_LABEL,
+        ],
+    },
+    BridgeCommand {
+        name: "wait_for_function",
+        description: "Waits until a JavaScript expression evaluates to a truthy value",
+        args: &[
+            arg(
+                "predicate",
+                ArgType::String,
+                "JavaScript expression to wait for",
+            )
+            .required(),
+            TIMEOUT_MS,
+            arg(
+                "pollIntervalMs",
+                ArgType::Integer,
+                "Minimum time between evaluations (defaults to every animation frame)",
+            ),
+            WINDOW_LABEL,
+        ],
+    },
+    BridgeCommand {
+        name: "wait_for_idle",
+        description: "Waits until no request is in flight and the DOM stays unchanged for a quiet period",
+        args: &[
+            arg(
+                "quietMs",
+                ArgType::Integer,
+                "How long the app must stay quiet to count as idle (defaults to 500)",
+            ),
+            TIMEOUT_MS,
+            WINDOW_LABEL,
+        ],
+    },
+    BridgeCommand {
+        name: "list_bridge_commands",
+        description: "Lists the bridge's own commands with their argument schemas",
+        args: &[],
+    },
+];
+
+/// Lists the commands of the bridge's WebSocket server.
+///
+/// Unlike `introspect_commands`, which lists the app's own commands, this describes the
+/// bridge itself: every command it accepts, what it does, and a JSON Schema of its
+/// `args`, so a client connecting for the first time can find out what it can do.
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `commands`: `{ name, description, args_schema }` for each command
+///   - `count`: Number of commands
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const { commands } = await invoke('plugin:mcp-bridge|list_bridge_commands');
+/// const executeJs = commands.find((c) => c.name === 'execute_js');
+/// console.log(executeJs.args_schema.required); // ["script"]
+/// ```
+#[command]
+pub async fn list_bridge_commands() -> Result<Value, String> {
+    let commands: Vec<Value> = BRIDGE_COMMANDS
+        .iter()
+        .map(|command| {
+            serde_json::json!({
+                "name": command.name,
+                "description": command.description,
+                "args_schema": command.args_schema()
+            })
+        })
+        .collect();
+
+    Ok(serde_json::json!({
+        "count": commands.len(),
+        "commands": commands
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::websocket::WEBSOCKET_COMMANDS;
+
+    #[test]
+    fn test_registry_matches_websocket_commands() {
+        let mut registered: Vec<&str> = BRIDGE_COMMANDS.iter().map(|c| c.name).collect();
+        registered.sort_unstable();
+        let count = registered.len();
+        registered.dedup();
+        assert_eq!(registered.len(), count, "duplicate command in the registry");
+
+        let mut listed = WEBSOCKET_COMMANDS.to_vec();
+        listed.sort_unstable();
+        assert_eq!(registered, listed);
+    }
+
+    #[test]
+    fn test_registered_args_are_read_by_the_dispatcher() {
+        let source = include_str!("../websocket.rs");
+        for command in BRIDGE_COMMANDS {
+            assert!(
+                source.contains(&format!("cmd_name == \"{}\"", command.name)),
+                "{} is never dispatched",
+                command.name
+            );
+            for arg in command.args {
+                assert!(
+                    source.contains(&format!("\"{}\"", arg.name)),
+                    "{} argument {} is never read",
+                    command.name,
+                    arg.name
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_args_schema() {
+        let execute_js = BRIDGE_COMMANDS
+            .iter()
+            .find(|c| c.name == "execute_js")
+            .unwrap();
+        let schema = execute_js.args_schema();
+        assert_eq!(schema["type"], "object");
+        assert_eq!(schema["required"], serde_json::json!(["script"]));
+        assert_eq!(schema["properties"]["timeoutMs"]["type"], "integer");
+        assert!(schema["properties"]["args"].get("type").is_none());
+
+        let wait = BRIDGE_COMMANDS
+            .iter()
+            .find(|c| c.name == "wait_for_selector")
+            .unwrap();
+        assert_eq!(
+            wait.args_schema()["properties"]["state"]["enum"],
+            serde_json::json!(["attached", "visible", "hidden"])
+        );
+    }
+}
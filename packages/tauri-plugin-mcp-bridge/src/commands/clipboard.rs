@@ -0,0 +1,168 @@
+//! Reading and writing the system clipboard.
+
+use super::execute_js::run_script;
+use super::list_windows::target_window;
+use super::script_executor::ScriptExecutor;
+use serde_json::Value;
+use tauri::{command, Runtime, State, WebviewWindow};
+
+/// The only MIME type supported so far.
+const TEXT_PLAIN: &str = "text/plain";
+
+/// Calls a command of the Tauri clipboard plugin, or the web Clipboard API when the
+/// plugin isn't installed or the app doesn't allow the command.
+///
+/// Defines `clipboard(pluginCommand, pluginArgs, web)`, which resolves to
+/// `{ source, value }`. A denied web API call throws with `error_kind:
+/// "permission_denied"`, and a missing one with `error_kind: "clipboard_unavailable"`.
+const CLIPBOARD_JS: &str = r#"
+async function clipboard(pluginCommand, pluginArgs, web) {
+    let pluginError = null;
+    const internals = window.__TAURI_INTERNALS__;
+    if (internals && typeof internals.invoke === 'function') {
+        try {
+            return {
+                source: 'plugin',
+                value: await internals.invoke('plugin:clipboard-manager|' + pluginCommand, pluginArgs)
+            };
+        } catch (error) {
+            pluginError = String(error && error.message || error);
+        }
+    }
+    const pluginNote = pluginError === null ? '' : ' (clipboard plugin: ' + pluginError + ')';
+    if (!navigator.clipboard) {
+        const error = new Error('No clipboard access: the clipboard plugin is not available and '
+            + 'the web Clipboard API is missing' + pluginNote);
+        error.__mcpErrorKind = 'clipboard_unavailable';
+        throw error;
+    }
+    try {
+        return { source: 'web', value: await web(navigator.clipboard) };
+    } catch (error) {
+        if (error && error.name === 'NotAllowedError') {
+            const denied = new Error('Clipboard access was denied: ' + error.message
+                + '. The page may need focus, or the user has to allow it' + pluginNote);
+            denied.__mcpErrorKind = 'permission_denied';
+            throw denied;
+        }
+        throw error;
+    }
+}
+"#;
+
+/// Reads the clipboard's text. Expects `CLIPBOARD_JS` before it.
+const READ_CLIPBOARD_SCRIPT: &str = r#"
+const read = await clipboard('read_text', {}, function(api) { return api.readText(); });
+({ source: read.source, items: [{ mime_type: 'text/plain', data: read.value }] })
+"#;
+
+/// Writes `text` to the clipboard. Expects `text` and `CLIPBOARD_JS` before it.
+const WRITE_CLIPBOARD_SCRIPT: &str = r#"
+const written = await clipboard('write_text', { text: text }, function(api) { return api.writeText(text); });
+({ source: written.source, mime_type: 'text/plain' })
+"#;
+
+/// Reads the system clipboard.
+///
+/// Uses the Tauri clipboard plugin (`tauri-plugin-clipboard-manager`) when the app has
+/// it and allows `clipboard-manager:allow-read-text`, since it works without a prompt
+/// and regardless of focus. Otherwise the page's web Clipboard API is used, which the
+/// webview may only allow while the page has focus, or after asking the user.
+///
+/// Only text is supported for now, but the result is a list of items with a MIME
+/// type, so other types can be added without changing its shape.
+///
+/// # Arguments
+///
+/// * `window` - The window whose webview reads the clipboard
+/// * `window_label` - Label of the window to target (defaults to the calling window)
+/// * `executor` - Script executor state
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the clipboard was read
+///   - `data`: `{ source, items }` where `source` is `"plugin"` or `"web"` and `items`
+///     is `[{ mime_type: "text/plain", data }]`
+///   - `error`: Error message (if failed)
+///   - `error_kind`: `"permission_denied"` when the web API refused access, or
+///     `"clipboard_unavailable"` when there's no way to reach the clipboard
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const clipboard = await invoke('plugin:mcp-bridge|read_clipboard');
+/// console.log(clipboard.data.items[0].data);
+/// ```
+#[command]
+pub async fn read_clipboard<R: Runtime>(
+    window: WebviewWindow<R>,
+    window_label: Option<String>,
+    executor: State<'_, ScriptExecutor>,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    let script = format!("{CLIPBOARD_JS}{READ_CLIPBOARD_SCRIPT}");
+
+    run_script(window, script, None, None, None, None, None, executor).await
+}
+
+/// Writes to the system clipboard.
+///
+/// Like [`read_clipboard`], the Tauri clipboard plugin is used when the app allows
+/// `clipboard-manager:allow-write-text`, and the web Clipboard API otherwise.
+///
+/// # Arguments
+///
+/// * `window` - The window whose webview writes the clipboard
+/// * `text` - The text to put on the clipboard
+/// * `mime_type` - MIME type of the content (defaults to `"text/plain"`, the only one
+///   supported so far)
+/// * `window_label` - Label of the window to target (defaults to the calling window)
+/// * `executor` - Script executor state
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the clipboard was written
+///   - `data`: `{ source, mime_type }` where `source` is `"plugin"` or `"web"`
+///   - `error`: Error message (if failed)
+///   - `error_kind`: Like for [`read_clipboard`]
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// await invoke('plugin:mcp-bridge|write_clipboard', { text: 'Copied text' });
+/// ```
+#[command]
+pub async fn write_clipboard<R: Runtime>(
+    window: WebviewWindow<R>,
+    text: String,
+    mime_type: Option<String>,
+    window_label: Option<String>,
+    executor: State<'_, ScriptExecutor>,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    if let Some(mime_type) = mime_type.filter(|mime_type| mime_type != TEXT_PLAIN) {
+        return Ok(serde_json::json!({
+            "success": false,
+            "error": format!("Unsupported MIME type '{mime_type}': only \"{TEXT_PLAIN}\" is supported")
+        }));
+    }
+
+    let text_js = serde_json::to_string(&text).map_err(|e| e.to_string())?;
+    let script = format!("const text = {text_js};\n{CLIPBOARD_JS}{WRITE_CLIPBOARD_SCRIPT}");
+
+    run_script(window, script, None, None, None, None, None, executor).await
+}
@@ -0,0 +1,233 @@
+//! Call counts and durations of the commands clients send.
+
+use super::script_executor::ScriptExecutor;
+use serde_json::{Map, Value};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{command, State};
+
+/// How many of its latest durations each command keeps for the percentiles.
+const DURATION_SAMPLES: usize = 1000;
+
+/// What's recorded for one command.
+#[derive(Debug, Default)]
+struct Stats {
+    /// Finished calls
+    calls: u64,
+    /// Finished calls that failed, timeouts included
+    errors: u64,
+    /// Failed calls whose error kind is `timeout`
+    timeouts: u64,
+    /// Calls started but not finished yet
+    in_flight: usize,
+    /// Durations of the latest finished calls, oldest first
+    durations: VecDeque<Duration>,
+    /// Longest duration ever recorded
+    max: Duration,
+}
+
+impl Stats {
+    fn to_json(&self) -> Value {
+        let mut sorted: Vec<Duration> = self.durations.iter().copied().collect();
+        sorted.sort_unstable();
+
+        serde_json::json!({
+            "calls": self.calls,
+            "errors": self.errors,
+            "timeouts": self.timeouts,
+            "in_flight": self.in_flight,
+            "p50_ms": percentile(&sorted, 50).map(as_ms),
+            "p95_ms": percentile(&sorted, 95).map(as_ms),
+            "max_ms": (self.calls > 0).then(|| as_ms(self.max))
+        })
+    }
+}
+
+/// Returns the nearest-rank percentile of sorted durations, or `None` if there are none.
+fn percentile(sorted: &[Duration], percent: usize) -> Option<Duration> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = ((sorted.len() * percent + 99) / 100).max(1);
+    Some(sorted[rank - 1])
+}
+
+/// Milliseconds, rounded to microseconds.
+fn as_ms(duration: Duration) -> f64 {
+    (duration.as_secs_f64() * 1_000_000.0).round() / 1000.0
+}
+
+/// Per-command call counts, error counts and durations.
+///
+/// Cheap to clone: clones share the same counters.
+#[derive(Debug, Clone, Default)]
+pub struct CommandMetrics {
+    stats: Arc<Mutex<HashMap<String, Stats>>>,
+}
+
+impl CommandMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the start of a call, and returns the handle to record its end with.
+    ///
+    /// A call whose handle is dropped without [`CommandCall::finish`], for example
+    /// because the client disconnected, stops counting as in flight but isn't
+    /// recorded as a finished call.
+    pub fn start(&self, command: &str) -> CommandCall {
+        self.with_stats(command, |stats| stats.in_flight += 1);
+        CommandCall {
+            metrics: self.clone(),
+            command: command.to_string(),
+            started: Instant::now(),
+        }
+    }
+
+    /// Returns the metrics of every command called so far, and their totals.
+    pub fn snapshot(&self) -> Value {
+        let stats = self.stats.lock().unwrap_or_else(|e| e.into_inner());
+
+        let mut names: Vec<&String> = stats.keys().collect();
+        names.sort();
+        let commands: Map<String, Value> = names
+            .into_iter()
+            .map(|name| (name.clone(), stats[name].to_json()))
+            .collect();
+
+        serde_json::json!({
+            "commands": commands,
+            "totals": {
+                "calls": stats.values().map(|s| s.calls).sum::<u64>(),
+                "errors": stats.values().map(|s| s.errors).sum::<u64>(),
+                "timeouts": stats.values().map(|s| s.timeouts).sum::<u64>(),
+                "in_flight": stats.values().map(|s| s.in_flight).sum::<usize>()
+            }
+        })
+    }
+
+    fn with_stats(&self, command: &str, update: impl FnOnce(&mut Stats)) {
+        // A panic while holding the lock leaves nothing half-updated worth refusing
+        let mut stats = self.stats.lock().unwrap_or_else(|e| e.into_inner());
+        update(stats.entry(command.to_string()).or_default());
+    }
+}
+
+/// A call in progress, from [`CommandMetrics::start`].
+#[derive(Debug)]
+pub struct CommandCall {
+    metrics: CommandMetrics,
+    command: String,
+    started: Instant,
+}
+
+impl CommandCall {
+    /// Records that the call finished with this response.
+    ///
+    /// It failed if the response's `success` is false, and timed out if its
+    /// `errorKind` (or `error_kind`) is `"timeout"` as well.
+    pub fn finish(self, response: &Value) {
+        let failed = response.get("success").and_then(|v| v.as_bool()) == Some(false);
+        let timed_out = failed
+            && response
+                .get("errorKind")
+                .or_else(|| response.get("error_kind"))
+                .and_then(|v| v.as_str())
+                == Some("timeout");
+        let duration = self.started.elapsed();
+
+        self.metrics.with_stats(&self.command, |stats| {
+            stats.calls += 1;
+            stats.errors += u64::from(failed);
+            stats.timeouts += u64::from(timed_out);
+            stats.max = stats.max.max(duration);
+            if stats.durations.len() == DURATION_SAMPLES {
+                stats.durations.pop_front();
+            }
+            stats.durations.push_back(duration);
+        });
+        // Dropping `self` takes the call out of flight
+    }
+}
+
+impl Drop for CommandCall {
+    fn drop(&mut self) {
+        self.metrics.with_stats(&self.command, |stats| {
+            stats.in_flight = stats.in_flight.saturating_sub(1);
+        });
+    }
+}
+
+/// Returns per-command metrics of the requests clients sent over the WebSocket.
+///
+/// Durations are measured in the bridge, from receiving a request to having its
+/// response, so comparing them with the time a script itself took (`script_ms` of
+/// `execute_js`) tells whether the bridge or the app is slow. Percentiles are over
+/// each command's latest 1000 calls.
+///
+/// # Arguments
+///
+/// * `state` - Script executor state
+///
+/// # Returns
+///
+/// * `Ok(Value)` - `{ commands, totals }`, where `commands` maps each command called
+///   so far to `{ calls, errors, timeouts, in_flight, p50_ms, p95_ms, max_ms }` and
+///   `totals` sums the counts over all commands. `calls` counts finished calls,
+///   `errors` includes `timeouts`, and the durations are null until a call finished.
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const metrics = await invoke('plugin:mcp-bridge|get_metrics');
+/// console.log(metrics.commands.execute_js?.p95_ms);
+/// ```
+#[command]
+pub async fn get_metrics(state: State<'_, ScriptExecutor>) -> Result<Value, String> {
+    Ok(state.metrics.snapshot())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentile_uses_nearest_rank() {
+        let sorted: Vec<Duration> = (1..=20).map(Duration::from_millis).collect();
+        assert_eq!(percentile(&sorted, 50), Some(Duration::from_millis(10)));
+        assert_eq!(percentile(&sorted, 95), Some(Duration::from_millis(19)));
+        assert_eq!(percentile(&sorted[..1], 95), Some(Duration::from_millis(1)));
+        assert_eq!(percentile(&[], 50), None);
+    }
+
+    #[test]
+    fn test_counts_calls_errors_and_in_flight() {
+        let metrics = CommandMetrics::new();
+
+        let ok = metrics.start("execute_js");
+        let timed_out = metrics.start("execute_js");
+        let abandoned = metrics.start("ping");
+        assert_eq!(metrics.snapshot()["totals"]["in_flight"], 3);
+
+        ok.finish(&serde_json::json!({ "success": true }));
+        timed_out.finish(&serde_json::json!({ "success": false, "errorKind": "timeout" }));
+        drop(abandoned);
+
+        let snapshot = metrics.snapshot();
+        let execute_js = &snapshot["commands"]["execute_js"];
+        assert_eq!(execute_js["calls"], 2);
+        assert_eq!(execute_js["errors"], 1);
+        assert_eq!(execute_js["timeouts"], 1);
+        assert_eq!(execute_js["in_flight"], 0);
+        assert!(execute_js["p95_ms"].is_number());
+
+        let ping = &snapshot["commands"]["ping"];
+        assert_eq!(ping["calls"], 0);
+        assert_eq!(ping["in_flight"], 0);
+        assert!(ping["p50_ms"].is_null());
+        assert_eq!(snapshot["totals"]["calls"], 2);
+    }
+}
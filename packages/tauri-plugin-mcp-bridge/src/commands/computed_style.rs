@@ -0,0 +1,132 @@
+//! Reading an element's computed styles.
+
+use super::execute_js::run_script;
+use super::list_windows::target_window;
+use super::script_executor::ScriptExecutor;
+use serde_json::Value;
+use tauri::{command, Runtime, State, WebviewWindow};
+
+/// Properties listed first when no `properties` are requested, so the ones most layout
+/// and styling checks need survive the cap.
+const DEFAULT_STYLE_PROPERTIES: &[&str] = &[
+    "display",
+    "visibility",
+    "opacity",
+    "position",
+    "width",
+    "height",
+    "margin",
+    "padding",
+    "color",
+    "background-color",
+    "font-family",
+    "font-size",
+    "font-weight",
+    "border",
+    "z-index",
+    "overflow",
+    "transform",
+    "pointer-events",
+];
+
+/// Maximum number of properties returned when no `properties` are requested.
+///
+/// A full computed style has several hundred entries, most of them defaults.
+const MAX_STYLE_PROPERTIES: usize = 100;
+
+/// Reads computed style values from the element matching `selector`.
+///
+/// Expects `selector`, `properties`, `defaults` and `maxProperties` to be defined
+/// before it. With `properties` null, the defaults come first and the rest of the
+/// computed style fills up to `maxProperties`.
+const GET_COMPUTED_STYLE_SCRIPT: &str = r#"
+const el = document.querySelector(selector);
+if (el === null) {
+    throw new Error('No element matches selector ' + JSON.stringify(selector));
+}
+const style = window.getComputedStyle(el);
+function cssName(name) {
+    return name.startsWith('--') ? name : name.replace(/[A-Z]/g, function(c) {
+        return '-' + c.toLowerCase();
+    });
+}
+const styles = {};
+let total = 0;
+let truncated = false;
+if (properties !== null) {
+    properties.forEach(function(name) {
+        styles[name] = style.getPropertyValue(cssName(name));
+    });
+    total = properties.length;
+} else {
+    const names = defaults.slice();
+    for (let i = 0; i < style.length; i++) {
+        if (names.indexOf(style[i]) === -1) names.push(style[i]);
+    }
+    total = names.length;
+    truncated = names.length > maxProperties;
+    names.slice(0, maxProperties).forEach(function(name) {
+        styles[name] = style.getPropertyValue(name);
+    });
+}
+({ styles: styles, total: total, truncated: truncated })
+"#;
+
+/// Returns the computed styles of the element matching a CSS selector.
+///
+/// Values come from `getComputedStyle`, so they are the resolved values the browser
+/// uses, with stylesheets, inheritance and inline styles all applied.
+///
+/// # Arguments
+///
+/// * `window` - The window containing the element
+/// * `selector` - CSS selector of the element (the first match is used)
+/// * `properties` - CSS properties to read, in kebab-case (`background-color`) or
+///   camelCase (`backgroundColor`). When omitted, common properties like `display`,
+///   `visibility` and `color` are returned first, followed by the rest of the
+///   computed style, up to 100 properties.
+/// * `window_label` - Label of the window to target (defaults to the calling window)
+/// * `executor` - Script executor state
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the styles were read
+///   - `data`: `{ styles, total, truncated }` where `styles` maps each property to its
+///     value (an empty string for unknown properties), `total` is the number of
+///     properties available and `truncated` is whether some were left out
+///   - `error`: Error message (if nothing matched the selector)
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const result = await invoke('plugin:mcp-bridge|get_computed_style', {
+///   selector: '.error-banner',
+///   properties: ['display', 'color']
+/// });
+/// console.log(result.data.styles.display);
+/// ```
+#[command]
+pub async fn get_computed_style<R: Runtime>(
+    window: WebviewWindow<R>,
+    selector: String,
+    properties: Option<Vec<String>>,
+    window_label: Option<String>,
+    executor: State<'_, ScriptExecutor>,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    let selector_js = serde_json::to_string(&selector).map_err(|e| e.to_string())?;
+    let properties_js = serde_json::to_string(&properties).map_err(|e| e.to_string())?;
+    let defaults_js = serde_json::to_string(DEFAULT_STYLE_PROPERTIES).map_err(|e| e.to_string())?;
+    let script = format!(
+        "const selector = {selector_js};\nconst properties = {properties_js};\nconst defaults = {defaults_js};\nconst maxProperties = {MAX_STYLE_PROPERTIES};\n{GET_COMPUTED_STYLE_SCRIPT}"
+    );
+
+    run_script(window, script, None, None, None, None, None, executor).await
+}
@@ -0,0 +1,138 @@
+//! Console output captured by the bridge script.
+//!
+//! `bridge.js` wraps `console.log`, `debug`, `info`, `warn` and `error` as soon as a
+//! page loads and keeps the most recent entries in a bounded buffer on the page. These
+//! commands read and reset that buffer.
+
+use super::execute_js::run_script;
+use super::list_windows::target_window;
+use super::script_executor::ScriptExecutor;
+use serde_json::Value;
+use tauri::{command, Runtime, State, WebviewWindow};
+
+/// Console levels that are captured.
+const CONSOLE_LEVELS: &[&str] = &["log", "debug", "info", "warn", "error"];
+
+/// Reads buffered console entries after the `since` cursor, optionally for one level.
+///
+/// Expects `level` and `since` to be defined before it.
+const GET_CONSOLE_LOGS_SCRIPT: &str = r#"
+const logs = window.__MCP_CONSOLE_LOGS__ || [];
+const entries = logs.filter(function(entry) {
+    return entry.seq > since && (level === null || entry.level === level);
+});
+({
+    entries: entries,
+    cursor: logs.length > 0 ? Math.max(since, logs[logs.length - 1].seq) : since
+})
+"#;
+
+/// Empties the console buffer while keeping entry numbering, so cursors stay valid.
+const CLEAR_CONSOLE_LOGS_SCRIPT: &str = r#"
+const logs = window.__MCP_CONSOLE_LOGS__ || [];
+const cleared = logs.length;
+logs.length = 0;
+({ cleared: cleared })
+"#;
+
+/// Returns console entries captured in the webview.
+///
+/// Entries are numbered in the order they were logged. Pass the returned `cursor` as
+/// `since` on the next call to receive only newer entries. The buffer holds the most
+/// recent 1000 entries, and is reset when the page reloads.
+///
+/// # Arguments
+///
+/// * `window` - The window to read logs from
+/// * `level` - Only return entries of this level: `"log"`, `"debug"`, `"info"`,
+///   `"warn"` or `"error"`
+/// * `since` - Only return entries numbered after this cursor (defaults to 0, all entries)
+/// * `window_label` - Label of the window to target (defaults to the calling window)
+/// * `executor` - Script executor state
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the logs were read
+///   - `data`: `{ entries, cursor }` where each entry is
+///     `{ seq, level, message, args, timestamp }`
+///   - `error`: Error message (if failed)
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const first = await invoke('plugin:mcp-bridge|get_console_logs', { level: 'error' });
+/// const newer = await invoke('plugin:mcp-bridge|get_console_logs', {
+///   since: first.data.cursor
+/// });
+/// ```
+#[command]
+pub async fn get_console_logs<R: Runtime>(
+    window: WebviewWindow<R>,
+    level: Option<String>,
+    since: Option<u64>,
+    window_label: Option<String>,
+    executor: State<'_, ScriptExecutor>,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    if let Some(level) = &level {
+        if !CONSOLE_LEVELS.contains(&level.as_str()) {
+            return Err(format!(
+                "Invalid level '{level}': expected one of {}",
+                CONSOLE_LEVELS.join(", ")
+            ));
+        }
+    }
+
+    let level_js = serde_json::to_string(&level).map_err(|e| e.to_string())?;
+    let since = since.unwrap_or(0);
+    let script =
+        format!("const level = {level_js};\nconst since = {since};\n{GET_CONSOLE_LOGS_SCRIPT}");
+
+    run_script(window, script, None, None, None, None, None, executor).await
+}
+
+/// Clears the console entries captured in the webview.
+///
+/// Entry numbering continues after a clear, so cursors from earlier
+/// `get_console_logs` calls remain valid.
+///
+/// # Arguments
+///
+/// * `window_label` - Label of the window to target (defaults to the calling window)
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the buffer was cleared
+///   - `data`: `{ cleared }` with the number of entries removed
+///   - `error`: Error message (if failed)
+#[command]
+pub async fn clear_console_logs<R: Runtime>(
+    window: WebviewWindow<R>,
+    window_label: Option<String>,
+    executor: State<'_, ScriptExecutor>,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    run_script(
+        window,
+        CLEAR_CONSOLE_LOGS_SCRIPT.to_string(),
+        None,
+        None,
+        None,
+        None,
+        None,
+        executor,
+    )
+    .await
+}
@@ -0,0 +1,104 @@
+//! Named contexts that keep state across `execute_js` calls.
+
+use super::execute_js::{is_sync_expression, run_script};
+use super::list_windows::target_window;
+use super::script_executor::ScriptExecutor;
+use serde_json::Value;
+use tauri::{command, Runtime, State, WebviewWindow};
+
+/// Returns the JavaScript expression that evaluates to the context object named `name`,
+/// creating it if needed.
+///
+/// Contexts live on the window of the document the script sees, so isolated scripts
+/// share them with the page while frame scripts get the frame's own.
+fn context_js(name: &str) -> String {
+    let name = Value::String(name.to_string());
+    format!(
+        "(function(view) {{ \
+            var contexts = view.__MCP_CONTEXTS__ || (view.__MCP_CONTEXTS__ = Object.create(null)); \
+            return contexts[{name}] || (contexts[{name}] = {{}}); \
+        }})(document.defaultView)"
+    )
+}
+
+/// Binds the context named `name` to `$context` for the script.
+///
+/// Like `$args`, a single expression is wrapped in an arrow function so it keeps the
+/// fast path, and anything else gets a `const` declaration in front.
+pub(super) fn with_context(name: &str, script: &str) -> String {
+    let context = context_js(name);
+    if is_sync_expression(script) {
+        let expression = script.trim().trim_end_matches(';');
+        format!("(($context) => (\n{expression}\n))({context})")
+    } else {
+        format!("const $context = {context};\n{script}")
+    }
+}
+
+/// Removes a named context and everything stored in it.
+///
+/// The next `execute_js` call with the same `context` starts over with an empty object.
+/// Contexts are also lost when the page reloads or navigates. This clears contexts of
+/// the top-level page only, not those of frames.
+///
+/// # Arguments
+///
+/// * `window` - The window whose context to clear
+/// * `context` - Name of the context
+/// * `window_label` - Label of the window to target (defaults to the calling window)
+/// * `executor` - Script executor state
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the context was cleared
+///   - `data`: `{ cleared }`, false if there was no such context
+///   - `error`: Error message (if failed)
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// await invoke('plugin:mcp-bridge|clear_context', { context: 'checkout' });
+/// ```
+#[command]
+pub async fn clear_context<R: Runtime>(
+    window: WebviewWindow<R>,
+    context: String,
+    window_label: Option<String>,
+    executor: State<'_, ScriptExecutor>,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    let name = Value::String(context);
+    let script = format!(
+        "(function() {{ \
+            var contexts = window.__MCP_CONTEXTS__; \
+            var cleared = !!contexts && {name} in contexts; \
+            if (cleared) {{ delete contexts[{name}]; }} \
+            return {{ cleared: cleared }}; \
+        }})()"
+    );
+    run_script(window, script, None, None, None, None, None, executor).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_context_is_bound_without_losing_the_fast_path() {
+        let expression = with_context("it's", "$context.count = ($context.count || 0) + 1;");
+        assert!(is_sync_expression(&expression));
+        assert!(expression.starts_with("(($context) => (\n$context.count = "));
+        assert!(expression.contains("contexts[\"it's\"]"));
+
+        let statements = with_context("cart", "const items = [];\n$context.items = items");
+        assert!(statements.starts_with("const $context = (function(view) {"));
+        assert!(statements.ends_with(";\nconst items = [];\n$context.items = items"));
+    }
+}
@@ -0,0 +1,328 @@
+//! Cookie inspection and manipulation.
+//!
+//! Cookies are read from and written to the webview's native cookie store when the page
+//! is served over http or https. That store includes HttpOnly cookies. Pages served
+//! from Tauri's own protocol, or webviews whose store can't be reached, fall back to
+//! `document.cookie`, which only sees the names and values of cookies that aren't
+//! HttpOnly. Every result says which `source` was used, so an empty list from the
+//! document isn't mistaken for "no cookies".
+
+use super::execute_js::run_script;
+use super::list_windows::target_window;
+use super::script_executor::ScriptExecutor;
+use serde::Deserialize;
+use serde_json::Value;
+use tauri::webview::cookie::{time::OffsetDateTime, Cookie};
+use tauri::{command, Runtime, State, Url, WebviewWindow};
+
+/// Parses `document.cookie` into name/value pairs.
+const GET_DOCUMENT_COOKIES_SCRIPT: &str = r#"
+const cookies = document.cookie === '' ? [] : document.cookie.split('; ').map(function(pair) {
+    const index = pair.indexOf('=');
+    return {
+        name: index === -1 ? '' : pair.slice(0, index),
+        value: index === -1 ? pair : pair.slice(index + 1),
+        domain: null,
+        path: null,
+        expires: null,
+        httpOnly: false,
+        secure: null,
+        sameSite: null
+    };
+});
+({ cookies: cookies })
+"#;
+
+/// Writes a cookie through `document.cookie` and checks whether the page can see it.
+///
+/// Expects `cookieString` and `name` to be defined before it.
+const SET_DOCUMENT_COOKIE_SCRIPT: &str = r#"
+document.cookie = cookieString;
+const visible = document.cookie.split('; ').some(function(pair) {
+    return pair.split('=')[0] === name;
+});
+({ name: name, visible: visible })
+"#;
+
+/// A cookie to set with `set_cookie`.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetCookieParams {
+    /// Cookie name
+    pub name: String,
+    /// Cookie value
+    pub value: String,
+    /// Domain the cookie applies to (defaults to the page's host)
+    pub domain: Option<String>,
+    /// Path the cookie applies to (defaults to "/")
+    pub path: Option<String>,
+    /// Expiry as a Unix timestamp in seconds (a session cookie if omitted)
+    pub expires: Option<i64>,
+    /// Hide the cookie from page scripts. Only possible with the native cookie store.
+    pub http_only: Option<bool>,
+    /// Only send the cookie over https
+    pub secure: Option<bool>,
+}
+
+/// Returns whether the native cookie store can be used for a page.
+///
+/// The native stores only hold cookies for http and https URLs.
+fn has_native_cookies(url: &Url) -> bool {
+    matches!(url.scheme(), "http" | "https")
+}
+
+/// Converts a native cookie to the JSON shape returned by the cookie commands.
+fn cookie_json(cookie: &Cookie<'_>) -> Value {
+    serde_json::json!({
+        "name": cookie.name(),
+        "value": cookie.value(),
+        "domain": cookie.domain(),
+        "path": cookie.path(),
+        "expires": cookie.expires_datetime().map(|expires| expires.unix_timestamp()),
+        "httpOnly": cookie.http_only().unwrap_or(false),
+        "secure": cookie.secure().unwrap_or(false),
+        "sameSite": cookie.same_site().map(|same_site| same_site.to_string())
+    })
+}
+
+/// Builds a cookie from the command parameters.
+fn build_cookie(
+    params: &SetCookieParams,
+    default_domain: Option<&str>,
+) -> Result<Cookie<'static>, String> {
+    let mut builder = Cookie::build((params.name.clone(), params.value.clone()))
+        .path(params.path.clone().unwrap_or_else(|| "/".to_string()));
+
+    if let Some(domain) = params.domain.as_deref().or(default_domain) {
+        builder = builder.domain(domain.to_string());
+    }
+    if let Some(expires) = params.expires {
+        let expires = OffsetDateTime::from_unix_timestamp(expires)
+            .map_err(|e| format!("Invalid expires timestamp {expires}: {e}"))?;
+        builder = builder.expires(expires);
+    }
+    if let Some(http_only) = params.http_only {
+        builder = builder.http_only(http_only);
+    }
+    if let Some(secure) = params.secure {
+        builder = builder.secure(secure);
+    }
+
+    Ok(builder.build())
+}
+
+/// Marks a successful `document.cookie` result with its source and a note on its limits.
+fn with_document_source(mut result: Value, note: String) -> Value {
+    if let Some(data) = result.get_mut("data").and_then(|data| data.as_object_mut()) {
+        data.insert("source".to_string(), Value::from("document"));
+        data.insert("httpOnlyIncluded".to_string(), Value::Bool(false));
+        data.insert("note".to_string(), Value::String(note));
+    }
+    result
+}
+
+/// Explains why the native cookie store wasn't used for a page.
+fn document_fallback_note(url: &Url, native_error: Option<String>) -> String {
+    match native_error {
+        Some(error) => format!(
+            "Native cookie store unavailable ({error}); used document.cookie, \
+             which can't see or set HttpOnly cookies"
+        ),
+        None => format!(
+            "Native cookies are only available for http and https pages, not '{}:' pages; \
+             used document.cookie, which can't see or set HttpOnly cookies",
+            url.scheme()
+        ),
+    }
+}
+
+/// Returns the cookies of the page loaded in the webview.
+///
+/// On http and https pages the cookies come from the webview's native cookie store,
+/// including HttpOnly cookies. Otherwise they're parsed from `document.cookie`, which
+/// only has names and values and never includes HttpOnly cookies. Check
+/// `httpOnlyIncluded` before concluding that a cookie doesn't exist.
+///
+/// # Arguments
+///
+/// * `window` - The window whose cookies to read
+/// * `window_label` - Label of the window to target (defaults to the calling window)
+/// * `executor` - Script executor state
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the cookies were read
+///   - `data`: `{ cookies, source, httpOnlyIncluded, note }` where each cookie is
+///     `{ name, value, domain, path, expires, httpOnly, secure, sameSite }`, `expires`
+///     is a Unix timestamp in seconds (null for session cookies), `source` is
+///     `"native"` or `"document"`, and `note` explains a `document` fallback
+///   - `error`: Error message (if failed)
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const result = await invoke('plugin:mcp-bridge|get_cookies');
+/// if (!result.data.httpOnlyIncluded) {
+///   console.warn(result.data.note);
+/// }
+/// ```
+#[command]
+pub async fn get_cookies<R: Runtime>(
+    window: WebviewWindow<R>,
+    window_label: Option<String>,
+    executor: State<'_, ScriptExecutor>,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    let url = window
+        .url()
+        .map_err(|e| format!("Failed to get URL: {e}"))?;
+
+    let native_error = if has_native_cookies(&url) {
+        // Reading cookies can deadlock on Windows unless it happens off the async runtime
+        let native_window = window.clone();
+        let page_url = url.clone();
+        let native = tokio::task::spawn_blocking(move || native_window.cookies_for_url(page_url))
+            .await
+            .map_err(|e| e.to_string())
+            .and_then(|cookies| cookies.map_err(|e| e.to_string()));
+
+        match native {
+            Ok(cookies) => {
+                let cookies: Vec<Value> = cookies.iter().map(cookie_json).collect();
+                return Ok(serde_json::json!({
+                    "success": true,
+                    "data": {
+                        "cookies": cookies,
+                        "source": "native",
+                        "httpOnlyIncluded": true
+                    }
+                }));
+            }
+            Err(error) => Some(error),
+        }
+    } else {
+        None
+    };
+
+    let note = document_fallback_note(&url, native_error);
+    let result = run_script(
+        window,
+        GET_DOCUMENT_COOKIES_SCRIPT.to_string(),
+        None,
+        None,
+        None,
+        None,
+        None,
+        executor,
+    )
+    .await?;
+
+    Ok(with_document_source(result, note))
+}
+
+/// Sets a cookie for the page loaded in the webview.
+///
+/// On http and https pages the cookie is written to the webview's native cookie store,
+/// which supports HttpOnly cookies. Otherwise it's written through `document.cookie`,
+/// which can't set HttpOnly cookies, and `visible` reports whether the page accepted
+/// it. An `expires` in the past deletes the cookie.
+///
+/// # Arguments
+///
+/// * `window` - The window whose cookies to change
+/// * `cookie` - The cookie to set: `{ name, value, domain, path, expires, httpOnly,
+///   secure }`, where only `name` and `value` are required
+/// * `window_label` - Label of the window to target (defaults to the calling window)
+/// * `executor` - Script executor state
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the cookie was set
+///   - `data`: `{ cookie, source: "native" }` with the stored cookie, or
+///     `{ name, visible, source: "document", httpOnlyIncluded, note }`
+///   - `error`: Error message (if failed, for example for an HttpOnly cookie without a
+///     native cookie store)
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// await invoke('plugin:mcp-bridge|set_cookie', {
+///   cookie: { name: 'session', value: 'abc123', httpOnly: true }
+/// });
+/// ```
+#[command]
+pub async fn set_cookie<R: Runtime>(
+    window: WebviewWindow<R>,
+    cookie: SetCookieParams,
+    window_label: Option<String>,
+    executor: State<'_, ScriptExecutor>,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    let url = window
+        .url()
+        .map_err(|e| format!("Failed to get URL: {e}"))?;
+    let http_only = cookie.http_only.unwrap_or(false);
+
+    let native_error = if has_native_cookies(&url) {
+        let native_cookie = build_cookie(&cookie, url.host_str())?;
+        let stored = cookie_json(&native_cookie);
+        let native_window = window.clone();
+        let native = tokio::task::spawn_blocking(move || native_window.set_cookie(native_cookie))
+            .await
+            .map_err(|e| e.to_string())
+            .and_then(|result| result.map_err(|e| e.to_string()));
+
+        match native {
+            Ok(()) => {
+                return Ok(serde_json::json!({
+                    "success": true,
+                    "data": {
+                        "cookie": stored,
+                        "source": "native"
+                    }
+                }));
+            }
+            Err(error) => Some(error),
+        }
+    } else {
+        None
+    };
+
+    if http_only {
+        return Ok(serde_json::json!({
+            "success": false,
+            "error": match native_error {
+                Some(error) => format!("Failed to set HttpOnly cookie: {error}"),
+                None => format!(
+                    "HttpOnly cookies can only be set on http and https pages, not '{}:' pages",
+                    url.scheme()
+                ),
+            }
+        }));
+    }
+
+    let note = document_fallback_note(&url, native_error);
+    let cookie_js = serde_json::to_string(&build_cookie(&cookie, None)?.to_string())
+        .map_err(|e| e.to_string())?;
+    let name_js = serde_json::to_string(&cookie.name).map_err(|e| e.to_string())?;
+    let script = format!(
+        "const cookieString = {cookie_js};\nconst name = {name_js};\n{SET_DOCUMENT_COOKIE_SCRIPT}"
+    );
+    let result = run_script(window, script, None, None, None, None, None, executor).await?;
+
+    Ok(with_document_source(result, note))
+}
@@ -0,0 +1,365 @@
+//! Streaming DOM mutations to WebSocket clients.
+//!
+//! A subscription installs a `MutationObserver` in the page. The page batches the
+//! records it sees and emits them as `__dom_mutations` events, which are forwarded as
+//! notifications to the WebSocket client that subscribed. Subscriptions only exist
+//! over the WebSocket, since notifications are pushed on the subscribing connection.
+
+use super::execute_js::run_script;
+use super::script_executor::ScriptExecutor;
+use crate::logging::{mcp_log_debug, mcp_log_error};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tauri::{AppHandle, Listener, Manager, Runtime, State, WebviewWindow};
+use tokio::sync::mpsc::UnboundedSender;
+use uuid::Uuid;
+
+/// Event the page emits with a batch of mutation records.
+const DOM_MUTATIONS_EVENT: &str = "__dom_mutations";
+
+/// Default time the page collects mutations before sending them as one batch.
+pub const DEFAULT_BATCH_INTERVAL_MS: u64 = 100;
+
+/// Bounds for the batch interval, so a subscription can neither flood the client nor
+/// go quiet for good.
+const MIN_BATCH_INTERVAL_MS: u64 = 10;
+const MAX_BATCH_INTERVAL_MS: u64 = 60_000;
+
+/// Default number of records kept per batch. Records past it are only counted.
+pub const DEFAULT_MAX_BATCH_RECORDS: usize = 500;
+
+/// Installs the observer and evaluates to a description of the observed element.
+///
+/// Expects `subscriptionId`, `selector`, `batchInterval` and `maxRecords` to be defined
+/// before it. Each batch is emitted at most `batchInterval` ms after the first
+/// mutation in it, so a steady stream of changes still arrives regularly.
+const SUBSCRIBE_SCRIPT: &str = r#"
+const target = selector ? document.querySelector(selector) : document.documentElement;
+if (!target) {
+    throw new Error(`No element matches selector: ${selector}`);
+}
+if (!(window.__TAURI__ && window.__TAURI__.event)) {
+    throw new Error('Tauri event API unavailable in webview');
+}
+
+const describe = (node) => {
+    if (!node) return null;
+    if (node.nodeType === Node.ELEMENT_NODE) {
+        let description = node.tagName.toLowerCase();
+        if (node.id) description += '#' + node.id;
+        if (typeof node.className === 'string' && node.className.trim()) {
+            description += '.' + node.className.trim().split(/\s+/).join('.');
+        }
+        return description;
+    }
+    return node.nodeName.toLowerCase();
+};
+const clip = (text) => (typeof text === 'string' && text.length > 200 ? text.slice(0, 200) + '…' : text);
+
+let records = [];
+let dropped = 0;
+let timer = null;
+const flush = () => {
+    timer = null;
+    const batch = { subscriptionId, records, dropped };
+    records = [];
+    dropped = 0;
+    window.__TAURI__.event.emit('__dom_mutations', batch);
+};
+
+const observer = new MutationObserver((mutations) => {
+    for (const mutation of mutations) {
+        if (records.length >= maxRecords) {
+            dropped++;
+            continue;
+        }
+        const record = { type: mutation.type, target: describe(mutation.target) };
+        if (mutation.type === 'childList') {
+            record.added = Array.from(mutation.addedNodes, describe);
+            record.removed = Array.from(mutation.removedNodes, describe);
+        } else if (mutation.type === 'attributes') {
+            record.attribute = mutation.attributeName;
+            record.oldValue = clip(mutation.oldValue);
+            record.value = clip(mutation.target.getAttribute(mutation.attributeName));
+        } else {
+            record.oldValue = clip(mutation.oldValue);
+            record.value = clip(mutation.target.data);
+        }
+        records.push(record);
+    }
+    if (timer === null) {
+        timer = setTimeout(flush, batchInterval);
+    }
+});
+observer.observe(target, {
+    subtree: true,
+    childList: true,
+    attributes: true,
+    attributeOldValue: true,
+    characterData: true,
+    characterDataOldValue: true
+});
+
+const observers = (window.__MCP_MUTATION_OBSERVERS__ = window.__MCP_MUTATION_OBSERVERS__ || {});
+observers[subscriptionId] = () => {
+    observer.disconnect();
+    if (timer !== null) clearTimeout(timer);
+};
+describe(target)
+"#;
+
+/// Disconnects an observer. Expects `subscriptionId` to be defined before it.
+const UNSUBSCRIBE_SCRIPT: &str = r#"
+const observers = window.__MCP_MUTATION_OBSERVERS__ || {};
+if (observers[subscriptionId]) {
+    observers[subscriptionId]();
+    delete observers[subscriptionId];
+}
+"#;
+
+/// A client's subscription to one window's mutations.
+struct Subscription {
+    window_label: String,
+    /// Sends notifications to the subscribing WebSocket client
+    client: UnboundedSender<String>,
+}
+
+/// Active DOM mutation subscriptions by ID.
+///
+/// Managed as app state.
+#[derive(Default)]
+pub struct MutationSubscriptions(Mutex<HashMap<String, Subscription>>);
+
+impl MutationSubscriptions {
+    /// Forwards the page's mutation batches to the clients that subscribed to them.
+    pub fn listen<R: Runtime>(app: &AppHandle<R>) {
+        let app_handle = app.clone();
+        app.listen_any(
+            DOM_MUTATIONS_EVENT,
+            move |event| match serde_json::from_str::<Map<String, Value>>(event.payload()) {
+                Ok(batch) => forward_batch(&app_handle, batch),
+                Err(e) => mcp_log_error(
+                    "DOM_MUTATIONS",
+                    &format!("Failed to parse {DOM_MUTATIONS_EVENT} payload: {e}"),
+                ),
+            },
+        );
+    }
+
+    /// Removes every subscription of a client, for when it disconnects.
+    pub fn remove_client<R: Runtime>(app: &AppHandle<R>, client: &UnboundedSender<String>) {
+        let subscriptions = app.state::<MutationSubscriptions>();
+        let removed: Vec<(String, String)> = {
+            let mut map = subscriptions.0.lock().unwrap_or_else(|e| e.into_inner());
+            let ids: Vec<String> = map
+                .iter()
+                .filter(|(_, subscription)| subscription.client.same_channel(client))
+                .map(|(id, _)| id.clone())
+                .collect();
+            ids.into_iter()
+                .filter_map(|id| map.remove(&id).map(|s| (id, s.window_label)))
+                .collect()
+        };
+
+        for (id, window_label) in removed {
+            mcp_log_debug(
+                "DOM_MUTATIONS",
+                &format!("Removing subscription {id} of disconnected client"),
+            );
+            disconnect_observer(app, &window_label, &id);
+        }
+    }
+}
+
+/// Sends a batch from the page to its subscriber as a notification.
+///
+/// Batches for unknown subscriptions are ignored. A subscriber that has gone away
+/// loses its subscription.
+fn forward_batch<R: Runtime>(app: &AppHandle<R>, batch: Map<String, Value>) {
+    let Some(id) = batch.get("subscriptionId").and_then(|v| v.as_str()) else {
+        return;
+    };
+
+    let subscriptions = app.state::<MutationSubscriptions>();
+    let mut map = subscriptions.0.lock().unwrap_or_else(|e| e.into_inner());
+    let Some(subscription) = map.get(id) else {
+        return;
+    };
+
+    let notification = serde_json::json!({
+        "type": "dom_mutations",
+        "subscriptionId": id,
+        "windowLabel": subscription.window_label,
+        "records": batch.get("records").cloned().unwrap_or_else(|| Value::Array(Vec::new())),
+        "dropped": batch.get("dropped").cloned().unwrap_or(Value::from(0))
+    });
+
+    if subscription.client.send(notification.to_string()).is_err() {
+        if let Some(subscription) = map.remove(id) {
+            drop(map);
+            disconnect_observer(app, &subscription.window_label, id);
+        }
+    }
+}
+
+/// Disconnects a subscription's observer in its window, without waiting for it.
+///
+/// Does nothing if the window is gone, since its observers went with it.
+fn disconnect_observer<R: Runtime>(app: &AppHandle<R>, window_label: &str, id: &str) {
+    let Some(window) = app.get_webview_window(window_label) else {
+        return;
+    };
+    let Ok(id_js) = serde_json::to_string(id) else {
+        return;
+    };
+    let script =
+        format!("(() => {{\nconst subscriptionId = {id_js};\n{UNSUBSCRIBE_SCRIPT}\n}})();");
+    if let Err(e) = window.eval(&script) {
+        mcp_log_error(
+            "DOM_MUTATIONS",
+            &format!("Failed to disconnect observer {id}: {e}"),
+        );
+    }
+}
+
+/// Starts streaming a window's DOM mutations to a WebSocket client.
+///
+/// A `MutationObserver` watches the element matching `selector` (or the whole
+/// document) and its subtree for added and removed nodes, attribute changes and text
+/// changes. The page collects records for `batch_interval_ms` after the first change
+/// and then sends them together, so bursts of changes arrive as one notification:
+///
+/// ```json
+/// {
+///   "type": "dom_mutations",
+///   "subscriptionId": "…",
+///   "windowLabel": "main",
+///   "records": [
+///     { "type": "childList", "target": "ul#items", "added": ["li.item"], "removed": [] },
+///     { "type": "attributes", "target": "button#save", "attribute": "disabled",
+///       "oldValue": null, "value": "" }
+///   ],
+///   "dropped": 0
+/// }
+/// ```
+///
+/// Nodes are described as `tag#id.class`, and attribute and text values are cut off at
+/// 200 characters. Records past `max_records` in one batch are counted in `dropped`.
+///
+/// The observer lives in the page, so navigating or reloading ends the stream. The
+/// subscription is removed by `unsubscribe_dom_mutations` or when the client
+/// disconnects.
+///
+/// # Arguments
+///
+/// * `window` - The window to observe
+/// * `selector` - CSS selector of the element to observe (defaults to the whole document)
+/// * `batch_interval_ms` - How long to collect mutations before sending them (defaults
+///   to 100, clamped to 10-60000)
+/// * `max_records` - Maximum records per batch (defaults to 500)
+/// * `client` - Sends notifications to the subscribing client
+/// * `subscriptions` - Active subscriptions
+/// * `executor` - Script executor state
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the observer was installed
+///   - `data`: `{ subscription_id, window_label, target, batch_interval_ms }`
+///   - `error`: Error message (if failed, for example when nothing matches `selector`)
+pub async fn subscribe_dom_mutations<R: Runtime>(
+    window: WebviewWindow<R>,
+    selector: Option<String>,
+    batch_interval_ms: Option<u64>,
+    max_records: Option<usize>,
+    client: UnboundedSender<String>,
+    subscriptions: &MutationSubscriptions,
+    executor: State<'_, ScriptExecutor>,
+) -> Result<Value, String> {
+    let id = Uuid::new_v4().to_string();
+    let window_label = window.label().to_string();
+    let batch_interval_ms = batch_interval_ms
+        .unwrap_or(DEFAULT_BATCH_INTERVAL_MS)
+        .clamp(MIN_BATCH_INTERVAL_MS, MAX_BATCH_INTERVAL_MS);
+    let max_records = max_records.unwrap_or(DEFAULT_MAX_BATCH_RECORDS);
+
+    let id_js = serde_json::to_string(&id).map_err(|e| e.to_string())?;
+    let selector_js = serde_json::to_string(&selector).map_err(|e| e.to_string())?;
+    let script = format!(
+        "const subscriptionId = {id_js};\nconst selector = {selector_js};\nconst batchInterval = {batch_interval_ms};\nconst maxRecords = {max_records};\n{SUBSCRIBE_SCRIPT}"
+    );
+
+    // Registered first so no batch can arrive before its subscription exists
+    subscriptions
+        .0
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(
+            id.clone(),
+            Subscription {
+                window_label: window_label.clone(),
+                client,
+            },
+        );
+
+    let result = run_script(window, script, None, None, None, None, None, executor).await?;
+    if result.get("success").and_then(|v| v.as_bool()) != Some(true) {
+        subscriptions
+            .0
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&id);
+        return Ok(result);
+    }
+
+    Ok(serde_json::json!({
+        "success": true,
+        "data": {
+            "subscription_id": id,
+            "window_label": window_label,
+            "target": result.get("data").cloned().unwrap_or(Value::Null),
+            "batch_interval_ms": batch_interval_ms
+        }
+    }))
+}
+
+/// Stops a DOM mutation subscription and disconnects its observer.
+///
+/// # Arguments
+///
+/// * `app` - The Tauri application handle
+/// * `subscription_id` - ID returned by `subscribe_dom_mutations`
+/// * `subscriptions` - Active subscriptions
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the subscription existed
+///   - `data`: `{ subscription_id }` (if it existed)
+///   - `error`: Error message (if no subscription has that ID)
+pub fn unsubscribe_dom_mutations<R: Runtime>(
+    app: &AppHandle<R>,
+    subscription_id: &str,
+    subscriptions: &MutationSubscriptions,
+) -> Result<Value, String> {
+    let removed = subscriptions
+        .0
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(subscription_id);
+
+    match removed {
+        Some(subscription) => {
+            disconnect_observer(app, &subscription.window_label, subscription_id);
+            Ok(serde_json::json!({
+                "success": true,
+                "data": { "subscription_id": subscription_id }
+            }))
+        }
+        None => Ok(serde_json::json!({
+            "success": false,
+            "error": format!("No DOM mutation subscription '{subscription_id}'")
+        })),
+    }
+}
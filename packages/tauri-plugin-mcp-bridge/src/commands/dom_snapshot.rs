@@ -0,0 +1,498 @@
+//! Serialized snapshots of the page DOM, and the differences between them.
+
+use super::execute_js::run_script;
+use super::list_windows::target_window;
+use super::script_executor::ScriptExecutor;
+use serde_json::{Map, Value};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use tauri::{command, Runtime, State, WebviewWindow};
+use uuid::Uuid;
+
+/// Number of snapshot trees kept for `diff_snapshots`. Taking another snapshot evicts
+/// the oldest one.
+pub const MAX_CACHED_SNAPSHOTS: usize = 16;
+
+/// Largest number of child pairs compared when aligning the children of two nodes.
+/// Beyond it, children are only matched up by position.
+const MAX_ALIGNMENT_CELLS: usize = 1_000_000;
+
+/// Serializes the subtree under `selector` as HTML and as a JSON tree.
+///
+/// Expects `selector`, `maxDepth` and `includeScripts` to be defined before it.
+/// Works on a clone so stripping script contents never touches the live page.
+const DOM_SNAPSHOT_SCRIPT: &str = r#"
+const source = selector === null ? document.documentElement : document.querySelector(selector);
+if (source === null) {
+    throw new Error('No element matches selector ' + JSON.stringify(selector));
+}
+const root = source.cloneNode(true);
+if (!includeScripts) {
+    if (root.tagName === 'SCRIPT') root.textContent = '';
+    root.querySelectorAll('script').forEach(function(s) { s.textContent = ''; });
+}
+
+function walk(el, depth) {
+    const node = { tag: el.tagName.toLowerCase(), attributes: {} };
+    for (const attr of el.attributes) node.attributes[attr.name] = attr.value;
+    if (maxDepth !== null && depth >= maxDepth) {
+        if (el.childNodes.length > 0) node.truncated = true;
+        return node;
+    }
+    node.children = [];
+    for (const child of el.childNodes) {
+        if (child.nodeType === Node.ELEMENT_NODE) {
+            node.children.push(walk(child, depth + 1));
+        } else if (child.nodeType === Node.TEXT_NODE && child.textContent.trim() !== '') {
+            node.children.push({ text: child.textContent });
+        }
+    }
+    return node;
+}
+
+({ html: root.outerHTML, tree: walk(root, 0) })
+"#;
+
+/// Snapshot trees taken by `get_dom_snapshot`, oldest first, for `diff_snapshots`.
+///
+/// Managed as app state.
+#[derive(Default)]
+pub struct DomSnapshots(Mutex<VecDeque<(String, Arc<Value>)>>);
+
+impl DomSnapshots {
+    /// Keeps a snapshot tree, evicting the oldest one when the cache is full.
+    fn insert(&self, id: String, tree: Value) {
+        let mut snapshots = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        while snapshots.len() >= MAX_CACHED_SNAPSHOTS {
+            snapshots.pop_front();
+        }
+        snapshots.push_back((id, Arc::new(tree)));
+    }
+
+    fn get(&self, id: &str) -> Option<Arc<Value>> {
+        let snapshots = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        snapshots
+            .iter()
+            .find(|(snapshot_id, _)| snapshot_id == id)
+            .map(|(_, tree)| tree.clone())
+    }
+}
+
+/// Returns a serialized snapshot of the DOM, or of the subtree matching a selector.
+///
+/// The result holds both the `outerHTML` and a structured tree of elements with their
+/// tag, attributes and text. Large pages are sent back in chunks like any other
+/// big `execute_js` result, so they aren't limited by the size of a single event.
+///
+/// The tree is also kept under `snapshot_id`, so `diff_snapshots` can compare it with
+/// another one later. Only the last 16 snapshots are kept.
+///
+/// # Arguments
+///
+/// * `window` - The window to snapshot
+/// * `selector` - CSS selector of the subtree root (defaults to `document.documentElement`)
+/// * `max_depth` - How many levels below the root to include in `tree` (unlimited by
+///   default). Elements cut off at the limit have `truncated: true`.
+/// * `include_scripts` - Keep the contents of `<script>` elements (stripped by default)
+/// * `window_label` - Label of the window to target (defaults to the calling window)
+/// * `snapshots` - Snapshot cache state
+/// * `executor` - Script executor state
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the snapshot was taken
+///   - `data`: `{ html, tree, snapshot_id }` where each `tree` node is
+///     `{ tag, attributes, children }` for elements or `{ text }` for non-blank text
+///   - `error`: Error message (if nothing matched the selector)
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const snapshot = await invoke('plugin:mcp-bridge|get_dom_snapshot', {
+///   selector: 'main',
+///   maxDepth: 3
+/// });
+/// ```
+#[command]
+pub async fn get_dom_snapshot<R: Runtime>(
+    window: WebviewWindow<R>,
+    selector: Option<String>,
+    max_depth: Option<u32>,
+    include_scripts: Option<bool>,
+    window_label: Option<String>,
+    snapshots: State<'_, DomSnapshots>,
+    executor: State<'_, ScriptExecutor>,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    let selector_js = serde_json::to_string(&selector).map_err(|e| e.to_string())?;
+    let max_depth_js = serde_json::to_string(&max_depth).map_err(|e| e.to_string())?;
+    let include_scripts = include_scripts.unwrap_or(false);
+    let script = format!(
+        "const selector = {selector_js};\nconst maxDepth = {max_depth_js};\nconst includeScripts = {include_scripts};\n{DOM_SNAPSHOT_SCRIPT}"
+    );
+
+    let mut result = run_script(window, script, None, None, None, None, None, executor).await?;
+    if result.get("success").and_then(|v| v.as_bool()) != Some(true) {
+        return Ok(result);
+    }
+
+    let snapshot_id = Uuid::new_v4().to_string();
+    snapshots.insert(snapshot_id.clone(), result["data"]["tree"].clone());
+    result["data"]["snapshot_id"] = Value::String(snapshot_id);
+    Ok(result)
+}
+
+/// Compares two snapshots taken by `get_dom_snapshot` and lists what changed.
+///
+/// Children are aligned so that inserting or removing a node reports just that node,
+/// not every sibling after it: unchanged subtrees are matched up first, then the
+/// remaining nodes with the same tag and `id`. Matched nodes are compared by their
+/// attributes and text, and removed and added nodes are reported whole.
+///
+/// Nodes are addressed by paths like `/html/body[1]/ul[0]/li[2]/#text[0]`, where each
+/// step is the node's tag (or `#text`) and its index among its parent's children in
+/// the snapshot, text included. Paths of removed nodes are in the `before` snapshot;
+/// all others are in the `after` one. Compare snapshots of the same subtree taken with
+/// the same options, or the differences in `max_depth` show up as changes too.
+///
+/// # Arguments
+///
+/// * `before` - `snapshot_id` of the earlier snapshot
+/// * `after` - `snapshot_id` of the later snapshot
+/// * `snapshots` - Snapshot cache state
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether both snapshots were found
+///   - `data`: Object with:
+///     - `identical`: Whether the snapshots are the same
+///     - `added`: `[{ path, node }]` with the added subtrees
+///     - `removed`: `[{ path, node }]` with the removed subtrees
+///     - `changed`: `[{ path, attributes, text }]`, where `attributes` maps each changed
+///       attribute to `{ before, after }` (null when absent) and `text` is
+///       `{ before, after }` for text nodes. Each only appears when it changed.
+///   - `error`: Error message (if a snapshot is unknown or was evicted)
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const before = await invoke('plugin:mcp-bridge|get_dom_snapshot', { selector: 'main' });
+/// await invoke('plugin:mcp-bridge|click_element', { selector: '#add-item' });
+/// const after = await invoke('plugin:mcp-bridge|get_dom_snapshot', { selector: 'main' });
+/// const diff = await invoke('plugin:mcp-bridge|diff_snapshots', {
+///   before: before.data.snapshot_id,
+///   after: after.data.snapshot_id
+/// });
+/// ```
+#[command]
+pub async fn diff_snapshots(
+    before: String,
+    after: String,
+    snapshots: State<'_, DomSnapshots>,
+) -> Result<Value, String> {
+    let (before_tree, after_tree) = match (snapshots.get(&before), snapshots.get(&after)) {
+        (Some(before), Some(after)) => (before, after),
+        (before_tree, _) => {
+            let missing = if before_tree.is_none() { before } else { after };
+            return Ok(serde_json::json!({
+                "success": false,
+                "error": format!(
+                    "Unknown snapshot '{missing}': it was never taken or has been evicted (only the last {MAX_CACHED_SNAPSHOTS} are kept)"
+                )
+            }));
+        }
+    };
+
+    let diff = diff_trees(&before_tree, &after_tree);
+    Ok(serde_json::json!({
+        "success": true,
+        "data": {
+            "identical": diff.added.is_empty() && diff.removed.is_empty() && diff.changed.is_empty(),
+            "added": diff.added,
+            "removed": diff.removed,
+            "changed": diff.changed
+        }
+    }))
+}
+
+/// The differences between two snapshot trees.
+#[derive(Debug, Default)]
+struct SnapshotDiff {
+    added: Vec<Value>,
+    removed: Vec<Value>,
+    changed: Vec<Value>,
+}
+
+/// Compares two snapshot trees, whose roots are at `/<tag>`.
+fn diff_trees(before: &Value, after: &Value) -> SnapshotDiff {
+    let mut diff = SnapshotDiff::default();
+    let before_path = format!("/{}", node_name(before));
+    let after_path = format!("/{}", node_name(after));
+    if node_key(before) == node_key(after) {
+        diff_nodes(before, after, &before_path, &after_path, &mut diff);
+    } else {
+        diff.removed
+            .push(serde_json::json!({ "path": before_path, "node": before }));
+        diff.added
+            .push(serde_json::json!({ "path": after_path, "node": after }));
+    }
+    diff
+}
+
+/// The tag of an element node, or `#text`.
+fn node_name(node: &Value) -> &str {
+    node.get("tag")
+        .and_then(|tag| tag.as_str())
+        .unwrap_or("#text")
+}
+
+/// What makes two nodes the same node in both snapshots: the tag and `id` of an element,
+/// or just being text.
+fn node_key(node: &Value) -> (&str, Option<&str>) {
+    let id = node
+        .get("attributes")
+        .and_then(|attributes| attributes.get("id"))
+        .and_then(|id| id.as_str());
+    (node_name(node), id)
+}
+
+/// The attributes of an element node.
+fn attributes(node: &Value) -> Option<&Map<String, Value>> {
+    node.get("attributes")
+        .and_then(|attributes| attributes.as_object())
+}
+
+/// The children of an element node, none for text or a node cut off by `max_depth`.
+fn children(node: &Value) -> &[Value] {
+    node.get("children")
+        .and_then(|children| children.as_array())
+        .map(|children| children.as_slice())
+        .unwrap_or(&[])
+}
+
+/// Compares two nodes with the same key, and their children.
+fn diff_nodes(
+    before: &Value,
+    after: &Value,
+    before_path: &str,
+    after_path: &str,
+    diff: &mut SnapshotDiff,
+) {
+    if before == after {
+        return;
+    }
+
+    let mut change = Map::new();
+    if before.get("text") != after.get("text") {
+        change.insert(
+            "text".to_string(),
+            serde_json::json!({ "before": before.get("text"), "after": after.get("text") }),
+        );
+    }
+    let no_attributes = Map::new();
+    let before_attributes = attributes(before).unwrap_or(&no_attributes);
+    let after_attributes = attributes(after).unwrap_or(&no_attributes);
+    let mut changed_attributes = Map::new();
+    for name in before_attributes.keys().chain(after_attributes.keys()) {
+        let (old, new) = (before_attributes.get(name), after_attributes.get(name));
+        if old != new && !changed_attributes.contains_key(name) {
+            changed_attributes.insert(
+                name.clone(),
+                serde_json::json!({ "before": old, "after": new }),
+            );
+        }
+    }
+    if !changed_attributes.is_empty() {
+        change.insert("attributes".to_string(), Value::Object(changed_attributes));
+    }
+    if !change.is_empty() {
+        change.insert("path".to_string(), Value::String(after_path.to_string()));
+        diff.changed.push(Value::Object(change));
+    }
+
+    diff_children(
+        children(before),
+        children(after),
+        before_path,
+        after_path,
+        diff,
+    );
+}
+
+/// Aligns and compares the children of two matched nodes.
+fn diff_children(
+    before: &[Value],
+    after: &[Value],
+    before_path: &str,
+    after_path: &str,
+    diff: &mut SnapshotDiff,
+) {
+    let child_path =
+        |parent: &str, node: &Value, index: usize| format!("{parent}/{}[{index}]", node_name(node));
+    // Unchanged children anchor the alignment, so an insertion doesn't shift the rest
+    let anchors = align(before, after, |a, b| a == b);
+
+    let (mut i, mut j) = (0, 0);
+    for (anchor_i, anchor_j) in anchors
+        .into_iter()
+        .chain(std::iter::once((before.len(), after.len())))
+    {
+        let (gap_before, gap_after) = (&before[i..anchor_i], &after[j..anchor_j]);
+        let pairs = align(gap_before, gap_after, |a, b| node_key(a) == node_key(b));
+
+        let (mut gi, mut gj) = (0, 0);
+        for (pair_i, pair_j) in pairs
+            .into_iter()
+            .chain(std::iter::once((gap_before.len(), gap_after.len())))
+        {
+            for (k, node) in gap_before[gi..pair_i].iter().enumerate() {
+                let path = child_path(before_path, node, i + gi + k);
+                diff.removed
+                    .push(serde_json::json!({ "path": path, "node": node }));
+            }
+            for (k, node) in gap_after[gj..pair_j].iter().enumerate() {
+                let path = child_path(after_path, node, j + gj + k);
+                diff.added
+                    .push(serde_json::json!({ "path": path, "node": node }));
+            }
+            if pair_i < gap_before.len() {
+                let (old, new) = (&gap_before[pair_i], &gap_after[pair_j]);
+                diff_nodes(
+                    old,
+                    new,
+                    &child_path(before_path, old, i + pair_i),
+                    &child_path(after_path, new, j + pair_j),
+                    diff,
+                );
+            }
+            (gi, gj) = (pair_i + 1, pair_j + 1);
+        }
+        (i, j) = (anchor_i + 1, anchor_j + 1);
+    }
+}
+
+/// Returns the index pairs of a longest common subsequence of `a` and `b` under `eq`.
+///
+/// Common prefixes and suffixes are matched directly. If what's left between them is
+/// too large to align, its nodes are only matched where they're equal at the same
+/// offset.
+fn align<T>(a: &[T], b: &[T], eq: impl Fn(&T, &T) -> bool) -> Vec<(usize, usize)> {
+    let prefix = a.iter().zip(b).take_while(|(x, y)| eq(x, y)).count();
+    let suffix = a[prefix..]
+        .iter()
+        .rev()
+        .zip(b[prefix..].iter().rev())
+        .take_while(|(x, y)| eq(x, y))
+        .count();
+    let (middle_a, middle_b) = (&a[prefix..a.len() - suffix], &b[prefix..b.len() - suffix]);
+
+    let mut pairs: Vec<(usize, usize)> = (0..prefix).map(|k| (k, k)).collect();
+    if middle_a.len().saturating_mul(middle_b.len()) > MAX_ALIGNMENT_CELLS {
+        pairs.extend(
+            middle_a
+                .iter()
+                .zip(middle_b)
+                .enumerate()
+                .filter(|(_, (x, y))| eq(x, y))
+                .map(|(k, _)| (prefix + k, prefix + k)),
+        );
+    } else if !middle_a.is_empty() && !middle_b.is_empty() {
+        // lengths[x][y] is the length of the common subsequence of middle_a[x..] and
+        // middle_b[y..]
+        let (n, m) = (middle_a.len(), middle_b.len());
+        let mut lengths = vec![vec![0u32; m + 1]; n + 1];
+        for x in (0..n).rev() {
+            for y in (0..m).rev() {
+                lengths[x][y] = if eq(&middle_a[x], &middle_b[y]) {
+                    lengths[x + 1][y + 1] + 1
+                } else {
+                    lengths[x + 1][y].max(lengths[x][y + 1])
+                };
+            }
+        }
+        let (mut x, mut y) = (0, 0);
+        while x < n && y < m {
+            if eq(&middle_a[x], &middle_b[y]) {
+                pairs.push((prefix + x, prefix + y));
+                x += 1;
+                y += 1;
+            } else if lengths[x + 1][y] >= lengths[x][y + 1] {
+                x += 1;
+            } else {
+                y += 1;
+            }
+        }
+    }
+    let (suffix_a, suffix_b) = (a.len() - suffix, b.len() - suffix);
+    pairs.extend((0..suffix).map(|k| (suffix_a + k, suffix_b + k)));
+    pairs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn item(text: &str) -> Value {
+        json!({ "tag": "li", "attributes": {}, "children": [{ "text": text }] })
+    }
+
+    #[test]
+    fn test_diff_reports_only_the_nodes_that_changed() {
+        let before = json!({ "tag": "ul", "attributes": { "class": "list" }, "children": [
+            item("one"), item("two"), item("three")
+        ]});
+        let after = json!({ "tag": "ul", "attributes": { "class": "list done" }, "children": [
+            item("one"), item("new"), item("two"), item("3")
+        ]});
+
+        let diff = diff_trees(&before, &after);
+        assert!(diff.removed.is_empty());
+        assert_eq!(
+            diff.added,
+            vec![json!({ "path": "/ul/li[1]", "node": item("new") })]
+        );
+        assert_eq!(
+            diff.changed,
+            vec![
+                json!({
+                    "path": "/ul",
+                    "attributes": { "class": { "before": "list", "after": "list done" } }
+                }),
+                json!({
+                    "path": "/ul/li[3]/#text[0]",
+                    "text": { "before": "three", "after": "3" }
+                }),
+            ]
+        );
+
+        let identical = diff_trees(&before, &before);
+        assert!(identical.added.is_empty() && identical.changed.is_empty());
+    }
+
+    #[test]
+    fn test_alignment_keeps_order_and_handles_large_inputs() {
+        assert_eq!(
+            align(&[1, 2, 3, 4], &[1, 3, 5, 4], |a, b| a == b),
+            vec![(0, 0), (2, 1), (3, 3)]
+        );
+        // Too large to align, so only equal nodes at the same offset are matched
+        let a: Vec<u32> = (0..2000).collect();
+        let b: Vec<u32> = (0..2000)
+            .map(|n| if n % 2 == 0 { n } else { n + 10000 })
+            .collect();
+        let pairs = align(&a, &b, |x, y| x == y);
+        assert_eq!(pairs.len(), 1000);
+        assert!(pairs.iter().all(|&(x, y)| x == y && x % 2 == 0));
+    }
+}
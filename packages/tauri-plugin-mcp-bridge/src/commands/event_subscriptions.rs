@@ -0,0 +1,222 @@
+//! Forwarding app events to WebSocket clients.
+//!
+//! Like DOM mutation subscriptions, these only exist over the WebSocket, since
+//! notifications are pushed on the subscribing connection.
+
+use crate::logging::mcp_log_debug;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tauri::{AppHandle, EventId, Listener, Manager, Runtime};
+use tokio::sync::mpsc::UnboundedSender;
+use uuid::Uuid;
+
+/// A client's subscription to one event.
+struct EventSubscription {
+    event: String,
+    listener: EventId,
+    /// Sends notifications to the subscribing WebSocket client
+    client: UnboundedSender<String>,
+}
+
+/// Active event subscriptions by ID.
+///
+/// Managed as app state.
+#[derive(Default)]
+pub struct EventSubscriptions(Mutex<HashMap<String, EventSubscription>>);
+
+impl EventSubscriptions {
+    /// Removes every subscription of a client, for when it disconnects.
+    pub fn remove_client<R: Runtime>(app: &AppHandle<R>, client: &UnboundedSender<String>) {
+        let subscriptions = app.state::<EventSubscriptions>();
+        let mut map = subscriptions.0.lock().unwrap_or_else(|e| e.into_inner());
+        map.retain(|id, subscription| {
+            if !subscription.client.same_channel(client) {
+                return true;
+            }
+            mcp_log_debug(
+                "EVENTS",
+                &format!("Removing subscription {id} of disconnected client"),
+            );
+            app.unlisten(subscription.listener);
+            false
+        });
+    }
+}
+
+/// Checks that an event name can be subscribed to.
+///
+/// Names must be valid Tauri event names (Tauri panics on others), and names starting
+/// with `__` are the bridge's own channels, such as `__script_result`.
+fn validate_event_name(event: &str) -> Result<(), String> {
+    if event.is_empty()
+        || !event
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '-' | '/' | ':' | '_'))
+    {
+        return Err(format!(
+            "Invalid event name '{event}': only alphanumeric characters, '-', '/', ':' and '_' are allowed"
+        ));
+    }
+    if event.starts_with("__") {
+        return Err(format!(
+            "Cannot subscribe to '{event}': events starting with '__' are internal to the bridge"
+        ));
+    }
+    Ok(())
+}
+
+/// Starts forwarding an app event to a WebSocket client.
+///
+/// Every emission of the event, from the backend or any webview, is pushed to the
+/// client as:
+///
+/// ```json
+/// { "type": "event", "subscriptionId": "…", "event": "user-logged-in", "payload": { "id": 42 } }
+/// ```
+///
+/// `payload` is the event's JSON payload. The subscription is removed by
+/// `unsubscribe_event` or when the client disconnects.
+///
+/// # Arguments
+///
+/// * `app` - The Tauri application handle
+/// * `event` - Name of the event to forward
+/// * `client` - Sends notifications to the subscribing client
+/// * `subscriptions` - Active subscriptions
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the subscription was created
+///   - `data`: `{ subscription_id, event }`
+///   - `error`: Error message (if the event name is invalid or internal)
+pub fn subscribe_event<R: Runtime>(
+    app: &AppHandle<R>,
+    event: &str,
+    client: UnboundedSender<String>,
+    subscriptions: &EventSubscriptions,
+) -> Result<Value, String> {
+    if let Err(error) = validate_event_name(event) {
+        return Ok(serde_json::json!({
+            "success": false,
+            "error": error
+        }));
+    }
+
+    let id = Uuid::new_v4().to_string();
+    let sender = client.clone();
+    let app_handle = app.clone();
+    let (subscription_id, event_name) = (id.clone(), event.to_string());
+
+    // Holding the map lock keeps an emission from arriving before the subscription
+    // is stored, which the listener would take for a stale subscription
+    let mut map = subscriptions.0.lock().unwrap_or_else(|e| e.into_inner());
+    let listener = app.listen_any(event, move |emitted| {
+        let payload = serde_json::from_str::<Value>(emitted.payload())
+            .unwrap_or_else(|_| Value::String(emitted.payload().to_string()));
+        let notification = serde_json::json!({
+            "type": "event",
+            "subscriptionId": subscription_id,
+            "event": event_name,
+            "payload": payload
+        });
+
+        // The client is gone, so drop the subscription outside of this listener
+        if sender.send(notification.to_string()).is_err() {
+            let app_handle = app_handle.clone();
+            let subscription_id = subscription_id.clone();
+            tauri::async_runtime::spawn(async move {
+                let subscriptions = app_handle.state::<EventSubscriptions>();
+                let removed = subscriptions
+                    .0
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .remove(&subscription_id);
+                if let Some(subscription) = removed {
+                    app_handle.unlisten(subscription.listener);
+                }
+            });
+        }
+    });
+    map.insert(
+        id.clone(),
+        EventSubscription {
+            event: event.to_string(),
+            listener,
+            client,
+        },
+    );
+
+    Ok(serde_json::json!({
+        "success": true,
+        "data": {
+            "subscription_id": id,
+            "event": event
+        }
+    }))
+}
+
+/// Stops forwarding an event.
+///
+/// # Arguments
+///
+/// * `app` - The Tauri application handle
+/// * `subscription_id` - ID returned by `subscribe_event`
+/// * `subscriptions` - Active subscriptions
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the subscription existed
+///   - `data`: `{ subscription_id, event }` (if it existed)
+///   - `error`: Error message (if no subscription has that ID)
+pub fn unsubscribe_event<R: Runtime>(
+    app: &AppHandle<R>,
+    subscription_id: &str,
+    subscriptions: &EventSubscriptions,
+) -> Result<Value, String> {
+    let removed = subscriptions
+        .0
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(subscription_id);
+
+    match removed {
+        Some(subscription) => {
+            app.unlisten(subscription.listener);
+            Ok(serde_json::json!({
+                "success": true,
+                "data": {
+                    "subscription_id": subscription_id,
+                    "event": subscription.event
+                }
+            }))
+        }
+        None => Ok(serde_json::json!({
+            "success": false,
+            "error": format!("No event subscription '{subscription_id}'")
+        })),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_app_event_names_are_accepted() {
+        assert!(validate_event_name("user-logged-in").is_ok());
+        assert!(validate_event_name("app:settings/changed").is_ok());
+        assert!(validate_event_name("my_event").is_ok());
+    }
+
+    #[test]
+    fn test_internal_and_invalid_names_are_rejected() {
+        assert!(validate_event_name("__script_result").is_err());
+        assert!(validate_event_name("__dom_mutations").is_err());
+        assert!(validate_event_name("").is_err());
+        assert!(validate_event_name("has space").is_err());
+        assert!(validate_event_name("dot.ted").is_err());
+    }
+}
@@ -1,27 +1,275 @@
 //! JavaScript execution in webview.
 
-use super::script_executor::ScriptExecutor;
-use crate::logging::mcp_log_error;
+use super::contexts::with_context;
+use super::handles::{handle_helper_js, HANDLE_REGISTRY_JS};
+use super::list_windows::target_window;
+use super::msgpack::{unpack_result, MSGPACK_SCRIPT};
+use super::script_executor::{PendingResult, ScriptExecutor};
+use crate::logging::{in_execution_span, mcp_log_debug, mcp_log_warn};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine as _;
+use serde::Deserialize;
 use serde_json::Value;
-use tauri::{command, Listener, Runtime, State, WebviewWindow};
+use std::time::Duration;
+use tauri::{command, Runtime, State, WebviewWindow};
 use tokio::sync::oneshot;
+use tokio::time::Instant;
 use uuid::Uuid;
 
+/// Default time to wait for a script result when no timeout is given.
+pub const DEFAULT_TIMEOUT_MS: u64 = 5000;
+
+/// How long the event path waits for a missing Tauri event API before giving up, in
+/// case the script was injected just before the API finished loading.
+const EVENT_API_RETRY_DELAY_MS: u64 = 250;
+
+/// Runs the prepared script in `frameSource` inside the iframe matching `frameSelector`.
+///
+/// The script is compiled with the frame's own `Function` constructor, so its globals
+/// (`window`, `document`, ...) are the frame's. Expects `frameSelector` and
+/// `frameSource` to be defined before it.
+const IN_FRAME_SCRIPT: &str = r#"
+function frameError(message) {
+    const error = new Error(message);
+    error.__mcpErrorKind = 'frame_unavailable';
+    return error;
+}
+const frame = document.querySelector(frameSelector);
+if (frame === null) {
+    throw frameError('No frame matches selector ' + JSON.stringify(frameSelector));
+}
+if (frame.tagName !== 'IFRAME' && frame.tagName !== 'FRAME') {
+    throw frameError('Element matching ' + JSON.stringify(frameSelector) + ' is a <'
+        + frame.tagName.toLowerCase() + '>, not an <iframe>');
+}
+// Cross-origin frames, and sandboxed ones without allow-same-origin, hide their document
+let frameDocument = null;
+try {
+    frameDocument = frame.contentDocument;
+} catch (e) {}
+if (frameDocument === null || !frame.contentWindow) {
+    throw frameError('Frame matching ' + JSON.stringify(frameSelector)
+        + ' is cross-origin or not loaded, so scripts can\'t run in it');
+}
+const FrameAsyncFunction = frame.contentWindow.Function('return (async function() {}).constructor')();
+let frameFunction;
+try {
+    frameFunction = new FrameAsyncFunction('__sendProgress', '__mcp', frameSource);
+} catch (error) {
+    error.__mcpParseError = true;
+    throw error;
+}
+// The frame's navigation doesn't unload this page, so nothing else would notice it
+const frameGone = new Promise(function(resolve, reject) {
+    frame.contentWindow.addEventListener('pagehide', function() {
+        const error = new Error('The frame navigated away or was removed before the script finished');
+        error.__mcpErrorKind = 'navigated_during_execution';
+        reject(error);
+    }, { once: true });
+});
+return await Promise.race([frameFunction(__sendProgress, __mcp), frameGone]);
+"#;
+
+/// Runs the script in `__mcpSource` and returns the part of its value at
+/// `__mcpResultPath`, or a marker object when the path doesn't exist.
+///
+/// Expects `__mcpResultPath` to be defined before it, and the prepared script to follow
+/// it as the body of `__mcpSource`.
+const RESULT_PATH_SCRIPT: &str = r#"
+let __mcpValue = await __mcpSource();
+let __mcpMatched = true;
+for (const key of __mcpResultPath) {
+    if (__mcpValue === null || __mcpValue === undefined || !(key in Object(__mcpValue))) {
+        __mcpMatched = false;
+        break;
+    }
+    __mcpValue = __mcpValue[key];
+}
+__mcpMatched ? __mcpValue : { __mcpPathUnmatched: true }
+"#;
+
+/// Runs `__mcpSource` while collecting what it logs to the console.
+///
+/// Expects `__mcpSource` to be defined before it, and to run on the event path, where
+/// `__mcp` exists. The console is patched once for all the captures in progress, each
+/// registered under its execution ID, and restored when the last one ends, even if
+/// the script throws. Its lines are left in `done` for the wrapper's result message.
+const CAPTURE_CONSOLE_SCRIPT: &str = r#"
+const __mcpCaptures = window.__MCP_CONSOLE_CAPTURES__
+    || (window.__MCP_CONSOLE_CAPTURES__ = { active: new Map(), done: {}, original: null });
+if (__mcpCaptures.original === null) {
+    const original = {};
+    ['log', 'debug', 'info', 'warn', 'error'].forEach(function(level) {
+        original[level] = console[level];
+        console[level] = function() {
+            const args = Array.prototype.slice.call(arguments);
+            try {
+                const message = args.map(function(arg) {
+                    if (arg instanceof Error) return arg.stack || String(arg);
+                    if (typeof arg === 'object' && arg !== null) {
+                        try { return JSON.stringify(arg); } catch (e) { return String(arg); }
+                    }
+                    return String(arg);
+                }).join(' ');
+                const timestamp = Date.now();
+                __mcpCaptures.active.forEach(function(lines) {
+                    lines.push({ level: level, message: message, timestamp: timestamp });
+                });
+            } catch (e) {
+                // Capture is best-effort; never break the app's own logging
+            }
+            return original[level].apply(console, args);
+        };
+    });
+    __mcpCaptures.original = original;
+}
+const __mcpConsoleLines = [];
+__mcpCaptures.active.set(__mcp.execId, __mcpConsoleLines);
+let __mcpCaptured;
+try {
+    __mcpCaptured = await __mcpSource();
+} finally {
+    __mcpCaptures.active.delete(__mcp.execId);
+    __mcpCaptures.done[__mcp.execId] = __mcpConsoleLines;
+    if (__mcpCaptures.active.size === 0) {
+        const original = __mcpCaptures.original;
+        Object.keys(original).forEach(function(level) { console[level] = original[level]; });
+        __mcpCaptures.original = null;
+    }
+}
+__mcpCaptured
+"#;
+
+/// Waits until `document.readyState` reaches `__mcpReadyState`, listening for
+/// `readystatechange`.
+///
+/// Expects `__mcpReadyState` to be defined before it, and the script to follow it.
+/// Everything else stays inside the promise, so it can't clash with the script's names.
+const WAIT_READY_STATE_SCRIPT: &str = r#"
+await new Promise(function(resolve) {
+    const order = ['loading', 'interactive', 'complete'];
+    function reached() {
+        return order.indexOf(document.readyState) >= order.indexOf(__mcpReadyState);
+    }
+    if (reached()) {
+        resolve();
+        return;
+    }
+    document.addEventListener('readystatechange', function onChange() {
+        if (reached()) {
+            document.removeEventListener('readystatechange', onChange);
+            resolve();
+        }
+    });
+});
+"#;
+
+/// Which failures `execute_js` retries.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RetryOn {
+    /// Only executions that timed out
+    Timeout,
+    /// Only executions that failed some other way, like a script that threw
+    Error,
+    /// Both
+    #[default]
+    Any,
+}
+
+/// How `execute_js` retries a failed execution.
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct RetryPolicy {
+    /// Total number of tries, including the first (defaults to 3)
+    #[serde(default = "RetryPolicy::default_attempts")]
+    pub attempts: u32,
+    /// Delay before the first retry in milliseconds, doubled for each further retry
+    /// (defaults to 500)
+    #[serde(default = "RetryPolicy::default_delay_ms", alias = "delayMs")]
+    pub delay_ms: u64,
+    #[serde(default)]
+    pub on: RetryOn,
+}
+
+impl RetryPolicy {
+    /// Upper limit for `attempts`, so a typo can't keep a call busy for hours.
+    const MAX_ATTEMPTS: u32 = 20;
+
+    fn default_attempts() -> u32 {
+        3
+    }
+
+    fn default_delay_ms() -> u64 {
+        500
+    }
+
+    /// Returns whether a failed execution's result is worth another try.
+    ///
+    /// Syntax errors and cancelled executions never are, since running the same script
+    /// again ends the same way.
+    fn should_retry(&self, result: &Value) -> bool {
+        if result["success"] == true {
+            return false;
+        }
+        match result["error_kind"].as_str() {
+            Some("parse_error" | "cancelled") => false,
+            Some("timeout") => self.on != RetryOn::Error,
+            _ => self.on != RetryOn::Timeout,
+        }
+    }
+
+    /// Delay before retry number `retry`, counting from 1.
+    fn delay(&self, retry: u32) -> Duration {
+        let factor = 1u64 << (retry - 1).min(16);
+        Duration::from_millis(self.delay_ms.saturating_mul(factor))
+    }
+}
+
+/// Error reported when the webview has no Tauri event API to send results through.
+const EVENT_API_UNAVAILABLE: &str = "Tauri event API unavailable in webview";
+
+/// Error reported by the wrapper when its page unloads before the script finished.
+const NAVIGATED_DURING_EXECUTION: &str = "The page navigated away before the script finished";
+
 /// Executes JavaScript code in the webview context.
 ///
-/// This command evaluates arbitrary JavaScript in the webview and returns the result.
+/// The script's last expression is its result, and it may use `await`. The "Running
+/// Scripts" section of the plugin README describes the options below, how results
+/// that aren't JSON are returned, and the `error_kind` values of failures.
 ///
 /// # Arguments
 ///
 /// * `window` - The Tauri window handle
 /// * `script` - JavaScript code to execute
+/// * `args` - Value available to the script as `$args`
+/// * `context` - Name of a context whose state the script shares with other scripts as
+///   `$context`
+/// * `timeout_ms` - Maximum time to wait for the result in milliseconds (defaults to 5000)
+/// * `exec_id` - Execution ID, so the call can be cancelled with `cancel_execution`
+/// * `isolated` - Run the script in a realm with its own globals (defaults to false)
+/// * `decode_binary` - Return binary results as byte values instead of base64
+/// * `frame_selector` - CSS selector of an `<iframe>` to run the script in
+/// * `result_path` - Path of the part of the result to return, like `user.roles[0]`
+/// * `retry` - `{ attempts, delay_ms, on }` to run the script again when it fails
+/// * `wait_for_result` - Wait for the script's result (defaults to true)
+/// * `wait_ready_state` - `"interactive"` or `"complete"` to wait for before running
+/// * `capture_console` - Return the console lines the script logs (defaults to false)
+/// * `encoding` - `"json"` (the default) or `"msgpack"`, how the result is sent
+/// * `window_label` - Label of the window to target (defaults to the calling window)
 ///
 /// # Returns
 ///
 /// * `Ok(Value)` - JSON object containing:
 ///   - `success`: Whether execution succeeded
-///   - `result`: The result of the script execution (if successful)
-///   - `error`: Error message (if failed)
+///   - `data`: The result of the script execution (if successful)
+///   - `result_type`: `typeof` the result, or `"null"` (if successful)
+///   - `error`, `error_stack`, `error_kind`: What went wrong (if failed)
+///   - `exec_id`: The ID of this execution
+///   - `duration_ms`, `script_ms`: Time until the result arrived, and time the script ran
+///   - `last_progress`: The latest reported progress (if timed out)
+///   - `path_matched`, `attempts_made`, `attempt_exec_ids`, `console`: Set by the
+///     matching arguments
+///   - `truncated`, `original_size`: Set when `data` was larger than the maximum size
 ///
 /// # Examples
 ///
@@ -29,97 +277,816 @@ use uuid::Uuid;
 /// import { invoke } from '@tauri-apps/api/core';
 ///
 /// const result = await invoke('plugin:mcp-bridge|execute_js', {
-///   script: 'document.title'
+///   script: 'document.querySelector($args.selector).textContent',
+///   args: { selector: 'h1' }
 /// });
-/// console.log(result.result); // Page title
+/// console.log(result.data); // Heading text
 /// ```
 #[command]
+#[allow(clippy::too_many_arguments)]
 pub async fn execute_js<R: Runtime>(
     window: WebviewWindow<R>,
     script: String,
+    args: Option<Value>,
+    context: Option<String>,
+    timeout_ms: Option<u64>,
+    exec_id: Option<String>,
+    isolated: Option<bool>,
+    decode_binary: Option<bool>,
+    frame_selector: Option<String>,
+    result_path: Option<String>,
+    retry: Option<RetryPolicy>,
+    wait_for_result: Option<bool>,
+    wait_ready_state: Option<String>,
+    capture_console: Option<bool>,
+    encoding: Option<String>,
+    window_label: Option<String>,
     state: State<'_, ScriptExecutor>,
 ) -> Result<Value, String> {
-    // Generate unique execution ID
-    let exec_id = Uuid::new_v4().to_string();
+    if let Err(blocked) = state.script_policy.check(&script) {
+        let exec_id = exec_id.unwrap_or_else(|| Uuid::new_v4().to_string());
+        let result = blocked.to_result(&exec_id);
+        mcp_log_warn(
+            "EXECUTE_JS",
+            &format!("Execution {exec_id} blocked: {}", result["error"]),
+        );
+        return Ok(result);
+    }
 
-    // Create oneshot channel for the result
-    let (tx, rx) = oneshot::channel();
+    let msgpack = match encoding.as_deref() {
+        None | Some("json") => false,
+        Some("msgpack") if wait_for_result == Some(false) => {
+            return Ok(invalid_argument(
+                "encoding: \"msgpack\" can't be combined with wait_for_result: false",
+            ));
+        }
+        Some("msgpack") => true,
+        Some(encoding) => {
+            return Ok(invalid_argument(format!(
+                "Invalid encoding '{encoding}': expected \"json\" or \"msgpack\""
+            )));
+        }
+    };
 
-    // Store the sender for when result comes back
-    {
-        let mut pending = state.pending_results.lock().await;
-        pending.insert(exec_id.clone(), tx);
-    }
-
-    // Set up event listener for the result
-    let exec_id_clone = exec_id.clone();
-    let pending_clone = state.pending_results.clone();
-
-    let unlisten = window.listen("__script_result", move |event| {
-        let raw_payload = event.payload();
-
-        match serde_json::from_str::<serde_json::Map<String, Value>>(raw_payload) {
-            Ok(payload) => {
-                if let Some(Value::String(event_exec_id)) = payload.get("exec_id") {
-                    if event_exec_id == &exec_id_clone {
-                        // Forward to our result handler
-                        let pending = pending_clone.clone();
-                        let payload = payload.clone();
-                        let exec_id_for_task = exec_id_clone.clone();
-
-                        tokio::spawn(async move {
-                            let mut pending_guard = pending.lock().await;
-                            if let Some(sender) = pending_guard.remove(&exec_id_for_task) {
-                                let result = if payload
-                                    .get("success")
-                                    .and_then(|v| v.as_bool())
-                                    .unwrap_or(false)
-                                {
-                                    serde_json::json!({
-                                        "success": true,
-                                        "data": payload.get("data").cloned().unwrap_or(Value::Null)
-                                    })
-                                } else {
-                                    serde_json::json!({
-                                        "success": false,
-                                        "error": payload.get("error")
-                                            .and_then(|v| v.as_str())
-                                            .unwrap_or("Unknown error")
-                                    })
-                                };
-
-                                let _ = sender.send(result);
-                            }
-                        });
-                    }
-                }
+    let script = match args {
+        Some(args) => with_args(&args, &script),
+        None => script,
+    };
+
+    let script = match context.as_deref() {
+        Some("") => {
+            return Ok(invalid_argument("context can't be empty"));
+        }
+        Some(context) => with_context(context, &script),
+        None => script,
+    };
+
+    let script = match wait_ready_state.as_deref() {
+        Some(ready_state @ ("interactive" | "complete")) => with_ready_state(ready_state, &script),
+        Some(ready_state) => {
+            return Ok(invalid_argument(format!(
+                    "Invalid wait_ready_state '{ready_state}': expected \"interactive\" or \"complete\""
+                )));
+        }
+        None => script,
+    };
+
+    let script = if capture_console.unwrap_or(false) {
+        if isolated.unwrap_or(false) || frame_selector.is_some() || wait_for_result == Some(false) {
+            return Ok(invalid_argument("capture_console can't be combined with isolated, frame_selector or wait_for_result: false"));
+        }
+        with_console_capture(&script)
+    } else {
+        script
+    };
+
+    let script = match frame_selector {
+        Some(_) if isolated.unwrap_or(false) => {
+            return Ok(invalid_argument(
+                "isolated and frame_selector can't be combined",
+            ));
+        }
+        Some(frame_selector) => in_frame(&frame_selector, &script),
+        None => script,
+    };
+
+    if !wait_for_result.unwrap_or(true) {
+        // These only shape the result or how long to wait for it, and nothing waits
+        let waiting_only = [
+            ("retry", retry.is_some()),
+            ("timeout_ms", timeout_ms.is_some()),
+            ("result_path", result_path.is_some()),
+            ("decode_binary", decode_binary.unwrap_or(false)),
+        ];
+        if let Some((name, _)) = waiting_only.iter().find(|(_, given)| *given) {
+            return Ok(invalid_argument(format!(
+                "{name} can't be combined with wait_for_result: false"
+            )));
+        }
+        return run_detached(
+            window,
+            script,
+            exec_id,
+            isolated,
+            window_label,
+            &state.result_channel,
+        );
+    }
+
+    if let Some(retry) = retry {
+        if !(1..=RetryPolicy::MAX_ATTEMPTS).contains(&retry.attempts) {
+            return Ok(invalid_argument(format!(
+                "Invalid retry attempts {}: must be between 1 and {}",
+                retry.attempts,
+                RetryPolicy::MAX_ATTEMPTS
+            )));
+        }
+    }
+
+    let (script, has_result_path) = match result_path.as_deref().map(parse_result_path) {
+        Some(Ok(path)) => (with_result_path(&path, &script), true),
+        Some(Err(error)) => {
+            return Ok(invalid_argument(error));
+        }
+        None => (script, false),
+    };
+    let script = if msgpack {
+        with_msgpack(&script)
+    } else {
+        script
+    };
+
+    let mut attempts_made = 1;
+    let mut result = run_script(
+        window.clone(),
+        script.clone(),
+        timeout_ms,
+        attempt_exec_id(exec_id.as_deref(), attempts_made),
+        isolated,
+        decode_binary,
+        window_label.clone(),
+        state.clone(),
+    )
+    .await?;
+
+    if let Some(retry) = retry {
+        let mut attempt_exec_ids = Vec::new();
+        while attempts_made < retry.attempts && retry.should_retry(&result) {
+            let delay = retry.delay(attempts_made);
+            mcp_log_debug(
+                "EXECUTE_JS",
+                &format!(
+                    "Attempt {attempts_made} failed ({}), retrying in {}ms",
+                    result["error_kind"],
+                    delay.as_millis()
+                ),
+            );
+            tokio::time::sleep(delay).await;
+            attempts_made += 1;
+            attempt_exec_ids.push(result["exec_id"].take());
+            result = run_script(
+                window.clone(),
+                script.clone(),
+                timeout_ms,
+                attempt_exec_id(exec_id.as_deref(), attempts_made),
+                isolated,
+                decode_binary,
+                window_label.clone(),
+                state.clone(),
+            )
+            .await?;
+        }
+        attempt_exec_ids.push(result["exec_id"].clone());
+        result["attempts_made"] = Value::from(attempts_made);
+        result["attempt_exec_ids"] = Value::Array(attempt_exec_ids);
+    }
+
+    if msgpack && result["success"] == true {
+        let unpacked = unpack_result(&mut result).and_then(|()| {
+            if decode_binary.unwrap_or(false) {
+                decode_binary_data(&mut result["data"])
+            } else {
+                Ok(())
             }
-            Err(e) => {
-                mcp_log_error(
-                    "EXECUTE_JS",
-                    &format!("Failed to parse __script_result payload: {e}. Raw: {raw_payload}"),
-                );
+        });
+        if let Err(error) = unpacked {
+            result["success"] = Value::Bool(false);
+            result["data"] = Value::Null;
+            result["error"] = Value::String(error);
+            result["error_kind"] = Value::from("runtime_error");
+        }
+    }
+
+    if has_result_path && result["success"] == true {
+        let matched = result["data"].get("__mcpPathUnmatched") != Some(&Value::Bool(true));
+        if !matched {
+            result["data"] = Value::Null;
+            result["result_type"] = Value::String("undefined".to_string());
+        }
+        result["path_matched"] = Value::Bool(matched);
+    }
+    Ok(result)
+}
+
+/// Builds the result of a call whose arguments can't be used, so the script never ran.
+fn invalid_argument(error: impl Into<String>) -> Value {
+    serde_json::json!({
+        "success": false,
+        "error": error.into(),
+        "error_kind": "invalid_argument"
+    })
+}
+
+/// Binds `args` to `$args` for the script.
+///
+/// A single expression is wrapped in an arrow function called with the arguments, so
+/// it stays a single expression and keeps the fast path. Anything else gets a `const`
+/// declaration in front.
+fn with_args(args: &Value, script: &str) -> String {
+    if is_sync_expression(script) {
+        let expression = script.trim().trim_end_matches(';');
+        format!("(($args) => (\n{expression}\n))({args})")
+    } else {
+        format!("const $args = {args};\n{script}")
+    }
+}
+
+/// Makes a script wait for the document to reach `ready_state` before it runs.
+fn with_ready_state(ready_state: &str, script: &str) -> String {
+    let ready_state = Value::String(ready_state.to_string());
+    format!("const __mcpReadyState = {ready_state};\n{WAIT_READY_STATE_SCRIPT}{script}")
+}
+
+/// Wraps a script so the console lines it logs are returned with its result.
+fn with_console_capture(script: &str) -> String {
+    format!(
+        "const __mcpSource = async () => {{\n{}\n}};\n{CAPTURE_CONSOLE_SCRIPT}",
+        prepare_script(script)
+    )
+}
+
+/// Wraps a script so its value is returned encoded as MessagePack.
+fn with_msgpack(script: &str) -> String {
+    format!(
+        "const __mcpSource = async () => {{\n{}\n}};\n{MSGPACK_SCRIPT}",
+        prepare_script(script)
+    )
+}
+
+/// Splits a dotted or JSONPath-style path into property names and array indices.
+///
+/// Accepts `a.b.0`, `$.a.b[0]` and `$['a']["b"]`. An empty path or `$` alone selects
+/// the whole result.
+fn parse_result_path(path: &str) -> Result<Vec<Value>, String> {
+    let invalid = |reason: &str| format!("Invalid result_path '{path}': {reason}");
+    let trimmed = path.trim();
+    // `$` is the root only when a step or nothing follows, so `$el.id` is a property
+    let root = trimmed
+        .strip_prefix('$')
+        .filter(|rest| rest.is_empty() || rest.starts_with(['.', '[']));
+    let mut rest = root.unwrap_or(trimmed);
+    let mut segments = Vec::new();
+    let mut first = root.is_none();
+
+    while !rest.is_empty() {
+        if let Some(bracketed) = rest.strip_prefix('[') {
+            let quote = bracketed.chars().next().filter(|c| *c == '\'' || *c == '"');
+            let (segment, after) = if let Some(quote) = quote {
+                let end = bracketed[1..]
+                    .find(quote)
+                    .ok_or_else(|| invalid("unterminated quoted name"))?;
+                let name = &bracketed[1..end + 1];
+                (Value::String(name.to_string()), &bracketed[end + 2..])
+            } else {
+                let end = bracketed.find(']').ok_or_else(|| invalid("missing ']'"))?;
+                let index = bracketed[..end].trim();
+                let index = index.parse::<u64>().map_err(|_| {
+                    invalid(&format!(
+                        "'[{index}]' is not an array index or a quoted name"
+                    ))
+                })?;
+                (Value::from(index), &bracketed[end..])
+            };
+            rest = after
+                .strip_prefix(']')
+                .ok_or_else(|| invalid("missing ']'"))?;
+            segments.push(segment);
+        } else {
+            let name = rest
+                .strip_prefix('.')
+                .or(first.then_some(rest))
+                .ok_or_else(|| invalid("expected '.' or '['"))?;
+            if name.starts_with('.') {
+                return Err(invalid("recursive descent ('..') is not supported"));
+            }
+            let end = name.find(['.', '[']).unwrap_or(name.len());
+            let key = &name[..end];
+            if key.is_empty() {
+                return Err(invalid("empty property name"));
             }
+            if key == "*" {
+                return Err(invalid("wildcards are not supported"));
+            }
+            segments.push(Value::String(key.to_string()));
+            rest = &name[end..];
         }
-    });
+        first = false;
+    }
+
+    Ok(segments)
+}
+
+/// Wraps a script so it returns only the part of its value at `path`.
+///
+/// The script becomes the body of an inner async function, so its own declarations
+/// can't clash with the extraction code. The result always takes the event path.
+fn with_result_path(path: &[Value], script: &str) -> String {
+    let path = Value::Array(path.to_vec());
+    format!(
+        "const __mcpResultPath = {path};\nconst __mcpSource = async () => {{\n{}\n}};\n{RESULT_PATH_SCRIPT}",
+        prepare_script(script)
+    )
+}
+
+/// Wraps a script so it runs inside the `<iframe>` matching `frame_selector`.
+///
+/// The value of the script's last expression is returned as usual. The result always
+/// takes the event path.
+pub(crate) fn in_frame(frame_selector: &str, script: &str) -> String {
+    let selector = Value::String(frame_selector.to_string());
+    let source = Value::String(prepare_script(script));
+    format!("const frameSelector = {selector};\nconst frameSource = {source};\n{IN_FRAME_SCRIPT}")
+}
+
+/// Starts a script without waiting for it, for `wait_for_result: false`.
+fn run_detached<R: Runtime>(
+    window: WebviewWindow<R>,
+    script: String,
+    exec_id: Option<String>,
+    isolated: Option<bool>,
+    window_label: Option<String>,
+    result_channel: &str,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    let exec_id = exec_id.unwrap_or_else(|| Uuid::new_v4().to_string());
+    let exec_id_js = serde_json::to_string(&exec_id).map_err(|e| e.to_string())?;
+    let prefix = format!("__mcp_{}", &Uuid::new_v4().simple().to_string()[..12]);
+    let wrapped_script = detached_script(
+        &exec_id_js,
+        &prefix,
+        result_channel,
+        &prepare_script(&script),
+        isolated.unwrap_or(false),
+    );
+
+    if let Err(e) = window.eval(&wrapped_script) {
+        mcp_log_warn("EXECUTE_JS", &format!("Failed to inject script: {e}"));
+        return Ok(serde_json::json!({
+            "success": false,
+            "error": format!("Failed to execute script: {}", e),
+            "error_kind": "injection_failed",
+            "exec_id": exec_id
+        }));
+    }
+    mcp_log_debug(
+        "EXECUTE_JS",
+        &format!("Started detached execution {exec_id}"),
+    );
+
+    Ok(serde_json::json!({
+        "success": true,
+        "exec_id": exec_id
+    }))
+}
+
+/// Runs a script like `execute_js`, without checking it against the script rules.
+///
+/// For the bridge's own scripts. Scripts from clients go through `execute_js`.
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn run_script<R: Runtime>(
+    window: WebviewWindow<R>,
+    script: String,
+    timeout_ms: Option<u64>,
+    exec_id: Option<String>,
+    isolated: Option<bool>,
+    decode_binary: Option<bool>,
+    window_label: Option<String>,
+    state: State<'_, ScriptExecutor>,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    let timeout_ms = timeout_ms.unwrap_or(DEFAULT_TIMEOUT_MS);
+    let deadline = Instant::now() + Duration::from_millis(timeout_ms);
+    let chunk_threshold = state.chunk_threshold;
+    let max_function_source = state.max_function_source;
+    let result_channel = state.result_channel.clone();
+
+    // Use the caller's execution ID or generate a unique one
+    let exec_id = exec_id.unwrap_or_else(|| Uuid::new_v4().to_string());
+    let exec_id_js = serde_json::to_string(&exec_id).map_err(|e| e.to_string())?;
+
+    in_execution_span(&exec_id.clone(), async {
+        // Time spent waiting for a slot counts against the timeout
+        let _slot = match state.acquire_slot(deadline).await {
+            Ok(slot) => slot,
+            Err(error) => {
+                return Ok(serde_json::json!({
+                    "success": false,
+                    "error": error,
+                    "error_kind": "busy",
+                    "exec_id": exec_id
+                }));
+            }
+        };
+
+        // Create oneshot channel for the result
+        let (tx, rx) = oneshot::channel();
+
+        // Store the sender for when result comes back
+        {
+            let mut pending = state.pending_results.lock(&exec_id).await;
+            // Checked under the lock of the execution's shard, which a shutdown draining
+            // the pending results takes too, so it can't miss an execution that started
+            // in the meantime
+            if state.is_shutting_down() {
+                return Ok(serde_json::json!({
+                    "success": false,
+                    "error": "The app is shutting down",
+                    "error_kind": "shutting_down",
+                    "exec_id": exec_id
+                }));
+            }
+            if pending.contains_key(&exec_id) {
+                return Ok(serde_json::json!({
+                    "success": false,
+                    "error": format!("Execution '{exec_id}' is already in progress"),
+                    "error_kind": "busy",
+                    "exec_id": exec_id
+                }));
+            }
+            pending.insert(
+                exec_id.clone(),
+                PendingResult::new(tx, window.label(), Duration::from_millis(timeout_ms)),
+            );
+        }
+
+        // Helper names are unique per execution so scripts can't collide with them
+        let prefix = format!("__mcp_{}", &Uuid::new_v4().simple().to_string()[..12]);
+
+        // Simple synchronous expressions skip the async wrapper and the event round trip
+        let isolated = isolated.unwrap_or(false);
+        let fast_path = !isolated && is_sync_expression(&script) && !uses_helpers(&script);
+        mcp_log_debug(
+            "EXECUTE_JS",
+            &format!(
+                "Running script on the {} path",
+                if fast_path { "fast" } else { "event" }
+            ),
+        );
+        let (wrapped_script, function_body) = if fast_path {
+            (
+                fast_path_script(&exec_id_js, &prefix, &script, max_function_source),
+                fast_path_body(&script),
+            )
+        } else {
+            let prepared = prepare_script(&script);
+            (
+                event_path_script(
+                    &exec_id_js,
+                    &prefix,
+                    &result_channel,
+                    chunk_threshold,
+                    max_function_source,
+                    &prepared,
+                    isolated,
+                ),
+                prepared,
+            )
+        };
+
+        // Execute the wrapped script
+        let eval_started = Instant::now();
+        if let Err(e) = window.eval(&wrapped_script) {
+            // Clean up pending result on error
+            state.pending_results.remove(&exec_id).await;
+            mcp_log_warn("EXECUTE_JS", &format!("Failed to inject script: {e}"));
+
+            return Ok(serde_json::json!({
+                "success": false,
+                "error": format!("Failed to execute script: {}", e),
+                "error_kind": "injection_failed",
+                "exec_id": exec_id
+            }));
+        }
+
+        // A syntax error stops the whole wrapper from running, so nothing would report it.
+        // This separate script notices and reports a parse error instead of a timeout.
+        let _ = window.eval(parse_check_script(&exec_id_js, &prefix, &function_body));
+
+        // Wait for result with timeout
+        let mut result = match tokio::time::timeout_at(deadline, rx).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(_)) => {
+                // Channel was dropped, either by cancel_execution or unexpectedly
+                let (error, error_kind) = if state.cancelled.lock().await.remove(&exec_id) {
+                    ("Script execution cancelled", "cancelled")
+                } else {
+                    ("Script execution failed: channel closed", "channel_closed")
+                };
+                serde_json::json!({
+                    "success": false,
+                    "error": error,
+                    "error_kind": error_kind
+                })
+            }
+            Err(_) => {
+                // Timeout - clean up pending result and any chunks received so far
+                state.pending_results.remove(&exec_id).await;
+                state.partial_results.lock().await.discard(&exec_id);
+                mcp_log_warn("EXECUTE_JS", &format!("No result within {timeout_ms}ms"));
+
+                serde_json::json!({
+                    "success": false,
+                    "error": format!("Script execution timeout after {timeout_ms}ms"),
+                    "error_kind": "timeout"
+                })
+            }
+        };
+
+        // The execution is no longer pending, so no further progress can be recorded
+        let last_progress = state.take_progress(&exec_id).await;
+        if result["error_kind"] == "timeout" {
+            if let Some(last_progress) = last_progress {
+                result["last_progress"] = last_progress;
+            }
+        }
+
+        if decode_binary.unwrap_or(false) {
+            if let Some(data) = result.get_mut("data") {
+                if let Err(error) = decode_binary_data(data) {
+                    result = serde_json::json!({
+                        "success": false,
+                        "error": error,
+                        "error_kind": "runtime_error"
+                    });
+                }
+            }
+        }
+
+        result["duration_ms"] = Value::from(round_ms(eval_started.elapsed()));
+        result["exec_id"] = Value::String(exec_id);
+        Ok(result)
+    })
+    .await
+}
+
+/// Returns the execution ID to use for a retry's `attempt`, counting from 1.
+///
+/// Every attempt gets its own ID, so a late result of an attempt that timed out can't
+/// complete the next one. The first attempt keeps the caller's ID, and later ones
+/// append `#{attempt}` to it. Without an ID from the caller, each attempt generates one.
+fn attempt_exec_id(exec_id: Option<&str>, attempt: u32) -> Option<String> {
+    match exec_id {
+        Some(exec_id) if attempt > 1 => Some(format!("{exec_id}#{attempt}")),
+        exec_id => exec_id.map(str::to_string),
+    }
+}
+
+/// Converts a duration to milliseconds, rounded to two decimals like `script_ms`.
+fn round_ms(duration: Duration) -> f64 {
+    (duration.as_secs_f64() * 100_000.0).round() / 100.0
+}
+
+/// Decodes the bytes of a binary result, if `data` is one.
+///
+/// Returns `None` for anything but a `{ __binary: true, encoding: "base64", data }`
+/// marker produced by the injected wrapper.
+pub fn binary_result_bytes(data: &Value) -> Option<Result<Vec<u8>, String>> {
+    if data.get("__binary").and_then(|v| v.as_bool()) != Some(true)
+        || data.get("encoding").and_then(|v| v.as_str()) != Some("base64")
+    {
+        return None;
+    }
+
+    let encoded = data
+        .get("data")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default();
+    Some(
+        STANDARD
+            .decode(encoded)
+            .map_err(|e| format!("Failed to decode binary result: {e}")),
+    )
+}
 
-    // Prepare the script with appropriate return handling
-    let prepared_script = prepare_script(&script);
+/// Replaces the base64 payload of a binary result with its byte values.
+fn decode_binary_data(data: &mut Value) -> Result<(), String> {
+    let Some(bytes) = binary_result_bytes(data).transpose()? else {
+        return Ok(());
+    };
+    data["data"] = Value::from(bytes);
+    data["encoding"] = Value::from("bytes");
+    Ok(())
+}
+
+/// Defines `{prefix}_encodeBinary(value)`, which turns binary results into base64 markers.
+///
+/// Buffers and typed arrays are encoded right away; a `Blob` has to be read first, so
+/// a promise is returned for it. Anything else is returned unchanged. Types are checked
+/// by tag rather than `instanceof`, so values from the isolated realm are recognized.
+fn binary_helper_js(prefix: &str) -> String {
+    format!(
+        r#"
+            function {prefix}_encodeBinary(value) {{
+                function encode(bytes, mime) {{
+                    var binary = '';
+                    for (var i = 0; i < bytes.length; i += 0x8000) {{
+                        binary += String.fromCharCode.apply(null, bytes.subarray(i, i + 0x8000));
+                    }}
+                    return {{ __binary: true, encoding: 'base64', mime: mime, data: btoa(binary) }};
+                }}
+                var tag = Object.prototype.toString.call(value);
+                if (tag === '[object Blob]' || tag === '[object File]') {{
+                    return value.arrayBuffer().then(function(buffer) {{
+                        return encode(new Uint8Array(buffer), value.type || 'application/octet-stream');
+                    }});
+                }}
+                if (tag === '[object ArrayBuffer]') {{
+                    return encode(new Uint8Array(value), 'application/octet-stream');
+                }}
+                if (ArrayBuffer.isView(value)) {{
+                    return encode(
+                        new Uint8Array(value.buffer, value.byteOffset, value.byteLength),
+                        'application/octet-stream'
+                    );
+                }}
+                return value;
+            }}
+        "#
+    )
+}
 
-    // Create wrapped script that uses event emission for result communication
-    // We use a double-wrapped approach to catch both parse and runtime errors
-    let wrapped_script = format!(
+/// Defines `{prefix}_encodeFunction(value)`, which turns a function into a source marker.
+///
+/// The source comes from `Function.prototype.toString` and is cut off after
+/// `max_source` characters. Anything else is returned unchanged.
+fn function_helper_js(prefix: &str, max_source: usize) -> String {
+    format!(
+        r#"
+            function {prefix}_encodeFunction(value) {{
+                if (typeof value !== 'function') {{
+                    return value;
+                }}
+                var source;
+                try {{
+                    source = Function.prototype.toString.call(value);
+                }} catch (e) {{
+                    source = null;
+                }}
+                var encoded = {{ __function: true, name: value.name || null, source: source }};
+                if (source !== null && source.length > {max_source}) {{
+                    encoded.source = source.slice(0, {max_source});
+                    encoded.truncated = true;
+                    encoded.original_length = source.length;
+                }}
+                return encoded;
+            }}
+        "#
+    )
+}
+
+/// Wraps a script so its result is sent back through `result_channel` events.
+///
+/// The script runs inside an async function, so it may use `await` and may return a
+/// promise. Results over `chunk_threshold` characters are split into events on
+/// `result_channel` with `_chunk` appended. A double-wrapped approach catches both parse and
+/// runtime errors. When `isolated` is set the script runs in a separate realm, see
+/// [`isolated_function_js`].
+///
+/// The script can call `__sendProgress(data)`, which emits an event on `result_channel`
+/// with `_progress` appended for this execution, and use the helpers in `__mcp` (see [`helpers_js`]).
+///
+/// Before the script runs, the wrapper checks that the event API exists. If it's still
+/// missing after one retry, the failure is reported through the `script_result` command
+/// instead, which only needs the IPC internals that Tauri always injects.
+fn event_path_script(
+    exec_id_js: &str,
+    prefix: &str,
+    result_channel: &str,
+    chunk_threshold: usize,
+    max_function_source: usize,
+    prepared_script: &str,
+    isolated: bool,
+) -> String {
+    let result_channel_js = Value::String(result_channel.to_string());
+    let chunk_channel_js = Value::String(format!("{result_channel}_chunk"));
+    let progress_channel_js = Value::String(format!("{result_channel}_progress"));
+    let unavailable_js = Value::String(EVENT_API_UNAVAILABLE.to_string());
+    let navigated_js = Value::String(NAVIGATED_DURING_EXECUTION.to_string());
+    let binary_helper = binary_helper_js(prefix);
+    let handle_helper = handle_helper_js(prefix);
+    let function_helper = function_helper_js(prefix, max_function_source);
+    let helpers = helpers_js(exec_id_js, result_channel);
+    let (isolated_helper, execute_script) = if isolated {
+        let source = Value::String(prepared_script.to_string());
+        (
+            isolated_function_js(prefix),
+            format!("{prefix}_isolatedFunction({source}, __sendProgress, __mcp)"),
+        )
+    } else {
+        (
+            String::new(),
+            format!("async () => {{\n{prepared_script}\n}}"),
+        )
+    };
+
+    format!(
         r#"
         (function() {{
+            // Tells the parse check that ran after this script that it was parsed
+            window['{prefix}_started'] = true;
+
+            // When the user script started, for `script_ms`
+            var {prefix}_startedAt = null;
+
+            function {prefix}_scriptMs() {{
+                return {prefix}_startedAt === null
+                    ? null
+                    : Math.round((performance.now() - {prefix}_startedAt) * 100) / 100;
+            }}
+
+            // Whether a result was sent, so a script resumed from the back-forward cache
+            // after its navigation was reported can't report again
+            var {prefix}_sent = false;
+
             // Helper to send result back - checks for __TAURI__ availability
-            function __sendResult(success, data, error) {{
+            function {prefix}_sendResult(success, data, error, stack, resultType, errorKind) {{
+                if ({prefix}_sent) {{
+                    return;
+                }}
+                {prefix}_sent = true;
+                window.removeEventListener('pagehide', {prefix}_onPageHide);
+
+                // Lines from `capture_console`, taken even when nobody waits for them
+                var captures = window.__MCP_CONSOLE_CAPTURES__;
+                var consoleLines = captures && captures.done[{exec_id_js}] || null;
+                if (consoleLines) {{
+                    delete captures.done[{exec_id_js}];
+                }}
+
+                // Cancelled executions no longer have anyone waiting for them
+                var cancelled = window.__MCP_CANCELLED_EXECUTIONS__;
+                if (cancelled && cancelled[{exec_id_js}]) {{
+                    delete cancelled[{exec_id_js}];
+                    return;
+                }}
+
                 try {{
                     if (window.__TAURI__ && window.__TAURI__.event) {{
-                        window.__TAURI__.event.emit('__script_result', {{
-                            exec_id: '{exec_id}',
+                        var message = {{
+                            exec_id: {exec_id_js},
                             success: success,
                             data: data,
-                            error: error
+                            error: error,
+                            stack: stack || null,
+                            result_type: resultType || null,
+                            error_kind: errorKind || null,
+                            script_ms: {prefix}_scriptMs(),
+                            console: consoleLines
+                        }};
+                        var json = JSON.stringify(message);
+
+                        if (json.length <= {chunk_threshold}) {{
+                            window.__TAURI__.event.emit({result_channel_js}, message);
+                            return;
+                        }}
+
+                        // Too large for a single event: send numbered chunks instead,
+                        // never splitting a surrogate pair across two chunks
+                        var chunks = [];
+                        for (var start = 0; start < json.length;) {{
+                            var end = Math.min(start + {chunk_threshold}, json.length);
+                            var code = json.charCodeAt(end - 1);
+                            if (end < json.length && end - 1 > start && code >= 0xD800 && code <= 0xDBFF) {{
+                                end -= 1;
+                            }}
+                            chunks.push(json.slice(start, end));
+                            start = end;
+                        }}
+                        chunks.forEach(function(chunk, index) {{
+                            window.__TAURI__.event.emit({chunk_channel_js}, {{
+                                exec_id: {exec_id_js},
+                                index: index,
+                                total: chunks.length,
+                                chunk: chunk
+                            }});
                         }});
                     }} else {{
                         console.error('[MCP] __TAURI__ not available, cannot send result');
@@ -129,108 +1096,1106 @@ pub async fn execute_js<R: Runtime>(
                 }}
             }}
 
-            // Execute the user script
-            (async () => {{
+            // Lets the script report how far it got, returned if the call times out
+            function __sendProgress(data) {{
+                var cancelled = window.__MCP_CANCELLED_EXECUTIONS__;
+                if (cancelled && cancelled[{exec_id_js}]) {{
+                    return;
+                }}
                 try {{
-                    // Create function to execute user script
-                    const __executeScript = async () => {{
-                        {prepared_script}
-                    }};
+                    window.__TAURI__.event.emit({progress_channel_js}, {{
+                        exec_id: {exec_id_js},
+                        data: data === undefined ? null : data
+                    }});
+                }} catch (e) {{
+                    console.error('[MCP] Failed to emit progress:', e);
+                }}
+            }}
 
-                    // Execute and get result
-                    const __result = await __executeScript();
+            var __mcp = {helpers};
 
-                    __sendResult(true, __result !== undefined ? __result : null, null);
-                }} catch (error) {{
-                    __sendResult(false, null, error.message || String(error));
+            // Helper to report a thrown value, keeping the stack when it's an Error
+            function {prefix}_sendError(error) {{
+                var message = (error && error.message) || String(error);
+                var stack = error && typeof error.stack === 'string' ? error.stack : null;
+                var kind = error && error.__mcpParseError
+                    ? 'parse_error'
+                    : (error && error.__mcpErrorKind) || 'runtime_error';
+                {prefix}_sendResult(false, null, message, stack, null, kind);
+            }}
+
+            // Helper to name a result's type, telling `null` apart from `undefined`
+            function {prefix}_resultType(value) {{
+                return value === null ? 'null' : typeof value;
+            }}
+
+            {isolated_helper}
+            {binary_helper}
+            {handle_helper}
+            {function_helper}
+
+            // A navigation tears the wrapper down, so report it while the page still can
+            function {prefix}_onPageHide() {{
+                {prefix}_sendResult(false, null, {navigated_js}, null, null, 'navigated_during_execution');
+            }}
+
+            // Execute the user script
+            function {prefix}_run() {{
+                window.addEventListener('pagehide', {prefix}_onPageHide);
+                (async () => {{
+                    try {{
+                        // Create function to execute user script
+                        const {prefix}_executeScript = {execute_script};
+
+                        // Execute and get result
+                        {prefix}_startedAt = performance.now();
+                        const {prefix}_result = await {prefix}_executeScript();
+                        const {prefix}_encoded = await {prefix}_encodeBinary({prefix}_encodeHandles({prefix}_encodeFunction({prefix}_result)));
+
+                        {prefix}_sendResult(true, {prefix}_encoded !== undefined ? {prefix}_encoded : null, null, null, {prefix}_resultType({prefix}_result));
+                    }} catch (error) {{
+                        {prefix}_sendError(error);
+                    }}
+                }})().catch(function(error) {{
+                    // Catch any unhandled promise rejections
+                    {prefix}_sendError(error);
+                }});
+            }}
+
+            // Results can only come back through the event API, so don't run the script
+            // without it; report the problem over IPC rather than letting the call time out
+            function {prefix}_eventApiReady() {{
+                return !!(window.__TAURI__ && window.__TAURI__.event);
+            }}
+            function {prefix}_reportEventApiUnavailable() {{
+                var internals = window.__TAURI_INTERNALS__;
+                if (!internals || typeof internals.invoke !== 'function') {{
+                    console.error('[MCP] __TAURI__ not available, cannot send result');
+                    return;
                 }}
-            }})().catch(function(error) {{
-                // Catch any unhandled promise rejections
-                __sendResult(false, null, error.message || String(error));
+                internals.invoke('plugin:mcp-bridge|script_result', {{
+                    execId: {exec_id_js},
+                    success: false,
+                    data: null,
+                    error: {unavailable_js},
+                    stack: null,
+                    errorKind: 'injection_failed'
+                }}).catch(function(e) {{
+                    console.error('[MCP] Failed to send result:', e);
+                }});
+            }}
+
+            if ({prefix}_eventApiReady()) {{
+                {prefix}_run();
+            }} else {{
+                setTimeout(function() {{
+                    if ({prefix}_eventApiReady()) {{
+                        {prefix}_run();
+                    }} else {{
+                        {prefix}_reportEventApiUnavailable();
+                    }}
+                }}, {EVENT_API_RETRY_DELAY_MS});
+            }}
+        }})();
+        "#
+    )
+}
+
+/// Wraps a script to run without reporting back, for detached executions.
+///
+/// The script runs like on the event path, but its value is dropped and failures are
+/// only logged to the console. `__sendProgress` is accepted and ignored.
+fn detached_script(
+    exec_id_js: &str,
+    prefix: &str,
+    result_channel: &str,
+    prepared_script: &str,
+    isolated: bool,
+) -> String {
+    let helpers = helpers_js(exec_id_js, result_channel);
+    let (isolated_helper, execute_script) = if isolated {
+        let source = Value::String(prepared_script.to_string());
+        (
+            isolated_function_js(prefix),
+            format!("{prefix}_isolatedFunction({source}, __sendProgress, __mcp)"),
+        )
+    } else {
+        (
+            String::new(),
+            format!("async () => {{\n{prepared_script}\n}}"),
+        )
+    };
+
+    format!(
+        r#"
+        (function() {{
+            function __sendProgress() {{}}
+
+            var __mcp = {helpers};
+
+            function {prefix}_logError(error) {{
+                console.error('[MCP] Detached script failed:', error);
+            }}
+
+            {isolated_helper}
+
+            try {{
+                const {prefix}_executeScript = {execute_script};
+                {prefix}_executeScript().catch({prefix}_logError);
+            }} catch (error) {{
+                {prefix}_logError(error);
+            }}
+        }})();
+        "#
+    )
+}
+
+/// Reports a parse error if the wrapper evaluated just before it never started.
+///
+/// The wrapper embeds the user's script, so a syntax error in it keeps the wrapper from
+/// running at all. This check is evaluated as a separate script right after: if the
+/// wrapper's start marker is missing, it compiles `function_body` on its own to get the
+/// syntax error message (falling back to a generic one when the page's CSP forbids
+/// compiling strings) and sends it to `script_result`.
+fn parse_check_script(exec_id_js: &str, prefix: &str, function_body: &str) -> String {
+    let source = Value::String(function_body.to_string());
+    format!(
+        r#"
+        (function() {{
+            if (window['{prefix}_started']) {{
+                delete window['{prefix}_started'];
+                return;
+            }}
+            var message = 'Script could not be parsed';
+            try {{
+                var AsyncFunction = Object.getPrototypeOf(async function() {{}}).constructor;
+                new AsyncFunction({source});
+            }} catch (error) {{
+                if (error && error.name === 'SyntaxError') {{
+                    message = 'SyntaxError: ' + error.message;
+                }}
+            }}
+            var internals = window.__TAURI_INTERNALS__;
+            if (!internals || typeof internals.invoke !== 'function') {{
+                console.error('[MCP] ' + message);
+                return;
+            }}
+            internals.invoke('plugin:mcp-bridge|script_result', {{
+                execId: {exec_id_js},
+                success: false,
+                data: null,
+                error: message,
+                stack: null,
+                errorKind: 'parse_error'
+            }}).catch(function(e) {{
+                console.error('[MCP] Failed to send result:', e);
             }});
         }})();
         "#
-    );
+    )
+}
 
-    // Execute the wrapped script
-    if let Err(e) = window.eval(&wrapped_script) {
-        // Clean up pending result on error
-        let mut pending = state.pending_results.lock().await;
-        pending.remove(&exec_id);
+/// Wraps a synchronous expression so its value is sent straight to `script_result`.
+///
+/// This skips the async wrapper and the event listener hop: the expression is evaluated
+/// in place and the result is handed to the command over IPC, which completes the
+/// pending execution directly. If the expression evaluates to a promise anyway, its
+/// settled value is sent instead.
+fn fast_path_script(
+    exec_id_js: &str,
+    prefix: &str,
+    script: &str,
+    max_function_source: usize,
+) -> String {
+    let expression = script.trim().trim_end_matches(';');
+    let binary_helper = binary_helper_js(prefix);
+    let handle_helper = handle_helper_js(prefix);
+    let function_helper = function_helper_js(prefix, max_function_source);
+    format!(
+        r#"
+        (function() {{
+            window['{prefix}_started'] = true;
+            var {prefix}_startedAt = performance.now();
 
-        return Ok(serde_json::json!({
-            "success": false,
-            "error": format!("Failed to execute script: {}", e)
-        }));
-    }
+            function {prefix}_sendResult(success, data, error, stack, resultType, errorKind) {{
+                var cancelled = window.__MCP_CANCELLED_EXECUTIONS__;
+                if (cancelled && cancelled[{exec_id_js}]) {{
+                    delete cancelled[{exec_id_js}];
+                    return;
+                }}
+                // Never throw: a failure here would be sent again by `sendError`
+                try {{
+                    window.__TAURI_INTERNALS__.invoke('plugin:mcp-bridge|script_result', {{
+                        execId: {exec_id_js},
+                        success: success,
+                        data: data === undefined ? null : data,
+                        error: error,
+                        stack: stack,
+                        resultType: resultType || null,
+                        errorKind: errorKind || null,
+                        scriptMs: Math.round((performance.now() - {prefix}_startedAt) * 100) / 100
+                    }}).catch(function(e) {{
+                        console.error('[MCP] Failed to send result:', e);
+                    }});
+                }} catch (e) {{
+                    console.error('[MCP] Failed to send result:', e);
+                }}
+            }}
 
-    // Wait for result with timeout
-    let result = match tokio::time::timeout(std::time::Duration::from_secs(5), rx).await {
-        Ok(Ok(result)) => Ok(result),
-        Ok(Err(_)) => {
-            // Channel was dropped
-            Ok(serde_json::json!({
-                "success": false,
-                "error": "Script execution failed: channel closed"
-            }))
-        }
-        Err(_) => {
-            // Timeout - clean up pending result
-            let mut pending = state.pending_results.lock().await;
-            pending.remove(&exec_id);
+            function {prefix}_sendError(error) {{
+                var message = (error && error.message) || String(error);
+                var stack = error && typeof error.stack === 'string' ? error.stack : null;
+                {prefix}_sendResult(false, null, message, stack, null, 'runtime_error');
+            }}
 
-            Ok(serde_json::json!({
-                "success": false,
-                "error": "Script execution timeout"
-            }))
-        }
-    };
+            function {prefix}_resultType(value) {{
+                return value === null ? 'null' : typeof value;
+            }}
+            {binary_helper}
+            {handle_helper}
+            {function_helper}
+
+            function {prefix}_sendValue(value) {{
+                var encoded = {prefix}_encodeBinary({prefix}_encodeHandles({prefix}_encodeFunction(value)));
+                if (encoded && typeof encoded.then === 'function') {{
+                    encoded.then(function(data) {{ {prefix}_sendResult(true, data, null, null, {prefix}_resultType(value)); }}, {prefix}_sendError);
+                }} else {{
+                    {prefix}_sendResult(true, encoded, null, null, {prefix}_resultType(value));
+                }}
+            }}
+
+            try {{
+                var {prefix}_result = (
+{expression}
+                );
+                if ({prefix}_result && typeof {prefix}_result.then === 'function') {{
+                    {prefix}_result.then({prefix}_sendValue, {prefix}_sendError);
+                }} else {{
+                    {prefix}_sendValue({prefix}_result);
+                }}
+            }} catch (error) {{
+                {prefix}_sendError(error);
+            }}
+        }})();
+        "#
+    )
+}
+
+/// Returns the `__mcp` helpers object for an execution, as a JavaScript expression.
+///
+/// Expects `__sendProgress` to be defined where it's evaluated. These helpers are the
+/// public API scripts may rely on, so they only change along with `version`. `emit`
+/// refuses the bridge's own events, including `result_channel` and its variants.
+fn helpers_js(exec_id_js: &str, result_channel: &str) -> String {
+    let unavailable_js = Value::String(EVENT_API_UNAVAILABLE.to_string());
+    let reserved_js = serde_json::json!([
+        result_channel,
+        format!("{result_channel}_chunk"),
+        format!("{result_channel}_progress"),
+    ]);
+    format!(
+        r#"Object.freeze({{
+                version: 1,
+                execId: {exec_id_js},
+                sendProgress: __sendProgress,
+                emit: function(event, payload) {{
+                    if (typeof event !== 'string' || event === '' || event.indexOf('__script_') === 0
+                        || {reserved_js}.indexOf(event) !== -1) {{
+                        return Promise.reject(new Error('Invalid event name: ' + JSON.stringify(event)));
+                    }}
+                    if (!window.__TAURI__ || !window.__TAURI__.event) {{
+                        return Promise.reject(new Error({unavailable_js}));
+                    }}
+                    return window.__TAURI__.event.emit(event, payload === undefined ? null : payload);
+                }},
+                element: function(handle) {{
+                    {HANDLE_REGISTRY_JS}
+                    return window.__MCP_HANDLES__.get(
+                        handle !== null && typeof handle === 'object' ? handle.__handle : handle
+                    );
+                }}
+            }})"#
+    )
+}
 
-    // Clean up event listener
-    window.unlisten(unlisten);
+/// Returns the function body the fast path compiles for a single expression.
+pub(super) fn fast_path_body(script: &str) -> String {
+    format!("return (\n{}\n)", script.trim().trim_end_matches(';'))
+}
 
-    result
+/// Returns whether a script uses the wrapper's helpers, which the fast path lacks.
+pub(super) fn uses_helpers(script: &str) -> bool {
+    script.contains("__mcp") || script.contains("__sendProgress")
 }
 
-/// Prepare script by adding return statement if needed.
-fn prepare_script(script: &str) -> String {
+/// Defines `{prefix}_isolatedFunction(source, sendProgress, helpers)`, which compiles a
+/// script in an isolated realm.
+///
+/// The realm is a hidden, same-origin `about:blank` iframe kept on the page and reused
+/// across executions. The compiled async function sees that realm's globals, so
+/// built-ins like `fetch`, `JSON` or `Promise` are pristine even if the app replaced
+/// them, and globals the script creates don't leak into the page. `document` is bound
+/// to the page's document, `parent` is the page's `window`, and `__sendProgress` and
+/// `__mcp` are the wrapper's `sendProgress` and `helpers`.
+///
+/// This is not a security boundary: the page can still reach the iframe, and the
+/// script can reach the page through `parent`. Compiling uses the iframe's `Function`
+/// constructor, so it fails on pages whose CSP disallows `unsafe-eval`.
+fn isolated_function_js(prefix: &str) -> String {
+    format!(
+        r#"
+            function {prefix}_isolatedFunction(source, sendProgress, helpers) {{
+                var frame = window.__MCP_ISOLATED_FRAME__;
+                if (!frame || !frame.isConnected || !frame.contentWindow) {{
+                    frame = document.createElement('iframe');
+                    frame.style.display = 'none';
+                    frame.setAttribute('aria-hidden', 'true');
+                    document.documentElement.appendChild(frame);
+                    window.__MCP_ISOLATED_FRAME__ = frame;
+                }}
+                var AsyncFunction = frame.contentWindow.Function(
+                    'return (async function() {{}}).constructor'
+                )();
+                var compiled;
+                try {{
+                    compiled = new AsyncFunction('document', '__sendProgress', '__mcp', source);
+                }} catch (error) {{
+                    error.__mcpParseError = true;
+                    throw error;
+                }}
+                return function() {{
+                    return compiled(document, sendProgress, helpers);
+                }};
+            }}
+        "#
+    )
+}
+
+/// Returns whether a script is a single expression that can be evaluated synchronously.
+///
+/// Such scripts take the fast path. Anything with more than one statement, a
+/// non-expression statement, or an `await` anywhere (even inside a string, to stay
+/// conservative) goes through the async event path instead.
+pub(super) fn is_sync_expression(script: &str) -> bool {
+    let trimmed = script.trim().trim_end_matches(';').trim_end();
+    let starts = statement_starts(trimmed);
+
+    starts.len() == 1
+        && is_expression_statement(trimmed, true)
+        && !trimmed
+            .split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+            .any(|word| word == "await")
+}
+
+/// Leading keywords that make a statement something other than an expression.
+const STATEMENT_KEYWORDS: &[&str] = &[
+    "break", "case", "catch", "class", "const", "continue", "debugger", "default", "do", "else",
+    "export", "finally", "for", "function", "if", "import", "let", "return", "switch", "throw",
+    "try", "var", "while", "with",
+];
+
+/// Keywords that continue the previous statement even when they start a new line.
+const CONTINUATION_KEYWORDS: &[&str] = &["catch", "else", "finally", "while"];
+
+/// Prepares a script so that the value of its last expression statement is returned.
+///
+/// The script is split into top-level statements by a small tokenizer that skips over
+/// strings, template literals, comments, regex literals and bracketed code, so a `;` or
+/// newline inside any of those never counts as a statement boundary. If the final
+/// statement is an expression, `return` is inserted in front of it; declarations and
+/// control-flow statements are left untouched.
+pub(super) fn prepare_script(script: &str) -> String {
+    prefix_last_expression(script, "return ")
+}
+
+/// Prepares a script so that the value of its last expression statement is assigned
+/// to `target` instead of returned.
+///
+/// Used by `batch_execute`, where several scripts share one function body and a
+/// `return` would end the whole batch. The statement is split the same way as in
+/// [`prepare_script`], and a newline before the closing `;` keeps a trailing line
+/// comment from swallowing it.
+pub(super) fn capture_script(script: &str, target: &str) -> String {
+    let prepared = prefix_last_expression(script, &format!("{target} = "));
+    format!("{prepared}\n;")
+}
+
+/// Inserts `prefix` in front of the last statement of `script` if it's an expression.
+fn prefix_last_expression(script: &str, prefix: &str) -> String {
     let trimmed = script.trim();
-    let needs_return = !trimmed.starts_with("return ");
+    let starts = statement_starts(trimmed);
 
-    // Check if it's a multi-statement script
-    let has_real_semicolons = if let Some(without_trailing) = trimmed.strip_suffix(';') {
-        without_trailing.contains(';')
-    } else {
-        trimmed.contains(';')
+    let Some(&last_start) = starts.last() else {
+        return script.to_string();
     };
 
-    let is_multi_statement = has_real_semicolons
-        || trimmed.starts_with("const ")
-        || trimmed.starts_with("let ")
-        || trimmed.starts_with("var ")
-        || trimmed.starts_with("if ")
-        || trimmed.starts_with("for ")
-        || trimmed.starts_with("while ")
-        || trimmed.starts_with("function ")
-        || trimmed.starts_with("class ")
-        || trimmed.starts_with("try ");
-
-    // Single expression patterns
-    let is_single_expression = trimmed.starts_with("await ")
-        || trimmed.starts_with("(")
-        || trimmed.starts_with("JSON.")
-        || trimmed.starts_with("{")
-        || trimmed.starts_with("[")
-        || trimmed.ends_with(")()");
-
-    let is_wrapped_expression = (trimmed.starts_with("(") && trimmed.ends_with(")"))
-        || (trimmed.starts_with("(") && trimmed.ends_with(")()"))
-        || (trimmed.starts_with("JSON.") && trimmed.ends_with(")"))
-        || (trimmed.starts_with("await "));
-
-    if needs_return && (is_single_expression || is_wrapped_expression || !is_multi_statement) {
-        format!("return {trimmed}")
+    if is_expression_statement(&trimmed[last_start..], starts.len() == 1) {
+        format!(
+            "{}{prefix}{}",
+            &trimmed[..last_start],
+            &trimmed[last_start..]
+        )
     } else {
         script.to_string()
     }
 }
+
+/// Returns whether a statement is an expression whose value can be returned.
+///
+/// A leading `{` is treated as an object literal only when it's the whole script;
+/// after other statements it's far more likely to be a block.
+fn is_expression_statement(statement: &str, is_only_statement: bool) -> bool {
+    let word = leading_word(statement);
+
+    if statement.starts_with('{') {
+        return is_only_statement;
+    }
+    if statement.starts_with('}') || STATEMENT_KEYWORDS.contains(&word) {
+        return false;
+    }
+    if word == "async" {
+        return leading_word(statement["async".len()..].trim_start()) != "function";
+    }
+    !statement.is_empty()
+}
+
+/// Returns the identifier at the start of `text`, or an empty string.
+fn leading_word(text: &str) -> &str {
+    let end = text
+        .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+        .unwrap_or(text.len());
+    &text[..end]
+}
+
+/// Returns the byte offsets at which each top-level statement of `script` starts.
+///
+/// Statements end at a top-level `;`, or at a top-level newline where JavaScript's
+/// automatic semicolon insertion would apply (the previous line doesn't end in an
+/// operator and the next one doesn't continue the expression).
+fn statement_starts(script: &str) -> Vec<usize> {
+    let chars: Vec<(usize, char)> = script.char_indices().collect();
+    let peek = |i: usize| chars.get(i).map(|&(_, c)| c);
+
+    let mut starts = Vec::new();
+    let mut depth = 0usize;
+    // Bracket depth at which each open template `${` substitution started
+    let mut templates: Vec<usize> = Vec::new();
+    let mut at_boundary = true;
+    let mut saw_newline = false;
+    let mut last: Option<char> = None;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let (pos, c) = chars[i];
+
+        // Comments are whitespace; a block comment spanning lines counts as a newline
+        if c == '/' && peek(i + 1) == Some('/') {
+            while i < chars.len() && chars[i].1 != '\n' {
+                i += 1;
+            }
+            continue;
+        }
+        if c == '/' && peek(i + 1) == Some('*') {
+            i += 2;
+            while i < chars.len() && !(chars[i].1 == '*' && peek(i + 1) == Some('/')) {
+                saw_newline |= chars[i].1 == '\n' && depth == 0;
+                i += 1;
+            }
+            i += 2;
+            continue;
+        }
+        if c.is_whitespace() {
+            saw_newline |= c == '\n' && depth == 0;
+            i += 1;
+            continue;
+        }
+
+        if saw_newline && !at_boundary && ends_expression(last) && !continues_line(&chars, i) {
+            at_boundary = true;
+        }
+        saw_newline = false;
+
+        if at_boundary && c != ';' {
+            starts.push(pos);
+            at_boundary = false;
+        }
+
+        match c {
+            '\'' | '"' => {
+                i = skip_string(&chars, i);
+                last = Some('"');
+                continue;
+            }
+            '`' => {
+                let (next, opened_substitution) = skip_template(&chars, i + 1);
+                if opened_substitution {
+                    templates.push(depth);
+                    depth += 1;
+                }
+                i = next;
+                last = Some('`');
+                continue;
+            }
+            '/' if regex_allowed(last) => {
+                i = skip_regex(&chars, i);
+                last = Some('"');
+                continue;
+            }
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' => depth = depth.saturating_sub(1),
+            '}' => {
+                depth = depth.saturating_sub(1);
+                if templates.last() == Some(&depth) {
+                    // End of a `${...}` substitution: resume scanning the template
+                    templates.pop();
+                    let (next, opened_substitution) = skip_template(&chars, i + 1);
+                    if opened_substitution {
+                        templates.push(depth);
+                        depth += 1;
+                    }
+                    i = next;
+                    last = Some('`');
+                    continue;
+                }
+            }
+            ';' if depth == 0 => at_boundary = true,
+            _ => {}
+        }
+
+        last = Some(c);
+        i += 1;
+    }
+
+    starts
+}
+
+/// Returns whether a line ending in `last` can end a statement.
+fn ends_expression(last: Option<char>) -> bool {
+    match last {
+        Some(c) => !"+-*/%=&|^!~?:,.<>([{".contains(c),
+        None => false,
+    }
+}
+
+/// Returns whether the token at `i` continues the statement from the previous line.
+fn continues_line(chars: &[(usize, char)], i: usize) -> bool {
+    let c = chars[i].1;
+    if ".?:,)]}([+-*/%=&|^<>".contains(c) {
+        return true;
+    }
+    let word: String = chars[i..]
+        .iter()
+        .map(|&(_, c)| c)
+        .take_while(|c| c.is_alphanumeric() || *c == '_' || *c == '$')
+        .collect();
+    CONTINUATION_KEYWORDS.contains(&word.as_str())
+}
+
+/// Returns whether a `/` following `last` starts a regex literal rather than a division.
+fn regex_allowed(last: Option<char>) -> bool {
+    match last {
+        Some(c) => "(,=:[!&|?{};+-*%<>~^".contains(c),
+        None => true,
+    }
+}
+
+/// Skips a quoted string starting at `i`, returning the index after its closing quote.
+fn skip_string(chars: &[(usize, char)], i: usize) -> usize {
+    let quote = chars[i].1;
+    let mut j = i + 1;
+    while j < chars.len() {
+        match chars[j].1 {
+            '\\' => j += 2,
+            '\n' => return j,
+            c if c == quote => return j + 1,
+            _ => j += 1,
+        }
+    }
+    j
+}
+
+/// Skips template literal text starting at `i` (just after a backtick or a closing `}`).
+///
+/// Returns the index to resume at and whether a `${` substitution was opened.
+fn skip_template(chars: &[(usize, char)], i: usize) -> (usize, bool) {
+    let mut j = i;
+    while j < chars.len() {
+        match chars[j].1 {
+            '\\' => j += 2,
+            '`' => return (j + 1, false),
+            '$' if chars.get(j + 1).map(|&(_, c)| c) == Some('{') => return (j + 2, true),
+            _ => j += 1,
+        }
+    }
+    (j, false)
+}
+
+/// Skips a regex literal starting at `i`, returning the index after its closing `/`.
+fn skip_regex(chars: &[(usize, char)], i: usize) -> usize {
+    let mut j = i + 1;
+    let mut in_class = false;
+    while j < chars.len() {
+        match chars[j].1 {
+            '\\' => j += 2,
+            '\n' => return j,
+            '[' => {
+                in_class = true;
+                j += 1;
+            }
+            ']' => {
+                in_class = false;
+                j += 1;
+            }
+            '/' if !in_class => return j + 1,
+            _ => j += 1,
+        }
+    }
+    j
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::script_executor::{
+        deliver_result, parse_payload, DEFAULT_MAX_RESULT_SIZE,
+    };
+
+    #[test]
+    fn test_bare_expression_is_returned() {
+        assert_eq!(prepare_script("document.title"), "return document.title");
+        assert_eq!(prepare_script("  1 + 2;  "), "return 1 + 2;");
+        assert_eq!(
+            prepare_script("await fetch('/api')"),
+            "return await fetch('/api')"
+        );
+    }
+
+    #[test]
+    fn test_explicit_return_is_untouched() {
+        assert_eq!(prepare_script("return 42"), "return 42");
+        assert_eq!(
+            prepare_script("const a = 1;\nreturn a;"),
+            "const a = 1;\nreturn a;"
+        );
+    }
+
+    #[test]
+    fn test_capture_assigns_last_expression() {
+        assert_eq!(
+            capture_script("var a = 1; a + 1 // two", "value"),
+            "var a = 1; value = a + 1 // two\n;"
+        );
+        assert_eq!(capture_script("var a = 1;", "value"), "var a = 1;\n;");
+    }
+
+    #[test]
+    fn test_each_attempt_gets_its_own_exec_id() {
+        assert_eq!(attempt_exec_id(Some("req-1"), 1).as_deref(), Some("req-1"));
+        assert_eq!(
+            attempt_exec_id(Some("req-1"), 3).as_deref(),
+            Some("req-1#3")
+        );
+        assert_eq!(attempt_exec_id(None, 2), None);
+    }
+
+    #[tokio::test]
+    async fn test_binary_results_are_encoded_then_decoded() {
+        let script = event_path_script(
+            "\"id\"",
+            "__mcp_test",
+            "__script_result",
+            1024,
+            10_000,
+            "return 1",
+            false,
+        );
+        assert!(script.contains("function __mcp_test_encodeBinary(value) {"));
+        assert!(script.contains("await __mcp_test_encodeBinary("));
+        assert!(script
+            .contains("{ __binary: true, encoding: 'base64', mime: mime, data: btoa(binary) }"));
+
+        // What the wrapper sends for a 1x1 PNG from `canvas.toBlob()`
+        let payload = parse_payload(
+            r#"{
+                "exec_id": "id",
+                "success": true,
+                "data": {
+                    "__binary": true,
+                    "encoding": "base64",
+                    "mime": "image/png",
+                    "data": "iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mNkYPhfDwAChwGA60e6kgAAAABJRU5ErkJggg=="
+                },
+                "error": null,
+                "stack": null,
+                "result_type": "object",
+                "error_kind": null
+            }"#,
+        )
+        .unwrap();
+        let executor = ScriptExecutor::new();
+        let (tx, rx) = oneshot::channel();
+        executor
+            .pending_results
+            .insert(
+                "id".into(),
+                PendingResult::new(tx, "main", Duration::from_secs(5)),
+            )
+            .await;
+        deliver_result(&executor.pending_results, &payload, DEFAULT_MAX_RESULT_SIZE).await;
+        let mut result = rx.await.unwrap();
+        assert_eq!(result["success"], true);
+
+        let bytes = binary_result_bytes(&result["data"]).unwrap().unwrap();
+        assert_eq!(&bytes[..8], b"\x89PNG\r\n\x1a\n");
+        decode_binary_data(&mut result["data"]).unwrap();
+        assert_eq!(result["data"]["encoding"], "bytes");
+        assert_eq!(result["data"]["mime"], "image/png");
+        assert_eq!(
+            result["data"]["data"].as_array().unwrap().len(),
+            bytes.len()
+        );
+    }
+
+    #[test]
+    fn test_non_binary_result_is_untouched() {
+        let mut data = serde_json::json!({ "data": "aGk=" });
+        assert!(binary_result_bytes(&data).is_none());
+        decode_binary_data(&mut data).unwrap();
+        assert_eq!(data["data"], "aGk=");
+    }
+
+    #[test]
+    fn test_multi_statement_returns_last_expression() {
+        assert_eq!(
+            prepare_script("const a = 1; const b = 2; a + b"),
+            "const a = 1; const b = 2; return a + b"
+        );
+        assert_eq!(
+            prepare_script("const el = document.body\nel.children.length"),
+            "const el = document.body\nreturn el.children.length"
+        );
+    }
+
+    #[test]
+    fn test_trailing_declaration_returns_nothing() {
+        assert_eq!(prepare_script("const x = 5"), "const x = 5");
+        assert_eq!(prepare_script("foo(); let y = 2;"), "foo(); let y = 2;");
+        assert_eq!(
+            prepare_script("if (x) { a(); } else { b(); }"),
+            "if (x) { a(); } else { b(); }"
+        );
+        assert_eq!(
+            prepare_script("async function f() {}"),
+            "async function f() {}"
+        );
+    }
+
+    #[test]
+    fn test_object_literals() {
+        assert_eq!(prepare_script("{ a: 1, b: 2 }"), "return { a: 1, b: 2 }");
+        assert_eq!(
+            prepare_script("const o = { a: 1, b: 'x;y' }; o"),
+            "const o = { a: 1, b: 'x;y' }; return o"
+        );
+        assert_eq!(
+            prepare_script("const o = {\n  a: 1\n}\no.a"),
+            "const o = {\n  a: 1\n}\nreturn o.a"
+        );
+    }
+
+    #[test]
+    fn test_result_paths() {
+        let path = |p: &str| Value::Array(parse_result_path(p).unwrap());
+        assert_eq!(
+            path("user.roles.0"),
+            serde_json::json!(["user", "roles", "0"])
+        );
+        assert_eq!(
+            path("$.user.roles[0]"),
+            serde_json::json!(["user", "roles", 0])
+        );
+        assert_eq!(
+            path("$['display name'][\"a.b\"]"),
+            serde_json::json!(["display name", "a.b"])
+        );
+        assert_eq!(path("$el.id"), serde_json::json!(["$el", "id"]));
+        assert_eq!(path("$"), serde_json::json!([]));
+        assert_eq!(path(""), serde_json::json!([]));
+    }
+
+    #[test]
+    fn test_unsupported_result_paths_are_rejected() {
+        assert!(parse_result_path("$..name").is_err());
+        assert!(parse_result_path("items[*]").is_err());
+        assert!(parse_result_path("items.*").is_err());
+        assert!(parse_result_path("items[?(@.id)]").is_err());
+        assert!(parse_result_path("a..b").is_err());
+        assert!(parse_result_path("a[0").is_err());
+        assert!(parse_result_path("a['b").is_err());
+        assert!(parse_result_path("a[0]b").is_err());
+    }
+
+    #[test]
+    fn test_args_are_embedded_as_json() {
+        let args = serde_json::json!({
+            "quotes": "it's \"quoted\" `templated` ${x}",
+            "lines": "one\ntwo\r\nthree\u{2028}",
+            "nested": { "list": [1, null, { "deep": "</script>" }], "flag": true }
+        });
+
+        let script = with_args(&args, "const el = document.body;\nel.id = $args.quotes");
+        let literal = script
+            .strip_prefix("const $args = ")
+            .and_then(|rest| rest.split_once(";\n"))
+            .map(|(literal, _)| literal)
+            .unwrap();
+        assert!(!literal.contains('\n'));
+        assert_eq!(serde_json::from_str::<Value>(literal).unwrap(), args);
+
+        let expression = with_args(&args, "$args.nested.list.length;");
+        assert!(is_sync_expression(&expression));
+        let literal = expression
+            .strip_prefix("(($args) => (\n$args.nested.list.length\n))(")
+            .and_then(|rest| rest.strip_suffix(')'))
+            .unwrap();
+        assert_eq!(serde_json::from_str::<Value>(literal).unwrap(), args);
+    }
+
+    #[test]
+    fn test_ready_state_wait_runs_before_the_script() {
+        let script = with_ready_state("complete", "document.title");
+        assert!(script.starts_with("const __mcpReadyState = \"complete\";\n"));
+        assert!(script.ends_with("});\ndocument.title"));
+        assert!(!is_sync_expression(&script));
+        assert_eq!(
+            prepare_script(&script).lines().last(),
+            Some("return document.title")
+        );
+    }
+
+    #[test]
+    fn test_msgpack_encodes_the_script_value() {
+        let script = with_msgpack("document.title");
+        assert!(script.starts_with("const __mcpSource = async () => {\nreturn document.title\n};"));
+        assert!(!is_sync_expression(&script));
+        assert!(prepare_script(&script).contains("\nreturn (function(root) {\n"));
+    }
+
+    #[test]
+    fn test_retry_policy_defaults() {
+        let retry: RetryPolicy = serde_json::from_value(serde_json::json!({})).unwrap();
+        assert_eq!(
+            (retry.attempts, retry.delay_ms, retry.on),
+            (3, 500, RetryOn::Any)
+        );
+        let retry: RetryPolicy =
+            serde_json::from_value(serde_json::json!({ "delayMs": 100, "on": "timeout" })).unwrap();
+        assert_eq!((retry.delay_ms, retry.on), (100, RetryOn::Timeout));
+        assert_eq!(retry.delay(1), Duration::from_millis(100));
+        assert_eq!(retry.delay(3), Duration::from_millis(400));
+    }
+
+    #[test]
+    fn test_retry_policy_picks_failures() {
+        let failed = |kind: &str| serde_json::json!({ "success": false, "error_kind": kind });
+        let retry = |on| RetryPolicy {
+            attempts: 3,
+            delay_ms: 0,
+            on,
+        };
+        assert!(!retry(RetryOn::Any).should_retry(&serde_json::json!({ "success": true })));
+        assert!(retry(RetryOn::Any).should_retry(&failed("timeout")));
+        assert!(retry(RetryOn::Any).should_retry(&failed("runtime_error")));
+        assert!(retry(RetryOn::Timeout).should_retry(&failed("timeout")));
+        assert!(!retry(RetryOn::Timeout).should_retry(&failed("runtime_error")));
+        assert!(retry(RetryOn::Error).should_retry(&failed("injection_failed")));
+        assert!(!retry(RetryOn::Error).should_retry(&failed("timeout")));
+        assert!(!retry(RetryOn::Any).should_retry(&failed("parse_error")));
+        assert!(!retry(RetryOn::Any).should_retry(&failed("cancelled")));
+    }
+
+    #[test]
+    fn test_ternary() {
+        assert_eq!(prepare_script("a ? b : c"), "return a ? b : c");
+        assert_eq!(
+            prepare_script("const n = 3;\nn > 2\n  ? 'big'\n  : 'small'"),
+            "const n = 3;\nreturn n > 2\n  ? 'big'\n  : 'small'"
+        );
+    }
+
+    #[test]
+    fn test_semicolons_inside_strings() {
+        assert_eq!(prepare_script("'a;b;c'"), "return 'a;b;c'");
+        assert_eq!(prepare_script(r#""x;\";y""#), r#"return "x;\";y""#);
+        assert_eq!(prepare_script("`a;${b};c`"), "return `a;${b};c`");
+        assert_eq!(
+            prepare_script("`${ {a: 1}.a };${`${x};`}`"),
+            "return `${ {a: 1}.a };${`${x};`}`"
+        );
+        assert_eq!(
+            prepare_script("'a;b'.split(/;/).length"),
+            "return 'a;b'.split(/;/).length"
+        );
+        assert_eq!(prepare_script("x // a; b"), "return x // a; b");
+        assert_eq!(prepare_script("/* a; b */ x"), "/* a; b */ return x");
+    }
+
+    #[test]
+    fn test_functions_and_arrows() {
+        assert_eq!(
+            prepare_script("(() => { const a = 1; return a; })()"),
+            "return (() => { const a = 1; return a; })()"
+        );
+        assert_eq!(prepare_script("x => x * 2"), "return x => x * 2");
+        assert_eq!(
+            prepare_script("async () => { await a; }"),
+            "return async () => { await a; }"
+        );
+        assert_eq!(
+            prepare_script("function f() { return 1; }\nf()"),
+            "function f() { return 1; }\nreturn f()"
+        );
+    }
+
+    #[test]
+    fn test_for_loop_semicolons() {
+        assert_eq!(
+            prepare_script("let s = 0; for (let i = 0; i < 3; i++) { s += i; }\ns"),
+            "let s = 0; for (let i = 0; i < 3; i++) { s += i; }\nreturn s"
+        );
+    }
+
+    #[test]
+    fn test_method_chain_across_lines() {
+        assert_eq!(
+            prepare_script("[1, 2]\n  .map(x => x * 2)\n  .join(';')"),
+            "return [1, 2]\n  .map(x => x * 2)\n  .join(';')"
+        );
+    }
+
+    #[test]
+    fn test_empty_script() {
+        assert_eq!(prepare_script("   "), "   ");
+    }
+
+    #[test]
+    fn test_sync_expressions_take_fast_path() {
+        assert!(is_sync_expression("document.title"));
+        assert!(is_sync_expression(
+            "  document.querySelectorAll('li').length;  "
+        ));
+        assert!(is_sync_expression("{ a: 1 }"));
+        assert!(is_sync_expression("fetch('/api').then(r => r.status)"));
+    }
+
+    #[test]
+    fn test_async_and_multi_statement_scripts_take_event_path() {
+        assert!(!is_sync_expression("await fetch('/api')"));
+        assert!(!is_sync_expression("const a = 1; a + 1"));
+        assert!(!is_sync_expression("return 42"));
+        assert!(!is_sync_expression("if (x) { y }"));
+        assert!(!is_sync_expression(""));
+    }
+
+    #[test]
+    fn test_scripts_using_helpers_take_event_path() {
+        assert!(uses_helpers("__mcp.sendProgress(1)"));
+        assert!(uses_helpers("__sendProgress(1)"));
+        assert!(!uses_helpers("document.title"));
+
+        let script = event_path_script(
+            "\"id\"",
+            "__mcp_test",
+            "__script_result",
+            1024,
+            10_000,
+            "return 1",
+            false,
+        );
+        assert!(script.contains("var __mcp = Object.freeze("));
+        assert!(script.contains("execId: \"id\""));
+    }
+
+    #[test]
+    fn test_function_results_are_returned_as_source() {
+        let helper = function_helper_js("__mcp_test", 120);
+        assert!(helper.contains("function __mcp_test_encodeFunction(value) {"));
+        assert!(helper.contains("source.length > 120"));
+        assert!(helper.contains("source.slice(0, 120)"));
+
+        let script = fast_path_script("\"id\"", "__mcp_test", "window.onload", 120);
+        assert!(script.contains("__mcp_test_encodeHandles(__mcp_test_encodeFunction(value))"));
+    }
+
+    #[test]
+    fn test_fast_path_strips_trailing_semicolon() {
+        let script = fast_path_script("\"id\"", "__mcp_test", "document.title;  ", 10_000);
+        assert!(script.contains("\ndocument.title\n"));
+    }
+
+    #[test]
+    fn test_isolated_script_is_compiled_from_a_string() {
+        let script = event_path_script(
+            "\"id\"",
+            "__mcp_test",
+            "__script_result",
+            1024,
+            10_000,
+            "return 1",
+            true,
+        );
+        assert!(script.contains("__mcp_test_isolatedFunction(\"return 1\", __sendProgress, __mcp)"));
+        assert!(!script.contains("__sendResult"));
+    }
+
+    #[test]
+    fn test_results_are_sent_on_the_configured_channel() {
+        let script = event_path_script(
+            "\"id\"",
+            "__mcp_test",
+            "app_results",
+            1024,
+            10_000,
+            "return 1",
+            false,
+        );
+        assert!(script.contains("event.emit(\"app_results\", message)"));
+        assert!(script.contains("event.emit(\"app_results_chunk\", {"));
+        assert!(script.contains("event.emit(\"app_results_progress\", {"));
+        assert!(!script.contains("__script_result"));
+    }
+
+    #[test]
+    fn test_emit_rejects_the_result_channels() {
+        let helpers = helpers_js("\"id\"", "app_results");
+        assert!(helpers.contains(
+            "[\"app_results\",\"app_results_chunk\",\"app_results_progress\"].indexOf(event) !== -1"
+        ));
+        assert!(helpers.contains("event.indexOf('__script_') === 0"));
+    }
+
+    #[test]
+    fn test_navigation_is_reported_once() {
+        let script = event_path_script(
+            "\"id\"",
+            "__mcp_test",
+            "__script_result",
+            1024,
+            10_000,
+            "return 1",
+            false,
+        );
+        assert!(script.contains("window.addEventListener('pagehide', __mcp_test_onPageHide)"));
+        assert!(script.contains("'navigated_during_execution'"));
+        assert!(script.contains("if (__mcp_test_sent) {"));
+    }
+
+    #[test]
+    fn test_console_capture_wraps_the_script() {
+        let script = with_console_capture("console.log('hi');\n1 + 1");
+        assert!(script.starts_with("const __mcpSource = async () => {\n"));
+        assert!(script.contains("return 1 + 1"));
+        assert!(script.trim_end().ends_with("__mcpCaptured"));
+        assert!(uses_helpers(&script));
+    }
+}
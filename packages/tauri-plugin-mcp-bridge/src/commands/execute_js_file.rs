@@ -0,0 +1,99 @@
+//! Running script files from the configured script directory.
+//!
+//! Scripts can only be loaded from the directory set with `Builder::script_dir`. Paths
+//! are resolved against it, symlinks included, and anything that ends up outside it is
+//! rejected, so the command can't be used to read arbitrary files.
+
+use super::execute_js::execute_js;
+use super::list_windows::target_window;
+use super::paths::resolve_within;
+use super::script_executor::ScriptExecutor;
+use serde_json::Value;
+use std::path::PathBuf;
+use tauri::{command, Runtime, State, WebviewWindow};
+
+/// The directory `execute_js_file` may load scripts from.
+///
+/// Managed as app state. `None` when no directory is configured, which disables the
+/// command.
+pub struct ScriptDir(pub Option<PathBuf>);
+
+/// Runs a JavaScript file from the script directory in the webview.
+///
+/// The file is read on every call and run like the `script` of `execute_js`, so the
+/// value of its last expression is the result and `await` works at the top level. The
+/// `args` object is available to the script as `__args`.
+///
+/// The command is disabled unless the app configures a directory with
+/// `Builder::script_dir`.
+///
+/// # Arguments
+///
+/// * `window` - The window to run the script in
+/// * `path` - Path of the script file, relative to the script directory
+/// * `args` - JSON object exposed to the script as `__args` (defaults to `{}`)
+/// * `timeout_ms` - Maximum time to wait for the result in milliseconds (defaults to
+///   5000)
+/// * `exec_id` - ID for the execution, so it can be cancelled with `cancel_execution`
+/// * `window_label` - Label of the window to target (defaults to the calling window)
+/// * `script_dir` - Configured script directory
+/// * `state` - Script executor state
+///
+/// # Returns
+///
+/// * `Ok(Value)` - The same result as `execute_js`
+/// * `Err(String)` - If no script directory is configured, the path is outside it, the
+///   file can't be read, or `args` isn't an object
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// // helpers/count.js: document.querySelectorAll(__args.selector).length
+/// const result = await invoke('plugin:mcp-bridge|execute_js_file', {
+///   path: 'helpers/count.js',
+///   args: { selector: 'li' }
+/// });
+/// ```
+#[command]
+#[allow(clippy::too_many_arguments)]
+pub async fn execute_js_file<R: Runtime>(
+    window: WebviewWindow<R>,
+    path: String,
+    args: Option<Value>,
+    timeout_ms: Option<u64>,
+    exec_id: Option<String>,
+    window_label: Option<String>,
+    script_dir: State<'_, ScriptDir>,
+    state: State<'_, ScriptExecutor>,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    let Some(base) = script_dir.0.as_deref() else {
+        return Err(
+            "execute_js_file is disabled: the app has no script directory configured".to_string(),
+        );
+    };
+
+    let args = args.unwrap_or_else(|| Value::Object(Default::default()));
+    if !args.is_object() {
+        return Err("Invalid args: expected a JSON object".to_string());
+    }
+
+    let file = resolve_within(base, &path, "script")?;
+    let source = tokio::fs::read_to_string(&file)
+        .await
+        .map_err(|e| format!("Failed to read script '{path}': {e}"))?;
+
+    let script = format!("const __args = {args};\n{source}");
+
+    execute_js(
+        window, script, None, None, timeout_ms, exec_id, None, None, None, None, None, None, None,
+        None, None, None, state,
+    )
+    .await
+}
@@ -0,0 +1,238 @@
+//! Choosing files in `<input type="file">` elements.
+//!
+//! Pages can't set the files of a file input from script, so uploads normally need a
+//! person to pick them in a dialog. Here the files are read on the Rust side and sent
+//! to the page as base64, where they become `File` objects assigned through a
+//! `DataTransfer`. Files can only be read from the directory set with
+//! `Builder::upload_dir`, checked like `execute_js_file`'s scripts (see [`super::paths`]).
+
+use super::execute_js::run_script;
+use super::handles::ElementTarget;
+use super::list_windows::target_window;
+use super::paths::resolve_within;
+use super::script_executor::ScriptExecutor;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine as _;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use tauri::{command, Runtime, State, WebviewWindow};
+
+/// The directory `set_file_input` may read files from.
+///
+/// Managed as app state. `None` when no directory is configured, which disables the
+/// command.
+pub struct UploadDir(pub Option<PathBuf>);
+
+/// Largest total size of the files for one `set_file_input` call, in bytes.
+///
+/// The files travel to the page inside the injected script, as base64.
+const MAX_UPLOAD_BYTES: u64 = 25 * 1024 * 1024;
+
+/// Assigns `files` (`[{ name, type, lastModified, data }]`, with base64 `data`) to the
+/// file input matching `selector` and fires `input` and `change`.
+///
+/// Expects `selector` and `files` to be defined before it.
+const SET_FILE_INPUT_SCRIPT: &str = r#"
+const el = document.querySelector(selector);
+if (el === null) {
+    throw new Error('No element matches selector ' + JSON.stringify(selector));
+}
+if (el.tagName !== 'INPUT' || el.type !== 'file') {
+    throw new Error('Element matching ' + JSON.stringify(selector) + ' is not an <input type="file">');
+}
+if (files.length > 1 && !el.multiple) {
+    throw new Error('Element matching ' + JSON.stringify(selector)
+        + ' takes one file, but ' + files.length + ' were given');
+}
+
+const transfer = new DataTransfer();
+for (const file of files) {
+    const binary = atob(file.data);
+    const bytes = new Uint8Array(binary.length);
+    for (let i = 0; i < binary.length; i++) {
+        bytes[i] = binary.charCodeAt(i);
+    }
+    transfer.items.add(new File([bytes], file.name, {
+        type: file.type,
+        lastModified: file.lastModified
+    }));
+}
+el.files = transfer.files;
+el.dispatchEvent(new Event('input', { bubbles: true }));
+el.dispatchEvent(new Event('change', { bubbles: true }));
+
+({ files: Array.from(el.files, function(f) { return { name: f.name, size: f.size, type: f.type }; }) })
+"#;
+
+/// Returns the MIME type a browser would give a file with this name, or an empty
+/// string when it's unknown, as browsers do.
+fn mime_type(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(|extension| extension.to_ascii_lowercase());
+    match extension.as_deref() {
+        Some("txt") => "text/plain",
+        Some("html" | "htm") => "text/html",
+        Some("css") => "text/css",
+        Some("csv") => "text/csv",
+        Some("js" | "mjs") => "text/javascript",
+        Some("json") => "application/json",
+        Some("xml") => "application/xml",
+        Some("pdf") => "application/pdf",
+        Some("zip") => "application/zip",
+        Some("gz") => "application/gzip",
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
+        Some("svg") => "image/svg+xml",
+        Some("ico") => "image/x-icon",
+        Some("bmp") => "image/bmp",
+        Some("mp3") => "audio/mpeg",
+        Some("wav") => "audio/wav",
+        Some("mp4") => "video/mp4",
+        Some("webm") => "video/webm",
+        _ => "",
+    }
+}
+
+/// Chooses files in an `<input type="file">`, as if the user had picked them.
+///
+/// The files are read from the upload directory and handed to the input, which then
+/// fires `input` and `change`, so upload forms can be tested end to end. An empty
+/// `paths` list clears the input. Each file keeps its name and modification time, and
+/// gets a MIME type guessed from its extension.
+///
+/// The command is disabled unless the app configures a directory with
+/// `Builder::upload_dir`, and it can't read files outside it, so it can't be used to
+/// send arbitrary files from the machine to the page. The files may be 25MB in total.
+///
+/// # Arguments
+///
+/// * `window` - The window containing the input
+/// * `selector` - CSS selector of the input
+/// * `handle` - Handle of the input from an earlier `execute_js` result, in place of
+///   `selector`
+/// * `by` - How `selector` locates the input: `"css"` (the default), `"xpath"`,
+///   `"text"`, `"id"` or `"name"`
+/// * `paths` - Paths of the files, relative to the upload directory
+/// * `window_label` - Label of the window to target (defaults to the calling window)
+/// * `upload_dir` - Configured upload directory
+/// * `executor` - Script executor state
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the files were set
+///   - `data`: `{ files: [{ name, size, type }] }` as the input now holds them
+///   - `error`: Error message (if nothing matched, the element isn't a file input, or
+///     it takes one file and several were given)
+/// * `Err(String)` - If no upload directory is configured, a path is outside it, a file
+///   can't be read, or the files are too large
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// await invoke('plugin:mcp-bridge|set_file_input', {
+///   selector: 'input[name=avatar]',
+///   paths: ['images/avatar.png']
+/// });
+/// ```
+#[command]
+#[allow(clippy::too_many_arguments)]
+pub async fn set_file_input<R: Runtime>(
+    window: WebviewWindow<R>,
+    selector: Option<String>,
+    handle: Option<String>,
+    by: Option<String>,
+    paths: Vec<String>,
+    window_label: Option<String>,
+    upload_dir: State<'_, UploadDir>,
+    executor: State<'_, ScriptExecutor>,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    let target = match ElementTarget::new(selector, handle, by) {
+        Ok(target) => target,
+        Err(error) => {
+            return Ok(serde_json::json!({
+                "success": false,
+                "error": error
+            }))
+        }
+    };
+
+    let Some(base) = upload_dir.0.as_deref() else {
+        return Err(
+            "set_file_input is disabled: the app has no upload directory configured".to_string(),
+        );
+    };
+
+    let mut files = Vec::with_capacity(paths.len());
+    let mut total_size = 0;
+    for path in &paths {
+        let file = resolve_within(base, path, "upload")?;
+        let metadata = tokio::fs::metadata(&file)
+            .await
+            .map_err(|e| format!("Failed to read file '{path}': {e}"))?;
+        total_size += metadata.len();
+        if total_size > MAX_UPLOAD_BYTES {
+            return Err(format!(
+                "Files are too large: at most {}MB can be set at once",
+                MAX_UPLOAD_BYTES / (1024 * 1024)
+            ));
+        }
+        let data = tokio::fs::read(&file)
+            .await
+            .map_err(|e| format!("Failed to read file '{path}': {e}"))?;
+        let last_modified = metadata
+            .modified()
+            .ok()
+            .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+            .map(|modified| modified.as_millis() as u64);
+
+        files.push(serde_json::json!({
+            "name": file.file_name().map(|name| name.to_string_lossy()),
+            "type": mime_type(&file),
+            "lastModified": last_modified,
+            "data": STANDARD.encode(data)
+        }));
+    }
+
+    let selector_js = serde_json::to_string(&target.selector()).map_err(|e| e.to_string())?;
+    let files_js = Value::Array(files);
+    let script = format!(
+        "const selector = {selector_js};\nconst files = {files_js};\n{SET_FILE_INPUT_SCRIPT}"
+    );
+
+    run_script(
+        window,
+        target.wrap(script),
+        None,
+        None,
+        None,
+        None,
+        None,
+        executor,
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mime_type_comes_from_the_extension() {
+        assert_eq!(mime_type(Path::new("images/avatar.PNG")), "image/png");
+        assert_eq!(mime_type(Path::new("report.pdf")), "application/pdf");
+        assert_eq!(mime_type(Path::new("notes")), "");
+    }
+}
@@ -0,0 +1,272 @@
+//! Handles to DOM elements that outlive the script that found them.
+//!
+//! A script can't send a DOM node back as JSON, so when `execute_js` returns an element
+//! (or an array or `NodeList` of them) each one is registered in the page and replaced
+//! with `{ __handle, tag, id, text }`. Commands that act on one element accept the
+//! handle in place of a selector, and scripts can get the element back with
+//! `__mcp.element(handle)`. The registry only holds weak references, so handles don't
+//! keep removed elements alive, and it's gone after a navigation or reload.
+
+use super::locators::{By, LOCATE_JS};
+
+/// Installs the page's handle registry as `window.__MCP_HANDLES__`, if it isn't yet.
+///
+/// `add(el)` returns the element's handle, the same one every time for the same
+/// element. `get(handle)` returns the element, or throws an error with
+/// `error_kind: "stale_handle"` when it was garbage-collected, removed from the page,
+/// or never registered.
+pub(crate) const HANDLE_REGISTRY_JS: &str = r#"
+if (!window.__MCP_HANDLES__) {
+    window.__MCP_HANDLES__ = (function() {
+        var refs = new Map();
+        var ids = new WeakMap();
+        var next = 1;
+        function makeRef(el) {
+            return typeof WeakRef === 'function' ? new WeakRef(el) : { deref: function() { return el; } };
+        }
+        function live(ref) {
+            var el = ref ? ref.deref() : undefined;
+            return el && el.isConnected ? el : null;
+        }
+        return {
+            add: function(el) {
+                var id = ids.get(el);
+                if (id === undefined || !refs.has(id)) {
+                    id = 'h' + next++;
+                    ids.set(el, id);
+                    refs.set(id, makeRef(el));
+                    // Drop dead entries now and then, so the map doesn't only grow
+                    if (refs.size % 100 === 0) {
+                        refs.forEach(function(ref, key) {
+                            if (!live(ref)) refs.delete(key);
+                        });
+                    }
+                }
+                return id;
+            },
+            get: function(id) {
+                var el = live(refs.get(id));
+                if (!el) {
+                    refs.delete(id);
+                    var error = new Error('Stale handle ' + JSON.stringify(id)
+                        + ': the element was removed from the page or garbage-collected');
+                    error.__mcpErrorKind = 'stale_handle';
+                    throw error;
+                }
+                return el;
+            }
+        };
+    })();
+}
+"#;
+
+/// Resolves the element of `__mcpHandle`, which is expected to be defined before it,
+/// and defines `__mcpFindTarget()` returning it.
+const RESOLVE_HANDLE_SCRIPT: &str = r#"
+const __mcpHandleElement = window.__MCP_HANDLES__.get(__mcpHandle);
+function __mcpFindTarget() {
+    return [__mcpHandleElement];
+}
+"#;
+
+/// Defines `__mcpFindTarget()`, returning the elements `__mcpLocator` matches.
+///
+/// Expects `__mcpLocator` (`{ by, value }`) and `LOCATE_JS` before it. The elements are
+/// looked up on every call, so commands that wait see the page as it changes.
+const RESOLVE_LOCATOR_SCRIPT: &str = r#"
+function __mcpFindTarget() {
+    return __mcpLocate(__mcpLocator.by, __mcpLocator.value);
+}
+"#;
+
+/// Makes looking up `__mcpTargetSelector` find the elements of `__mcpFindTarget()`.
+///
+/// Expects `__mcpTargetSelector` and `__mcpFindTarget` to be defined before it, and a
+/// command's script to follow it. `document` is shadowed for that script only, so its
+/// `querySelector` and `querySelectorAll` return the target for the target's selector
+/// and behave as usual for any other selector.
+const SHADOW_DOCUMENT_SCRIPT: &str = r#"
+const document = new Proxy(window.document, {
+    get: function(target, prop) {
+        if (prop === 'querySelector') {
+            return function(selector) {
+                if (selector !== __mcpTargetSelector) return target.querySelector(selector);
+                const found = __mcpFindTarget();
+                return found.length > 0 ? found[0] : null;
+            };
+        }
+        if (prop === 'querySelectorAll') {
+            return function(selector) {
+                return selector === __mcpTargetSelector ? __mcpFindTarget() : target.querySelectorAll(selector);
+            };
+        }
+        const value = target[prop];
+        return typeof value === 'function' ? value.bind(target) : value;
+    }
+});
+"#;
+
+/// Defines `{prefix}_encodeHandles(value)`, which replaces elements with handles.
+///
+/// An element, or an array, `NodeList` or `HTMLCollection` holding elements, is
+/// converted. Anything else, including elements nested deeper in objects, is returned
+/// unchanged. Elements are recognized by `nodeType` rather than `instanceof`, so those
+/// returned from the isolated realm or a frame are too.
+pub(crate) fn handle_helper_js(prefix: &str) -> String {
+    format!(
+        r#"
+            function {prefix}_encodeHandles(value) {{
+                function isElement(item) {{
+                    return item !== null && typeof item === 'object' && item.nodeType === 1
+                        && typeof item.tagName === 'string';
+                }}
+                function toHandle(el) {{
+                    {HANDLE_REGISTRY_JS}
+                    var text = (el.innerText !== undefined ? el.innerText : el.textContent || '').trim();
+                    return {{
+                        __handle: window.__MCP_HANDLES__.add(el),
+                        tag: el.tagName.toLowerCase(),
+                        id: el.id || null,
+                        text: text.length > 100 ? text.slice(0, 100) + '…' : text
+                    }};
+                }}
+                if (isElement(value)) {{
+                    return toHandle(value);
+                }}
+                var tag = Object.prototype.toString.call(value);
+                var isList = Array.isArray(value) || tag === '[object NodeList]' || tag === '[object HTMLCollection]';
+                if (isList && Array.prototype.some.call(value, isElement)) {{
+                    return Array.prototype.map.call(value, function(item) {{
+                        return isElement(item) ? toHandle(item) : item;
+                    }});
+                }}
+                return value;
+            }}
+        "#
+    )
+}
+
+/// The element a command acts on: the match of a CSS selector, the element behind a
+/// handle, or the matches of another locator strategy.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum ElementTarget {
+    Selector(String),
+    Handle(String),
+    Locator(By, String),
+}
+
+impl ElementTarget {
+    /// Picks the target from a command's `selector`, `handle` and `by` arguments.
+    ///
+    /// Exactly one of `selector` and `handle` must be given, and `by` says how to read
+    /// `selector` (CSS by default).
+    pub(crate) fn new(
+        selector: Option<String>,
+        handle: Option<String>,
+        by: Option<String>,
+    ) -> Result<Self, String> {
+        let by = By::parse(by.as_deref())?;
+        match (selector, handle) {
+            (Some(selector), None) if by == By::Css => Ok(Self::Selector(selector)),
+            (Some(selector), None) => Ok(Self::Locator(by, selector)),
+            (None, Some(_)) if by != By::Css => {
+                Err(format!("by \"{by}\" only applies to selector, not handle"))
+            }
+            (None, Some(handle)) => Ok(Self::Handle(handle)),
+            (Some(_), Some(_)) => Err("Pass either selector or handle, not both".to_string()),
+            (None, None) => Err("Missing selector or handle argument".to_string()),
+        }
+    }
+
+    /// The string the command's script looks the element up by.
+    ///
+    /// For a handle it's `handle:<id>`, and for another locator strategy it's
+    /// `<by>=<selector>`, like `xpath=//button`. Neither is ever a valid CSS selector,
+    /// so they can't be confused with one and read well in error messages.
+    pub(crate) fn selector(&self) -> String {
+        match self {
+            Self::Selector(selector) => selector.clone(),
+            Self::Handle(handle) => format!("handle:{handle}"),
+            Self::Locator(by, selector) => format!("{by}={selector}"),
+        }
+    }
+
+    /// Prepares a command's script so that looking up [`Self::selector`] finds the
+    /// target.
+    ///
+    /// Scripts for a CSS selector are left as they are. For a handle, the element is
+    /// resolved first, so a stale handle fails before the script runs. Scripts for
+    /// another locator strategy may run inside a frame, where they look in the frame's
+    /// document.
+    pub(crate) fn wrap(&self, script: String) -> String {
+        let selector_js = serde_json::Value::String(self.selector());
+        match self {
+            Self::Selector(_) => script,
+            Self::Handle(handle) => {
+                let handle_js = serde_json::Value::String(handle.clone());
+                format!(
+                    "const __mcpHandle = {handle_js};\nconst __mcpTargetSelector = {selector_js};\n{HANDLE_REGISTRY_JS}{RESOLVE_HANDLE_SCRIPT}{SHADOW_DOCUMENT_SCRIPT}{script}"
+                )
+            }
+            Self::Locator(by, selector) => {
+                let locator_js = serde_json::json!({ "by": by.to_string(), "value": selector });
+                format!(
+                    "const __mcpLocator = {locator_js};\nconst __mcpTargetSelector = {selector_js};\n{LOCATE_JS}{RESOLVE_LOCATOR_SCRIPT}{SHADOW_DOCUMENT_SCRIPT}{script}"
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_target_needs_exactly_one_of_selector_and_handle() {
+        assert_eq!(
+            ElementTarget::new(Some("#a".into()), None, None),
+            Ok(ElementTarget::Selector("#a".into()))
+        );
+        assert_eq!(
+            ElementTarget::new(None, Some("h1".into()), None),
+            Ok(ElementTarget::Handle("h1".into()))
+        );
+        assert!(ElementTarget::new(Some("#a".into()), Some("h1".into()), None).is_err());
+        assert!(ElementTarget::new(None, None, None).is_err());
+    }
+
+    #[test]
+    fn test_locators_other_than_css_resolve_in_the_page() {
+        assert_eq!(
+            ElementTarget::new(Some("#a".into()), None, Some("css".into())),
+            Ok(ElementTarget::Selector("#a".into()))
+        );
+        assert!(ElementTarget::new(None, Some("h1".into()), Some("xpath".into())).is_err());
+        assert!(ElementTarget::new(Some("a".into()), None, Some("link".into())).is_err());
+
+        let target = ElementTarget::new(Some("//button".into()), None, Some("xpath".into()));
+        let target = target.unwrap();
+        assert_eq!(target.selector(), "xpath=//button");
+        let script = target.wrap("selector".into());
+        assert!(
+            script.starts_with("const __mcpLocator = {\"by\":\"xpath\",\"value\":\"//button\"};\n")
+        );
+        assert!(script.contains("function __mcpLocate(by, value)"));
+        assert!(script.ends_with("selector"));
+    }
+
+    #[test]
+    fn test_handle_scripts_resolve_the_element_first() {
+        let target = ElementTarget::Handle("h7".into());
+        assert_eq!(target.selector(), "handle:h7");
+
+        let script = target.wrap("const selector = \"handle:h7\";\nselector".into());
+        assert!(script.starts_with("const __mcpHandle = \"h7\";\n"));
+        assert!(script.contains("window.__MCP_HANDLES__.get(__mcpHandle)"));
+        assert!(script.ends_with("const selector = \"handle:h7\";\nselector"));
+
+        let selector = ElementTarget::Selector("#a".into());
+        assert_eq!(selector.wrap("x".into()), "x");
+    }
+}
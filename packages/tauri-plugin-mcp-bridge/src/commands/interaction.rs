@@ -0,0 +1,1488 @@
+//! Simulated user interaction with page elements.
+
+use super::execute_js::{run_script, DEFAULT_TIMEOUT_MS};
+use super::handles::ElementTarget;
+use super::list_windows::target_window;
+use super::script_executor::ScriptExecutor;
+use serde::Deserialize;
+use serde_json::Value;
+use tauri::{command, Runtime, State, WebviewWindow};
+
+/// Defines `pickIndex(matches, selector, nth, strict)`, which returns the index of the
+/// match to act on, and `pickElement` with the same arguments, which returns the match.
+///
+/// `nth` is `null` when not given. Several matches without an `nth` throw with
+/// `error_kind: "ambiguous_selector"` when `strict` is set, and pick the first one
+/// otherwise. `pickElement` also throws when there's no element to pick.
+const PICK_INDEX_JS: &str = r#"
+function pickIndex(matches, selector, nth, strict) {
+    if (matches.length > 1 && nth === null && strict) {
+        const error = new Error('Ambiguous selector ' + JSON.stringify(selector) + ': it matched '
+            + matches.length + ' elements. Pass nth to pick one, or strict: false to use the first');
+        error.__mcpErrorKind = 'ambiguous_selector';
+        throw error;
+    }
+    return nth === null ? 0 : nth;
+}
+function pickElement(matches, selector, nth, strict) {
+    if (matches.length === 0) {
+        throw new Error('No element matches selector ' + JSON.stringify(selector));
+    }
+    const index = pickIndex(matches, selector, nth, strict);
+    if (index >= matches.length) {
+        throw new Error('Selector ' + JSON.stringify(selector) + ' matched ' + matches.length
+            + ' element(s), so there is no element at index ' + index);
+    }
+    return matches[index];
+}
+"#;
+
+/// Clicks the element matching `selector` that `pickElement` picks, with a full pointer
+/// and mouse event sequence.
+///
+/// Expects `selector`, `nth`, `strict` and `PICK_INDEX_JS` before it.
+const CLICK_ELEMENT_SCRIPT: &str = r#"
+const matches = document.querySelectorAll(selector);
+const el = pickElement(matches, selector, nth, strict);
+el.scrollIntoView({ block: 'center', inline: 'center' });
+
+const style = window.getComputedStyle(el);
+const rect = el.getBoundingClientRect();
+if (style.display === 'none' || style.visibility === 'hidden' || rect.width === 0 || rect.height === 0) {
+    throw new Error('Element matching ' + JSON.stringify(selector) + ' is not visible');
+}
+if (el.matches(':disabled')) {
+    throw new Error('Element matching ' + JSON.stringify(selector) + ' is disabled');
+}
+
+const x = rect.left + rect.width / 2;
+const y = rect.top + rect.height / 2;
+const init = {
+    bubbles: true,
+    cancelable: true,
+    composed: true,
+    view: window,
+    clientX: x,
+    clientY: y,
+    screenX: window.screenX + x,
+    screenY: window.screenY + y,
+    button: 0
+};
+const pointerInit = Object.assign({ pointerId: 1, pointerType: 'mouse', isPrimary: true }, init);
+
+el.dispatchEvent(new PointerEvent('pointerdown', Object.assign({ buttons: 1 }, pointerInit)));
+el.dispatchEvent(new MouseEvent('mousedown', Object.assign({ buttons: 1 }, init)));
+if (typeof el.focus === 'function') el.focus();
+el.dispatchEvent(new PointerEvent('pointerup', pointerInit));
+el.dispatchEvent(new MouseEvent('mouseup', init));
+el.dispatchEvent(new MouseEvent('click', init));
+
+({ tag: el.tagName.toLowerCase(), x: Math.round(x), y: Math.round(y), matched_count: matches.length })
+"#;
+
+/// Clicks an element the way a user would.
+///
+/// Unlike calling `.click()` from `execute_js`, this dispatches `pointerdown`,
+/// `mousedown`, `pointerup`, `mouseup` and `click` at the center of the element,
+/// so handlers that rely on the full sequence or on coordinates (as many React and
+/// Vue components do) see a real click. The element is scrolled into view first.
+///
+/// A selector matching several elements fails with `error_kind: "ambiguous_selector"`
+/// unless `nth` says which one to click, so a vague selector doesn't click the wrong
+/// element. Pass `strict: false` to click the first match instead.
+///
+/// # Arguments
+///
+/// * `window` - The window containing the element
+/// * `selector` - CSS selector of the element to click
+/// * `handle` - Handle of the element from an earlier `execute_js` result, in place of
+///   `selector`
+/// * `by` - How `selector` locates the element: `"css"` (the default), `"xpath"`,
+///   `"text"`, `"id"` or `"name"`
+/// * `nth` - Which match to click when the selector matches several, counting from 0
+/// * `strict` - Whether several matches without `nth` are an error (defaults to true)
+/// * `window_label` - Label of the window to target (defaults to the calling window)
+/// * `executor` - Script executor state
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the element was clicked
+///   - `data`: `{ tag, x, y, matched_count }` with the clicked element's tag and
+///     viewport coordinates, and how many elements the selector matched
+///   - `error`: Error message (if nothing matched, the selector is ambiguous, or the
+///     element is hidden or disabled)
+///   - `error_kind`: `"ambiguous_selector"` when several elements matched and no `nth`
+///     was given
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// await invoke('plugin:mcp-bridge|click_element', { selector: 'li.item', nth: 2 });
+/// ```
+#[command]
+#[allow(clippy::too_many_arguments)]
+pub async fn click_element<R: Runtime>(
+    window: WebviewWindow<R>,
+    selector: Option<String>,
+    handle: Option<String>,
+    by: Option<String>,
+    nth: Option<usize>,
+    strict: Option<bool>,
+    window_label: Option<String>,
+    executor: State<'_, ScriptExecutor>,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    let target = match ElementTarget::new(selector, handle, by) {
+        Ok(target) => target,
+        Err(error) => {
+            return Ok(serde_json::json!({
+                "success": false,
+                "error": error
+            }))
+        }
+    };
+
+    let args_js = match_args_js(&target.selector(), nth, strict)?;
+    let script = format!("{args_js}{PICK_INDEX_JS}{CLICK_ELEMENT_SCRIPT}");
+
+    run_script(
+        window,
+        target.wrap(script),
+        None,
+        None,
+        None,
+        None,
+        None,
+        executor,
+    )
+    .await
+}
+
+/// Moves the pointer onto the element matching `selector` that `pickIndex` picks,
+/// reporting whether it was found and visible.
+///
+/// Expects `selector`, `nth`, `strict` and `PICK_INDEX_JS` before it.
+const HOVER_ELEMENT_SCRIPT: &str = r#"
+const matches = document.querySelectorAll(selector);
+const index = pickIndex(matches, selector, nth, strict);
+const el = index < matches.length ? matches[index] : null;
+let result = { found: el !== null, visible: false, matched_count: matches.length };
+if (el !== null) {
+    el.scrollIntoView({ block: 'center', inline: 'center' });
+
+    const style = window.getComputedStyle(el);
+    const rect = el.getBoundingClientRect();
+    if (style.display !== 'none' && style.visibility !== 'hidden' && rect.width > 0 && rect.height > 0) {
+        const x = rect.left + rect.width / 2;
+        const y = rect.top + rect.height / 2;
+        const init = {
+            cancelable: true,
+            composed: true,
+            view: window,
+            clientX: x,
+            clientY: y,
+            screenX: window.screenX + x,
+            screenY: window.screenY + y
+        };
+        const bubbling = Object.assign({ bubbles: true }, init);
+        const pointer = { pointerId: 1, pointerType: 'mouse', isPrimary: true };
+
+        // `over` and `move` bubble, while `enter` is sent to the element only
+        el.dispatchEvent(new PointerEvent('pointerover', Object.assign({}, pointer, bubbling)));
+        el.dispatchEvent(new MouseEvent('mouseover', bubbling));
+        el.dispatchEvent(new PointerEvent('pointerenter', Object.assign({ bubbles: false }, pointer, init)));
+        el.dispatchEvent(new MouseEvent('mouseenter', Object.assign({ bubbles: false }, init)));
+        el.dispatchEvent(new PointerEvent('pointermove', Object.assign({}, pointer, bubbling)));
+        el.dispatchEvent(new MouseEvent('mousemove', bubbling));
+
+        result = {
+            found: true,
+            visible: true,
+            tag: el.tagName.toLowerCase(),
+            x: Math.round(x),
+            y: Math.round(y),
+            matched_count: matches.length
+        };
+    }
+}
+result
+"#;
+
+/// Hovers over an element the way a user's pointer would.
+///
+/// Dispatches `pointerover`, `mouseover`, `pointerenter`, `mouseenter`, `pointermove`
+/// and `mousemove` at the center of the element, after scrolling it into view, so
+/// tooltips, dropdowns and menus that open on hover appear. Follow up with
+/// `wait_for_selector` to wait for the revealed content.
+///
+/// Only the events are simulated: CSS `:hover` rules don't apply to synthetic events,
+/// so UI revealed purely by CSS stays hidden.
+///
+/// A missing or hidden element isn't an error. No events are dispatched then, and
+/// `found` and `visible` say why. Several matches without `nth` are, as for
+/// [`click_element`], an error unless `strict` is false.
+///
+/// # Arguments
+///
+/// * `window` - The window containing the element
+/// * `selector` - CSS selector of the element to hover
+/// * `handle` - Handle of the element from an earlier `execute_js` result, in place of
+///   `selector`
+/// * `by` - How `selector` locates the element: `"css"` (the default), `"xpath"`,
+///   `"text"`, `"id"` or `"name"`
+/// * `nth` - Which match to hover when the selector matches several, counting from 0
+/// * `strict` - Whether several matches without `nth` are an error (defaults to true)
+/// * `window_label` - Label of the window to target (defaults to the calling window)
+/// * `executor` - Script executor state
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the script ran
+///   - `data`: `{ found, visible, matched_count }`, plus `{ tag, x, y }` with the
+///     element's tag and viewport coordinates when it was hovered
+///   - `error`: Error message (if failed)
+///   - `error_kind`: `"ambiguous_selector"` when several elements matched and no `nth`
+///     was given
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const hover = await invoke('plugin:mcp-bridge|hover_element', { selector: '.info-icon' });
+/// if (hover.data.visible) {
+///   await invoke('plugin:mcp-bridge|wait_for_selector', { selector: '[role="tooltip"]' });
+/// }
+/// ```
+#[command]
+#[allow(clippy::too_many_arguments)]
+pub async fn hover_element<R: Runtime>(
+    window: WebviewWindow<R>,
+    selector: Option<String>,
+    handle: Option<String>,
+    by: Option<String>,
+    nth: Option<usize>,
+    strict: Option<bool>,
+    window_label: Option<String>,
+    executor: State<'_, ScriptExecutor>,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    let target = match ElementTarget::new(selector, handle, by) {
+        Ok(target) => target,
+        Err(error) => {
+            return Ok(serde_json::json!({
+                "success": false,
+                "error": error
+            }))
+        }
+    };
+
+    let args_js = match_args_js(&target.selector(), nth, strict)?;
+    let script = format!("{args_js}{PICK_INDEX_JS}{HOVER_ELEMENT_SCRIPT}");
+
+    run_script(
+        window,
+        target.wrap(script),
+        None,
+        None,
+        None,
+        None,
+        None,
+        executor,
+    )
+    .await
+}
+
+/// Declares `selector`, `nth` (`null` when not given) and `strict` for `PICK_INDEX_JS`.
+fn match_args_js(
+    selector: &str,
+    nth: Option<usize>,
+    strict: Option<bool>,
+) -> Result<String, String> {
+    let selector_js = serde_json::to_string(selector).map_err(|e| e.to_string())?;
+    let nth_js = nth.map_or_else(|| "null".to_string(), |nth| nth.to_string());
+    let strict = strict.unwrap_or(true);
+    Ok(format!(
+        "const selector = {selector_js};\nconst nth = {nth_js};\nconst strict = {strict};\n"
+    ))
+}
+
+/// Number of intermediate `pointermove` steps in a pointer drag.
+const POINTER_DRAG_STEPS: u32 = 10;
+
+/// Drags from `source` to `target`, each `{ selector, nth }` or `{ x, y }` in viewport
+/// coordinates, with HTML5 drag events or pointer events depending on `kind`.
+///
+/// Expects `source`, `target`, `kind`, `steps`, `strict` and `PICK_INDEX_JS` before it.
+const DRAG_AND_DROP_SCRIPT: &str = r#"
+function resolvePoint(spec, role) {
+    if (spec.selector !== undefined) {
+        const matches = document.querySelectorAll(spec.selector);
+        if (matches.length === 0) {
+            throw new Error('No ' + role + ' element matches selector ' + JSON.stringify(spec.selector));
+        }
+        const el = pickElement(matches, spec.selector, spec.nth, strict);
+        const style = window.getComputedStyle(el);
+        const rect = el.getBoundingClientRect();
+        if (style.display === 'none' || style.visibility === 'hidden' || rect.width === 0 || rect.height === 0) {
+            throw new Error(role[0].toUpperCase() + role.slice(1) + ' element matching '
+                + JSON.stringify(spec.selector) + ' is not visible');
+        }
+        return {
+            el: el,
+            x: rect.left + rect.width / 2,
+            y: rect.top + rect.height / 2,
+            matchedCount: matches.length
+        };
+    }
+    const el = document.elementFromPoint(spec.x, spec.y);
+    if (el === null) {
+        throw new Error('No ' + role + ' element at (' + spec.x + ', ' + spec.y + ')');
+    }
+    return { el: el, x: spec.x, y: spec.y };
+}
+function eventInit(point, extra) {
+    return Object.assign({
+        bubbles: true,
+        cancelable: true,
+        composed: true,
+        view: window,
+        clientX: point.x,
+        clientY: point.y,
+        screenX: window.screenX + point.x,
+        screenY: window.screenY + point.y
+    }, extra);
+}
+function describe(point) {
+    const described = { tag: point.el.tagName.toLowerCase(), x: Math.round(point.x), y: Math.round(point.y) };
+    if (point.matchedCount !== undefined) described.matched_count = point.matchedCount;
+    return described;
+}
+
+// Only the source is scrolled to, since scrolling to the target could move it away
+if (source.selector !== undefined) {
+    const matches = document.querySelectorAll(source.selector);
+    const index = pickIndex(matches, source.selector, source.nth, strict);
+    if (index < matches.length) matches[index].scrollIntoView({ block: 'nearest', inline: 'nearest' });
+}
+const from = resolvePoint(source, 'source');
+const to = resolvePoint(target, 'target');
+let dropped;
+
+if (kind === 'html5') {
+    const dataTransfer = new DataTransfer();
+    const fire = function(type, el, point) {
+        return el.dispatchEvent(new DragEvent(type, eventInit(point, { dataTransfer: dataTransfer })));
+    };
+    // A cancelled dragstart means the element refused to be dragged
+    if (!fire('dragstart', from.el, from)) {
+        dropped = false;
+    } else {
+        fire('dragenter', to.el, to);
+        // As in a real drag, the drop only happens if a dragover handler accepted it
+        dropped = !fire('dragover', to.el, to);
+        if (dropped) {
+            fire('drop', to.el, to);
+        }
+        fire('dragend', from.el, to);
+    }
+} else {
+    const pointer = { pointerId: 1, pointerType: 'mouse', isPrimary: true, button: 0 };
+    from.el.dispatchEvent(new PointerEvent('pointerdown', eventInit(from, Object.assign({ buttons: 1 }, pointer))));
+    from.el.dispatchEvent(new MouseEvent('mousedown', eventInit(from, { button: 0, buttons: 1 })));
+    for (let i = 1; i <= steps; i++) {
+        const point = { x: from.x + (to.x - from.x) * i / steps, y: from.y + (to.y - from.y) * i / steps };
+        const el = document.elementFromPoint(point.x, point.y) || from.el;
+        el.dispatchEvent(new PointerEvent('pointermove', eventInit(point, Object.assign({ buttons: 1 }, pointer))));
+        el.dispatchEvent(new MouseEvent('mousemove', eventInit(point, { button: 0, buttons: 1 })));
+        // Give libraries that track movement per frame a chance to react
+        await new Promise(function(resolve) { setTimeout(resolve, 16); });
+    }
+    const release = document.elementFromPoint(to.x, to.y) || to.el;
+    release.dispatchEvent(new PointerEvent('pointerup', eventInit(to, pointer)));
+    release.dispatchEvent(new MouseEvent('mouseup', eventInit(to, { button: 0 })));
+    dropped = true;
+}
+
+({ kind: kind, source: describe(from), target: describe(to), dropped: dropped })
+"#;
+
+/// A viewport position for `drag_and_drop`.
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct DragPosition {
+    pub x: f64,
+    pub y: f64,
+}
+
+/// Builds one end of a drag from a selector or a position, whichever was given.
+///
+/// `nth` picks among several matches of the selector, so it can't go with a position.
+fn drag_endpoint(
+    selector: Option<String>,
+    position: Option<DragPosition>,
+    nth: Option<usize>,
+    role: &str,
+) -> Result<Value, String> {
+    if nth.is_some() && selector.is_none() {
+        return Err(format!("{role}_nth can only be given with {role}_selector"));
+    }
+    match (selector, position) {
+        (Some(selector), None) => Ok(serde_json::json!({ "selector": selector, "nth": nth })),
+        (None, Some(position)) if position.x.is_finite() && position.y.is_finite() => {
+            Ok(serde_json::json!({ "x": position.x, "y": position.y }))
+        }
+        (None, Some(_)) => Err(format!("Invalid {role} position: x and y must be finite")),
+        (Some(_), Some(_)) => Err(format!(
+            "Pass either {role}_selector or {role}_position, not both"
+        )),
+        (None, None) => Err(format!("Missing {role}_selector or {role}_position")),
+    }
+}
+
+/// Drags one element onto another.
+///
+/// Each end is given as a selector (its center is used) or a position in viewport
+/// coordinates (the element there is used). `kind` picks the events:
+///
+/// - `"html5"` (the default): `dragstart` on the source, then `dragenter`, `dragover`
+///   and `drop` on the target and `dragend` on the source, all sharing one
+///   `DataTransfer`, for native drag and drop such as sortable lists and drop zones. As
+///   in a real drag, `drop` only fires if a `dragover` handler called
+///   `preventDefault()`, and nothing happens after a cancelled `dragstart`.
+/// - `"pointer"`: `pointerdown` and `mousedown` on the source, a series of
+///   `pointermove` and `mousemove` events along the way, and `pointerup` and `mouseup`
+///   on the target, for libraries that implement dragging themselves.
+///
+/// Files can't be put into the `DataTransfer`, so dropping files from outside the app
+/// can't be simulated.
+///
+/// # Arguments
+///
+/// * `window` - The window containing the elements
+/// * `source_selector` - CSS selector of the element to drag
+/// * `target_selector` - CSS selector of the element to drop onto
+/// * `source_position` - `{ x, y }` to start dragging at, instead of `source_selector`
+/// * `target_position` - `{ x, y }` to drop at, instead of `target_selector`
+/// * `kind` - `"html5"` (the default) or `"pointer"`
+/// * `source_nth` - Which match of `source_selector` to drag when it matches several,
+///   counting from 0
+/// * `target_nth` - Which match of `target_selector` to drop onto when it matches
+///   several, counting from 0
+/// * `strict` - Whether a selector matching several elements without its `nth` is an
+///   error (defaults to true)
+/// * `window_label` - Label of the window to target (defaults to the calling window)
+/// * `executor` - Script executor state
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the drag was performed
+///   - `data`: `{ kind, source, target, dropped }` where `source` and `target` are
+///     `{ tag, x, y }`, plus `matched_count` for an end given by selector, and
+///     `dropped` is whether the target accepted the drop (always true for pointer
+///     drags)
+///   - `error`: Error message (if an end is missing, hidden, invalid or ambiguous)
+///   - `error_kind`: `"ambiguous_selector"` when a selector matched several elements
+///     and its `nth` wasn't given
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// await invoke('plugin:mcp-bridge|drag_and_drop', {
+///   sourceSelector: '#card-1',
+///   targetSelector: '#done-column'
+/// });
+/// await invoke('plugin:mcp-bridge|drag_and_drop', {
+///   sourceSelector: '.slider-handle',
+///   targetPosition: { x: 400, y: 120 },
+///   kind: 'pointer'
+/// });
+/// ```
+#[command]
+#[allow(clippy::too_many_arguments)]
+pub async fn drag_and_drop<R: Runtime>(
+    window: WebviewWindow<R>,
+    source_selector: Option<String>,
+    target_selector: Option<String>,
+    source_position: Option<DragPosition>,
+    target_position: Option<DragPosition>,
+    kind: Option<String>,
+    source_nth: Option<usize>,
+    target_nth: Option<usize>,
+    strict: Option<bool>,
+    window_label: Option<String>,
+    executor: State<'_, ScriptExecutor>,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    let kind = kind.unwrap_or_else(|| "html5".to_string());
+    let endpoints = if kind != "html5" && kind != "pointer" {
+        Err(format!(
+            "Invalid kind '{kind}': expected 'html5' or 'pointer'"
+        ))
+    } else {
+        drag_endpoint(source_selector, source_position, source_nth, "source").and_then(|source| {
+            drag_endpoint(target_selector, target_position, target_nth, "target")
+                .map(|target| (source, target))
+        })
+    };
+    let (source, target) = match endpoints {
+        Ok(endpoints) => endpoints,
+        Err(error) => {
+            return Ok(serde_json::json!({
+                "success": false,
+                "error": error
+            }))
+        }
+    };
+
+    let kind_js = serde_json::to_string(&kind).map_err(|e| e.to_string())?;
+    let strict = strict.unwrap_or(true);
+    let script = format!(
+        "const source = {source};\nconst target = {target};\nconst kind = {kind_js};\nconst steps = {POINTER_DRAG_STEPS};\nconst strict = {strict};\n{PICK_INDEX_JS}{DRAG_AND_DROP_SCRIPT}"
+    );
+
+    run_script(window, script, None, None, None, None, None, executor).await
+}
+
+/// Sets the value of the element matching `selector` that `pickElement` picks, through
+/// the native setter.
+///
+/// Expects `selector`, `nth`, `strict`, `value` and `PICK_INDEX_JS` before it.
+const SET_INPUT_VALUE_SCRIPT: &str = r#"
+const matches = document.querySelectorAll(selector);
+const el = pickElement(matches, selector, nth, strict);
+
+let proto;
+if (el instanceof HTMLInputElement) proto = HTMLInputElement.prototype;
+else if (el instanceof HTMLTextAreaElement) proto = HTMLTextAreaElement.prototype;
+else if (el instanceof HTMLSelectElement) proto = HTMLSelectElement.prototype;
+else {
+    throw new Error('Element matching ' + JSON.stringify(selector) + ' is a <'
+        + el.tagName.toLowerCase() + '>, not an <input>, <textarea> or <select>');
+}
+
+// Frameworks like React track the value through the instance's own setter, so
+// going through the prototype's setter is what makes them notice the change
+Object.getOwnPropertyDescriptor(proto, 'value').set.call(el, value);
+el.dispatchEvent(new Event('input', { bubbles: true }));
+el.dispatchEvent(new Event('change', { bubbles: true }));
+
+({ value: el.value, matched_count: matches.length })
+"#;
+
+/// Sets the value of an `<input>`, `<textarea>` or `<select>` so frameworks see the change.
+///
+/// Assigning `element.value` directly doesn't fire `input` or `change`, so React and
+/// Vue forms don't update. This uses the native value setter and then dispatches both
+/// events. For a `<select>`, `value` must match one of its options.
+///
+/// # Arguments
+///
+/// * `window` - The window containing the element
+/// * `selector` - CSS selector of the element
+/// * `handle` - Handle of the element from an earlier `execute_js` result, in place of
+///   `selector`
+/// * `by` - How `selector` locates the element: `"css"` (the default), `"xpath"`,
+///   `"text"`, `"id"` or `"name"`
+/// * `value` - The value to set
+/// * `nth` - Which match to set when the selector matches several, counting from 0
+/// * `strict` - Whether several matches without `nth` are an error (defaults to true)
+/// * `window_label` - Label of the window to target (defaults to the calling window)
+/// * `executor` - Script executor state
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the value was set
+///   - `data`: `{ value, matched_count }` with the element's value after the update,
+///     and how many elements the selector matched
+///   - `error`: Error message (if nothing matched, the selector is ambiguous, or the
+///     element isn't input-like)
+///   - `error_kind`: `"ambiguous_selector"` when several elements matched and no `nth`
+///     was given
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// await invoke('plugin:mcp-bridge|set_input_value', { selector: '#email', value: 'a@b.c' });
+/// ```
+#[command]
+#[allow(clippy::too_many_arguments)]
+pub async fn set_input_value<R: Runtime>(
+    window: WebviewWindow<R>,
+    selector: Option<String>,
+    handle: Option<String>,
+    by: Option<String>,
+    value: String,
+    nth: Option<usize>,
+    strict: Option<bool>,
+    window_label: Option<String>,
+    executor: State<'_, ScriptExecutor>,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    let target = match ElementTarget::new(selector, handle, by) {
+        Ok(target) => target,
+        Err(error) => {
+            return Ok(serde_json::json!({
+                "success": false,
+                "error": error
+            }))
+        }
+    };
+
+    let args_js = match_args_js(&target.selector(), nth, strict)?;
+    let value_js = serde_json::to_string(&value).map_err(|e| e.to_string())?;
+    let script =
+        format!("{args_js}const value = {value_js};\n{PICK_INDEX_JS}{SET_INPUT_VALUE_SCRIPT}");
+
+    run_script(
+        window,
+        target.wrap(script),
+        None,
+        None,
+        None,
+        None,
+        None,
+        executor,
+    )
+    .await
+}
+
+/// Whether `el` has focus, also for elements inside a shadow root.
+///
+/// Expects `el` to be defined before it.
+const HAS_FOCUS_JS: &str = r#"
+function hasFocus(el) {
+    return el.getRootNode().activeElement === el;
+}
+"#;
+
+/// Focuses the element matching `selector` that `pickElement` picks, failing when it
+/// can't take focus.
+///
+/// A webview without system focus may move focus without firing any events, in which
+/// case `blur`/`focusout` and `focus`/`focusin` are dispatched by hand. Expects
+/// `selector`, `nth`, `strict`, `preventScroll`, `PICK_INDEX_JS` and `HAS_FOCUS_JS`
+/// before it.
+const FOCUS_ELEMENT_SCRIPT: &str = r#"
+const matches = document.querySelectorAll(selector);
+const el = pickElement(matches, selector, nth, strict);
+const previous = document.activeElement;
+const wasFocused = hasFocus(el);
+let fired = false;
+function onFocus() { fired = true; }
+el.addEventListener('focus', onFocus);
+el.focus({ preventScroll: preventScroll });
+el.removeEventListener('focus', onFocus);
+
+if (!hasFocus(el)) {
+    const reason = el.disabled ? 'it is disabled' : 'it is hidden, inert or not focusable';
+    throw new Error('Element matching ' + JSON.stringify(selector) + " can't be focused: " + reason);
+}
+if (!wasFocused && !fired) {
+    if (previous && previous !== document.body && previous !== el) {
+        previous.dispatchEvent(new FocusEvent('blur', { relatedTarget: el }));
+        previous.dispatchEvent(new FocusEvent('focusout', { bubbles: true, relatedTarget: el }));
+    }
+    el.dispatchEvent(new FocusEvent('focus', { relatedTarget: previous }));
+    el.dispatchEvent(new FocusEvent('focusin', { bubbles: true, relatedTarget: previous }));
+}
+
+({ focused: hasFocus(el), tag: el.tagName.toLowerCase(), matched_count: matches.length })
+"#;
+
+/// Removes focus from the element matching `selector`, or from the focused element
+/// when `selector` is null.
+///
+/// Like [`FOCUS_ELEMENT_SCRIPT`], `blur`/`focusout` are dispatched by hand when the
+/// browser didn't fire them. Expects `selector` to be defined before it.
+const BLUR_ELEMENT_SCRIPT: &str = r#"
+const el = selector === null ? document.activeElement : document.querySelector(selector);
+if (el === null && selector !== null) {
+    throw new Error('No element matches selector ' + JSON.stringify(selector));
+}
+const wasFocused = el !== null && el !== document.body && hasFocus(el);
+if (wasFocused) {
+    let fired = false;
+    function onBlur() { fired = true; }
+    el.addEventListener('blur', onBlur);
+    el.blur();
+    el.removeEventListener('blur', onBlur);
+    if (!fired) {
+        el.dispatchEvent(new FocusEvent('blur'));
+        el.dispatchEvent(new FocusEvent('focusout', { bubbles: true }));
+    }
+}
+
+({ focused: el !== null && el !== document.body && hasFocus(el), was_focused: wasFocused })
+"#;
+
+/// Focuses an element, as clicking or tabbing to it would.
+///
+/// The element gets `focus` and `focusin` events (and the previously focused element
+/// `blur` and `focusout`), even when the app's window doesn't have focus itself, so
+/// focus-driven validation and keyboard shortcuts behave the same every run. Focusing
+/// the element that already has focus does nothing. Elements that can't take focus,
+/// like a disabled button or a plain `<div>` without `tabindex`, are an error.
+///
+/// # Arguments
+///
+/// * `window` - The window containing the element
+/// * `selector` - CSS selector of the element to focus
+/// * `handle` - Handle of the element from an earlier `execute_js` result, in place of
+///   `selector`
+/// * `by` - How `selector` locates the element: `"css"` (the default), `"xpath"`,
+///   `"text"`, `"id"` or `"name"`
+/// * `prevent_scroll` - Don't scroll the element into view (defaults to false)
+/// * `nth` - Which match to focus when the selector matches several, counting from 0
+/// * `strict` - Whether several matches without `nth` are an error (defaults to true)
+/// * `window_label` - Label of the window to target (defaults to the calling window)
+/// * `executor` - Script executor state
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the element was focused
+///   - `data`: `{ focused, tag, matched_count }` where `focused` is whether the element
+///     is now the active element
+///   - `error`: Error message (if nothing matched, the selector is ambiguous, or the
+///     element isn't focusable)
+///   - `error_kind`: `"ambiguous_selector"` when several elements matched and no `nth`
+///     was given
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// await invoke('plugin:mcp-bridge|focus_element', { selector: '#email', preventScroll: true });
+/// ```
+#[command]
+#[allow(clippy::too_many_arguments)]
+pub async fn focus_element<R: Runtime>(
+    window: WebviewWindow<R>,
+    selector: Option<String>,
+    handle: Option<String>,
+    by: Option<String>,
+    prevent_scroll: Option<bool>,
+    nth: Option<usize>,
+    strict: Option<bool>,
+    window_label: Option<String>,
+    executor: State<'_, ScriptExecutor>,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    let target = match ElementTarget::new(selector, handle, by) {
+        Ok(target) => target,
+        Err(error) => {
+            return Ok(serde_json::json!({
+                "success": false,
+                "error": error
+            }))
+        }
+    };
+
+    let args_js = match_args_js(&target.selector(), nth, strict)?;
+    let prevent_scroll = prevent_scroll.unwrap_or(false);
+    let script = format!(
+        "{args_js}const preventScroll = {prevent_scroll};\n{PICK_INDEX_JS}{HAS_FOCUS_JS}{FOCUS_ELEMENT_SCRIPT}"
+    );
+
+    run_script(
+        window,
+        target.wrap(script),
+        None,
+        None,
+        None,
+        None,
+        None,
+        executor,
+    )
+    .await
+}
+
+/// Removes focus from an element.
+///
+/// The element gets `blur` and `focusout` events, even when the app's window doesn't
+/// have focus itself. Blurring an element that doesn't have focus does nothing.
+///
+/// # Arguments
+///
+/// * `window` - The window containing the element
+/// * `selector` - CSS selector of the element to blur (defaults to the focused element)
+/// * `window_label` - Label of the window to target (defaults to the calling window)
+/// * `executor` - Script executor state
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the element was blurred
+///   - `data`: `{ focused, was_focused }` where `focused` is whether the element is
+///     still the active element
+///   - `error`: Error message (if nothing matched the selector)
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// // Trigger the field's on-blur validation
+/// await invoke('plugin:mcp-bridge|blur_element', { selector: '#email' });
+/// ```
+#[command]
+pub async fn blur_element<R: Runtime>(
+    window: WebviewWindow<R>,
+    selector: Option<String>,
+    window_label: Option<String>,
+    executor: State<'_, ScriptExecutor>,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    let selector_js = serde_json::to_string(&selector).map_err(|e| e.to_string())?;
+    let script = format!("const selector = {selector_js};\n{HAS_FOCUS_JS}{BLUR_ELEMENT_SCRIPT}");
+
+    run_script(window, script, None, None, None, None, None, executor).await
+}
+
+/// Selects the options of the `<select>` matching `selector` that `pickElement` picks
+/// whose `by` (`"value"`, `"label"` or `"index"`) is in `targets`.
+///
+/// Expects `selector`, `nth`, `strict`, `by`, `targets` and `PICK_INDEX_JS` before it.
+const SELECT_OPTION_SCRIPT: &str = r#"
+const matches = document.querySelectorAll(selector);
+const el = pickElement(matches, selector, nth, strict);
+if (!(el instanceof HTMLSelectElement)) {
+    throw new Error('Element matching ' + JSON.stringify(selector) + ' is a <'
+        + el.tagName.toLowerCase() + '>, not a <select>');
+}
+if (el.matches(':disabled')) {
+    throw new Error('Element matching ' + JSON.stringify(selector) + ' is disabled');
+}
+if (targets.length > 1 && !el.multiple) {
+    throw new Error('Element matching ' + JSON.stringify(selector)
+        + ' is not a multiple select, so only one option can be selected');
+}
+
+const options = Array.from(el.options);
+const chosen = targets.map(function(target) {
+    const option = by === 'index'
+        ? options[target]
+        : options.find(function(o) {
+            return by === 'value' ? o.value === target : (o.label === target || o.text.trim() === target);
+        });
+    if (option === undefined) {
+        throw new Error('No option with ' + by + ' ' + JSON.stringify(target) + ' in select matching '
+            + JSON.stringify(selector));
+    }
+    if (option.disabled) {
+        throw new Error('Option with ' + by + ' ' + JSON.stringify(target) + ' is disabled');
+    }
+    return option;
+});
+
+options.forEach(function(o) { o.selected = chosen.indexOf(o) !== -1; });
+el.dispatchEvent(new Event('input', { bubbles: true }));
+el.dispatchEvent(new Event('change', { bubbles: true }));
+
+const selected = Array.from(el.selectedOptions);
+({
+    value: el.value,
+    values: selected.map(function(o) { return o.value; }),
+    labels: selected.map(function(o) { return o.label; }),
+    matched_count: matches.length
+})
+"#;
+
+/// Works out how `select_option` picks options, from whichever of `value`, `label` and
+/// `index` was given.
+///
+/// Each may be a single value or an array, for multiple selects. Returns the kind of
+/// match and the values to match.
+fn option_targets(
+    value: Option<Value>,
+    label: Option<Value>,
+    index: Option<Value>,
+) -> Result<(&'static str, Vec<Value>), String> {
+    let given: Vec<(&'static str, Value)> = [("value", value), ("label", label), ("index", index)]
+        .into_iter()
+        .filter_map(|(by, arg)| arg.filter(|v| !v.is_null()).map(|v| (by, v)))
+        .collect();
+
+    let (by, arg) = match given.as_slice() {
+        [(by, arg)] => (*by, arg.clone()),
+        [] => return Err("Missing option: pass one of value, label or index".to_string()),
+        _ => return Err("Pass only one of value, label or index".to_string()),
+    };
+
+    let targets = match arg {
+        Value::Array(targets) => targets,
+        target => vec![target],
+    };
+    if targets.is_empty() {
+        return Err(format!("No {by} given"));
+    }
+
+    let valid = |target: &Value| {
+        if by == "index" {
+            target.is_u64()
+        } else {
+            target.is_string()
+        }
+    };
+    if !targets.iter().all(valid) {
+        let expected = if by == "index" {
+            "non-negative integers"
+        } else {
+            "strings"
+        };
+        return Err(format!("Option {by}s must be {expected}"));
+    }
+
+    Ok((by, targets))
+}
+
+/// Selects options of a native `<select>` and dispatches `input` and `change`.
+///
+/// Options are picked by exactly one of `value`, `label` (the visible text) or
+/// `index`. Pass an array to select several options of a `<select multiple>`; the
+/// options not listed are deselected.
+///
+/// # Arguments
+///
+/// * `window` - The window containing the select
+/// * `selector` - CSS selector of the `<select>` element
+/// * `value` - Option value, or values for a multiple select
+/// * `label` - Option label, or labels for a multiple select
+/// * `index` - Option index, or indexes for a multiple select
+/// * `nth` - Which match to use when the selector matches several, counting from 0
+/// * `strict` - Whether several matches without `nth` are an error (defaults to true)
+/// * `window_label` - Label of the window to target (defaults to the calling window)
+/// * `executor` - Script executor state
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the options were selected
+///   - `data`: `{ value, values, labels, matched_count }` with the select's value, the
+///     values and labels of every selected option afterwards, and how many elements
+///     the selector matched
+///   - `error`: Error message (if nothing matched, the selector is ambiguous, the
+///     element isn't a `<select>`, or an option doesn't exist or is disabled)
+///   - `error_kind`: `"ambiguous_selector"` when several elements matched and no `nth`
+///     was given
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// await invoke('plugin:mcp-bridge|select_option', { selector: '#country', label: 'Norway' });
+/// await invoke('plugin:mcp-bridge|select_option', { selector: '#tags', value: ['rust', 'ts'] });
+/// ```
+#[command]
+#[allow(clippy::too_many_arguments)]
+pub async fn select_option<R: Runtime>(
+    window: WebviewWindow<R>,
+    selector: String,
+    value: Option<Value>,
+    label: Option<Value>,
+    index: Option<Value>,
+    nth: Option<usize>,
+    strict: Option<bool>,
+    window_label: Option<String>,
+    executor: State<'_, ScriptExecutor>,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    let (by, targets) = match option_targets(value, label, index) {
+        Ok(targets) => targets,
+        Err(error) => {
+            return Ok(serde_json::json!({
+                "success": false,
+                "error": error
+            }))
+        }
+    };
+
+    let args_js = match_args_js(&selector, nth, strict)?;
+    let targets_js = serde_json::to_string(&targets).map_err(|e| e.to_string())?;
+    let script = format!(
+        "{args_js}const by = '{by}';\nconst targets = {targets_js};\n{PICK_INDEX_JS}{SELECT_OPTION_SCRIPT}"
+    );
+
+    run_script(window, script, None, None, None, None, None, executor).await
+}
+
+/// Modifier keys accepted by `press_key`, with the `key` value each dispatches.
+const MODIFIER_KEYS: &[(&str, &str)] = &[
+    ("Control", "Control"),
+    ("Ctrl", "Control"),
+    ("Shift", "Shift"),
+    ("Alt", "Alt"),
+    ("Meta", "Meta"),
+    ("Cmd", "Meta"),
+];
+
+/// Helpers shared by the keyboard scripts.
+///
+/// `keyInfo` maps a character or key name to its `key`, `code` and legacy `keyCode`,
+/// following a US keyboard layout. `insertText` edits the focused field the way typing
+/// would: through the native value setter, so frameworks notice, followed by an
+/// `input` event.
+const KEYBOARD_HELPERS_JS: &str = r#"
+const NAMED_KEYS = {
+    Enter: ['Enter', 13], Tab: ['Tab', 9], Escape: ['Escape', 27], Backspace: ['Backspace', 8],
+    Delete: ['Delete', 46], ArrowUp: ['ArrowUp', 38], ArrowDown: ['ArrowDown', 40],
+    ArrowLeft: ['ArrowLeft', 37], ArrowRight: ['ArrowRight', 39], Home: ['Home', 36],
+    End: ['End', 35], PageUp: ['PageUp', 33], PageDown: ['PageDown', 34], Insert: ['Insert', 45],
+    Control: ['ControlLeft', 17], Shift: ['ShiftLeft', 16], Alt: ['AltLeft', 18], Meta: ['MetaLeft', 91]
+};
+for (let i = 1; i <= 12; i++) NAMED_KEYS['F' + i] = ['F' + i, 111 + i];
+const PUNCTUATION = {
+    ' ': ['Space', 32], '-': ['Minus', 189], '=': ['Equal', 187], '[': ['BracketLeft', 219],
+    ']': ['BracketRight', 221], '\\': ['Backslash', 220], ';': ['Semicolon', 186],
+    "'": ['Quote', 222], ',': ['Comma', 188], '.': ['Period', 190], '/': ['Slash', 191],
+    '`': ['Backquote', 192]
+};
+const SHIFTED = {
+    '!': '1', '@': '2', '#': '3', '$': '4', '%': '5', '^': '6', '&': '7', '*': '8', '(': '9',
+    ')': '0', '_': '-', '+': '=', '{': '[', '}': ']', '|': '\\', ':': ';', '"': "'", '<': ',',
+    '>': '.', '?': '/', '~': '`'
+};
+function keyInfo(key) {
+    if (key === '\n') key = 'Enter';
+    if (key === '\t') key = 'Tab';
+    if (NAMED_KEYS[key]) {
+        return { key: key, code: NAMED_KEYS[key][0], keyCode: NAMED_KEYS[key][1], shift: false, printable: false };
+    }
+    if (key.length !== 1) {
+        throw new Error('Unknown key ' + JSON.stringify(key));
+    }
+    const base = SHIFTED[key] || key;
+    const shift = SHIFTED[key] !== undefined || (key !== key.toLowerCase());
+    let code = null;
+    let keyCode = 0;
+    if (/[a-z]/i.test(base)) {
+        code = 'Key' + base.toUpperCase();
+        keyCode = base.toUpperCase().charCodeAt(0);
+    } else if (/[0-9]/.test(base)) {
+        code = 'Digit' + base;
+        keyCode = base.charCodeAt(0);
+    } else if (PUNCTUATION[base]) {
+        code = PUNCTUATION[base][0];
+        keyCode = PUNCTUATION[base][1];
+    }
+    // Characters that aren't on a US keyboard still get a key, just no code
+    return { key: key, code: code || '', keyCode: keyCode, shift: shift, printable: true };
+}
+function keyEvent(target, type, info, modifiers) {
+    const event = new KeyboardEvent(type, {
+        key: info.key,
+        code: info.code,
+        keyCode: info.keyCode,
+        which: info.keyCode,
+        charCode: type === 'keypress' ? info.key.charCodeAt(0) : 0,
+        ctrlKey: modifiers.ctrl,
+        shiftKey: modifiers.shift || info.shift,
+        altKey: modifiers.alt,
+        metaKey: modifiers.meta,
+        bubbles: true,
+        cancelable: true,
+        composed: true,
+        view: window
+    });
+    return target.dispatchEvent(event);
+}
+function valueSetter(el) {
+    if (el instanceof HTMLInputElement) return Object.getOwnPropertyDescriptor(HTMLInputElement.prototype, 'value').set;
+    if (el instanceof HTMLTextAreaElement) return Object.getOwnPropertyDescriptor(HTMLTextAreaElement.prototype, 'value').set;
+    return null;
+}
+function insertText(el, text) {
+    const setter = valueSetter(el);
+    if (setter === null) {
+        if (el.isContentEditable) document.execCommand('insertText', false, text);
+        return;
+    }
+    // Inputs like type=number don't expose a selection, so append there
+    let start = el.value.length;
+    let end = el.value.length;
+    try {
+        if (el.selectionStart !== null) {
+            start = el.selectionStart;
+            end = el.selectionEnd;
+        }
+    } catch (e) {}
+    setter.call(el, el.value.slice(0, start) + text + el.value.slice(end));
+    try {
+        el.setSelectionRange(start + text.length, start + text.length);
+    } catch (e) {}
+    el.dispatchEvent(new InputEvent('input', { bubbles: true, inputType: 'insertText', data: text }));
+}
+function typeKey(target, key, modifiers) {
+    const info = keyInfo(key);
+    const proceed = keyEvent(target, 'keydown', info, modifiers);
+    const shortcut = modifiers.ctrl || modifiers.alt || modifiers.meta;
+    const inserts = info.printable || (info.key === 'Enter' && target instanceof HTMLTextAreaElement);
+    if (proceed && inserts && !shortcut) {
+        if (keyEvent(target, 'keypress', info, modifiers)) {
+            insertText(target, info.key === 'Enter' ? '\n' : info.key);
+        }
+    }
+    keyEvent(target, 'keyup', info, modifiers);
+    return { key: info.key, code: info.code, defaultPrevented: !proceed };
+}
+function readValue(el) {
+    return valueSetter(el) !== null ? el.value : (el.isContentEditable ? el.textContent : null);
+}
+"#;
+
+/// Focuses the element matching `selector` that `pickElement` picks and types `text`
+/// into it key by key.
+///
+/// Expects `selector`, `nth`, `strict`, `text`, `clear` and `delayMs` to be defined
+/// before it, after `PICK_INDEX_JS` and `KEYBOARD_HELPERS_JS`.
+const TYPE_TEXT_SCRIPT: &str = r#"
+const matches = document.querySelectorAll(selector);
+const el = pickElement(matches, selector, nth, strict);
+if (el.matches(':disabled')) {
+    throw new Error('Element matching ' + JSON.stringify(selector) + ' is disabled');
+}
+if (typeof el.focus === 'function') el.focus();
+if (clear && valueSetter(el) !== null && el.value !== '') {
+    valueSetter(el).call(el, '');
+    el.dispatchEvent(new InputEvent('input', { bubbles: true, inputType: 'deleteContentBackward' }));
+}
+const noModifiers = { ctrl: false, shift: false, alt: false, meta: false };
+for (const ch of Array.from(text)) {
+    typeKey(el, ch, noModifiers);
+    if (delayMs > 0) await new Promise(function(resolve) { setTimeout(resolve, delayMs); });
+}
+({ value: readValue(el), focused: document.activeElement === el, matched_count: matches.length })
+"#;
+
+/// Presses `key` with `modifiers` held on the element matching `selector`, or on the
+/// focused element.
+///
+/// Expects `key`, `modifiers` and `selector` to be defined before it, after
+/// `KEYBOARD_HELPERS_JS`.
+const PRESS_KEY_SCRIPT: &str = r#"
+let target = document.activeElement || document.body;
+if (selector !== null) {
+    target = document.querySelector(selector);
+    if (target === null) {
+        throw new Error('No element matches selector ' + JSON.stringify(selector));
+    }
+    if (typeof target.focus === 'function') target.focus();
+}
+const held = { ctrl: false, shift: false, alt: false, meta: false };
+const flags = { Control: 'ctrl', Shift: 'shift', Alt: 'alt', Meta: 'meta' };
+modifiers.forEach(function(name) {
+    held[flags[name]] = true;
+    keyEvent(target, 'keydown', keyInfo(name), held);
+});
+const pressed = typeKey(target, key, held);
+modifiers.slice().reverse().forEach(function(name) {
+    held[flags[name]] = false;
+    keyEvent(target, 'keyup', keyInfo(name), held);
+});
+({
+    key: pressed.key,
+    code: pressed.code,
+    defaultPrevented: pressed.defaultPrevented,
+    target: target.tagName.toLowerCase(),
+    value: readValue(target)
+})
+"#;
+
+/// Types text into an element with real keyboard events.
+///
+/// The element is focused, then every character is typed as a `keydown`, `keypress`,
+/// `input` and `keyup` sequence with the matching `key` and `code`, so handlers that
+/// watch keys (autocomplete, masks, shortcuts) behave as they would for a user. Text is
+/// inserted at the caret through the native value setter, so React and Vue see it. A
+/// `keydown` handler that calls `preventDefault()` stops that character from being
+/// inserted. `\n` presses Enter, which adds a line break in a `<textarea>`, and `\t`
+/// presses Tab without moving focus.
+///
+/// # Arguments
+///
+/// * `window` - The window containing the element
+/// * `selector` - CSS selector of the element to type into
+/// * `text` - The text to type
+/// * `clear` - Empty the field before typing (defaults to false, typing at the caret)
+/// * `delay_ms` - Pause between characters in milliseconds (defaults to 0)
+/// * `nth` - Which match to type into when the selector matches several, counting
+///   from 0
+/// * `strict` - Whether several matches without `nth` are an error (defaults to true)
+/// * `window_label` - Label of the window to target (defaults to the calling window)
+/// * `executor` - Script executor state
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the text was typed
+///   - `data`: `{ value, focused, matched_count }` with the element's value afterwards
+///     (its text for content-editable elements), whether it still has focus, and how
+///     many elements the selector matched
+///   - `error`: Error message (if nothing matched, the selector is ambiguous, or the
+///     element is disabled)
+///   - `error_kind`: `"ambiguous_selector"` when several elements matched and no `nth`
+///     was given
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const result = await invoke('plugin:mcp-bridge|type_text', {
+///   selector: '#search',
+///   text: 'tauri',
+///   clear: true
+/// });
+/// console.log(result.data.value); // "tauri"
+/// ```
+#[command]
+#[allow(clippy::too_many_arguments)]
+pub async fn type_text<R: Runtime>(
+    window: WebviewWindow<R>,
+    selector: String,
+    text: String,
+    clear: Option<bool>,
+    delay_ms: Option<u64>,
+    nth: Option<usize>,
+    strict: Option<bool>,
+    window_label: Option<String>,
+    executor: State<'_, ScriptExecutor>,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    let args_js = match_args_js(&selector, nth, strict)?;
+    let text_js = serde_json::to_string(&text).map_err(|e| e.to_string())?;
+    let clear = clear.unwrap_or(false);
+    let delay_ms = delay_ms.unwrap_or(0);
+    let script = format!(
+        "{args_js}const text = {text_js};\nconst clear = {clear};\nconst delayMs = {delay_ms};\n{PICK_INDEX_JS}{KEYBOARD_HELPERS_JS}{TYPE_TEXT_SCRIPT}"
+    );
+
+    // Leave room for the pauses on top of the usual timeout
+    let typing_ms = delay_ms.saturating_mul(text.chars().count() as u64);
+    let timeout_ms = DEFAULT_TIMEOUT_MS.saturating_add(typing_ms);
+
+    run_script(
+        window,
+        script,
+        Some(timeout_ms),
+        None,
+        None,
+        None,
+        None,
+        executor,
+    )
+    .await
+}
+
+/// Presses a single key, optionally with modifiers held down.
+///
+/// Modifiers get their own `keydown` before the key and `keyup` after it, and every
+/// event carries the matching `ctrlKey`, `shiftKey`, `altKey` and `metaKey` flags, so
+/// keyboard shortcuts fire as they would for a user. A printable key pressed without
+/// Ctrl, Alt or Meta is also inserted into the focused field.
+///
+/// Only the events are simulated: the browser's own default actions for keys like Tab
+/// (moving focus), Enter (submitting a form) or the arrow keys (moving the caret) don't
+/// happen, since the webview ignores them for synthetic events.
+///
+/// # Arguments
+///
+/// * `window` - The window to press the key in
+/// * `key` - A single character like `"a"`, or a key name: `Enter`, `Tab`, `Escape`,
+///   `Backspace`, `Delete`, `ArrowUp`, `ArrowDown`, `ArrowLeft`, `ArrowRight`, `Home`,
+///   `End`, `PageUp`, `PageDown`, `Insert` or `F1` to `F12`
+/// * `modifiers` - Modifier keys to hold: `Control` (or `Ctrl`), `Shift`, `Alt` and
+///   `Meta` (or `Cmd`)
+/// * `selector` - CSS selector of the element to focus and press the key on (defaults
+///   to the focused element)
+/// * `window_label` - Label of the window to target (defaults to the calling window)
+/// * `executor` - Script executor state
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the key was pressed
+///   - `data`: `{ key, code, defaultPrevented, target, value }` where
+///     `defaultPrevented` is whether a `keydown` handler cancelled the key, `target` is
+///     the tag of the element that received it and `value` is its value afterwards (null
+///     if it isn't a field)
+///   - `error`: Error message (if the key is unknown or nothing matched the selector)
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// await invoke('plugin:mcp-bridge|press_key', { key: 'Enter', selector: '#search' });
+/// await invoke('plugin:mcp-bridge|press_key', { key: 's', modifiers: ['Control'] });
+/// ```
+#[command]
+pub async fn press_key<R: Runtime>(
+    window: WebviewWindow<R>,
+    key: String,
+    modifiers: Option<Vec<String>>,
+    selector: Option<String>,
+    window_label: Option<String>,
+    executor: State<'_, ScriptExecutor>,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    if key.is_empty() {
+        return Err("Missing key argument".to_string());
+    }
+    let mut held: Vec<&str> = Vec::new();
+    for modifier in modifiers.unwrap_or_default() {
+        let Some((_, name)) = MODIFIER_KEYS.iter().find(|(alias, _)| *alias == modifier) else {
+            return Err(format!(
+                "Invalid modifier '{modifier}': expected Control, Shift, Alt or Meta"
+            ));
+        };
+        if !held.contains(name) {
+            held.push(name);
+        }
+    }
+
+    let key_js = serde_json::to_string(&key).map_err(|e| e.to_string())?;
+    let modifiers_js = serde_json::to_string(&held).map_err(|e| e.to_string())?;
+    let selector_js = serde_json::to_string(&selector).map_err(|e| e.to_string())?;
+    let script = format!(
+        "const key = {key_js};\nconst modifiers = {modifiers_js};\nconst selector = {selector_js};\n{KEYBOARD_HELPERS_JS}{PRESS_KEY_SCRIPT}"
+    );
+
+    run_script(window, script, None, None, None, None, None, executor).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_match_args_default_to_strict_without_nth() {
+        assert_eq!(
+            match_args_js("li", None, None).unwrap(),
+            "const selector = \"li\";\nconst nth = null;\nconst strict = true;\n"
+        );
+        assert_eq!(
+            match_args_js("li", Some(2), Some(false)).unwrap(),
+            "const selector = \"li\";\nconst nth = 2;\nconst strict = false;\n"
+        );
+    }
+
+    #[test]
+    fn test_option_targets_accept_one_value_or_an_array() {
+        assert_eq!(
+            option_targets(Some(json!("no")), None, None),
+            Ok(("value", vec![json!("no")]))
+        );
+        assert_eq!(
+            option_targets(None, None, Some(json!([0, 2]))),
+            Ok(("index", vec![json!(0), json!(2)]))
+        );
+    }
+
+    #[test]
+    fn test_option_targets_need_exactly_one_valid_criterion() {
+        assert!(option_targets(None, None, None).is_err());
+        assert!(option_targets(Some(json!("a")), Some(json!("A")), None).is_err());
+        assert!(option_targets(None, Some(json!([])), None).is_err());
+        assert!(option_targets(None, None, Some(json!(-1))).is_err());
+        assert!(option_targets(None, None, Some(json!("1"))).is_err());
+        assert!(option_targets(Some(json!(["a", 1])), None, None).is_err());
+    }
+
+    #[test]
+    fn test_drag_endpoint_takes_a_selector_or_a_position() {
+        assert_eq!(
+            drag_endpoint(Some("#card".into()), None, None, "source"),
+            Ok(json!({ "selector": "#card", "nth": null }))
+        );
+        assert_eq!(
+            drag_endpoint(Some(".card".into()), None, Some(2), "source"),
+            Ok(json!({ "selector": ".card", "nth": 2 }))
+        );
+        assert_eq!(
+            drag_endpoint(
+                None,
+                Some(DragPosition { x: 10.0, y: 20.5 }),
+                None,
+                "target"
+            ),
+            Ok(json!({ "x": 10.0, "y": 20.5 }))
+        );
+        assert!(drag_endpoint(None, None, None, "source").is_err());
+        assert!(drag_endpoint(
+            Some("#card".into()),
+            Some(DragPosition { x: 0.0, y: 0.0 }),
+            None,
+            "source"
+        )
+        .is_err());
+        assert!(drag_endpoint(
+            None,
+            Some(DragPosition {
+                x: f64::NAN,
+                y: 0.0
+            }),
+            None,
+            "target"
+        )
+        .is_err());
+        assert_eq!(
+            drag_endpoint(
+                None,
+                Some(DragPosition { x: 0.0, y: 0.0 }),
+                Some(1),
+                "target"
+            ),
+            Err("target_nth can only be given with target_selector".to_string())
+        );
+    }
+}
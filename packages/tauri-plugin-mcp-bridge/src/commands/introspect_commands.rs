@@ -0,0 +1,102 @@
+//! Catalog of the app's own Tauri commands.
+//!
+//! Tauri doesn't expose the commands registered with `invoke_handler` at runtime, so
+//! the app lists them when building the plugin, with `Builder::with_commands` or
+//! `Builder::command`. `introspect_commands` returns that list.
+
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{command, State};
+
+/// Description of one of the app's commands, for `introspect_commands`.
+///
+/// # Examples
+///
+/// ```rust
+/// use tauri_plugin_mcp_bridge::CommandInfo;
+///
+/// let info = CommandInfo::new("add_numbers")
+///     .description("Adds two numbers")
+///     .arg("a")
+///     .arg("b");
+/// ```
+#[derive(Clone, Debug, Serialize)]
+pub struct CommandInfo {
+    /// Name the command is invoked with
+    pub name: String,
+    /// What the command does
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Names of the command's arguments, as passed to `invoke` (camelCase)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+}
+
+impl CommandInfo {
+    /// Creates a description for the command `name` with no metadata.
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            description: None,
+            args: Vec::new(),
+        }
+    }
+
+    /// Sets what the command does.
+    pub fn description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_string());
+        self
+    }
+
+    /// Adds an argument name.
+    pub fn arg(mut self, name: &str) -> Self {
+        self.args.push(name.to_string());
+        self
+    }
+}
+
+/// The app commands registered with the plugin.
+///
+/// Managed as app state.
+pub struct CommandCatalog(pub Vec<CommandInfo>);
+
+/// Returns the app's own commands, as registered with the plugin's builder.
+///
+/// Only commands the app listed with `Builder::with_commands` or `Builder::command`
+/// are known, since Tauri has no way to enumerate `invoke_handler` at runtime. The
+/// plugin's own commands aren't included.
+///
+/// # Arguments
+///
+/// * `catalog` - Registered command catalog
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `commands`: `{ name, description, args }` for each command, in registration
+///     order, where `description` and `args` are only present if provided
+///   - `count`: Number of commands
+///   - `note`: How to register commands (only present when none are registered)
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const { commands } = await invoke('plugin:mcp-bridge|introspect_commands');
+/// console.log(commands.map((c) => c.name)); // ["greet", "add_numbers", ...]
+/// ```
+#[command]
+pub async fn introspect_commands(catalog: State<'_, CommandCatalog>) -> Result<Value, String> {
+    let mut result = serde_json::json!({
+        "commands": catalog.0,
+        "count": catalog.0.len()
+    });
+    if catalog.0.is_empty() {
+        result["note"] = Value::from(
+            "No commands are registered. The app can list its commands with \
+             tauri_plugin_mcp_bridge::Builder::new().with_commands(&[...]).",
+        );
+    }
+    Ok(result)
+}
@@ -0,0 +1,102 @@
+//! Calling the app's own Tauri commands from the webview.
+
+use super::execute_js::run_script;
+use super::list_windows::target_window;
+use super::script_executor::ScriptExecutor;
+use serde_json::Value;
+use tauri::{command, Runtime, State, WebviewWindow};
+
+/// Invokes `command` with `args` and evaluates to its result.
+///
+/// Expects `command` and `args` to be defined before it. Uses the global API when the
+/// app sets `withGlobalTauri`, and the internal one that every webview has otherwise.
+/// Rejections are rethrown as errors naming the command, with the Tauri error text.
+const INVOKE_COMMAND_SCRIPT: &str = r#"
+let invoke = null;
+if (window.__TAURI__ && window.__TAURI__.core && typeof window.__TAURI__.core.invoke === 'function') {
+    invoke = window.__TAURI__.core.invoke;
+} else if (window.__TAURI_INTERNALS__ && typeof window.__TAURI_INTERNALS__.invoke === 'function') {
+    invoke = function(cmd, payload) {
+        return window.__TAURI_INTERNALS__.invoke(cmd, payload);
+    };
+}
+if (invoke === null) {
+    throw new Error('Tauri invoke API unavailable in webview');
+}
+let result;
+try {
+    result = await invoke(command, args);
+} catch (error) {
+    let message;
+    if (typeof error === 'string') message = error;
+    else if (error instanceof Error) message = error.message;
+    else message = JSON.stringify(error);
+    throw new Error('Command ' + JSON.stringify(command) + ' failed: ' + message);
+}
+result
+"#;
+
+/// Calls one of the app's Tauri commands and returns its result.
+///
+/// The call is made from the webview with Tauri's `invoke`, exactly as the app's
+/// frontend would make it, so the command gets the same `Window`, `State` and
+/// capability checks. This lets backend logic be tested without going through the UI.
+///
+/// # Arguments
+///
+/// * `window` - The window to invoke the command from
+/// * `command` - Name of the command, like `"greet"` or `"plugin:fs|read_file"`
+/// * `args` - Arguments object passed to the command, with camelCase keys (defaults to
+///   `{}`)
+/// * `timeout_ms` - Maximum time to wait for the command in milliseconds (defaults to
+///   5000)
+/// * `window_label` - Label of the window to target (defaults to the calling window)
+/// * `state` - Script executor state
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the command resolved
+///   - `data`: The command's return value
+///   - `error`: Error message (if the command doesn't exist, isn't allowed, or returned
+///     an error), including the error text from Tauri
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const result = await invoke('plugin:mcp-bridge|invoke_command', {
+///   command: 'add_numbers',
+///   args: { a: 2, b: 3 }
+/// });
+/// console.log(result.data); // 5
+/// ```
+#[command]
+pub async fn invoke_command<R: Runtime>(
+    window: WebviewWindow<R>,
+    command: String,
+    args: Option<Value>,
+    timeout_ms: Option<u64>,
+    window_label: Option<String>,
+    state: State<'_, ScriptExecutor>,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    if command.is_empty() {
+        return Err("Missing command argument".to_string());
+    }
+    let args = args.unwrap_or_else(|| Value::Object(Default::default()));
+    if !args.is_object() {
+        return Err("Invalid args: expected a JSON object".to_string());
+    }
+
+    let command_js = serde_json::to_string(&command).map_err(|e| e.to_string())?;
+    let script =
+        format!("const command = {command_js};\nconst args = {args};\n{INVOKE_COMMAND_SCRIPT}");
+
+    run_script(window, script, timeout_ms, None, None, None, None, state).await
+}
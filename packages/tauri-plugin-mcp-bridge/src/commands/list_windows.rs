@@ -18,6 +18,10 @@ pub struct WindowInfo {
     pub focused: bool,
     /// Whether this window is visible
     pub visible: bool,
+    /// Width of the window's client area in physical pixels (if available)
+    pub width: Option<u32>,
+    /// Height of the window's client area in physical pixels (if available)
+    pub height: Option<u32>,
     /// Whether this is the main window (label == "main")
     pub is_main: bool,
 }
@@ -25,7 +29,8 @@ pub struct WindowInfo {
 /// Lists all open webview windows in the application.
 ///
 /// Returns detailed information about each window including its label, title,
-/// URL, focus state, and visibility.
+/// URL, focus state, visibility, and inner size. Windows are ordered with "main"
+/// first and the rest by label, so the order is stable across calls.
 ///
 /// # Arguments
 ///
@@ -54,6 +59,7 @@ pub async fn list_windows<R: Runtime>(app: AppHandle<R>) -> Result<Value, String
         let url = window.url().ok().map(|u| u.to_string());
         let focused = window.is_focused().unwrap_or(false);
         let visible = window.is_visible().unwrap_or(false);
+        let size = window.inner_size().ok();
         let is_main = label == "main";
 
         window_list.push(WindowInfo {
@@ -62,6 +68,8 @@ pub async fn list_windows<R: Runtime>(app: AppHandle<R>) -> Result<Value, String
             url,
             focused,
             visible,
+            width: size.map(|s| s.width),
+            height: size.map(|s| s.height),
             is_main,
         });
     }
@@ -121,7 +129,7 @@ pub fn resolve_window_with_context<R: Runtime>(
 
     let window = app
         .get_webview_window(&target_label)
-        .ok_or_else(|| format!("Window '{target_label}' not found"))?;
+        .ok_or_else(|| window_not_found(app, &target_label))?;
 
     let warning = if !explicit_label && total_windows > 1 {
         Some(format!(
@@ -162,5 +170,53 @@ pub fn resolve_window<R: Runtime>(
 ) -> Result<tauri::WebviewWindow<R>, String> {
     let label = label.unwrap_or_else(|| "main".to_string());
     app.get_webview_window(&label)
-        .ok_or_else(|| format!("Window '{label}' not found"))
+        .ok_or_else(|| window_not_found(app, &label))
+}
+
+/// Picks the window a command should act on.
+///
+/// Commands invoked over IPC receive the calling window. When `label` is given, the
+/// window with that label is used instead, so multi-window apps can target any window.
+///
+/// # Arguments
+///
+/// * `window` - The calling window, used when no label is given
+/// * `label` - Optional label of the window to target
+///
+/// # Returns
+///
+/// * `Ok(WebviewWindow)` - The window to act on
+/// * `Err(Value)` - A `{ success: false, error, available_windows }` result if no
+///   window has the label, ready to be returned from the command
+pub fn target_window<R: Runtime>(
+    window: tauri::WebviewWindow<R>,
+    label: Option<String>,
+) -> Result<tauri::WebviewWindow<R>, Value> {
+    let Some(label) = label else {
+        return Ok(window);
+    };
+
+    let app = window.app_handle();
+    app.get_webview_window(&label).ok_or_else(|| {
+        serde_json::json!({
+            "success": false,
+            "error": window_not_found(app, &label),
+            "available_windows": window_labels(app)
+        })
+    })
+}
+
+/// Returns the labels of all webview windows, sorted.
+fn window_labels<R: Runtime>(app: &AppHandle<R>) -> Vec<String> {
+    let mut labels: Vec<String> = app.webview_windows().into_keys().collect();
+    labels.sort();
+    labels
+}
+
+/// Builds the error message for a window label that doesn't exist.
+fn window_not_found<R: Runtime>(app: &AppHandle<R>, label: &str) -> String {
+    format!(
+        "Window '{label}' not found. Available windows: {}",
+        window_labels(app).join(", ")
+    )
 }
@@ -0,0 +1,119 @@
+//! WebDriver-style strategies for locating elements.
+//!
+//! Commands that take a `selector` accept a `by` argument naming how to read it, so
+//! locators written for Selenium or WebDriver can be used as they are. The element
+//! lookup in the page goes through [`super::handles::ElementTarget`], like handles do.
+
+use std::fmt;
+
+/// How a command's `selector` argument locates elements.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum By {
+    /// A CSS selector, the default
+    Css,
+    /// An XPath expression, evaluated with `document.evaluate`
+    Xpath,
+    /// The element's whole text, with whitespace collapsed
+    Text,
+    /// The element's `id` attribute
+    Id,
+    /// The element's `name` attribute
+    Name,
+}
+
+impl By {
+    /// Parses a `by` argument, which defaults to CSS.
+    pub(crate) fn parse(by: Option<&str>) -> Result<Self, String> {
+        match by {
+            None | Some("css") => Ok(Self::Css),
+            Some("xpath") => Ok(Self::Xpath),
+            Some("text") => Ok(Self::Text),
+            Some("id") => Ok(Self::Id),
+            Some("name") => Ok(Self::Name),
+            Some(other) => Err(format!(
+                "Invalid by '{other}': expected \"css\", \"xpath\", \"text\", \"id\" or \"name\""
+            )),
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Css => "css",
+            Self::Xpath => "xpath",
+            Self::Text => "text",
+            Self::Id => "id",
+            Self::Name => "name",
+        }
+    }
+}
+
+impl fmt::Display for By {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Defines `__mcpLocate(by, value)`, which returns the elements a locator matches in
+/// document order.
+///
+/// Text matches the elements whose text, with runs of whitespace collapsed to one
+/// space and trimmed, is exactly `value`. Only the innermost of nested matches count,
+/// so a button matches rather than every element around it. The text of `<script>`,
+/// `<style>`, `<template>` and `<noscript>` elements is ignored. It uses
+/// `window.document`, since the command's script may shadow `document`.
+pub(crate) const LOCATE_JS: &str = r#"
+function __mcpLocate(by, value) {
+    const doc = window.document;
+    if (by === 'xpath') {
+        const result = doc.evaluate(value, doc, null, XPathResult.ORDERED_NODE_SNAPSHOT_TYPE, null);
+        const found = [];
+        for (let i = 0; i < result.snapshotLength; i++) {
+            const node = result.snapshotItem(i);
+            if (node.nodeType === Node.ELEMENT_NODE) found.push(node);
+        }
+        return found;
+    }
+    if (by === 'id') {
+        return Array.prototype.slice.call(doc.querySelectorAll('[id="' + CSS.escape(value) + '"]'));
+    }
+    if (by === 'name') {
+        return Array.prototype.slice.call(doc.getElementsByName(value));
+    }
+    const normalize = function(text) { return text.replace(/\s+/g, ' ').trim(); };
+    const wanted = normalize(value);
+    const skipped = /^(SCRIPT|STYLE|TEMPLATE|NOSCRIPT)$/;
+    const found = [];
+    // Children are visited before their parent, so nested matches are seen first
+    function visit(el) {
+        let text = '';
+        let matchedInside = false;
+        for (let node = el.firstChild; node; node = node.nextSibling) {
+            if (node.nodeType === Node.TEXT_NODE) {
+                text += node.data;
+            } else if (node.nodeType === Node.ELEMENT_NODE && !skipped.test(node.tagName)) {
+                const child = visit(node);
+                text += child.text;
+                matchedInside = matchedInside || child.matched;
+            }
+        }
+        const matched = !matchedInside && normalize(text) === wanted;
+        if (matched) found.push(el);
+        return { text: text, matched: matched || matchedInside };
+    }
+    visit(doc.body || doc.documentElement);
+    return found;
+}
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parses_strategies() {
+        assert_eq!(By::parse(None), Ok(By::Css));
+        assert_eq!(By::parse(Some("xpath")), Ok(By::Xpath));
+        assert_eq!(By::parse(Some("text")).unwrap().to_string(), "text");
+        assert!(By::parse(Some("link text")).is_err());
+    }
+}
@@ -5,33 +5,124 @@
 //! when invoked from the frontend.
 
 // Individual command modules
+pub mod accessibility_tree;
+pub mod animations;
+pub mod app_config;
+pub mod attributes;
 pub mod backend_state;
+pub mod batch;
+pub mod bridge_commands;
+pub mod clipboard;
+pub mod command_metrics;
+pub mod computed_style;
+pub mod console_logs;
+pub mod contexts;
+pub mod cookies;
+pub mod dom_mutations;
+pub mod dom_snapshot;
 pub mod emit_event;
+pub mod event_subscriptions;
 pub mod execute_command;
 pub mod execute_js;
+pub mod execute_js_file;
+pub mod file_input;
+pub mod handles;
+pub mod interaction;
+pub mod introspect_commands;
+pub mod invoke_command;
 pub mod ipc_monitor;
 pub mod list_windows;
+pub mod locators;
+pub mod msgpack;
+pub mod navigate;
+pub mod network_log;
+pub mod network_mocks;
+pub mod page_errors;
+pub mod page_info;
+pub mod page_metrics;
+pub mod page_source;
+pub mod page_title;
+pub mod paths;
+pub mod ping;
+pub mod query_elements;
 pub mod resize_window;
 pub mod screenshot;
 pub mod script_executor;
 pub mod script_injection;
+pub mod script_policy;
+pub mod scroll;
+pub mod selection;
+pub mod server_address;
+pub mod sharded_map;
+pub mod storage;
+pub mod validate_js;
+pub mod wait;
 pub mod window_info;
+pub mod window_visibility;
 
 // Re-export types and commands for convenience
-pub use script_executor::ScriptExecutor;
+pub use script_executor::{ScriptExecutor, WindowGone};
 
 // Re-export command functions (needed for generate_handler! macro)
+pub use accessibility_tree::get_accessibility_tree;
+pub use animations::{disable_animations, enable_animations};
+pub use app_config::get_app_config;
+pub use attributes::{get_attribute, set_attribute};
 pub use backend_state::get_backend_state;
+pub use batch::batch_execute;
+pub use bridge_commands::list_bridge_commands;
+pub use clipboard::{read_clipboard, write_clipboard};
+pub use command_metrics::{get_metrics, CommandMetrics};
+pub use computed_style::get_computed_style;
+pub use console_logs::{clear_console_logs, get_console_logs};
+pub use contexts::clear_context;
+pub use cookies::{get_cookies, set_cookie};
+pub use dom_mutations::{
+    subscribe_dom_mutations, unsubscribe_dom_mutations, MutationSubscriptions,
+};
+pub use dom_snapshot::{diff_snapshots, get_dom_snapshot, DomSnapshots};
 pub use emit_event::emit_event;
+pub use event_subscriptions::{subscribe_event, unsubscribe_event, EventSubscriptions};
 pub use execute_command::execute_command;
-pub use execute_js::execute_js;
+pub use execute_js::{execute_js, RetryOn, RetryPolicy};
+pub use execute_js_file::{execute_js_file, ScriptDir};
+pub use file_input::{set_file_input, UploadDir};
+pub use interaction::{
+    blur_element, click_element, drag_and_drop, focus_element, hover_element, press_key,
+    select_option, set_input_value, type_text, DragPosition,
+};
+pub use introspect_commands::{introspect_commands, CommandCatalog, CommandInfo};
+pub use invoke_command::invoke_command;
 pub use ipc_monitor::{get_ipc_events, start_ipc_monitor, stop_ipc_monitor};
 pub use list_windows::{
-    list_windows, resolve_window, resolve_window_with_context, ResolvedWindow, WindowContext,
-    WindowInfo,
+    list_windows, resolve_window, resolve_window_with_context, target_window, ResolvedWindow,
+    WindowContext, WindowInfo,
+};
+pub use navigate::{get_url, go_back, go_forward, navigate, reload};
+pub use network_log::{clear_network_log, get_network_log};
+pub use network_mocks::{clear_mocks, mock_request, unmock_request, NetworkMocks};
+pub use page_errors::get_page_errors;
+pub use page_info::get_page_info;
+pub use page_metrics::get_page_metrics;
+pub use page_source::get_html;
+pub use page_title::{get_title, set_title};
+pub use ping::{ping, StartTime, PROTOCOL_VERSION};
+pub use query_elements::{element_state, get_bounding_rects, query_elements};
+pub use resize_window::{
+    get_window_size, resize_window, set_window_size, ResizeWindowParams, ResizeWindowResult,
+};
+pub use screenshot::{
+    capture_failure, capture_native_screenshot, take_element_screenshot, take_screenshot,
+    FailureCapture,
 };
-pub use resize_window::{resize_window, ResizeWindowParams, ResizeWindowResult};
-pub use screenshot::capture_native_screenshot;
-pub use script_executor::script_result;
+pub use script_executor::{cancel_execution, get_execution_metrics, script_result};
 pub use script_injection::request_script_injection;
+pub use script_policy::ScriptPolicy;
+pub use scroll::{get_scroll, scroll_into_view, scroll_to, set_scroll};
+pub use selection::{get_selection, select_text};
+pub use server_address::{get_server_address, ServerAddress};
+pub use storage::{get_local_storage, set_local_storage};
+pub use validate_js::validate_js;
+pub use wait::{wait_for_function, wait_for_idle, wait_for_selector};
 pub use window_info::get_window_info;
+pub use window_visibility::{focus_window, hide_window, show_window};
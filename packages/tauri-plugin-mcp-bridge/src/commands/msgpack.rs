@@ -0,0 +1,475 @@
+//! MessagePack encoding of `execute_js` results, for `encoding: "msgpack"`.
+//!
+//! The page encodes the script's value with the small encoder in [`MSGPACK_SCRIPT`]
+//! and sends it base64'd inside a marker object, which [`unpack_result`] turns back
+//! into the value. No MessagePack crate is needed for the handful of types JSON can
+//! hold, plus binary data.
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine as _;
+use serde_json::{Map, Value};
+
+/// How deeply arrays and maps may nest, the same limit `serde_json` puts on JSON
+/// results, so a hostile page can't overflow the stack.
+const MAX_DEPTH: usize = 128;
+
+/// Runs the script in `__mcpSource` and evaluates to its value encoded as MessagePack,
+/// in a `{ __msgpack: true, result_type, data }` marker with base64 `data`.
+///
+/// Expects `__mcpSource` to be defined before it. Values are converted the way
+/// `JSON.stringify` converts them (`toJSON` is called, `undefined` and functions are
+/// dropped from objects and become nil in arrays, non-finite numbers become nil, and
+/// cycles throw), except that `ArrayBuffer`s and typed arrays become binary data and
+/// `BigInt`s that fit 64 bits become integers.
+pub(crate) const MSGPACK_SCRIPT: &str = r#"
+const __mcpUnpacked = await __mcpSource();
+(function(root) {
+    let buffer = new Uint8Array(4096);
+    let view = new DataView(buffer.buffer);
+    let length = 0;
+    const textEncoder = new TextEncoder();
+    const stack = [];
+    const tagOf = function(v) { return Object.prototype.toString.call(v); };
+
+    function reserve(n) {
+        if (length + n <= buffer.length) return;
+        let size = buffer.length * 2;
+        while (size < length + n) size *= 2;
+        const grown = new Uint8Array(size);
+        grown.set(buffer.subarray(0, length));
+        buffer = grown;
+        view = new DataView(buffer.buffer);
+    }
+    function byte(b) {
+        reserve(1);
+        buffer[length++] = b;
+    }
+    // Writes a length with the smallest of the given formats; `fixMax` is -1 and
+    // `code8` is null for the types that lack them
+    function header(n, fixBase, fixMax, code8, code16, code32) {
+        if (n <= fixMax) {
+            byte(fixBase | n);
+        } else if (n < 0x100 && code8 !== null) {
+            reserve(2);
+            buffer[length++] = code8;
+            buffer[length++] = n;
+        } else if (n < 0x10000) {
+            reserve(3);
+            buffer[length++] = code16;
+            view.setUint16(length, n);
+            length += 2;
+        } else {
+            reserve(5);
+            buffer[length++] = code32;
+            view.setUint32(length, n);
+            length += 4;
+        }
+    }
+    function bytes(data) {
+        header(data.length, 0, -1, 0xc4, 0xc5, 0xc6);
+        reserve(data.length);
+        buffer.set(data, length);
+        length += data.length;
+    }
+    // Whether `s[i]` starts a surrogate pair
+    function pair(s, i) {
+        const c = s.charCodeAt(i);
+        const next = s.charCodeAt(i + 1);
+        return c >= 0xd800 && c < 0xdc00 && next >= 0xdc00 && next < 0xe000;
+    }
+    function string(s) {
+        if (s.length > 64) {
+            const data = textEncoder.encode(s);
+            header(data.length, 0xa0, 31, 0xd9, 0xda, 0xdb);
+            reserve(data.length);
+            buffer.set(data, length);
+            length += data.length;
+            return;
+        }
+        // Short strings, like most keys, are quicker to encode by hand
+        let size = 0;
+        for (let i = 0; i < s.length; i++) {
+            const c = s.charCodeAt(i);
+            if (c < 0x80) size += 1;
+            else if (c < 0x800) size += 2;
+            else if (pair(s, i)) { size += 4; i++; }
+            else size += 3;
+        }
+        header(size, 0xa0, 31, 0xd9, 0xda, 0xdb);
+        reserve(size);
+        for (let i = 0; i < s.length; i++) {
+            let c = s.charCodeAt(i);
+            if (c < 0x80) {
+                buffer[length++] = c;
+            } else if (c < 0x800) {
+                buffer[length++] = 0xc0 | (c >> 6);
+                buffer[length++] = 0x80 | (c & 0x3f);
+            } else if (pair(s, i)) {
+                c = 0x10000 + ((c - 0xd800) << 10) + (s.charCodeAt(++i) - 0xdc00);
+                buffer[length++] = 0xf0 | (c >> 18);
+                buffer[length++] = 0x80 | ((c >> 12) & 0x3f);
+                buffer[length++] = 0x80 | ((c >> 6) & 0x3f);
+                buffer[length++] = 0x80 | (c & 0x3f);
+            } else {
+                // A lone surrogate can't be UTF-8, so it's replaced like TextEncoder does
+                if (c >= 0xd800 && c < 0xe000) c = 0xfffd;
+                buffer[length++] = 0xe0 | (c >> 12);
+                buffer[length++] = 0x80 | ((c >> 6) & 0x3f);
+                buffer[length++] = 0x80 | (c & 0x3f);
+            }
+        }
+    }
+    function bigint(n) {
+        reserve(9);
+        if (n >= 0n && n < 0x10000000000000000n) {
+            buffer[length++] = 0xcf;
+            view.setBigUint64(length, n);
+        } else if (n < 0n && n >= -0x8000000000000000n) {
+            buffer[length++] = 0xd3;
+            view.setBigInt64(length, n);
+        } else {
+            throw new TypeError('BigInt ' + n + ' does not fit in 64 bits');
+        }
+        length += 8;
+    }
+    function number(n) {
+        if (!isFinite(n)) {
+            byte(0xc0);
+        } else if (!Number.isSafeInteger(n) || Object.is(n, -0)) {
+            reserve(9);
+            buffer[length++] = 0xcb;
+            view.setFloat64(length, n);
+            length += 8;
+        } else if (n >= 0 && n < 0x80) {
+            byte(n);
+        } else if (n >= -32 && n < 0) {
+            byte(n & 0xff);
+        } else if (n >= 0 && n < 0x100) {
+            byte(0xcc);
+            byte(n);
+        } else if (n >= 0 && n < 0x10000) {
+            reserve(3);
+            buffer[length++] = 0xcd;
+            view.setUint16(length, n);
+            length += 2;
+        } else if (n >= 0 && n < 0x100000000) {
+            reserve(5);
+            buffer[length++] = 0xce;
+            view.setUint32(length, n);
+            length += 4;
+        } else if (n >= -0x80 && n < 0) {
+            reserve(2);
+            buffer[length++] = 0xd0;
+            view.setInt8(length++, n);
+        } else if (n >= -0x8000 && n < 0) {
+            reserve(3);
+            buffer[length++] = 0xd1;
+            view.setInt16(length, n);
+            length += 2;
+        } else if (n >= -0x80000000 && n < 0) {
+            reserve(5);
+            buffer[length++] = 0xd2;
+            view.setInt32(length, n);
+            length += 4;
+        } else {
+            bigint(BigInt(n));
+        }
+    }
+    // Applies `toJSON` and unwraps boxed primitives, like `JSON.stringify`
+    function prepare(v, key) {
+        if (v !== null && (typeof v === 'object' || typeof v === 'bigint')
+            && typeof v.toJSON === 'function') {
+            v = v.toJSON(key);
+        }
+        const tag = v !== null && typeof v === 'object' ? tagOf(v) : null;
+        if (tag === '[object Number]' || tag === '[object String]'
+            || tag === '[object Boolean]') {
+            v = v.valueOf();
+        }
+        return v;
+    }
+    function skipped(v) {
+        return v === undefined || typeof v === 'function' || typeof v === 'symbol';
+    }
+    function encode(v) {
+        if (v === null || skipped(v)) return byte(0xc0);
+        if (v === false) return byte(0xc2);
+        if (v === true) return byte(0xc3);
+        if (typeof v === 'number') return number(v);
+        if (typeof v === 'string') return string(v);
+        if (typeof v === 'bigint') return bigint(v);
+        if (tagOf(v) === '[object ArrayBuffer]') return bytes(new Uint8Array(v));
+        if (ArrayBuffer.isView(v)) {
+            return bytes(new Uint8Array(v.buffer, v.byteOffset, v.byteLength));
+        }
+        if (stack.indexOf(v) !== -1) {
+            throw new TypeError('Converting circular structure to MessagePack');
+        }
+        stack.push(v);
+        if (Array.isArray(v)) {
+            header(v.length, 0x90, 15, null, 0xdc, 0xdd);
+            for (let i = 0; i < v.length; i++) {
+                encode(prepare(v[i], String(i)));
+            }
+        } else {
+            const entries = [];
+            Object.keys(v).forEach(function(key) {
+                const item = prepare(v[key], key);
+                if (!skipped(item)) entries.push([key, item]);
+            });
+            header(entries.length, 0x80, 15, null, 0xde, 0xdf);
+            entries.forEach(function(entry) {
+                string(entry[0]);
+                encode(entry[1]);
+            });
+        }
+        stack.pop();
+    }
+
+    encode(prepare(root, ''));
+    let binary = '';
+    for (let i = 0; i < length; i += 0x8000) {
+        binary += String.fromCharCode.apply(null, buffer.subarray(i, Math.min(i + 0x8000, length)));
+    }
+    return {
+        __msgpack: true,
+        result_type: root === null ? 'null' : typeof root,
+        data: btoa(binary)
+    };
+})(__mcpUnpacked)
+"#;
+
+/// Replaces a MessagePack marker in a successful result's `data` with the value it
+/// holds, restoring `result_type` too.
+///
+/// Results without a marker, like failures or truncated results, are left as they are.
+pub(crate) fn unpack_result(result: &mut Value) -> Result<(), String> {
+    let data = &result["data"];
+    if data.get("__msgpack").and_then(|v| v.as_bool()) != Some(true) {
+        return Ok(());
+    }
+    let encoded = data
+        .get("data")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default();
+    let bytes = STANDARD
+        .decode(encoded)
+        .map_err(|e| format!("Failed to decode MessagePack result: {e}"))?;
+    let result_type = data.get("result_type").cloned().unwrap_or(Value::Null);
+
+    result["data"] = decode(&bytes)?;
+    result["result_type"] = result_type;
+    Ok(())
+}
+
+/// Decodes a single MessagePack value that takes up all of `bytes`.
+///
+/// Binary data becomes a `{ __binary: true, encoding: "base64", mime, data }` marker,
+/// like binary results in JSON. Extension types aren't supported, since the encoder
+/// never writes them.
+pub(crate) fn decode(bytes: &[u8]) -> Result<Value, String> {
+    let mut reader = Reader { bytes, pos: 0 };
+    let value = reader.value(0)?;
+    if reader.pos != bytes.len() {
+        return Err(format!(
+            "Invalid MessagePack result: {} bytes left after the value",
+            bytes.len() - reader.pos
+        ));
+    }
+    Ok(value)
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], String> {
+        let end = self
+            .pos
+            .checked_add(n)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| "Invalid MessagePack result: unexpected end of data".to_string())?;
+        let taken = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(taken)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], String> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.take(N)?);
+        Ok(array)
+    }
+
+    fn length(&mut self, size: usize) -> Result<usize, String> {
+        Ok(match size {
+            1 => self.array::<1>()?[0] as usize,
+            2 => u16::from_be_bytes(self.array()?) as usize,
+            _ => u32::from_be_bytes(self.array()?) as usize,
+        })
+    }
+
+    fn string(&mut self, len: usize) -> Result<String, String> {
+        let bytes = self.take(len)?;
+        String::from_utf8(bytes.to_vec())
+            .map_err(|_| "Invalid MessagePack result: string is not UTF-8".to_string())
+    }
+
+    fn value(&mut self, depth: usize) -> Result<Value, String> {
+        let marker = self.array::<1>()?[0];
+        Ok(match marker {
+            0x00..=0x7f => Value::from(marker),
+            0x80..=0x8f => self.map((marker & 0x0f) as usize, depth)?,
+            0x90..=0x9f => self.list((marker & 0x0f) as usize, depth)?,
+            0xa0..=0xbf => Value::String(self.string((marker & 0x1f) as usize)?),
+            0xc0 => Value::Null,
+            0xc2 => Value::Bool(false),
+            0xc3 => Value::Bool(true),
+            0xc4..=0xc6 => {
+                let len = self.length(1 << (marker - 0xc4))?;
+                let data = STANDARD.encode(self.take(len)?);
+                serde_json::json!({
+                    "__binary": true,
+                    "encoding": "base64",
+                    "mime": "application/octet-stream",
+                    "data": data
+                })
+            }
+            0xca => Value::from(f32::from_be_bytes(self.array()?) as f64),
+            0xcb => Value::from(f64::from_be_bytes(self.array()?)),
+            0xcc => Value::from(self.array::<1>()?[0]),
+            0xcd => Value::from(u16::from_be_bytes(self.array()?)),
+            0xce => Value::from(u32::from_be_bytes(self.array()?)),
+            0xcf => Value::from(u64::from_be_bytes(self.array()?)),
+            0xd0 => Value::from(i8::from_be_bytes(self.array()?)),
+            0xd1 => Value::from(i16::from_be_bytes(self.array()?)),
+            0xd2 => Value::from(i32::from_be_bytes(self.array()?)),
+            0xd3 => Value::from(i64::from_be_bytes(self.array()?)),
+            0xd9..=0xdb => {
+                let len = self.length(1 << (marker - 0xd9))?;
+                Value::String(self.string(len)?)
+            }
+            0xdc | 0xdd => {
+                let len = self.length(if marker == 0xdc { 2 } else { 4 })?;
+                self.list(len, depth)?
+            }
+            0xde | 0xdf => {
+                let len = self.length(if marker == 0xde { 2 } else { 4 })?;
+                self.map(len, depth)?
+            }
+            0xe0..=0xff => Value::from(marker as i8),
+            _ => {
+                return Err(format!(
+                    "Invalid MessagePack result: unsupported type 0x{marker:02x}"
+                ))
+            }
+        })
+    }
+
+    fn list(&mut self, len: usize, depth: usize) -> Result<Value, String> {
+        let depth = self.nested(depth)?;
+        // Every item takes at least a byte, so a bogus length can't allocate much
+        let mut items = Vec::with_capacity(len.min(self.bytes.len() - self.pos));
+        for _ in 0..len {
+            items.push(self.value(depth)?);
+        }
+        Ok(Value::Array(items))
+    }
+
+    fn map(&mut self, len: usize, depth: usize) -> Result<Value, String> {
+        let depth = self.nested(depth)?;
+        let mut map = Map::new();
+        for _ in 0..len {
+            let key = match self.value(depth)? {
+                Value::String(key) => key,
+                Value::Number(key) => key.to_string(),
+                other => {
+                    return Err(format!(
+                        "Invalid MessagePack result: map key {other} is not a string"
+                    ))
+                }
+            };
+            let value = self.value(depth)?;
+            map.insert(key, value);
+        }
+        Ok(Value::Object(map))
+    }
+
+    fn nested(&self, depth: usize) -> Result<usize, String> {
+        if depth >= MAX_DEPTH {
+            return Err(format!(
+                "Invalid MessagePack result: nested more than {MAX_DEPTH} levels deep"
+            ));
+        }
+        Ok(depth + 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decodes_every_format() {
+        let bytes = [
+            0x8a, // map of 10
+            0xa1, b'a', 0x93, 0x05, 0xff, 0xc0, // [5, -1, null]
+            0xa1, b'b', 0xcd, 0x12, 0x34, // 0x1234
+            0xa1, b'c', 0xd2, 0xff, 0xff, 0xff, 0x00, // -256
+            0xa1, b'd', 0xcb, 0x3f, 0xf8, 0, 0, 0, 0, 0, 0, // 1.5
+            0xa1, b'e', 0xd9, 0x02, 0xc3, 0xa9, // "é"
+            0xa1, b'f', 0xc4, 0x02, 0x01, 0x02, // bytes
+            0xa1, b'g', 0xc3, // true
+            0xa1, b'h', 0xcf, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, // u64::MAX
+            0xa1, b'i', 0xdc, 0x00, 0x01, 0x80, // [{}]
+            0xa1, b'j', 0xca, 0x3f, 0x00, 0x00, 0x00, // 0.5f32
+        ];
+        assert_eq!(
+            decode(&bytes).unwrap(),
+            serde_json::json!({
+                "a": [5, -1, null],
+                "b": 0x1234,
+                "c": -256,
+                "d": 1.5,
+                "e": "é",
+                "f": {
+                    "__binary": true,
+                    "encoding": "base64",
+                    "mime": "application/octet-stream",
+                    "data": "AQI="
+                },
+                "g": true,
+                "h": u64::MAX,
+                "i": [{}],
+                "j": 0.5
+            })
+        );
+    }
+
+    #[test]
+    fn test_malformed_data_is_rejected() {
+        // Truncated string, bogus array length, trailing bytes, extension type
+        assert!(decode(&[0xa3, b'a']).is_err());
+        assert!(decode(&[0xdd, 0xff, 0xff, 0xff, 0xff]).is_err());
+        assert!(decode(&[0x01, 0x02]).is_err());
+        assert!(decode(&[0xd4, 0x01, 0x00]).is_err());
+        assert!(decode(&[0x91; MAX_DEPTH + 1]).is_err());
+    }
+
+    #[test]
+    fn test_unpacks_marker() {
+        let mut result = serde_json::json!({
+            "success": true,
+            "data": { "__msgpack": true, "result_type": "object", "data": "gaF4AQ==" },
+            "result_type": "object"
+        });
+        unpack_result(&mut result).unwrap();
+        assert_eq!(result["data"], serde_json::json!({ "x": 1 }));
+        assert_eq!(result["result_type"], "object");
+
+        let mut plain = serde_json::json!({ "success": true, "data": "text" });
+        unpack_result(&mut plain).unwrap();
+        assert_eq!(plain["data"], "text");
+    }
+}
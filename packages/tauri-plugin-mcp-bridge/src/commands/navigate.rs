@@ -0,0 +1,705 @@
+//! Webview navigation.
+
+use super::execute_js::run_script;
+use super::list_windows::target_window;
+use super::script_executor::ScriptExecutor;
+use crate::{BRIDGE_PRESENT_CHECK, BRIDGE_SCRIPT};
+use serde_json::Value;
+use std::time::{Duration, Instant};
+use tauri::{command, Runtime, State, Url, WebviewWindow};
+use uuid::Uuid;
+
+/// Default time allowed for a navigation to reach the requested state.
+pub const DEFAULT_NAVIGATION_TIMEOUT_MS: u64 = 30000;
+
+/// How long a single probe for the new page may take before it's retried.
+const PROBE_TIMEOUT_MS: u64 = 500;
+
+/// Pause between probes while the old page is still loaded.
+const PROBE_INTERVAL_MS: u64 = 50;
+
+/// How long a history traversal may take to start before there's taken to be no
+/// entry to go to.
+const HISTORY_START_MS: u64 = 1000;
+
+/// How long the DOM must stay unchanged after a same-document traversal.
+const HISTORY_SETTLE_MS: u64 = 100;
+
+/// Longest wait for the DOM to settle after a same-document traversal.
+const HISTORY_MAX_SETTLE_MS: u64 = 2000;
+
+/// Goes `delta` entries through the session history, and resolves once a
+/// same-document traversal has settled.
+///
+/// Expects `delta`, `direction` (`"back"` or `"forward"`), `token`, `startMs`,
+/// `settleMs` and `maxSettleMs` to be defined before it. The page is marked with `token` like [`mark_page_script`] does, for when
+/// the traversal loads another document, which ends this script. Browsers don't say
+/// whether there's an entry to go to, except through the Navigation API where it
+/// exists, so a traversal that doesn't start in time counts as having none.
+const HISTORY_SCRIPT: &str = r#"
+function noEntry() {
+    const error = new Error('Nothing to go ' + direction + ' to: the window is at the '
+        + (delta < 0 ? 'start' : 'end') + ' of its history');
+    error.__mcpErrorKind = 'no_history_entry';
+    return error;
+}
+const canGo = window.navigation ? (delta < 0 ? navigation.canGoBack : navigation.canGoForward) : undefined;
+if (canGo === false) {
+    throw noEntry();
+}
+window.__MCP_NAVIGATION_PENDING__ = token;
+const traversed = await new Promise(function(resolve) {
+    const timer = setTimeout(function() { done(false); }, startMs);
+    function onTraverse() { done(true); }
+    function done(result) {
+        clearTimeout(timer);
+        window.removeEventListener('popstate', onTraverse);
+        window.removeEventListener('hashchange', onTraverse);
+        resolve(result);
+    }
+    window.addEventListener('popstate', onTraverse);
+    window.addEventListener('hashchange', onTraverse);
+    history.go(delta);
+});
+if (!traversed) {
+    throw noEntry();
+}
+// Give the app time to render the new route
+await new Promise(function(resolve) {
+    let timer = setTimeout(finish, settleMs);
+    const cap = setTimeout(finish, maxSettleMs);
+    const observer = new MutationObserver(function() {
+        clearTimeout(timer);
+        timer = setTimeout(finish, settleMs);
+    });
+    observer.observe(document.documentElement, {
+        childList: true, subtree: true, attributes: true, characterData: true
+    });
+    function finish() {
+        observer.disconnect();
+        clearTimeout(timer);
+        clearTimeout(cap);
+        resolve();
+    }
+});
+({ url: location.href, status: null, readyState: document.readyState, same_document: true })
+"#;
+
+/// Resolves once the page reaches `waitUntil`, with its URL and response status.
+///
+/// Expects `waitUntil` to be defined before it. The network is considered idle once
+/// no resource has finished loading for 500ms, which is a heuristic: requests still
+/// in flight aren't visible to the page until they complete.
+const LIFECYCLE_SCRIPT: &str = r#"
+if (waitUntil === 'domcontentloaded') {
+    if (document.readyState === 'loading') {
+        await new Promise(function(resolve) {
+            document.addEventListener('DOMContentLoaded', resolve, { once: true });
+        });
+    }
+} else if (document.readyState !== 'complete') {
+    await new Promise(function(resolve) {
+        window.addEventListener('load', resolve, { once: true });
+    });
+}
+
+if (waitUntil === 'networkidle') {
+    let count = performance.getEntriesByType('resource').length;
+    for (;;) {
+        await new Promise(function(resolve) { setTimeout(resolve, 500); });
+        const next = performance.getEntriesByType('resource').length;
+        if (next === count) break;
+        count = next;
+    }
+}
+
+const entry = performance.getEntriesByType('navigation')[0];
+({
+    url: location.href,
+    status: entry && entry.responseStatus ? entry.responseStatus : null,
+    readyState: document.readyState
+})
+"#;
+
+/// Describes where the page is.
+const GET_URL_SCRIPT: &str = r#"
+({
+    href: location.href,
+    origin: location.origin,
+    pathname: location.pathname,
+    search: location.search,
+    hash: location.hash,
+    history_length: history.length
+})
+"#;
+
+/// Re-fetches the page and every resource it loaded, bypassing and refreshing the
+/// HTTP cache, so a reload right after gets fresh copies.
+///
+/// Cross-origin resources are fetched in `no-cors` mode, which still updates the cache.
+const REFRESH_CACHE_SCRIPT: &str = r#"
+const urls = [location.href].concat(performance.getEntriesByType('resource').map(function(entry) {
+    return entry.name;
+}));
+const unique = urls.filter(function(url, index) {
+    return /^https?:/.test(url) && urls.indexOf(url) === index;
+});
+const results = await Promise.allSettled(unique.map(function(url) {
+    const sameOrigin = new URL(url).origin === location.origin;
+    return fetch(url, { cache: 'reload', mode: sameOrigin ? 'same-origin' : 'no-cors' });
+}));
+({ refreshed: results.filter(function(r) { return r.status === 'fulfilled'; }).length })
+"#;
+
+/// Marks the current page with `token`, so probes can tell it apart from the one
+/// being loaded.
+///
+/// A token per navigation, rather than a flag, keeps a page restored from the
+/// back-forward cache with an older mark from passing for the page being left.
+fn mark_page_script(token: &str) -> String {
+    format!(
+        "window.__MCP_NAVIGATION_PENDING__ = {};\ntrue",
+        Value::from(token)
+    )
+}
+
+/// Evaluates to `true` once the page marked with `token` has been replaced.
+fn probe_script(token: &str) -> String {
+    format!(
+        "window.__MCP_NAVIGATION_PENDING__ !== {}",
+        Value::from(token)
+    )
+}
+
+/// Checks a `wait_until` lifecycle state, falling back to `"load"` when omitted.
+fn lifecycle_state(wait_until: Option<String>) -> Result<String, String> {
+    let wait_until = wait_until.unwrap_or_else(|| "load".to_string());
+    if matches!(
+        wait_until.as_str(),
+        "load" | "domcontentloaded" | "networkidle"
+    ) {
+        Ok(wait_until)
+    } else {
+        Err(format!(
+            "Invalid wait_until '{wait_until}': expected \"load\", \"domcontentloaded\" or \"networkidle\""
+        ))
+    }
+}
+
+/// Navigates the webview to a URL and waits for the new page to load.
+///
+/// Resolves once the new page reaches the requested lifecycle state, and returns the
+/// final URL after any redirects. Relative URLs are resolved against the current one.
+///
+/// # Arguments
+///
+/// * `window` - The window to navigate
+/// * `url` - The URL to load
+/// * `wait_until` - `"load"` (the default), `"domcontentloaded"` or `"networkidle"`
+/// * `timeout_ms` - How long to wait in total (defaults to 30000ms)
+/// * `window_label` - Label of the window to target (defaults to the calling window)
+/// * `executor` - Script executor state
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the page reached the state in time
+///   - `data`: `{ url, status, readyState }` where `status` is the HTTP status if the
+///     webview reports it, or `null`
+///   - `error`: Error message (if the navigation timed out)
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const result = await invoke('plugin:mcp-bridge|navigate', {
+///   url: '/settings',
+///   waitUntil: 'networkidle'
+/// });
+/// console.log(result.data.url);
+/// ```
+#[command]
+pub async fn navigate<R: Runtime>(
+    window: WebviewWindow<R>,
+    url: String,
+    wait_until: Option<String>,
+    timeout_ms: Option<u64>,
+    window_label: Option<String>,
+    executor: State<'_, ScriptExecutor>,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    let wait_until = lifecycle_state(wait_until)?;
+    let target = match Url::parse(&url) {
+        Ok(target) => target,
+        Err(_) => window
+            .url()
+            .map_err(|e| e.to_string())?
+            .join(&url)
+            .map_err(|e| format!("Invalid URL '{url}': {e}"))?,
+    };
+
+    load_and_wait(
+        &window,
+        &wait_until,
+        timeout_ms.unwrap_or(DEFAULT_NAVIGATION_TIMEOUT_MS),
+        &executor,
+        |window| {
+            window
+                .navigate(target)
+                .map_err(|e| format!("Failed to navigate: {e}"))
+        },
+    )
+    .await
+}
+
+/// Reloads the webview's current page and waits for it to load again.
+///
+/// Resolves once the reloaded page reaches the requested lifecycle state, like
+/// `navigate`. The bridge's console and error capture normally comes back with the
+/// page; if it didn't (for example because the app turned off `reinject_on_load`),
+/// it's injected again so `get_console_logs` and `get_page_errors` keep working. Logs
+/// and errors from before the reload are lost.
+///
+/// With `ignore_cache`, the page and every resource it loaded are first re-fetched
+/// with the HTTP cache bypassed, which replaces their cached copies, so the reload
+/// gets fresh ones. Resources the reloaded page requests for the first time may still
+/// come from the cache.
+///
+/// # Arguments
+///
+/// * `window` - The window to reload
+/// * `ignore_cache` - Refresh cached resources before reloading (defaults to false)
+/// * `wait_until` - `"load"` (the default), `"domcontentloaded"` or `"networkidle"`
+/// * `timeout_ms` - How long to wait in total (defaults to 30000ms)
+/// * `window_label` - Label of the window to target (defaults to the calling window)
+/// * `executor` - Script executor state
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the reloaded page reached the state in time
+///   - `data`: `{ url, status, readyState, reinjected }` where `reinjected` is whether
+///     the console and error capture had to be injected again
+///   - `error`: Error message (if the reload timed out)
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const result = await invoke('plugin:mcp-bridge|reload', { ignoreCache: true });
+/// console.log(result.data.readyState); // "complete"
+/// ```
+#[command]
+pub async fn reload<R: Runtime>(
+    window: WebviewWindow<R>,
+    ignore_cache: Option<bool>,
+    wait_until: Option<String>,
+    timeout_ms: Option<u64>,
+    window_label: Option<String>,
+    executor: State<'_, ScriptExecutor>,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    let wait_until = lifecycle_state(wait_until)?;
+    let timeout_ms = timeout_ms.unwrap_or(DEFAULT_NAVIGATION_TIMEOUT_MS);
+    let deadline = Instant::now() + Duration::from_millis(timeout_ms);
+
+    if ignore_cache.unwrap_or(false) {
+        let refreshed = run_script(
+            window.clone(),
+            REFRESH_CACHE_SCRIPT.to_string(),
+            Some(timeout_ms),
+            None,
+            None,
+            None,
+            None,
+            executor.clone(),
+        )
+        .await?;
+        if refreshed.get("success").and_then(|v| v.as_bool()) != Some(true) {
+            return Ok(refreshed);
+        }
+    }
+
+    let mut result = load_and_wait(
+        &window,
+        &wait_until,
+        remaining_ms(deadline),
+        &executor,
+        |window| {
+            window
+                .reload()
+                .map_err(|e| format!("Failed to reload: {e}"))
+        },
+    )
+    .await?;
+    if result.get("success").and_then(|v| v.as_bool()) != Some(true) {
+        return Ok(result);
+    }
+
+    let shims = run_script(
+        window.clone(),
+        BRIDGE_PRESENT_CHECK.to_string(),
+        Some(PROBE_TIMEOUT_MS),
+        None,
+        None,
+        None,
+        None,
+        executor,
+    )
+    .await?;
+    let reinjected = shims.get("data").and_then(|v| v.as_bool()) != Some(true);
+    if reinjected {
+        window
+            .eval(BRIDGE_SCRIPT)
+            .map_err(|e| format!("Failed to re-inject bridge script: {e}"))?;
+    }
+    result["data"]["reinjected"] = Value::Bool(reinjected);
+
+    Ok(result)
+}
+
+/// Returns the page's current URL, split into its parts, and the history length.
+///
+/// A cheap read of where the page is, for example to check that a click or
+/// `navigate` ended up at the expected route. Unlike the URL of the window's last
+/// load, it includes changes made by the page itself with `history.pushState` or
+/// hash navigation, as single-page apps do.
+///
+/// # Arguments
+///
+/// * `window` - The window to read the URL of
+/// * `window_label` - Label of the window to target (defaults to the calling window)
+/// * `executor` - Script executor state
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the URL was read
+///   - `data`: `{ href, origin, pathname, search, hash, history_length }`, where the
+///     first five are the fields of `location` and `history_length` is
+///     `history.length`, the number of entries in the window's session history
+///   - `error`: Error message (if failed)
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const result = await invoke('plugin:mcp-bridge|get_url');
+/// console.log(result.data.pathname); // "/settings"
+/// ```
+#[command]
+pub async fn get_url<R: Runtime>(
+    window: WebviewWindow<R>,
+    window_label: Option<String>,
+    executor: State<'_, ScriptExecutor>,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    run_script(
+        window,
+        GET_URL_SCRIPT.to_string(),
+        None,
+        None,
+        None,
+        None,
+        None,
+        executor,
+    )
+    .await
+}
+
+/// Goes back one entry in the window's session history, and waits for the page there.
+///
+/// When going back loads another document, this waits for it to reach `wait_until`,
+/// like [`navigate`]. When the entry belongs to the same document, as with routes of
+/// single-page apps using the History API or hash changes, it resolves after
+/// `popstate` once the DOM has been unchanged for 100ms (2 seconds at most), so the
+/// app had time to render the route.
+///
+/// # Arguments
+///
+/// * `window` - The window to go back in
+/// * `wait_until` - `"load"` (the default), `"domcontentloaded"` or `"networkidle"`,
+///   when another document is loaded
+/// * `timeout_ms` - How long to wait in total (defaults to 30000ms)
+/// * `window_label` - Label of the window to target (defaults to the calling window)
+/// * `executor` - Script executor state
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the window went back and the page got ready in time
+///   - `data`: `{ url, status, readyState, same_document }` where `status` is the
+///     HTTP status if the webview reports it (always `null` within the same
+///     document), and `same_document` is whether the document stayed the same
+///   - `error`: Error message (if there's nothing to go back to or it timed out)
+///   - `error_kind`: `"no_history_entry"` when the window is at the start of its
+///     history. Where the webview lacks the Navigation API, that's detected by nothing
+///     happening for a second.
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const result = await invoke('plugin:mcp-bridge|go_back');
+/// console.log(result.data.url);
+/// ```
+#[command]
+pub async fn go_back<R: Runtime>(
+    window: WebviewWindow<R>,
+    wait_until: Option<String>,
+    timeout_ms: Option<u64>,
+    window_label: Option<String>,
+    executor: State<'_, ScriptExecutor>,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    traverse_history(&window, -1, wait_until, timeout_ms, &executor).await
+}
+
+/// Goes forward one entry in the window's session history, and waits for the page
+/// there.
+///
+/// Works like [`go_back`] in the other direction.
+///
+/// # Arguments
+///
+/// * `window` - The window to go forward in
+/// * `wait_until` - `"load"` (the default), `"domcontentloaded"` or `"networkidle"`,
+///   when another document is loaded
+/// * `timeout_ms` - How long to wait in total (defaults to 30000ms)
+/// * `window_label` - Label of the window to target (defaults to the calling window)
+/// * `executor` - Script executor state
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the window went forward and the page got ready in time
+///   - `data`: `{ url, status, readyState, same_document }`, like [`go_back`]
+///   - `error`: Error message (if there's nothing to go forward to or it timed out)
+///   - `error_kind`: `"no_history_entry"` when the window is at the end of its history
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// await invoke('plugin:mcp-bridge|go_back');
+/// const result = await invoke('plugin:mcp-bridge|go_forward');
+/// console.log(result.data.url);
+/// ```
+#[command]
+pub async fn go_forward<R: Runtime>(
+    window: WebviewWindow<R>,
+    wait_until: Option<String>,
+    timeout_ms: Option<u64>,
+    window_label: Option<String>,
+    executor: State<'_, ScriptExecutor>,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    traverse_history(&window, 1, wait_until, timeout_ms, &executor).await
+}
+
+/// Goes `delta` entries through the history, for `go_back` and `go_forward`.
+///
+/// The traversal starts in the page, which answers itself when it stays the same
+/// document. When another document loads instead, the script's execution fails as
+/// the old page goes away, and the new page is waited for like after [`navigate`].
+async fn traverse_history<R: Runtime>(
+    window: &WebviewWindow<R>,
+    delta: i32,
+    wait_until: Option<String>,
+    timeout_ms: Option<u64>,
+    executor: &State<'_, ScriptExecutor>,
+) -> Result<Value, String> {
+    let wait_until = lifecycle_state(wait_until)?;
+    let timeout_ms = timeout_ms.unwrap_or(DEFAULT_NAVIGATION_TIMEOUT_MS);
+    let deadline = Instant::now() + Duration::from_millis(timeout_ms);
+    let token = Uuid::new_v4().to_string();
+
+    let direction = if delta < 0 { "back" } else { "forward" };
+    let script = format!(
+        "const delta = {delta};\nconst direction = {};\nconst token = {};\nconst startMs = {HISTORY_START_MS};\nconst settleMs = {HISTORY_SETTLE_MS};\nconst maxSettleMs = {HISTORY_MAX_SETTLE_MS};\n{HISTORY_SCRIPT}",
+        Value::from(direction),
+        Value::from(token.as_str()),
+    );
+    // Long enough for a same-document traversal, after which a lost result means
+    // another document was loaded
+    let script_timeout = HISTORY_START_MS + HISTORY_MAX_SETTLE_MS + PROBE_TIMEOUT_MS;
+    let result = run_script(
+        window.clone(),
+        script,
+        Some(remaining_ms(deadline).min(script_timeout)),
+        None,
+        None,
+        None,
+        None,
+        executor.clone(),
+    )
+    .await?;
+
+    if result.get("success").and_then(|v| v.as_bool()) == Some(true) {
+        return Ok(serde_json::json!({
+            "success": true,
+            "data": result.get("data").cloned().unwrap_or(Value::Null)
+        }));
+    }
+    match result.get("error_kind").and_then(|v| v.as_str()) {
+        Some("webview_navigated") | Some("navigated_during_execution") | Some("timeout") => {}
+        _ => return Ok(result),
+    }
+
+    let mut result =
+        wait_for_new_page(window, &wait_until, timeout_ms, deadline, &token, executor).await?;
+    if let Some(data) = result.get_mut("data").and_then(|v| v.as_object_mut()) {
+        data.insert("same_document".to_string(), Value::Bool(false));
+    }
+    Ok(result)
+}
+
+/// Starts loading a page with `start` and waits for it to reach `wait_until`.
+///
+/// Shared by `navigate` and `reload`. The current page is marked first, so the new
+/// one can be told apart from it while it loads.
+async fn load_and_wait<R: Runtime>(
+    window: &WebviewWindow<R>,
+    wait_until: &str,
+    timeout_ms: u64,
+    executor: &State<'_, ScriptExecutor>,
+    start: impl FnOnce(&WebviewWindow<R>) -> Result<(), String>,
+) -> Result<Value, String> {
+    let deadline = Instant::now() + Duration::from_millis(timeout_ms);
+    let token = Uuid::new_v4().to_string();
+
+    // A page that can't be marked (for example one without __TAURI__) simply
+    // makes the first probe succeed right away
+    let _ = run_script(
+        window.clone(),
+        mark_page_script(&token),
+        Some(PROBE_TIMEOUT_MS),
+        None,
+        None,
+        None,
+        None,
+        executor.clone(),
+    )
+    .await;
+
+    start(window)?;
+
+    wait_for_new_page(window, wait_until, timeout_ms, deadline, &token, executor).await
+}
+
+/// Waits until the page marked with `token` has been replaced, and the new one has
+/// reached `wait_until`.
+async fn wait_for_new_page<R: Runtime>(
+    window: &WebviewWindow<R>,
+    wait_until: &str,
+    timeout_ms: u64,
+    deadline: Instant,
+    token: &str,
+    executor: &State<'_, ScriptExecutor>,
+) -> Result<Value, String> {
+    let timeout_error = || {
+        serde_json::json!({
+            "success": false,
+            "error": format!("Navigation timeout after {timeout_ms}ms waiting for '{wait_until}'")
+        })
+    };
+
+    // Scripts sent mid-navigation may run on the old page or be dropped entirely,
+    // so keep probing until the new page answers
+    loop {
+        let remaining = remaining_ms(deadline);
+        if remaining == 0 {
+            return Ok(timeout_error());
+        }
+        let probe = run_script(
+            window.clone(),
+            probe_script(token),
+            Some(remaining.min(PROBE_TIMEOUT_MS)),
+            None,
+            None,
+            None,
+            None,
+            executor.clone(),
+        )
+        .await?;
+        if probe.get("data").and_then(|v| v.as_bool()) == Some(true) {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(PROBE_INTERVAL_MS)).await;
+    }
+
+    let remaining = remaining_ms(deadline);
+    if remaining == 0 {
+        return Ok(timeout_error());
+    }
+    let wait_until_js = serde_json::to_string(wait_until).map_err(|e| e.to_string())?;
+    let result = run_script(
+        window.clone(),
+        format!("const waitUntil = {wait_until_js};\n{LIFECYCLE_SCRIPT}"),
+        Some(remaining),
+        None,
+        None,
+        None,
+        None,
+        executor.clone(),
+    )
+    .await?;
+
+    if result.get("success").and_then(|v| v.as_bool()) == Some(true) {
+        Ok(serde_json::json!({
+            "success": true,
+            "data": result.get("data").cloned().unwrap_or(Value::Null)
+        }))
+    } else if remaining_ms(deadline) == 0 {
+        Ok(timeout_error())
+    } else {
+        Ok(result)
+    }
+}
+
+/// Milliseconds left until `deadline`, or 0 if it has passed.
+fn remaining_ms(deadline: Instant) -> u64 {
+    deadline
+        .saturating_duration_since(Instant::now())
+        .as_millis() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_probes_tell_pages_apart_by_token() {
+        let mark = mark_page_script("a1");
+        assert!(mark.starts_with("window.__MCP_NAVIGATION_PENDING__ = \"a1\";"));
+        assert_eq!(
+            probe_script("a1"),
+            "window.__MCP_NAVIGATION_PENDING__ !== \"a1\""
+        );
+    }
+}
@@ -0,0 +1,128 @@
+//! Network requests captured by the bridge script.
+//!
+//! `bridge.js` wraps `fetch` and `XMLHttpRequest` as soon as a page loads and keeps the
+//! most recent completed requests in a bounded buffer on the page. These commands read
+//! and reset that buffer.
+
+use super::execute_js::run_script;
+use super::list_windows::target_window;
+use super::script_executor::ScriptExecutor;
+use serde_json::Value;
+use tauri::{command, Runtime, State, WebviewWindow};
+
+/// Reads buffered requests after the `since` cursor.
+///
+/// Expects `since` to be defined before it.
+const GET_NETWORK_LOG_SCRIPT: &str = r#"
+const log = window.__MCP_NETWORK_LOG__ || [];
+({
+    entries: log.filter(function(entry) { return entry.seq > since; }),
+    cursor: log.length > 0 ? Math.max(since, log[log.length - 1].seq) : since,
+    capturing_bodies: typeof window.__MCP_NETWORK_BODY_LIMIT__ === 'number'
+})
+"#;
+
+/// Empties the network buffer while keeping entry numbering, so cursors stay valid.
+const CLEAR_NETWORK_LOG_SCRIPT: &str = r#"
+const log = window.__MCP_NETWORK_LOG__ || [];
+const cleared = log.length;
+log.length = 0;
+({ cleared: cleared })
+"#;
+
+/// Returns the `fetch` and `XMLHttpRequest` calls the webview made.
+///
+/// Requests are recorded when they complete, numbered in that order. Pass the returned
+/// `cursor` as `since` on the next call to receive only newer requests. The buffer
+/// holds the most recent 500 requests, and is reset when the page reloads. Tauri's own
+/// IPC requests are left out.
+///
+/// Request and response bodies are only recorded when the app enables it with
+/// `Builder::capture_network_bodies`, and are cut off at the configured length.
+///
+/// # Arguments
+///
+/// * `window` - The window to read requests from
+/// * `since` - Only return requests numbered after this cursor (defaults to 0, all requests)
+/// * `window_label` - Label of the window to target (defaults to the calling window)
+/// * `executor` - Script executor state
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the log was read
+///   - `data`: `{ entries, cursor, capturing_bodies }` where each entry is
+///     `{ seq, type, method, url, status, duration_ms, request_size, response_size,
+///     timestamp }`, plus `error` for failed requests and `request_body` and
+///     `response_body` when bodies are captured. `type` is `"fetch"` or `"xhr"`, sizes
+///     are in bytes or characters and `null` when unknown, and `status` is `null` for
+///     requests that never got a response.
+///   - `error`: Error message (if failed)
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const first = await invoke('plugin:mcp-bridge|get_network_log');
+/// const failed = first.data.entries.filter((entry) => entry.status >= 400);
+/// const newer = await invoke('plugin:mcp-bridge|get_network_log', {
+///   since: first.data.cursor
+/// });
+/// ```
+#[command]
+pub async fn get_network_log<R: Runtime>(
+    window: WebviewWindow<R>,
+    since: Option<u64>,
+    window_label: Option<String>,
+    executor: State<'_, ScriptExecutor>,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    let since = since.unwrap_or(0);
+    let script = format!("const since = {since};\n{GET_NETWORK_LOG_SCRIPT}");
+
+    run_script(window, script, None, None, None, None, None, executor).await
+}
+
+/// Clears the network requests captured in the webview.
+///
+/// Entry numbering continues after a clear, so cursors from earlier
+/// `get_network_log` calls remain valid.
+///
+/// # Arguments
+///
+/// * `window_label` - Label of the window to target (defaults to the calling window)
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the buffer was cleared
+///   - `data`: `{ cleared }` with the number of entries removed
+///   - `error`: Error message (if failed)
+#[command]
+pub async fn clear_network_log<R: Runtime>(
+    window: WebviewWindow<R>,
+    window_label: Option<String>,
+    executor: State<'_, ScriptExecutor>,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    run_script(
+        window,
+        CLEAR_NETWORK_LOG_SCRIPT.to_string(),
+        None,
+        None,
+        None,
+        None,
+        None,
+        executor,
+    )
+    .await
+}
@@ -0,0 +1,340 @@
+//! Canned responses for `fetch` requests.
+//!
+//! Mock rules are kept in plugin state and copied into every webview, where the
+//! `fetch` wrapper in `bridge.js` answers matching requests without hitting the
+//! network. Rules are copied again whenever a page loads, so they survive navigation.
+
+use crate::logging::mcp_log_error;
+use regex::Regex;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tauri::{command, AppHandle, Manager, Runtime, State};
+use uuid::Uuid;
+
+/// A mock rule, in the form `bridge.js` reads it.
+#[derive(Debug, Clone, Serialize)]
+struct MockRule {
+    id: String,
+    /// The URL pattern as given
+    url: String,
+    url_match: String,
+    /// Regular expression the URL is tested against
+    pattern: String,
+    /// Only requests with this method match (any method if unset)
+    method: Option<String>,
+    status: u16,
+    headers: HashMap<String, String>,
+    body: String,
+}
+
+/// Active mock rules, oldest first.
+///
+/// Managed as app state.
+#[derive(Default)]
+pub struct NetworkMocks(Mutex<Vec<MockRule>>);
+
+impl NetworkMocks {
+    /// Script that replaces the mock rules in a webview.
+    ///
+    /// Rules are listed newest first, since the first matching rule wins.
+    fn apply_script(&self) -> String {
+        let rules: Vec<MockRule> = self
+            .0
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .rev()
+            .cloned()
+            .collect();
+        let rules = serde_json::to_string(&rules).unwrap_or_else(|_| "[]".to_string());
+
+        format!("window.__MCP_SET_NETWORK_MOCKS__ && window.__MCP_SET_NETWORK_MOCKS__({rules});")
+    }
+
+    fn is_empty(&self) -> bool {
+        self.0.lock().unwrap_or_else(|e| e.into_inner()).is_empty()
+    }
+}
+
+/// Script that restores the mock rules after a page load, if there are any.
+pub(crate) fn mocks_script<R: Runtime, M: Manager<R>>(manager: &M) -> Option<String> {
+    let mocks = manager.try_state::<NetworkMocks>()?;
+    if mocks.is_empty() {
+        None
+    } else {
+        Some(mocks.apply_script())
+    }
+}
+
+/// Copies the current rules into every open webview.
+fn apply_to_all<R: Runtime>(app: &AppHandle<R>, mocks: &NetworkMocks) {
+    let script = mocks.apply_script();
+    for (label, window) in app.webview_windows() {
+        if let Err(e) = window.eval(&script) {
+            mcp_log_error(
+                "NETWORK_MOCKS",
+                &format!("Failed to apply mocks to '{label}': {e}"),
+            );
+        }
+    }
+}
+
+/// Converts a URL glob into an anchored regular expression.
+///
+/// `*` matches any run of characters (including `/`) and `?` a single character.
+/// Everything else matches literally.
+fn glob_to_regex(glob: &str) -> String {
+    let mut pattern = String::from("^");
+    for c in glob.chars() {
+        match c {
+            '*' => pattern.push_str(".*"),
+            '?' => pattern.push('.'),
+            '\\' | '^' | '$' | '.' | '|' | '+' | '(' | ')' | '[' | ']' | '{' | '}' => {
+                pattern.push('\\');
+                pattern.push(c);
+            }
+            _ => pattern.push(c),
+        }
+    }
+    pattern.push('$');
+    pattern
+}
+
+/// Makes a request's URL pattern into the regular expression `bridge.js` tests.
+///
+/// Regular expressions are checked here, so they must also be valid for Rust's
+/// `regex` crate: lookaround and backreferences are rejected.
+fn url_pattern(url: &str, url_match: &str) -> Result<String, String> {
+    match url_match {
+        "glob" => Ok(glob_to_regex(url)),
+        "regex" => Regex::new(url)
+            .map(|_| url.to_string())
+            .map_err(|e| format!("Invalid URL regex '{url}': {e}")),
+        other => Err(format!(
+            "Unknown url_match '{other}', expected 'glob' or 'regex'"
+        )),
+    }
+}
+
+/// Answers matching `fetch` requests with a canned response.
+///
+/// The rule applies to every window, and stays active across page loads until it is
+/// removed with `unmock_request` or `clear_mocks`. When several rules match a request,
+/// the most recently added one wins. Mocked requests still appear in
+/// `get_network_log`, with a `mock_id` field.
+///
+/// Only `fetch` is mocked, not `XMLHttpRequest`. Rules are restored once a new page has
+/// loaded, so requests a page makes while it is still loading may reach the network.
+///
+/// # Arguments
+///
+/// * `app` - The Tauri application handle
+/// * `url` - URL pattern to match against the full request URL
+/// * `url_match` - `"glob"` (the default), where `*` matches anything and `?` one
+///   character, or `"regex"`, searched anywhere in the URL
+/// * `method` - Only mock requests with this HTTP method (defaults to any method)
+/// * `status` - Response status, 200 to 599 (defaults to 200)
+/// * `headers` - Response headers
+/// * `body` - Response body. Strings are sent as-is; other JSON values are serialized,
+///   with `Content-Type: application/json` unless `headers` sets one.
+/// * `mocks` - Active mock rules
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the rule was added
+///   - `data`: `{ mock_id, pattern }` with the regular expression URLs are tested against
+///   - `error`: Error message (if the pattern or status is invalid)
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const mock = await invoke('plugin:mcp-bridge|mock_request', {
+///   url: 'https://api.example.com/users/*',
+///   method: 'GET',
+///   status: 404,
+///   body: { error: 'Not found' }
+/// });
+/// await invoke('plugin:mcp-bridge|unmock_request', { mockId: mock.data.mock_id });
+/// ```
+#[command]
+#[allow(clippy::too_many_arguments)]
+pub async fn mock_request<R: Runtime>(
+    app: AppHandle<R>,
+    url: String,
+    url_match: Option<String>,
+    method: Option<String>,
+    status: Option<u16>,
+    headers: Option<HashMap<String, String>>,
+    body: Option<Value>,
+    mocks: State<'_, NetworkMocks>,
+) -> Result<Value, String> {
+    let url_match = url_match.unwrap_or_else(|| "glob".to_string());
+    let pattern = match url_pattern(&url, &url_match) {
+        Ok(pattern) => pattern,
+        Err(error) => {
+            return Ok(serde_json::json!({
+                "success": false,
+                "error": error
+            }))
+        }
+    };
+
+    let status = status.unwrap_or(200);
+    if !(200..=599).contains(&status) {
+        return Ok(serde_json::json!({
+            "success": false,
+            "error": format!("Invalid status {status}: must be between 200 and 599")
+        }));
+    }
+
+    let mut headers = headers.unwrap_or_default();
+    let body = match body {
+        None | Some(Value::Null) => String::new(),
+        Some(Value::String(text)) => text,
+        Some(json) => {
+            if !headers
+                .keys()
+                .any(|name| name.eq_ignore_ascii_case("content-type"))
+            {
+                headers.insert("Content-Type".to_string(), "application/json".to_string());
+            }
+            json.to_string()
+        }
+    };
+
+    let id = Uuid::new_v4().to_string();
+    mocks
+        .0
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .push(MockRule {
+            id: id.clone(),
+            url,
+            url_match,
+            pattern: pattern.clone(),
+            method: method.map(|m| m.to_uppercase()),
+            status,
+            headers,
+            body,
+        });
+    apply_to_all(&app, &mocks);
+
+    Ok(serde_json::json!({
+        "success": true,
+        "data": {
+            "mock_id": id,
+            "pattern": pattern
+        }
+    }))
+}
+
+/// Removes a mock rule.
+///
+/// # Arguments
+///
+/// * `app` - The Tauri application handle
+/// * `mock_id` - ID returned by `mock_request`
+/// * `mocks` - Active mock rules
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the rule existed
+///   - `data`: `{ mock_id, url }` (if it existed)
+///   - `error`: Error message (if no rule has that ID)
+#[command]
+pub async fn unmock_request<R: Runtime>(
+    app: AppHandle<R>,
+    mock_id: String,
+    mocks: State<'_, NetworkMocks>,
+) -> Result<Value, String> {
+    let removed = {
+        let mut rules = mocks.0.lock().unwrap_or_else(|e| e.into_inner());
+        rules
+            .iter()
+            .position(|rule| rule.id == mock_id)
+            .map(|index| rules.remove(index))
+    };
+
+    match removed {
+        Some(rule) => {
+            apply_to_all(&app, &mocks);
+            Ok(serde_json::json!({
+                "success": true,
+                "data": {
+                    "mock_id": mock_id,
+                    "url": rule.url
+                }
+            }))
+        }
+        None => Ok(serde_json::json!({
+            "success": false,
+            "error": format!("No mock rule '{mock_id}'")
+        })),
+    }
+}
+
+/// Removes every mock rule.
+///
+/// # Arguments
+///
+/// * `app` - The Tauri application handle
+/// * `mocks` - Active mock rules
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Always `true`
+///   - `data`: `{ cleared }` with the number of rules removed
+#[command]
+pub async fn clear_mocks<R: Runtime>(
+    app: AppHandle<R>,
+    mocks: State<'_, NetworkMocks>,
+) -> Result<Value, String> {
+    let cleared = {
+        let mut rules = mocks.0.lock().unwrap_or_else(|e| e.into_inner());
+        let cleared = rules.len();
+        rules.clear();
+        cleared
+    };
+    apply_to_all(&app, &mocks);
+
+    Ok(serde_json::json!({
+        "success": true,
+        "data": { "cleared": cleared }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_matches_whole_url() {
+        let regex = Regex::new(&glob_to_regex("https://api.example.com/users/*")).unwrap();
+        assert!(regex.is_match("https://api.example.com/users/42"));
+        assert!(regex.is_match("https://api.example.com/users/42/posts?page=2"));
+        assert!(!regex.is_match("https://api.example.com/teams/1"));
+        assert!(!regex.is_match("http://proxy/?u=https://api.example.com/users/42"));
+    }
+
+    #[test]
+    fn test_glob_escapes_regex_characters() {
+        let regex = Regex::new(&glob_to_regex("*/items?.json")).unwrap();
+        assert!(regex.is_match("https://x.dev/items1.json"));
+        assert!(!regex.is_match("https://x.dev/items1xjson"));
+        assert!(Regex::new(&glob_to_regex("*/search(+)[a]{1}|$^")).is_ok());
+    }
+
+    #[test]
+    fn test_url_pattern_rejects_bad_input() {
+        assert_eq!(url_pattern("/api/", "regex").unwrap(), "/api/");
+        assert!(url_pattern("/api/(", "regex").is_err());
+        assert!(url_pattern("/api/*", "prefix").is_err());
+    }
+}
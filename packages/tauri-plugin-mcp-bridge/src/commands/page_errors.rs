@@ -0,0 +1,73 @@
+//! Uncaught errors and unhandled promise rejections captured by the bridge script.
+//!
+//! `bridge.js` listens for `error` and `unhandledrejection` on the window as soon as a
+//! page loads and keeps the most recent entries in a bounded buffer on the page.
+
+use super::execute_js::run_script;
+use super::list_windows::target_window;
+use super::script_executor::ScriptExecutor;
+use serde_json::Value;
+use tauri::{command, Runtime, State, WebviewWindow};
+
+/// Reads buffered page errors after the `since` cursor.
+///
+/// Expects `since` to be defined before it.
+const GET_PAGE_ERRORS_SCRIPT: &str = r#"
+const errors = window.__MCP_PAGE_ERRORS__ || [];
+({
+    errors: errors.filter(function(entry) { return entry.seq > since; }),
+    cursor: errors.length > 0 ? Math.max(since, errors[errors.length - 1].seq) : since
+})
+"#;
+
+/// Returns uncaught errors and unhandled promise rejections that occurred in the webview.
+///
+/// Errors are numbered in the order they happened. To check that a test step raised
+/// no errors, read the `cursor` before the step and pass it as `since` afterwards. The
+/// buffer holds the most recent 200 errors, and is reset when the page reloads.
+///
+/// # Arguments
+///
+/// * `window` - The window to read errors from
+/// * `since` - Only return errors numbered after this cursor (defaults to 0, all errors)
+/// * `window_label` - Label of the window to target (defaults to the calling window)
+/// * `executor` - Script executor state
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the errors were read
+///   - `data`: `{ errors, cursor }` where each error is
+///     `{ seq, type, message, stack, source, line, column, timestamp }` and `type` is
+///     `"error"` or `"unhandledrejection"`
+///   - `error`: Error message (if failed)
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const before = await invoke('plugin:mcp-bridge|get_page_errors');
+/// // ... run the test step ...
+/// const after = await invoke('plugin:mcp-bridge|get_page_errors', {
+///   since: before.data.cursor
+/// });
+/// console.assert(after.data.errors.length === 0);
+/// ```
+#[command]
+pub async fn get_page_errors<R: Runtime>(
+    window: WebviewWindow<R>,
+    since: Option<u64>,
+    window_label: Option<String>,
+    executor: State<'_, ScriptExecutor>,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    let since = since.unwrap_or(0);
+    let script = format!("const since = {since};\n{GET_PAGE_ERRORS_SCRIPT}");
+
+    run_script(window, script, None, None, None, None, None, executor).await
+}
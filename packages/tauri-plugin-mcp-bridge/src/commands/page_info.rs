@@ -0,0 +1,105 @@
+//! Summarizing the loaded page in one call.
+
+use super::execute_js::run_script;
+use super::list_windows::target_window;
+use super::script_executor::ScriptExecutor;
+use serde_json::Value;
+use tauri::{command, Runtime, State, WebviewWindow};
+
+/// Evaluates to the page's metadata, with URLs resolved against the document's base URL.
+const PAGE_INFO_SCRIPT: &str = r#"
+(function() {
+    const absolute = function(href) {
+        if (!href) return null;
+        try {
+            return new URL(href, document.baseURI).href;
+        } catch (e) {
+            return href;
+        }
+    };
+    const description = document.querySelector('meta[name="description" i]');
+    const favicon = document.querySelector('link[rel~="icon" i][href]');
+
+    const openGraph = {};
+    for (const meta of document.querySelectorAll('meta[property^="og:" i][content]')) {
+        const key = meta.getAttribute('property').slice(3).toLowerCase();
+        if (key in openGraph) continue;
+        const value = meta.getAttribute('content');
+        const isUrl = ['url', 'image', 'video', 'audio'].includes(key)
+            || key.endsWith(':url') || key.endsWith(':secure_url');
+        openGraph[key] = isUrl ? absolute(value) : value;
+    }
+
+    return {
+        title: document.title,
+        url: location.href,
+        charset: document.characterSet,
+        language: document.documentElement ? document.documentElement.lang || null : null,
+        description: description ? description.getAttribute('content') : null,
+        open_graph: openGraph,
+        favicon: favicon ? absolute(favicon.getAttribute('href')) : null,
+        ready_state: document.readyState
+    };
+})()
+"#;
+
+/// Returns a summary of the page: its title, URL, metadata and load state.
+///
+/// Saves the several `execute_js` calls it would otherwise take to find out what page
+/// is loaded. Relative favicon and Open Graph URLs are resolved to absolute ones.
+///
+/// # Arguments
+///
+/// * `window` - The window to describe the page of
+/// * `window_label` - Label of the window to target (defaults to the calling window)
+/// * `executor` - Script executor state
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the page was read
+///   - `data`: Object with:
+///     - `title`: `document.title`
+///     - `url`: The page's URL
+///     - `charset`: The document's character encoding, like `"UTF-8"`
+///     - `language`: The `lang` attribute of `<html>`, or null
+///     - `description`: The `description` meta tag's content, or null
+///     - `open_graph`: The `og:` meta tags, keyed by property without the `og:`
+///       prefix (`title`, `image`, `image:width`, ...). The first tag wins when a
+///       property repeats.
+///     - `favicon`: URL of the first icon link, or null if the page declares none (the
+///       webview may still load `/favicon.ico`)
+///     - `ready_state`: `document.readyState`
+///   - `error`: Error message (if failed)
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const result = await invoke('plugin:mcp-bridge|get_page_info');
+/// console.log(result.data.title, result.data.open_graph.image);
+/// ```
+#[command]
+pub async fn get_page_info<R: Runtime>(
+    window: WebviewWindow<R>,
+    window_label: Option<String>,
+    executor: State<'_, ScriptExecutor>,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    run_script(
+        window,
+        PAGE_INFO_SCRIPT.to_string(),
+        None,
+        None,
+        None,
+        None,
+        None,
+        executor,
+    )
+    .await
+}
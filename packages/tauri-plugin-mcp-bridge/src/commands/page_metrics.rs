@@ -0,0 +1,75 @@
+//! Viewport and layout measurements of the page.
+
+use super::execute_js::run_script;
+use super::list_windows::target_window;
+use super::script_executor::ScriptExecutor;
+use serde_json::Value;
+use tauri::{command, Runtime, State, WebviewWindow};
+
+/// Reads viewport, document and scroll measurements in a single pass.
+const PAGE_METRICS_SCRIPT: &str = r#"({
+    devicePixelRatio: window.devicePixelRatio,
+    innerWidth: window.innerWidth,
+    innerHeight: window.innerHeight,
+    outerWidth: window.outerWidth,
+    outerHeight: window.outerHeight,
+    scrollWidth: document.documentElement.scrollWidth,
+    scrollHeight: document.documentElement.scrollHeight,
+    scrollX: window.scrollX,
+    scrollY: window.scrollY,
+    readyState: document.readyState
+})"#;
+
+/// Returns viewport and layout measurements of the page in one snapshot.
+///
+/// All values are read together, so they're consistent with each other, which makes
+/// them suitable for layout assertions. Sizes are in CSS pixels; multiply by
+/// `devicePixelRatio` for physical pixels.
+///
+/// # Arguments
+///
+/// * `window` - The window to measure
+/// * `window_label` - Label of the window to target (defaults to the calling window)
+/// * `executor` - Script executor state
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the metrics were read
+///   - `data`: `{ devicePixelRatio, innerWidth, innerHeight, outerWidth, outerHeight,
+///     scrollWidth, scrollHeight, scrollX, scrollY, readyState }`, where
+///     `scrollWidth`/`scrollHeight` are the size of the document element and
+///     `readyState` is `document.readyState`
+///   - `error`: Error message (if failed)
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const { data } = await invoke('plugin:mcp-bridge|get_page_metrics');
+/// const canScrollDown = data.scrollY + data.innerHeight < data.scrollHeight;
+/// ```
+#[command]
+pub async fn get_page_metrics<R: Runtime>(
+    window: WebviewWindow<R>,
+    window_label: Option<String>,
+    executor: State<'_, ScriptExecutor>,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    run_script(
+        window,
+        PAGE_METRICS_SCRIPT.to_string(),
+        None,
+        None,
+        None,
+        None,
+        None,
+        executor,
+    )
+    .await
+}
@@ -0,0 +1,322 @@
+//! The page's current HTML source.
+
+use super::execute_js::run_script;
+use super::list_windows::target_window;
+use super::script_executor::ScriptExecutor;
+use serde_json::Value;
+use tauri::{command, Runtime, State, WebviewWindow};
+
+/// Serializes the whole document, doctype included.
+///
+/// Written as several statements so it takes the event path, where large results are
+/// sent in chunks.
+const GET_HTML_SCRIPT: &str = r#"
+const doctype = document.doctype ? new XMLSerializer().serializeToString(document.doctype) + '\n' : '';
+const html = doctype + document.documentElement.outerHTML;
+html
+"#;
+
+/// Elements that never have a closing tag.
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+/// Elements whose content is kept exactly as it is, since whitespace matters there or
+/// it isn't HTML.
+const RAW_ELEMENTS: &[&str] = &["pre", "script", "style", "textarea"];
+
+/// A piece of HTML source, as split up by [`tokenize`].
+#[derive(Debug, PartialEq)]
+enum Token<'a> {
+    /// An opening tag, with its lowercase name
+    Open(&'a str, String),
+    /// A closing tag, with its lowercase name
+    Close(&'a str, String),
+    /// A void or self-closing tag, a comment or a doctype
+    Standalone(&'a str),
+    /// An element whose content is kept as-is, from its opening through its closing tag
+    Raw(&'a str),
+    /// Text between tags, with whitespace collapsed
+    Text(String),
+}
+
+/// Returns the index just past the `>` ending the tag that starts at `start`, skipping
+/// over quoted attribute values.
+fn tag_end(html: &str, start: usize) -> usize {
+    let mut quote = None;
+    for (offset, c) in html[start..].char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '>') => return start + offset + 1,
+            _ => {}
+        }
+    }
+    html.len()
+}
+
+/// Returns the lowercase name of a tag like `<div class="a">` or `</div>`.
+fn tag_name(tag: &str) -> String {
+    tag.trim_start_matches('<')
+        .trim_start_matches('/')
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == ':')
+        .collect::<String>()
+        .to_ascii_lowercase()
+}
+
+/// Whether `rest` starts with a tag, comment or doctype rather than a stray `<`.
+fn starts_tag(rest: &str) -> bool {
+    let mut chars = rest.chars();
+    chars.next() == Some('<')
+        && chars
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '/' || c == '!')
+}
+
+/// Splits HTML source into tags and text.
+fn tokenize(html: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+
+    while pos < html.len() {
+        let rest = &html[pos..];
+
+        if !starts_tag(rest) {
+            // Text runs until the next tag; a `<` that doesn't start one is text too
+            let next = rest
+                .char_indices()
+                .skip(1)
+                .find(|&(offset, _)| starts_tag(&rest[offset..]))
+                .map_or(html.len(), |(offset, _)| pos + offset);
+            let text = html[pos..next]
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ");
+            if !text.is_empty() {
+                tokens.push(Token::Text(text));
+            }
+            pos = next;
+            continue;
+        }
+
+        if rest.starts_with("<!--") {
+            let end = rest
+                .find("-->")
+                .map_or(html.len(), |offset| pos + offset + 3);
+            tokens.push(Token::Standalone(&html[pos..end]));
+            pos = end;
+            continue;
+        }
+
+        let end = tag_end(html, pos);
+        let tag = &html[pos..end];
+        let name = tag_name(tag);
+
+        if tag.starts_with("</") {
+            tokens.push(Token::Close(tag, name));
+        } else if tag.starts_with("<!")
+            || tag.ends_with("/>")
+            || VOID_ELEMENTS.contains(&name.as_str())
+        {
+            tokens.push(Token::Standalone(tag));
+        } else if RAW_ELEMENTS.contains(&name.as_str()) {
+            let closing = format!("</{name}");
+            let close_start = html[end..]
+                .to_ascii_lowercase()
+                .find(&closing)
+                .map_or(html.len(), |offset| end + offset);
+            let close_end = if close_start < html.len() {
+                tag_end(html, close_start)
+            } else {
+                html.len()
+            };
+            tokens.push(Token::Raw(&html[pos..close_end]));
+            pos = close_end;
+            continue;
+        } else {
+            tokens.push(Token::Open(tag, name));
+        }
+        pos = end;
+    }
+
+    tokens
+}
+
+/// Formats HTML with one tag per line, indented by nesting depth.
+///
+/// Whitespace in text is collapsed, and an element holding only text stays on one
+/// line. The content of `<pre>`, `<script>`, `<style>` and `<textarea>` is left exactly
+/// as it was. Formatting the same document always gives the same output, which makes
+/// it suitable for diffing.
+fn pretty_html(html: &str) -> String {
+    let tokens = tokenize(html);
+    let mut lines = Vec::new();
+    let mut depth: usize = 0;
+    let mut i = 0;
+
+    while i < tokens.len() {
+        let indent = "  ".repeat(depth);
+        match &tokens[i] {
+            Token::Open(open, name) => match (tokens.get(i + 1), tokens.get(i + 2)) {
+                (Some(Token::Close(close, close_name)), _) if close_name == name => {
+                    lines.push(format!("{indent}{open}{close}"));
+                    i += 1;
+                }
+                (Some(Token::Text(text)), Some(Token::Close(close, close_name)))
+                    if close_name == name =>
+                {
+                    lines.push(format!("{indent}{open}{text}{close}"));
+                    i += 2;
+                }
+                _ => {
+                    lines.push(format!("{indent}{open}"));
+                    depth += 1;
+                }
+            },
+            Token::Close(close, _) => {
+                depth = depth.saturating_sub(1);
+                lines.push(format!("{}{close}", "  ".repeat(depth)));
+            }
+            Token::Standalone(tag) | Token::Raw(tag) => lines.push(format!("{indent}{tag}")),
+            Token::Text(text) => lines.push(format!("{indent}{text}")),
+        }
+        i += 1;
+    }
+
+    lines.join("\n")
+}
+
+/// Returns the current HTML of the page.
+///
+/// Unlike `get_dom_snapshot`, this is the literal markup of the live document,
+/// including the doctype and any changes scripts made since it loaded, which makes it
+/// suitable for saving or diffing. Large pages are transferred in chunks.
+///
+/// With `pretty`, the HTML is reformatted by the plugin with one tag per line and
+/// two-space indentation, and whitespace in text collapsed, so the output is stable
+/// across runs. The content of `<pre>`, `<script>`, `<style>` and `<textarea>` is kept
+/// as-is.
+///
+/// # Arguments
+///
+/// * `window` - The window to read the page of
+/// * `pretty` - Reformat the HTML with consistent indentation (defaults to false)
+/// * `window_label` - Label of the window to target (defaults to the calling window)
+/// * `executor` - Script executor state
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the HTML was read
+///   - `data`: `{ html }`
+///   - `error`: Error message (if failed)
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const page = await invoke('plugin:mcp-bridge|get_html', { pretty: true });
+/// await writeTextFile('snapshot.html', page.data.html);
+/// ```
+#[command]
+pub async fn get_html<R: Runtime>(
+    window: WebviewWindow<R>,
+    pretty: Option<bool>,
+    window_label: Option<String>,
+    executor: State<'_, ScriptExecutor>,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    let result = run_script(
+        window,
+        GET_HTML_SCRIPT.to_string(),
+        None,
+        None,
+        None,
+        None,
+        None,
+        executor,
+    )
+    .await?;
+
+    let Some(html) = result.get("data").and_then(|v| v.as_str()) else {
+        return Ok(result);
+    };
+    let html = if pretty.unwrap_or(false) {
+        pretty_html(html)
+    } else {
+        html.to_string()
+    };
+
+    Ok(serde_json::json!({
+        "success": true,
+        "data": { "html": html }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pretty_html_indents_by_depth() {
+        let html =
+            "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>Demo</title></head>\
+                    <body><ul><li>One</li><li><b>Two</b> and  more</li></ul><br/></body></html>";
+        assert_eq!(
+            pretty_html(html),
+            [
+                "<!DOCTYPE html>",
+                "<html>",
+                "  <head>",
+                "    <meta charset=\"utf-8\">",
+                "    <title>Demo</title>",
+                "  </head>",
+                "  <body>",
+                "    <ul>",
+                "      <li>One</li>",
+                "      <li>",
+                "        <b>Two</b>",
+                "        and more",
+                "      </li>",
+                "    </ul>",
+                "    <br/>",
+                "  </body>",
+                "</html>",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_pretty_html_keeps_raw_content() {
+        let html = "<div><pre>  a\n    b</pre><script>if (a < b) { x(); }</script></div>";
+        assert_eq!(
+            pretty_html(html),
+            "<div>\n  <pre>  a\n    b</pre>\n  <script>if (a < b) { x(); }</script>\n</div>"
+        );
+    }
+
+    #[test]
+    fn test_pretty_html_handles_quoted_brackets_and_stray_angles() {
+        let html = "<p title=\"a > b\">1 < 2</p><!-- <p> --><div></div>";
+        assert_eq!(
+            pretty_html(html),
+            "<p title=\"a > b\">1 < 2</p>\n<!-- <p> -->\n<div></div>"
+        );
+    }
+
+    #[test]
+    fn test_pretty_html_is_stable() {
+        let html = "<main><section><h1>Title</h1><p>Body <i>text</i></p></section></main>";
+        let once = pretty_html(html);
+        assert_eq!(pretty_html(&once), once);
+    }
+}
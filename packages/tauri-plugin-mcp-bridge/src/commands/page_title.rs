@@ -0,0 +1,143 @@
+//! Reading and setting the document title.
+
+use super::execute_js::run_script;
+use super::list_windows::target_window;
+use super::script_executor::ScriptExecutor;
+use serde_json::Value;
+use tauri::{command, Runtime, State, WebviewWindow};
+
+/// Sets the document title to `title`, which is expected to be defined before it, and
+/// evaluates to the resulting one.
+const SET_TITLE_SCRIPT: &str = r#"
+document.title = title;
+({ title: document.title })
+"#;
+
+/// Returns the document title, along with the title of the window showing it.
+///
+/// The two are independent in Tauri unless the app keeps them in sync itself.
+///
+/// # Arguments
+///
+/// * `window` - The window to read the title of
+/// * `window_label` - Label of the window to target (defaults to the calling window)
+/// * `executor` - Script executor state
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the title was read
+///   - `data`: `{ title, window_title }`, where `title` is `document.title` and
+///     `window_title` is the native window's title (null if it can't be read)
+///   - `error`: Error message (if failed)
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const result = await invoke('plugin:mcp-bridge|get_title');
+/// console.log(result.data.title);
+/// ```
+#[command]
+pub async fn get_title<R: Runtime>(
+    window: WebviewWindow<R>,
+    window_label: Option<String>,
+    executor: State<'_, ScriptExecutor>,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    let mut result = run_script(
+        window.clone(),
+        "({ title: document.title })".to_string(),
+        None,
+        None,
+        None,
+        None,
+        None,
+        executor,
+    )
+    .await?;
+
+    if result.get("success").and_then(|v| v.as_bool()) == Some(true) {
+        result["data"]["window_title"] = window.title().ok().into();
+    }
+    Ok(result)
+}
+
+/// Sets the document title, and optionally the window title too.
+///
+/// Some apps show the document title in the window's title bar, but Tauri doesn't do
+/// that by itself, so `sync_window` sets the native window title as well. The
+/// resulting title is read back, since browsers normalize whitespace in it.
+///
+/// # Arguments
+///
+/// * `window` - The window whose document title to set
+/// * `title` - The new title
+/// * `sync_window` - Also set the native window title (defaults to false)
+/// * `window_label` - Label of the window to target (defaults to the calling window)
+/// * `executor` - Script executor state
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the title was set
+///   - `data`: `{ title, window_title }` after setting it, like [`get_title`]
+///   - `error`: Error message (if failed)
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// await invoke('plugin:mcp-bridge|set_title', {
+///   title: 'Untitled - Editor',
+///   syncWindow: true
+/// });
+/// ```
+#[command]
+pub async fn set_title<R: Runtime>(
+    window: WebviewWindow<R>,
+    title: String,
+    sync_window: Option<bool>,
+    window_label: Option<String>,
+    executor: State<'_, ScriptExecutor>,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    let title_js = serde_json::to_string(&title).map_err(|e| e.to_string())?;
+    let script = format!("const title = {title_js};\n{SET_TITLE_SCRIPT}");
+
+    let mut result = run_script(
+        window.clone(),
+        script,
+        None,
+        None,
+        None,
+        None,
+        None,
+        executor,
+    )
+    .await?;
+
+    if result.get("success").and_then(|v| v.as_bool()) != Some(true) {
+        return Ok(result);
+    }
+    if sync_window.unwrap_or(false) {
+        if let Err(e) = window.set_title(&title) {
+            return Ok(serde_json::json!({
+                "success": false,
+                "error": format!("Set the document title, but failed to set the window title: {e}")
+            }));
+        }
+    }
+    result["data"]["window_title"] = window.title().ok().into();
+    Ok(result)
+}
@@ -0,0 +1,92 @@
+//! Resolving client-supplied paths inside a configured directory.
+//!
+//! Commands that read files, like `execute_js_file` and `set_file_input`, only accept
+//! paths relative to a directory the app configured. Both paths are canonicalized, so
+//! `..` components and symlinks that lead out of the directory are rejected, and the
+//! commands can't be used to read arbitrary files.
+
+use std::path::{Path, PathBuf};
+
+/// Resolves `path` against `base` and checks that the result is a file inside `base`.
+///
+/// # Arguments
+///
+/// * `base` - The configured directory
+/// * `path` - The path the client gave, relative to `base`
+/// * `what` - What the directory holds, used in error messages (`"script"` gives
+///   "the script directory")
+///
+/// # Returns
+///
+/// * `Ok(PathBuf)` - The canonical path of the file
+/// * `Err(String)` - Why the path can't be used
+pub(crate) fn resolve_within(base: &Path, path: &str, what: &str) -> Result<PathBuf, String> {
+    let relative = Path::new(path);
+    if relative.is_absolute() {
+        return Err(format!(
+            "Path '{path}' must be relative to the {what} directory"
+        ));
+    }
+
+    let base = base.canonicalize().map_err(|e| {
+        format!(
+            "The {what} directory '{}' is not accessible: {e}",
+            base.display()
+        )
+    })?;
+    let resolved = base
+        .join(relative)
+        .canonicalize()
+        .map_err(|e| format!("Failed to read '{path}': {e}"))?;
+
+    if !resolved.starts_with(&base) {
+        return Err(format!("Path '{path}' is outside the {what} directory"));
+    }
+    if !resolved.is_file() {
+        return Err(format!("Path '{path}' is not a file"));
+    }
+
+    Ok(resolved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_path_must_stay_in_base() {
+        let root = std::env::temp_dir().join(format!("mcp-paths-{}", uuid::Uuid::new_v4()));
+        let base = root.join("base");
+        fs::create_dir_all(base.join("nested")).unwrap();
+        fs::write(base.join("nested/inside.js"), "1").unwrap();
+        fs::write(root.join("secret.txt"), "secret").unwrap();
+
+        let resolved = resolve_within(&base, "nested/inside.js", "script").unwrap();
+        assert!(resolved.ends_with("nested/inside.js"));
+        assert!(resolve_within(&base, "nested/../nested/inside.js", "script").is_ok());
+
+        let error = resolve_within(&base, "../secret.txt", "script").unwrap_err();
+        assert!(error.contains("outside the script directory"));
+        let absolute = root.join("secret.txt");
+        let error = resolve_within(&base, absolute.to_str().unwrap(), "upload").unwrap_err();
+        assert!(error.contains("must be relative to the upload directory"));
+        assert!(resolve_within(&base, "missing.js", "script").is_err());
+        assert!(resolve_within(&base, "nested", "script").is_err());
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::symlink;
+
+            symlink(root.join("secret.txt"), base.join("escape.txt")).unwrap();
+            let error = resolve_within(&base, "escape.txt", "script").unwrap_err();
+            assert!(error.contains("outside the script directory"));
+
+            symlink(base.join("nested/inside.js"), base.join("alias.js")).unwrap();
+            let resolved = resolve_within(&base, "alias.js", "script").unwrap();
+            assert!(resolved.ends_with("nested/inside.js"));
+        }
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
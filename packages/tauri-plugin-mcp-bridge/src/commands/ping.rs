@@ -0,0 +1,57 @@
+//! Liveness and version handshake.
+
+use serde_json::Value;
+use std::time::Instant;
+use tauri::{command, State};
+
+/// Version of the WebSocket request/response protocol the bridge speaks.
+///
+/// Bumped when a change to the request or response envelope would break existing
+/// clients. New commands and new response fields don't bump it.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// When the plugin was initialized.
+///
+/// Managed as app state during plugin setup.
+pub struct StartTime(pub Instant);
+
+/// Confirms the bridge is alive and reports the versions it speaks.
+///
+/// Never touches a webview, so it answers even while a page is loading or
+/// navigating. Clients can poll it to wait for the bridge to come up, and compare
+/// the versions before sending other commands.
+///
+/// # Arguments
+///
+/// * `start_time` - When the plugin was initialized
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `pong`: Always `true`
+///   - `plugin_version`: Version of this plugin
+///   - `protocol_version`: Version of the bridge protocol, see [`PROTOCOL_VERSION`]
+///   - `tauri_version`: Version of the Tauri runtime
+///   - `uptime_ms`: Milliseconds since the plugin was initialized, from a monotonic
+///     clock so it never goes backwards
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const { protocol_version } = await invoke('plugin:mcp-bridge|ping');
+/// if (protocol_version !== 1) {
+///   throw new Error('Unsupported bridge protocol');
+/// }
+/// ```
+#[command]
+pub async fn ping(start_time: State<'_, StartTime>) -> Result<Value, String> {
+    Ok(serde_json::json!({
+        "pong": true,
+        "plugin_version": env!("CARGO_PKG_VERSION"),
+        "protocol_version": PROTOCOL_VERSION,
+        "tauri_version": tauri::VERSION,
+        "uptime_ms": start_time.0.elapsed().as_millis() as u64
+    }))
+}
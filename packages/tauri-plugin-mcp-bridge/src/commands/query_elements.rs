@@ -0,0 +1,330 @@
+//! Structured element queries.
+
+use super::execute_js::{in_frame, run_script};
+use super::handles::ElementTarget;
+use super::list_windows::target_window;
+use super::script_executor::ScriptExecutor;
+use serde_json::Value;
+use tauri::{command, Runtime, State, WebviewWindow};
+
+/// Default maximum number of elements returned by `query_elements`.
+pub const DEFAULT_QUERY_LIMIT: usize = 100;
+
+/// Describes up to `limit` elements matching `selector`.
+///
+/// Expects `selector` and `limit` to be defined before it.
+const QUERY_ELEMENTS_SCRIPT: &str = r#"
+const matches = document.querySelectorAll(selector);
+const elements = Array.prototype.slice.call(matches, 0, limit).map(function(el) {
+    const style = window.getComputedStyle(el);
+    const rect = el.getBoundingClientRect();
+    return {
+        tag: el.tagName.toLowerCase(),
+        id: el.id || null,
+        classes: Array.prototype.slice.call(el.classList),
+        text: (el.innerText !== undefined ? el.innerText : el.textContent || '').trim(),
+        visible: style.display !== 'none' && style.visibility !== 'hidden'
+            && rect.width > 0 && rect.height > 0,
+        boundingRect: { x: rect.x, y: rect.y, width: rect.width, height: rect.height }
+    };
+});
+({ elements: elements, total: matches.length, truncated: matches.length > elements.length })
+"#;
+
+/// Describes the state of the first element matching `selector`.
+///
+/// Expects `selector` to be defined before it. An element is visible when neither it
+/// nor an ancestor has `display: none` or `opacity: 0`, it isn't `visibility: hidden`,
+/// and it has a non-zero size.
+const ELEMENT_STATE_SCRIPT: &str = r#"
+function isVisible(el) {
+    const style = window.getComputedStyle(el);
+    if (style.visibility === 'hidden' || style.visibility === 'collapse') return false;
+    const rect = el.getBoundingClientRect();
+    if (rect.width === 0 || rect.height === 0) return false;
+    for (let node = el; node; node = node.parentElement) {
+        const nodeStyle = window.getComputedStyle(node);
+        if (nodeStyle.display === 'none' || parseFloat(nodeStyle.opacity) === 0) return false;
+    }
+    return true;
+}
+const matches = document.querySelectorAll(selector);
+const el = matches[0];
+const rect = el ? el.getBoundingClientRect() : null;
+({
+    exists: !!el,
+    visible: !!el && isVisible(el),
+    enabled: !!el && !el.matches(':disabled') && el.getAttribute('aria-disabled') !== 'true',
+    focused: !!el && document.activeElement === el,
+    in_viewport: !!rect && rect.bottom > 0 && rect.right > 0
+        && rect.top < window.innerHeight && rect.left < window.innerWidth,
+    count: matches.length
+})
+"#;
+
+/// Returns the elements matching a CSS selector with their key properties.
+///
+/// # Arguments
+///
+/// * `window` - The window to query
+/// * `selector` - CSS selector to match
+/// * `by` - How `selector` locates elements: `"css"` (the default), `"xpath"`, `"text"`,
+///   `"id"` or `"name"`
+/// * `limit` - Maximum number of elements to return (defaults to 100)
+/// * `frame_selector` - CSS selector of a same-origin `<iframe>` to query inside
+///   (defaults to the top-level page)
+/// * `window_label` - Label of the window to target (defaults to the calling window)
+/// * `executor` - Script executor state
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the query ran
+///   - `data`: `{ elements, total, truncated }` where each element is
+///     `{ tag, id, classes, text, visible, boundingRect }`, `boundingRect` is in
+///     viewport coordinates, and `total` counts all matches even past `limit`
+///   - `error`: Error message (if the selector is invalid)
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const result = await invoke('plugin:mcp-bridge|query_elements', {
+///   selector: 'button',
+///   limit: 10
+/// });
+/// const labels = result.data.elements.map(el => el.text);
+/// ```
+#[command]
+pub async fn query_elements<R: Runtime>(
+    window: WebviewWindow<R>,
+    selector: String,
+    by: Option<String>,
+    limit: Option<usize>,
+    frame_selector: Option<String>,
+    window_label: Option<String>,
+    executor: State<'_, ScriptExecutor>,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    let target = match ElementTarget::new(Some(selector), None, by) {
+        Ok(target) => target,
+        Err(error) => {
+            return Ok(serde_json::json!({
+                "success": false,
+                "error": error
+            }))
+        }
+    };
+
+    let selector_js = serde_json::to_string(&target.selector()).map_err(|e| e.to_string())?;
+    let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT);
+    let script = target.wrap(format!(
+        "const selector = {selector_js};\nconst limit = {limit};\n{QUERY_ELEMENTS_SCRIPT}"
+    ));
+    let script = match frame_selector {
+        Some(frame_selector) => in_frame(&frame_selector, &script),
+        None => script,
+    };
+
+    run_script(window, script, None, None, None, None, None, executor).await
+}
+
+/// Reports whether an element exists and what state it is in, in one call.
+///
+/// Only the first match is described, while `count` counts all of them. When nothing
+/// matches, every flag is false and `count` is 0, which isn't an error.
+///
+/// `visible` is false when the element or an ancestor has `display: none` or
+/// `opacity: 0`, when it has `visibility: hidden`, or when it has no size. `enabled` is
+/// false for disabled form controls (including those in a disabled `<fieldset>`) and
+/// elements with `aria-disabled="true"`. `in_viewport` is true when any part of the
+/// element is inside the viewport.
+///
+/// # Arguments
+///
+/// * `window` - The window to check in
+/// * `selector` - CSS selector of the element
+/// * `handle` - Handle of the element from an earlier `execute_js` result, in place of
+///   `selector`
+/// * `by` - How `selector` locates the element: `"css"` (the default), `"xpath"`,
+///   `"text"`, `"id"` or `"name"`
+/// * `frame_selector` - CSS selector of a same-origin `<iframe>` to look inside
+///   (defaults to the top-level page). Can't be combined with `handle`.
+/// * `window_label` - Label of the window to target (defaults to the calling window)
+/// * `executor` - Script executor state
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the check ran
+///   - `data`: `{ exists, visible, enabled, focused, in_viewport, count }`
+///   - `error`: Error message (if the selector is invalid)
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const result = await invoke('plugin:mcp-bridge|element_state', {
+///   selector: '#submit'
+/// });
+/// if (result.data.visible && result.data.enabled) {
+///   // Ready to click
+/// }
+/// ```
+#[command]
+pub async fn element_state<R: Runtime>(
+    window: WebviewWindow<R>,
+    selector: Option<String>,
+    handle: Option<String>,
+    by: Option<String>,
+    frame_selector: Option<String>,
+    window_label: Option<String>,
+    executor: State<'_, ScriptExecutor>,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    let target = match ElementTarget::new(selector, handle, by) {
+        Ok(target) => target,
+        Err(error) => {
+            return Ok(serde_json::json!({
+                "success": false,
+                "error": error
+            }))
+        }
+    };
+
+    let selector_js = serde_json::to_string(&target.selector()).map_err(|e| e.to_string())?;
+    let script = target.wrap(format!(
+        "const selector = {selector_js};\n{ELEMENT_STATE_SCRIPT}"
+    ));
+    let script = match frame_selector {
+        Some(_) if matches!(target, ElementTarget::Handle(_)) => {
+            return Ok(serde_json::json!({
+                "success": false,
+                "error": "handle and frame_selector can't be combined"
+            }));
+        }
+        Some(frame_selector) => in_frame(&frame_selector, &script),
+        None => script,
+    };
+
+    run_script(window, script, None, None, None, None, None, executor).await
+}
+
+/// Measures up to `limit` elements for each of `selectors`, all at the same moment.
+///
+/// Expects `selectors` and `limit` to be defined before it.
+const BOUNDING_RECTS_SCRIPT: &str = r#"
+const viewportWidth = window.innerWidth;
+const viewportHeight = window.innerHeight;
+function measure(el) {
+    const rect = el.getBoundingClientRect();
+    return {
+        x: rect.x,
+        y: rect.y,
+        width: rect.width,
+        height: rect.height,
+        in_viewport: rect.bottom > 0 && rect.right > 0
+            && rect.top < viewportHeight && rect.left < viewportWidth,
+        fully_in_viewport: rect.width > 0 && rect.height > 0 && rect.top >= 0 && rect.left >= 0
+            && rect.bottom <= viewportHeight && rect.right <= viewportWidth
+    };
+}
+const results = selectors.map(function(selector) {
+    let matches;
+    try {
+        matches = document.querySelectorAll(selector);
+    } catch (error) {
+        return { selector: selector, error: error.message };
+    }
+    const rects = Array.prototype.slice.call(matches, 0, limit).map(measure);
+    return { selector: selector, rects: rects, total: matches.length, truncated: matches.length > rects.length };
+});
+({
+    results: results,
+    viewport: { width: viewportWidth, height: viewportHeight, scrollX: window.scrollX, scrollY: window.scrollY }
+})
+"#;
+
+/// Returns the bounding rectangles of the elements matching several CSS selectors.
+///
+/// Everything is measured in one script run, so the rectangles form a consistent
+/// snapshot of the layout, taken without the round trips of one call per element. A
+/// selector can match many elements, each measured in document order. Rectangles come
+/// from `getBoundingClientRect`, in viewport coordinates. `in_viewport` is true when
+/// any part of the element is inside the viewport, and `fully_in_viewport` when all of
+/// it is. An invalid selector only fails its own entry.
+///
+/// # Arguments
+///
+/// * `window` - The window to measure in
+/// * `selectors` - CSS selectors of the elements
+/// * `limit` - Maximum number of elements to measure per selector (defaults to 100)
+/// * `frame_selector` - CSS selector of a same-origin `<iframe>` to measure inside
+///   (defaults to the top-level page)
+/// * `window_label` - Label of the window to target (defaults to the calling window)
+/// * `executor` - Script executor state
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the elements were measured
+///   - `data`: `{ results, viewport }` with one result per selector, in order, as
+///     `{ selector, rects, total, truncated }` or `{ selector, error }`. Each rect is
+///     `{ x, y, width, height, in_viewport, fully_in_viewport }`, `total` counts all
+///     matches even past `limit`, and `viewport` is
+///     `{ width, height, scrollX, scrollY }`.
+///   - `error`: Error message (if no selectors were given)
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const result = await invoke('plugin:mcp-bridge|get_bounding_rects', {
+///   selectors: ['header', 'nav a', 'main']
+/// });
+/// const [header, links, main] = result.data.results;
+/// ```
+#[command]
+pub async fn get_bounding_rects<R: Runtime>(
+    window: WebviewWindow<R>,
+    selectors: Vec<String>,
+    limit: Option<usize>,
+    frame_selector: Option<String>,
+    window_label: Option<String>,
+    executor: State<'_, ScriptExecutor>,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    if selectors.is_empty() {
+        return Ok(serde_json::json!({
+            "success": false,
+            "error": "selectors can't be empty"
+        }));
+    }
+
+    let selectors_js = serde_json::to_string(&selectors).map_err(|e| e.to_string())?;
+    let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT);
+    let script = format!(
+        "const selectors = {selectors_js};\nconst limit = {limit};\n{BOUNDING_RECTS_SCRIPT}"
+    );
+    let script = match frame_selector {
+        Some(frame_selector) => in_frame(&frame_selector, &script),
+        None => script,
+    };
+
+    run_script(window, script, None, None, None, None, None, executor).await
+}
@@ -1,9 +1,12 @@
 //! Window resize functionality.
 
 use serde::{Deserialize, Serialize};
-use tauri::{AppHandle, LogicalSize, PhysicalSize, Runtime};
+use serde_json::Value;
+use std::time::Duration;
+use tauri::{command, AppHandle, LogicalSize, PhysicalSize, Runtime, WebviewWindow};
+use tokio::time::Instant;
 
-use super::list_windows::resolve_window;
+use super::list_windows::{resolve_window, target_window};
 
 /// Parameters for resizing a window.
 #[derive(Debug, Deserialize)]
@@ -108,3 +111,187 @@ pub async fn resize_window<R: Runtime>(
         }),
     }
 }
+
+/// How often the inner size is checked while waiting for a resize to apply.
+const RESIZE_POLL_INTERVAL_MS: u64 = 16;
+
+/// How long the inner size must stay the same for a resize to count as settled.
+const RESIZE_SETTLE_MS: u64 = 100;
+
+/// Default time to wait for a resize to settle.
+const DEFAULT_RESIZE_TIMEOUT_MS: u64 = 2000;
+
+/// Reads a window's inner size in logical and physical pixels.
+fn window_size<R: Runtime>(window: &WebviewWindow<R>) -> Result<Value, String> {
+    let physical = window
+        .inner_size()
+        .map_err(|e| format!("Failed to get size: {e}"))?;
+    let scale_factor = window
+        .scale_factor()
+        .map_err(|e| format!("Failed to get scale factor: {e}"))?;
+    let logical = physical.to_logical::<f64>(scale_factor);
+
+    Ok(serde_json::json!({
+        "window_label": window.label(),
+        "width": logical.width,
+        "height": logical.height,
+        "physical_width": physical.width,
+        "physical_height": physical.height,
+        "scale_factor": scale_factor
+    }))
+}
+
+/// Returns the inner size of a window, which is the size of its webview.
+///
+/// # Arguments
+///
+/// * `window` - The calling window
+/// * `window_label` - Label of the window to target (defaults to the calling window)
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the size was read
+///   - `data`: `{ window_label, width, height, physical_width, physical_height,
+///     scale_factor }` where `width` and `height` are in logical pixels
+///   - `error`: Error message (if failed or the window doesn't exist)
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const size = await invoke('plugin:mcp-bridge|get_window_size');
+/// console.log(`${size.data.width}x${size.data.height}`);
+/// ```
+#[command]
+pub async fn get_window_size<R: Runtime>(
+    window: WebviewWindow<R>,
+    window_label: Option<String>,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    match window_size(&window) {
+        Ok(data) => Ok(serde_json::json!({ "success": true, "data": data })),
+        Err(error) => Ok(serde_json::json!({ "success": false, "error": error })),
+    }
+}
+
+/// Sets the inner size of a window and waits for the resize to apply.
+///
+/// Resizing is asynchronous on most platforms, so this polls the inner size until it
+/// reaches the requested size or stops changing for a moment, then reports the size
+/// the window actually ended up with. That can differ from the request because of
+/// minimum or maximum sizes, the screen size, or a tiling window manager; `matches`
+/// tells whether it did.
+///
+/// Unlike `resize_window`, this also resizes windows the user can't resize.
+///
+/// # Arguments
+///
+/// * `window` - The calling window
+/// * `width` - Requested inner width
+/// * `height` - Requested inner height
+/// * `logical` - Whether `width` and `height` are logical pixels (the default) or
+///   physical pixels
+/// * `timeout_ms` - Maximum time to wait for the resize to settle in milliseconds
+///   (defaults to 2000)
+/// * `window_label` - Label of the window to target (defaults to the calling window)
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the resize was requested
+///   - `data`: The resulting size like `get_window_size`, plus `requested` (the
+///     requested `{ width, height, logical }`), `matches` (whether the result is the
+///     requested size) and `settled` (false if the size was still changing at the
+///     timeout)
+///   - `error`: Error message (if failed or the window doesn't exist)
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const result = await invoke('plugin:mcp-bridge|set_window_size', {
+///   width: 375,
+///   height: 812
+/// });
+/// if (!result.data.matches) {
+///   console.warn(`Window is ${result.data.width}x${result.data.height} instead`);
+/// }
+/// ```
+#[command]
+pub async fn set_window_size<R: Runtime>(
+    window: WebviewWindow<R>,
+    width: u32,
+    height: u32,
+    logical: Option<bool>,
+    timeout_ms: Option<u64>,
+    window_label: Option<String>,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    if width == 0 || height == 0 {
+        return Err("Invalid size: width and height must be greater than zero".to_string());
+    }
+
+    let logical = logical.unwrap_or(true);
+    let scale_factor = window.scale_factor().unwrap_or(1.0);
+    let target: PhysicalSize<u32> = if logical {
+        LogicalSize::new(width, height).to_physical(scale_factor)
+    } else {
+        PhysicalSize::new(width, height)
+    };
+
+    let resized = if logical {
+        window.set_size(LogicalSize::new(width, height))
+    } else {
+        window.set_size(target)
+    };
+    if let Err(e) = resized {
+        return Ok(serde_json::json!({
+            "success": false,
+            "error": format!("Failed to resize window: {e}")
+        }));
+    }
+
+    let deadline =
+        Instant::now() + Duration::from_millis(timeout_ms.unwrap_or(DEFAULT_RESIZE_TIMEOUT_MS));
+    let settle = Duration::from_millis(RESIZE_SETTLE_MS);
+    let mut last = window.inner_size().ok();
+    let mut unchanged_since = Instant::now();
+    let settled = loop {
+        if last == Some(target) {
+            break true;
+        }
+        if Instant::now() >= deadline {
+            break false;
+        }
+        tokio::time::sleep(Duration::from_millis(RESIZE_POLL_INTERVAL_MS)).await;
+
+        let current = window.inner_size().ok();
+        if current != last {
+            last = current;
+            unchanged_since = Instant::now();
+        } else if unchanged_since.elapsed() >= settle {
+            break true;
+        }
+    };
+
+    let mut data = match window_size(&window) {
+        Ok(data) => data,
+        Err(error) => return Ok(serde_json::json!({ "success": false, "error": error })),
+    };
+    data["requested"] = serde_json::json!({ "width": width, "height": height, "logical": logical });
+    data["matches"] = Value::Bool(last == Some(target));
+    data["settled"] = Value::Bool(settled);
+
+    Ok(serde_json::json!({ "success": true, "data": data }))
+}
@@ -1,6 +1,15 @@
 //! Native screenshot capture.
 
-use tauri::{command, Runtime, WebviewWindow};
+use super::execute_js::run_script;
+use super::list_windows::{resolve_window_with_context, target_window};
+use super::script_executor::ScriptExecutor;
+use crate::screenshot::{self, CropRect, Screenshot};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine as _;
+use serde_json::Value;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{command, AppHandle, Manager, Runtime, State, WebviewWindow};
 
 /// Native screenshot command using platform-specific APIs.
 ///
@@ -19,6 +28,7 @@ use tauri::{command, Runtime, WebviewWindow};
 /// * `window` - The window to capture
 /// * `format` - Image format ("png" or "jpeg")
 /// * `quality` - JPEG quality (0-100), only used for JPEG format
+/// * `window_label` - Label of the window to target (defaults to the calling window)
 ///
 /// # Returns
 ///
@@ -29,15 +39,412 @@ pub async fn capture_native_screenshot<R: Runtime>(
     window: WebviewWindow<R>,
     format: Option<String>,
     quality: Option<u8>,
+    window_label: Option<String>,
 ) -> Result<String, String> {
+    let window = target_window(window, window_label).map_err(|e| {
+        e.get("error")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string()
+    })?;
+
     let format = format.unwrap_or_else(|| "png".to_string());
     let quality = quality.unwrap_or(90);
 
     // Use the screenshot module for viewport capture
-    use crate::screenshot;
-
     match screenshot::capture_viewport_screenshot(&window, &format, quality).await {
         Ok(data_url) => Ok(data_url),
         Err(e) => Err(e.to_string()),
     }
 }
+
+/// How long to wait for the page to report that it has painted.
+const FIRST_PAINT_TIMEOUT_MS: u64 = 2000;
+
+/// How long to wait for the canvas fallback to render the page.
+const CANVAS_CAPTURE_TIMEOUT_MS: u64 = 10000;
+
+/// Resolves once a frame has been painted and the document has a body.
+const FIRST_PAINT_CHECK_SCRIPT: &str = r#"
+await new Promise(function(resolve) { requestAnimationFrame(function() { resolve(); }); });
+document.readyState !== 'loading' && !!document.body
+"#;
+
+/// Renders the visible viewport into a canvas through an SVG `foreignObject`.
+///
+/// Only inline and same-document styles are captured; external resources the
+/// serialized DOM references may be missing from the image.
+const CANVAS_CAPTURE_SCRIPT: &str = r#"
+const width = window.innerWidth;
+const height = window.innerHeight;
+const clone = document.documentElement.cloneNode(true);
+clone.querySelectorAll('script').forEach(function(s) { s.remove(); });
+const html = new XMLSerializer().serializeToString(clone);
+const svg = '<svg xmlns="http://www.w3.org/2000/svg" width="' + width + '" height="' + height + '">'
+    + '<foreignObject x="' + (-window.scrollX) + '" y="' + (-window.scrollY) + '" width="100%" height="100%">'
+    + html + '</foreignObject></svg>';
+const img = new Image();
+await new Promise(function(resolve, reject) {
+    img.onload = resolve;
+    img.onerror = function() { reject(new Error('Failed to render document to image')); };
+    img.src = 'data:image/svg+xml;charset=utf-8,' + encodeURIComponent(svg);
+});
+const canvas = document.createElement('canvas');
+canvas.width = width;
+canvas.height = height;
+canvas.getContext('2d').drawImage(img, 0, 0);
+({ dataUrl: canvas.toDataURL('image/png'), width: width, height: height })
+"#;
+
+/// Returns a viewport element's bounding rect in CSS pixels, with the viewport size.
+///
+/// Expects `selector` to be defined before it.
+const ELEMENT_RECT_SCRIPT: &str = r#"
+const element = document.querySelector(selector);
+if (!element) {
+    throw new Error('Element not found: ' + selector);
+}
+const rect = element.getBoundingClientRect();
+({
+    x: rect.left,
+    y: rect.top,
+    width: rect.width,
+    height: rect.height,
+    viewportWidth: window.innerWidth,
+    devicePixelRatio: window.devicePixelRatio || 1
+})
+"#;
+
+/// Captures the visible viewport as PNG, returning the image and where it came from.
+///
+/// Native platform capture is tried first. If it isn't available (for example on
+/// Linux) the page is rendered into a canvas from JavaScript instead. Before either
+/// path runs, the webview must report a painted frame.
+async fn capture_viewport<R: Runtime>(
+    window: &WebviewWindow<R>,
+    state: &State<'_, ScriptExecutor>,
+) -> Result<(Screenshot, &'static str), String> {
+    let ready = run_script(
+        window.clone(),
+        FIRST_PAINT_CHECK_SCRIPT.to_string(),
+        Some(FIRST_PAINT_TIMEOUT_MS),
+        None,
+        None,
+        None,
+        None,
+        state.clone(),
+    )
+    .await?;
+
+    if ready.get("data").and_then(|v| v.as_bool()) != Some(true) {
+        return Err(
+            "Webview has not finished its first paint; try again once the page has loaded"
+                .to_string(),
+        );
+    }
+
+    let native_error = match screenshot::capture_viewport_png(window) {
+        Ok(shot) if shot.png_dimensions().is_some() => return Ok((shot, "native")),
+        Ok(_) => "native capture did not return PNG data".to_string(),
+        Err(e) => e.to_string(),
+    };
+
+    let fallback = run_script(
+        window.clone(),
+        CANVAS_CAPTURE_SCRIPT.to_string(),
+        Some(CANVAS_CAPTURE_TIMEOUT_MS),
+        None,
+        None,
+        None,
+        None,
+        state.clone(),
+    )
+    .await?;
+
+    let image = fallback
+        .get("data")
+        .filter(|_| {
+            fallback
+                .get("success")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false)
+        })
+        .and_then(|d| d.get("dataUrl"))
+        .and_then(|v| v.as_str())
+        .and_then(|url| url.strip_prefix("data:image/png;base64,"))
+        .and_then(|image| STANDARD.decode(image).ok())
+        .map(|data| Screenshot { data })
+        .filter(|shot| shot.png_dimensions().is_some());
+
+    match image {
+        Some(shot) => Ok((shot, "canvas")),
+        None => {
+            let fallback_error = fallback
+                .get("error")
+                .and_then(|v| v.as_str())
+                .unwrap_or("canvas did not produce PNG data");
+            Err(format!(
+                "Screenshot failed: {native_error}; canvas fallback failed: {fallback_error}"
+            ))
+        }
+    }
+}
+
+/// Least time between two screenshots taken because a command failed.
+const FAILURE_CAPTURE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Whether failed commands get a screenshot, and when the last one was taken.
+pub struct FailureCapture {
+    enabled: bool,
+    last: Mutex<Option<Instant>>,
+}
+
+impl FailureCapture {
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            last: Mutex::new(None),
+        }
+    }
+
+    /// Claims a capture at `now`, unless captures are off or the last one was taken
+    /// less than `FAILURE_CAPTURE_INTERVAL` before.
+    fn try_claim(&self, now: Instant) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        let mut last = self.last.lock().unwrap_or_else(|e| e.into_inner());
+        match *last {
+            Some(last) if now.saturating_duration_since(last) < FAILURE_CAPTURE_INTERVAL => false,
+            _ => {
+                *last = Some(now);
+                true
+            }
+        }
+    }
+}
+
+/// Captures the window a command failed in, as base64 PNG data.
+///
+/// Returns `None` when capture on failure is off, a capture was taken within the last
+/// `FAILURE_CAPTURE_INTERVAL` (so a burst of failures doesn't take a burst of
+/// screenshots), or the capture itself failed.
+pub async fn capture_failure<R: Runtime>(
+    app: &AppHandle<R>,
+    window_label: Option<String>,
+) -> Option<String> {
+    if !app.try_state::<FailureCapture>()?.try_claim(Instant::now()) {
+        return None;
+    }
+    let window = resolve_window_with_context(app, window_label).ok()?.window;
+    let state = app.state::<ScriptExecutor>();
+    let (shot, _) = capture_viewport(&window, &state).await.ok()?;
+    Some(STANDARD.encode(&shot.data))
+}
+
+/// Takes a PNG screenshot of the webview's visible viewport.
+///
+/// Native platform capture is tried first. If it isn't available (for example on
+/// Linux) the page is rendered into a canvas from JavaScript instead. Before either
+/// path runs, the webview must report a painted frame; a webview that hasn't
+/// finished its first paint gets a structured error instead of a hang.
+///
+/// # Arguments
+///
+/// * `window` - The window to capture
+/// * `window_label` - Label of the window to target (defaults to the calling window)
+/// * `state` - Script executor state, used for the readiness check and the fallback
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the capture succeeded
+///   - `data`: `{ image, width, height, format, source }` where `image` is base64 PNG
+///     data and `source` is `"native"` or `"canvas"`
+///   - `error`: Error message (if failed)
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const shot = await invoke('plugin:mcp-bridge|take_screenshot');
+/// const img = `data:image/png;base64,${shot.data.image}`;
+/// ```
+#[command]
+pub async fn take_screenshot<R: Runtime>(
+    window: WebviewWindow<R>,
+    window_label: Option<String>,
+    state: State<'_, ScriptExecutor>,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    let (shot, source) = match capture_viewport(&window, &state).await {
+        Ok(capture) => capture,
+        Err(error) => {
+            return Ok(serde_json::json!({
+                "success": false,
+                "error": error
+            }));
+        }
+    };
+    let (width, height) = shot.png_dimensions().unwrap_or_default();
+
+    Ok(serde_json::json!({
+        "success": true,
+        "data": {
+            "image": STANDARD.encode(&shot.data),
+            "width": width,
+            "height": height,
+            "format": "png",
+            "source": source
+        }
+    }))
+}
+
+/// Takes a PNG screenshot of a single element.
+///
+/// The viewport is captured like `take_screenshot` and cropped to the element's
+/// bounding box. The box comes from `getBoundingClientRect`, so it already accounts
+/// for scrolling, and is scaled by the ratio of image to viewport width, which is the
+/// device pixel ratio for native captures and 1 for the canvas fallback. An element
+/// that's only partly visible is cropped to its visible part and reported as
+/// `clipped`; one that's entirely off-screen or has no size is an error, so scroll
+/// it into view first with `scroll_into_view`.
+///
+/// # Arguments
+///
+/// * `window` - The window to capture
+/// * `selector` - CSS selector of the element to capture
+/// * `window_label` - Label of the window to target (defaults to the calling window)
+/// * `state` - Script executor state, used to measure the element and for the capture
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the capture succeeded
+///   - `data`: `{ image, width, height, format, source, rect, element_rect, scale,
+///     clipped }` where `rect` is the crop in image pixels, `element_rect` is the
+///     element's bounding box in CSS pixels and `scale` is image pixels per CSS pixel
+///   - `error`: Error message (if the element is missing, off-screen or zero-size, or
+///     the capture failed)
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const shot = await invoke('plugin:mcp-bridge|take_element_screenshot', {
+///   selector: '#chart'
+/// });
+/// const img = `data:image/png;base64,${shot.data.image}`;
+/// ```
+#[command]
+pub async fn take_element_screenshot<R: Runtime>(
+    window: WebviewWindow<R>,
+    selector: String,
+    window_label: Option<String>,
+    state: State<'_, ScriptExecutor>,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    let fail = |error: String| {
+        Ok(serde_json::json!({
+            "success": false,
+            "error": error
+        }))
+    };
+
+    let selector_js = serde_json::to_string(&selector).map_err(|e| e.to_string())?;
+    let measured = run_script(
+        window.clone(),
+        format!("const selector = {selector_js};\n{ELEMENT_RECT_SCRIPT}"),
+        None,
+        None,
+        None,
+        None,
+        None,
+        state.clone(),
+    )
+    .await?;
+    if measured.get("success").and_then(|v| v.as_bool()) != Some(true) {
+        return Ok(measured);
+    }
+
+    let data = measured.get("data").cloned().unwrap_or(Value::Null);
+    let number = |key: &str| data.get(key).and_then(|v| v.as_f64()).unwrap_or(0.0);
+    let element_rect = (number("x"), number("y"), number("width"), number("height"));
+    if element_rect.2 <= 0.0 || element_rect.3 <= 0.0 {
+        return fail(format!("Element has zero size: {selector}"));
+    }
+
+    let (shot, source) = match capture_viewport(&window, &state).await {
+        Ok(capture) => capture,
+        Err(error) => return fail(error),
+    };
+    let (image_width, image_height) = shot.png_dimensions().unwrap_or_default();
+
+    let viewport_width = number("viewportWidth");
+    let scale = if viewport_width > 0.0 {
+        f64::from(image_width) / viewport_width
+    } else {
+        number("devicePixelRatio").max(1.0)
+    };
+
+    let Some(rect) = CropRect::from_css_rect(element_rect, scale, (image_width, image_height))
+    else {
+        return fail(format!(
+            "Element is outside the viewport: {selector}; scroll it into view first"
+        ));
+    };
+    let (x, y, width, height) = element_rect;
+    let clipped = x < 0.0
+        || y < 0.0
+        || (x + width) * scale > f64::from(image_width)
+        || (y + height) * scale > f64::from(image_height);
+
+    match shot.crop(rect) {
+        Ok(cropped) => Ok(serde_json::json!({
+            "success": true,
+            "data": {
+                "image": STANDARD.encode(&cropped.data),
+                "width": rect.width,
+                "height": rect.height,
+                "format": "png",
+                "source": source,
+                "rect": rect,
+                "element_rect": {
+                    "x": element_rect.0,
+                    "y": element_rect.1,
+                    "width": element_rect.2,
+                    "height": element_rect.3
+                },
+                "scale": scale,
+                "clipped": clipped
+            }
+        })),
+        Err(e) => fail(format!("Failed to crop screenshot: {e}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_failure_captures_are_throttled() {
+        let capture = FailureCapture::new(true);
+        let start = Instant::now();
+        assert!(capture.try_claim(start));
+        assert!(!capture.try_claim(start + Duration::from_secs(1)));
+        assert!(capture.try_claim(start + FAILURE_CAPTURE_INTERVAL));
+
+        assert!(!FailureCapture::new(false).try_claim(start));
+    }
+}
@@ -1,25 +1,611 @@
 //! Script executor state and result handling.
 
-use serde_json::Value;
-use std::collections::HashMap;
+use super::command_metrics::CommandMetrics;
+use super::script_policy::ScriptPolicy;
+use super::sharded_map::ShardedMap;
+use crate::logging::{mcp_log_error, mcp_log_info};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
-use tauri::{command, AppHandle, Manager, Runtime};
-use tokio::sync::{oneshot, Mutex};
+use std::time::Duration;
+use tauri::{command, AppHandle, Listener, Manager, Runtime, State, WebviewWindow};
+use tokio::sync::{oneshot, Mutex, OwnedSemaphorePermit, Semaphore};
+use tokio::time::Instant;
 
-/// Store for pending script execution results
-pub type PendingResults = Arc<Mutex<HashMap<String, oneshot::Sender<Value>>>>;
+/// Default size in characters above which a serialized result is sent in chunks.
+pub const DEFAULT_CHUNK_THRESHOLD: usize = 256 * 1024;
+
+/// Default size in bytes of serialized result data above which it's truncated.
+pub const DEFAULT_MAX_RESULT_SIZE: usize = 50 * 1024 * 1024;
+
+/// How many times `max_result_size` a chunked result may grow to while it's reassembled.
+///
+/// Reassembled results are truncated to `max_result_size` like any other, so this is
+/// only a safety cap: past it the chunks are dropped, so a webview can't make the app
+/// buffer without bound.
+const CHUNKED_RESULT_SIZE_FACTOR: usize = 4;
+
+/// Default number of characters of a function's source returned as a script result.
+pub const DEFAULT_MAX_FUNCTION_SOURCE: usize = 10_000;
+
+/// Default name of the event that script results are sent through.
+pub const DEFAULT_RESULT_CHANNEL: &str = "__script_result";
+
+/// Default number of scripts that may be executing at the same time.
+pub const DEFAULT_MAX_CONCURRENT_EXECUTIONS: usize = 32;
+
+/// Default time pending executions get to finish when the app exits.
+pub const DEFAULT_SHUTDOWN_GRACE: Duration = Duration::from_secs(5);
+
+/// How often a shutdown checks whether the pending executions finished.
+const DRAIN_POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// How long after an exit it drained for the executor decides the app prevented it.
+const EXIT_PREVENTED_DELAY: Duration = Duration::from_millis(500);
+
+/// How often the sweeper looks for stale pending results.
+const PENDING_SWEEP_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How long past its own timeout a pending result may linger before it's reaped.
+const PENDING_RESULT_GRACE: Duration = Duration::from_secs(60);
+
+/// A script execution waiting for its result.
+pub struct PendingResult {
+    /// Completes the waiting `execute_js` call
+    pub sender: oneshot::Sender<Value>,
+    /// Label of the window the script runs in
+    pub window_label: String,
+    /// When the execution was registered
+    pub registered: Instant,
+    /// How long the caller waits for the result
+    pub timeout: Duration,
+}
+
+impl PendingResult {
+    /// Creates an entry registered now.
+    pub fn new(sender: oneshot::Sender<Value>, window_label: &str, timeout: Duration) -> Self {
+        Self {
+            sender,
+            window_label: window_label.to_string(),
+            registered: Instant::now(),
+            timeout,
+        }
+    }
+
+    /// Returns whether nobody can receive this result anymore.
+    ///
+    /// That's the case once the caller stopped waiting (its future was dropped, for
+    /// example by a panic), or well after its timeout should have removed the entry.
+    fn is_stale(&self, now: Instant) -> bool {
+        self.sender.is_closed()
+            || now.saturating_duration_since(self.registered) > self.timeout + PENDING_RESULT_GRACE
+    }
+}
+
+/// Why the executions pending in a window can no longer finish.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowGone {
+    /// The window started loading another page
+    Navigated,
+    /// The window was closed
+    Closed,
+}
+
+impl WindowGone {
+    /// The `error` and `error_kind` reported to the waiting callers.
+    fn error(self) -> (&'static str, &'static str) {
+        match self {
+            WindowGone::Navigated => (
+                "The webview navigated away before the script finished",
+                "webview_navigated",
+            ),
+            WindowGone::Closed => (
+                "The window was closed before the script finished",
+                "webview_closed",
+            ),
+        }
+    }
+}
+
+/// Store for pending script execution results, sharded by execution ID so that
+/// concurrent executions rarely wait for each other's inserts and removals
+pub type PendingResults = Arc<ShardedMap<PendingResult>>;
+
+/// Store for results that are still arriving in chunks
+pub type PartialResults = Arc<Mutex<ChunkAssembler>>;
+
+/// Store for the latest progress reported by each pending execution
+pub type ProgressReports = Arc<Mutex<HashMap<String, Value>>>;
+
+/// Handles the raw payload of an event the executor listens to
+type EventHandler = Box<dyn Fn(&str) + Send + Sync>;
 
 /// Script executor state for managing JavaScript execution
 pub struct ScriptExecutor {
     pub pending_results: PendingResults,
+    pub partial_results: PartialResults,
+    /// Latest `__sendProgress` data of executions that are still pending
+    pub progress: ProgressReports,
+    /// Executions cancelled while their caller was still waiting
+    pub cancelled: Arc<Mutex<HashSet<String>>>,
+    /// Name of the event that script results are sent through
+    pub result_channel: String,
+    /// Serialized results longer than this are split into events on the `_chunk` channel
+    pub chunk_threshold: usize,
+    /// Result data whose serialization is longer than this many bytes is truncated
+    pub max_result_size: usize,
+    /// Functions returned by scripts have their source cut off after this many characters
+    pub max_function_source: usize,
+    /// Rules that client scripts must pass before they run
+    pub script_policy: ScriptPolicy,
+    /// Call counts and durations of the commands clients send
+    pub metrics: CommandMetrics,
+    /// One permit per execution allowed to run at the same time
+    execution_slots: Arc<Semaphore>,
+    /// Total number of permits in `execution_slots`
+    max_concurrent: usize,
+    /// Whether executions over the limit wait for a slot instead of failing
+    queue_when_busy: bool,
+    /// Number of executions currently waiting for a slot
+    queued: Arc<AtomicUsize>,
+    /// Whether the app is exiting, so new executions are refused
+    shutting_down: Arc<AtomicBool>,
+    /// Whether the executions were drained for an exit that's requested again now
+    exit_drained: Arc<AtomicBool>,
+    /// Whether the app is past its `Exit` event
+    exited: Arc<AtomicBool>,
 }
 
 impl ScriptExecutor {
     pub fn new() -> Self {
+        Self::with_chunk_threshold(DEFAULT_CHUNK_THRESHOLD)
+    }
+
+    /// Creates an executor that chunks results longer than `chunk_threshold` characters.
+    pub fn with_chunk_threshold(chunk_threshold: usize) -> Self {
         Self {
-            pending_results: Arc::new(Mutex::new(HashMap::new())),
+            pending_results: Arc::new(ShardedMap::new()),
+            partial_results: Arc::new(Mutex::new(ChunkAssembler::new(
+                chunk_threshold.max(1),
+                DEFAULT_MAX_RESULT_SIZE.saturating_mul(CHUNKED_RESULT_SIZE_FACTOR),
+            ))),
+            progress: Arc::new(Mutex::new(HashMap::new())),
+            cancelled: Arc::new(Mutex::new(HashSet::new())),
+            result_channel: DEFAULT_RESULT_CHANNEL.to_string(),
+            chunk_threshold: chunk_threshold.max(1),
+            max_result_size: DEFAULT_MAX_RESULT_SIZE,
+            max_function_source: DEFAULT_MAX_FUNCTION_SOURCE,
+            script_policy: ScriptPolicy::default(),
+            metrics: CommandMetrics::new(),
+            execution_slots: Arc::new(Semaphore::new(DEFAULT_MAX_CONCURRENT_EXECUTIONS)),
+            max_concurrent: DEFAULT_MAX_CONCURRENT_EXECUTIONS,
+            queue_when_busy: true,
+            queued: Arc::new(AtomicUsize::new(0)),
+            shutting_down: Arc::new(AtomicBool::new(false)),
+            exit_drained: Arc::new(AtomicBool::new(false)),
+            exited: Arc::new(AtomicBool::new(false)),
         }
     }
+
+    /// Limits how many executions may run at the same time.
+    ///
+    /// Executions over the limit wait for a free slot when `queue_when_busy` is set,
+    /// and fail right away otherwise.
+    pub fn with_concurrency_limit(mut self, max_concurrent: usize, queue_when_busy: bool) -> Self {
+        let max_concurrent = max_concurrent.max(1);
+        self.execution_slots = Arc::new(Semaphore::new(max_concurrent));
+        self.max_concurrent = max_concurrent;
+        self.queue_when_busy = queue_when_busy;
+        self
+    }
+
+    /// Truncates result data whose serialization is longer than `max_result_size` bytes.
+    pub fn with_max_result_size(mut self, max_result_size: usize) -> Self {
+        self.max_result_size = max_result_size.max(1);
+        self.partial_results = Arc::new(Mutex::new(ChunkAssembler::new(
+            self.chunk_threshold,
+            self.max_result_size
+                .saturating_mul(CHUNKED_RESULT_SIZE_FACTOR),
+        )));
+        self
+    }
+
+    /// Cuts off the source of functions returned by scripts after `max_function_source`
+    /// characters.
+    pub fn with_max_function_source(mut self, max_function_source: usize) -> Self {
+        self.max_function_source = max_function_source;
+        self
+    }
+
+    /// Sets the rules client scripts must pass before they run.
+    pub fn with_script_policy(mut self, policy: ScriptPolicy) -> Self {
+        self.script_policy = policy;
+        self
+    }
+
+    /// Sets the name of the event that script results are sent through.
+    ///
+    /// Chunks of large results are sent through the same name with `_chunk` appended,
+    /// and progress reports with `_progress` appended.
+    pub fn with_result_channel(mut self, name: impl Into<String>) -> Self {
+        self.result_channel = name.into();
+        self
+    }
+
+    /// Returns the name of the event that chunks of large results are sent through.
+    pub fn chunk_channel(&self) -> String {
+        format!("{}_chunk", self.result_channel)
+    }
+
+    /// Returns the name of the event that progress reports are sent through.
+    pub fn progress_channel(&self) -> String {
+        format!("{}_progress", self.result_channel)
+    }
+
+    /// Claims an execution slot, held until the returned permit is dropped.
+    ///
+    /// When every slot is taken, this waits until `deadline` for one to free up, or
+    /// fails immediately if the executor doesn't queue.
+    pub async fn acquire_slot(&self, deadline: Instant) -> Result<OwnedSemaphorePermit, String> {
+        let busy_error = || {
+            format!(
+                "Too many concurrent executions (limit {})",
+                self.max_concurrent
+            )
+        };
+
+        if !self.queue_when_busy {
+            return self
+                .execution_slots
+                .clone()
+                .try_acquire_owned()
+                .map_err(|_| busy_error());
+        }
+
+        self.queued.fetch_add(1, Ordering::SeqCst);
+        let permit =
+            tokio::time::timeout_at(deadline, self.execution_slots.clone().acquire_owned()).await;
+        self.queued.fetch_sub(1, Ordering::SeqCst);
+
+        match permit {
+            Ok(Ok(permit)) => Ok(permit),
+            Ok(Err(_)) => Err("Script executor is shut down".to_string()),
+            Err(_) => Err(format!(
+                "{} and none finished before the timeout",
+                busy_error()
+            )),
+        }
+    }
+
+    /// Returns the number of executions currently running.
+    pub fn in_flight(&self) -> usize {
+        self.max_concurrent - self.execution_slots.available_permits()
+    }
+
+    /// Returns the number of executions waiting for a free slot.
+    pub fn queued(&self) -> usize {
+        self.queued.load(Ordering::SeqCst)
+    }
+
+    /// Cancels a pending execution, returning its removed [`PendingResult`].
+    ///
+    /// The waiting `execute_js` call observes the closed channel once the returned
+    /// value is dropped, and reports the execution as cancelled. Returns `None` if
+    /// no execution with that ID is pending.
+    pub async fn cancel(&self, exec_id: &str) -> Option<PendingResult> {
+        let pending = self.pending_results.remove(exec_id).await;
+        self.partial_results.lock().await.discard(exec_id);
+        self.progress.lock().await.remove(exec_id);
+
+        if pending.is_some() {
+            // Record the cancellation before the waiter can observe the drop
+            self.cancelled.lock().await.insert(exec_id.to_string());
+        }
+        pending
+    }
+
+    /// Fails every execution pending in a window, returning how many.
+    ///
+    /// Scripts can't report back from a page that navigated away or a window that
+    /// closed, so their callers get a failure right away instead of waiting for their
+    /// timeouts.
+    pub async fn fail_window(&self, window_label: &str, reason: WindowGone) -> usize {
+        let (error, error_kind) = reason.error();
+        self.fail_where(
+            |entry| entry.window_label == window_label,
+            error,
+            error_kind,
+        )
+        .await
+    }
+
+    /// Fails the pending executions that `matches` picks, returning how many.
+    async fn fail_where(
+        &self,
+        matches: impl Fn(&PendingResult) -> bool,
+        error: &str,
+        error_kind: &str,
+    ) -> usize {
+        let failed = self.pending_results.remove_where(matches).await;
+
+        let mut partial = self.partial_results.lock().await;
+        for (exec_id, _) in &failed {
+            partial.discard(exec_id);
+        }
+        drop(partial);
+
+        // The waiting calls remove any progress once they receive the failure
+        let count = failed.len();
+        for (_, entry) in failed {
+            let _ = entry.sender.send(serde_json::json!({
+                "success": false,
+                "error": error,
+                "error_kind": error_kind
+            }));
+        }
+        count
+    }
+
+    /// Returns whether the app is exiting, so no new executions may start.
+    pub fn is_shutting_down(&self) -> bool {
+        self.shutting_down.load(Ordering::SeqCst)
+    }
+
+    /// Stops accepting executions and lets the pending ones finish, returning how many
+    /// didn't.
+    ///
+    /// Waits up to `grace` for the pending executions to complete, then fails the rest
+    /// with `error_kind: "shutting_down"`.
+    pub async fn shutdown(&self, grace: Duration) -> usize {
+        self.shutting_down.store(true, Ordering::SeqCst);
+
+        let deadline = Instant::now() + grace;
+        while Instant::now() < deadline && !self.pending_results.is_empty().await {
+            tokio::time::sleep(DRAIN_POLL_INTERVAL).await;
+        }
+
+        self.fail_where(
+            |_| true,
+            "The app is shutting down and the script didn't finish in time",
+            "shutting_down",
+        )
+        .await
+    }
+
+    /// Holds off an exit until the pending executions finished, from Tauri's
+    /// `ExitRequested` event.
+    ///
+    /// Returns whether the exit has to be prevented. In that case new executions are
+    /// refused from now on, the pending ones are drained in the background (see
+    /// [`Self::shutdown`]) and the exit is requested again afterwards, with the same
+    /// code. Executions are only accepted again if the app prevents that exit itself.
+    pub fn drain_before_exit<R: Runtime>(
+        app: &AppHandle<R>,
+        code: Option<i32>,
+        grace: Duration,
+    ) -> bool {
+        let executor = app.state::<ScriptExecutor>();
+        if executor.exit_drained.swap(false, Ordering::SeqCst) {
+            Self::resume_if_exit_prevented(app);
+            return false;
+        }
+        if tauri::async_runtime::block_on(executor.pending_results.is_empty()) {
+            return false;
+        }
+
+        executor.shutting_down.store(true, Ordering::SeqCst);
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            let executor = app.state::<ScriptExecutor>();
+            let failed = executor.shutdown(grace).await;
+            if failed > 0 {
+                mcp_log_info(
+                    "EXECUTE_JS",
+                    &format!("Failed {failed} pending execution(s) on exit"),
+                );
+            }
+            executor.exit_drained.store(true, Ordering::SeqCst);
+            app.exit(code.unwrap_or(0));
+        });
+        true
+    }
+
+    /// Accepts executions again if the app is still running shortly after the exit
+    /// requested by [`Self::drain_before_exit`], which means the app prevented it.
+    fn resume_if_exit_prevented<R: Runtime>(app: &AppHandle<R>) {
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            tokio::time::sleep(EXIT_PREVENTED_DELAY).await;
+            let executor = app.state::<ScriptExecutor>();
+            if !executor.exited.load(Ordering::SeqCst) {
+                executor.shutting_down.store(false, Ordering::SeqCst);
+                mcp_log_info(
+                    "EXECUTE_JS",
+                    "Exit was prevented, accepting executions again",
+                );
+            }
+        });
+    }
+
+    /// Fails the executions still pending when the app exits, from Tauri's `Exit` event.
+    pub fn fail_on_exit<R: Runtime>(app: &AppHandle<R>) {
+        let executor = app.state::<ScriptExecutor>();
+        executor.exited.store(true, Ordering::SeqCst);
+        tauri::async_runtime::block_on(executor.shutdown(Duration::ZERO));
+    }
+
+    /// Fails the executions pending in a window from a synchronous callback, such as a
+    /// page load or window event handler.
+    pub fn fail_window_later<R: Runtime>(
+        app: &AppHandle<R>,
+        window_label: &str,
+        reason: WindowGone,
+    ) {
+        let app = app.clone();
+        let window_label = window_label.to_string();
+        tauri::async_runtime::spawn(async move {
+            let failed = app
+                .state::<ScriptExecutor>()
+                .fail_window(&window_label, reason)
+                .await;
+            if failed > 0 {
+                mcp_log_info(
+                    "EXECUTE_JS",
+                    &format!(
+                        "Failed {failed} pending execution(s) in '{window_label}': {reason:?}"
+                    ),
+                );
+            }
+        });
+    }
+
+    /// Removes pending results that can no longer be delivered, returning how many.
+    ///
+    /// Entries are normally removed when their result arrives or their call times out.
+    /// This catches the ones that slipped through, such as a caller that stopped
+    /// waiting without cleaning up, so they don't pile up over a long session.
+    pub async fn reap_stale(&self) -> usize {
+        let now = Instant::now();
+        let stale: Vec<String> = self
+            .pending_results
+            .remove_where(|entry| entry.is_stale(now))
+            .await
+            .into_iter()
+            .map(|(exec_id, _)| exec_id)
+            .collect();
+
+        let mut partial = self.partial_results.lock().await;
+        for exec_id in &stale {
+            partial.discard(exec_id);
+        }
+        drop(partial);
+
+        let mut progress = self.progress.lock().await;
+        for exec_id in &stale {
+            progress.remove(exec_id);
+        }
+        stale.len()
+    }
+
+    /// Removes and returns the latest progress reported by an execution.
+    ///
+    /// Called once the execution is no longer pending, so no later report can
+    /// re-create the entry.
+    pub async fn take_progress(&self, exec_id: &str) -> Option<Value> {
+        self.progress.lock().await.remove(exec_id)
+    }
+
+    /// Starts a background task that periodically reaps stale pending results.
+    ///
+    /// This is called once at plugin setup, with the executor managed as app state.
+    pub fn spawn_sweeper<R: Runtime>(app: &AppHandle<R>) {
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            let mut interval = tokio::time::interval(PENDING_SWEEP_INTERVAL);
+            loop {
+                interval.tick().await;
+                let reaped = app.state::<ScriptExecutor>().reap_stale().await;
+                if reaped > 0 {
+                    mcp_log_info(
+                        "EXECUTE_JS",
+                        &format!("Reaped {reaped} stale pending script result(s)"),
+                    );
+                }
+            }
+        });
+    }
+
+    /// Registers the listeners that route script results to their pending executions.
+    ///
+    /// This is called once at plugin setup. Every event on the result channel and its
+    /// `_chunk` and `_progress` channels is dispatched by its `exec_id`, so
+    /// individual `execute_js` calls never register or remove listeners of their own.
+    pub fn listen<R: Runtime>(&self, app: &AppHandle<R>) {
+        for (event, handler) in self.event_handlers() {
+            app.listen_any(event, move |event| handler(event.payload()));
+        }
+    }
+
+    /// Returns the events this executor listens to, with the handler of each one's payload.
+    fn event_handlers(&self) -> Vec<(String, EventHandler)> {
+        let result_channel = self.result_channel.clone();
+        let pending = self.pending_results.clone();
+        let max_result_size = self.max_result_size;
+        let on_result: EventHandler =
+            Box::new(move |raw_payload| match parse_payload(raw_payload) {
+                Ok(payload) => {
+                    let pending = pending.clone();
+                    tauri::async_runtime::spawn(async move {
+                        deliver_result(&pending, &payload, max_result_size).await;
+                    });
+                }
+                Err(e) => mcp_log_error(
+                    "EXECUTE_JS",
+                    &format!("Failed to parse {result_channel} payload: {e}. Raw: {raw_payload}"),
+                ),
+            });
+
+        // Progress only matters while the execution is pending, so only the latest is kept
+        let progress_channel = self.progress_channel();
+        let pending = self.pending_results.clone();
+        let progress = self.progress.clone();
+        let on_progress: EventHandler =
+            Box::new(move |raw_payload| match parse_payload(raw_payload) {
+                Ok(payload) => {
+                    let pending = pending.clone();
+                    let progress = progress.clone();
+                    tauri::async_runtime::spawn(async move {
+                        record_progress(&pending, &progress, &payload).await;
+                    });
+                }
+                Err(e) => mcp_log_error(
+                    "EXECUTE_JS",
+                    &format!("Failed to parse {progress_channel} payload: {e}"),
+                ),
+            });
+
+        // Large results arrive as numbered chunks that are reassembled before delivery
+        let result_channel = self.result_channel.clone();
+        let pending = self.pending_results.clone();
+        let partial = self.partial_results.clone();
+        let max_result_size = self.max_result_size;
+        let on_chunk: EventHandler = Box::new(move |raw_payload| {
+            let chunk = match serde_json::from_str::<ResultChunk>(raw_payload) {
+                Ok(chunk) => chunk,
+                Err(e) => {
+                    mcp_log_error(
+                        "EXECUTE_JS",
+                        &format!("Failed to parse {result_channel}_chunk payload: {e}"),
+                    );
+                    return;
+                }
+            };
+
+            let result_channel = result_channel.clone();
+            let pending = pending.clone();
+            let partial = partial.clone();
+            tauri::async_runtime::spawn(async move {
+                let Some(joined) = collect_chunk(&pending, &partial, chunk).await else {
+                    return;
+                };
+                match parse_payload(&joined) {
+                    Ok(payload) => deliver_result(&pending, &payload, max_result_size).await,
+                    Err(e) => mcp_log_error(
+                        "EXECUTE_JS",
+                        &format!("Failed to parse reassembled {result_channel} payload: {e}"),
+                    ),
+                }
+            });
+        });
+
+        vec![
+            (self.result_channel.clone(), on_result),
+            (self.chunk_channel(), on_chunk),
+            (self.progress_channel(), on_progress),
+        ]
+    }
 }
 
 impl Default for ScriptExecutor {
@@ -28,39 +614,994 @@ impl Default for ScriptExecutor {
     }
 }
 
+/// One piece of a serialized result payload, sent on the executor's chunk channel.
+#[derive(Debug, Deserialize)]
+pub struct ResultChunk {
+    pub exec_id: String,
+    pub index: usize,
+    pub total: usize,
+    pub chunk: String,
+}
+
+/// Reassembles results that the webview split into numbered chunks.
+///
+/// Chunks may arrive in any order. Once every chunk of an execution has been
+/// received, the joined string is returned and the partial entry is dropped.
+///
+/// The webview decides how many chunks it sends, so results are bounded by a safety
+/// cap well above the executor's `max_result_size`: an execution announcing more chunks
+/// than a result of that size needs, or whose chunks add up to more bytes, is dropped.
+/// Results under the cap are truncated to `max_result_size` once joined.
+#[derive(Debug)]
+pub struct ChunkAssembler {
+    partials: HashMap<String, PartialResult>,
+    /// Most chunks a result may be split into
+    max_chunks: usize,
+    /// Most bytes buffered for one result
+    max_bytes: usize,
+}
+
+/// The chunks of one result received so far.
+#[derive(Debug)]
+struct PartialResult {
+    parts: Vec<Option<String>>,
+    bytes: usize,
+}
+
+impl ChunkAssembler {
+    /// Creates an empty assembler for results split into chunks of `chunk_threshold`
+    /// characters and at most `max_bytes` bytes long.
+    pub fn new(chunk_threshold: usize, max_bytes: usize) -> Self {
+        Self {
+            partials: HashMap::new(),
+            max_chunks: max_bytes / chunk_threshold.max(1) + 1,
+            max_bytes,
+        }
+    }
+
+    /// Stores a chunk, returning the full payload once all chunks have arrived.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(String))` - The joined payload, once the last chunk arrived
+    /// * `Ok(None)` - The chunk was stored, or ignored because it's out of range
+    /// * `Err(String)` - The result is too large, and its chunks were dropped
+    pub fn add_chunk(&mut self, chunk: ResultChunk) -> Result<Option<String>, String> {
+        if chunk.total == 0 || chunk.index >= chunk.total {
+            return Ok(None);
+        }
+        let too_large = || {
+            format!(
+                "Result is larger than the maximum of {} bytes",
+                self.max_bytes
+            )
+        };
+        if chunk.total > self.max_chunks {
+            self.partials.remove(&chunk.exec_id);
+            return Err(too_large());
+        }
+
+        let partial = self
+            .partials
+            .entry(chunk.exec_id.clone())
+            .or_insert_with(|| PartialResult {
+                parts: vec![None; chunk.total],
+                bytes: 0,
+            });
+        if partial.parts.len() != chunk.total {
+            return Ok(None);
+        }
+        let replaced = partial.parts[chunk.index].as_ref().map_or(0, String::len);
+        partial.bytes = partial.bytes - replaced + chunk.chunk.len();
+        if partial.bytes > self.max_bytes {
+            self.partials.remove(&chunk.exec_id);
+            return Err(too_large());
+        }
+        partial.parts[chunk.index] = Some(chunk.chunk);
+
+        if partial.parts.iter().any(Option::is_none) {
+            return Ok(None);
+        }
+        Ok(self
+            .partials
+            .remove(&chunk.exec_id)
+            .map(|partial| partial.parts.into_iter().flatten().collect()))
+    }
+
+    /// Discards any chunks received so far for an execution.
+    pub fn discard(&mut self, exec_id: &str) {
+        self.partials.remove(exec_id);
+    }
+
+    /// Returns the number of executions with chunks still outstanding.
+    pub fn len(&self) -> usize {
+        self.partials.len()
+    }
+
+    /// Returns true if no executions have chunks outstanding.
+    pub fn is_empty(&self) -> bool {
+        self.partials.is_empty()
+    }
+}
+
+/// Builds the `execute_js` result envelope from a result event payload.
+pub fn result_envelope(payload: &Map<String, Value>) -> Value {
+    if payload
+        .get("success")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+    {
+        let mut result = serde_json::json!({
+            "success": true,
+            "data": payload.get("data").cloned().unwrap_or(Value::Null)
+        });
+        // The JavaScript type before serialization, so `undefined` and `null` differ
+        if let Some(result_type) = payload.get("result_type").and_then(|v| v.as_str()) {
+            result["result_type"] = Value::String(result_type.to_string());
+        }
+        copy_script_ms(payload, &mut result);
+        copy_console(payload, &mut result);
+        result
+    } else {
+        let mut result = serde_json::json!({
+            "success": false,
+            "error": payload.get("error")
+                .and_then(|v| v.as_str())
+                .unwrap_or("Unknown error")
+        });
+        // Only Error instances carry a stack; omit it otherwise
+        if let Some(stack) = payload.get("stack").and_then(|v| v.as_str()) {
+            result["error_stack"] = Value::String(stack.to_string());
+        }
+        result["error_kind"] = payload
+            .get("error_kind")
+            .and_then(|v| v.as_str())
+            .map_or_else(|| Value::from("runtime_error"), Value::from);
+        copy_script_ms(payload, &mut result);
+        copy_console(payload, &mut result);
+        result
+    }
+}
+
+/// Copies the time the script ran in the webview, when it got to run.
+fn copy_script_ms(payload: &Map<String, Value>, result: &mut Value) {
+    if let Some(script_ms) = payload.get("script_ms").filter(|v| v.is_number()) {
+        result["script_ms"] = script_ms.clone();
+    }
+}
+
+/// Copies the console lines the script logged, when `capture_console` was set.
+fn copy_console(payload: &Map<String, Value>, result: &mut Value) {
+    if let Some(console) = payload.get("console").filter(|v| v.is_array()) {
+        result["console"] = console.clone();
+    }
+}
+
+/// Parses a script event payload into its fields.
+///
+/// Tauri sometimes delivers payloads encoded twice, as a JSON string holding the
+/// object, so a string is parsed again before giving up. The error is the one from
+/// the first attempt.
+pub fn parse_payload(raw_payload: &str) -> Result<Map<String, Value>, serde_json::Error> {
+    serde_json::from_str::<Map<String, Value>>(raw_payload).or_else(|error| {
+        match serde_json::from_str::<Value>(raw_payload) {
+            Ok(Value::String(inner)) => {
+                serde_json::from_str::<Map<String, Value>>(&inner).map_err(|_| error)
+            }
+            _ => Err(error),
+        }
+    })
+}
+
+/// Completes the pending execution a payload belongs to, if it's still waiting.
+///
+/// Data longer than `max_result_size` bytes once serialized is truncated, see
+/// [`truncate_data`].
+pub async fn deliver_result(
+    pending: &PendingResults,
+    payload: &Map<String, Value>,
+    max_result_size: usize,
+) {
+    let Some(Value::String(exec_id)) = payload.get("exec_id") else {
+        return;
+    };
+    if let Some(pending) = pending.remove(exec_id).await {
+        let mut envelope = result_envelope(payload);
+        truncate_data(&mut envelope, max_result_size);
+        let _ = pending.sender.send(envelope);
+    }
+}
+
+/// Stores a chunk of a pending execution's result, returning the full payload once all
+/// chunks have arrived.
+///
+/// Chunks of executions that are no longer pending are ignored, so a chunk arriving
+/// after a timeout or cancellation can't leave a partial result behind. When the
+/// result grows past the assembler's safety cap, the execution fails with
+/// `error_kind: "result_too_large"`. Smaller results are truncated once delivered.
+pub async fn collect_chunk(
+    pending: &PendingResults,
+    partial: &PartialResults,
+    chunk: ResultChunk,
+) -> Option<String> {
+    // Holding the shard keeps the execution from finishing, and its chunks from being
+    // discarded, while the chunk is stored
+    let mut pending = pending.lock(&chunk.exec_id).await;
+    if !pending.contains_key(&chunk.exec_id) {
+        return None;
+    }
+    let exec_id = chunk.exec_id.clone();
+    match partial.lock().await.add_chunk(chunk) {
+        Ok(joined) => joined,
+        Err(error) => {
+            if let Some(entry) = pending.remove(&exec_id) {
+                let _ = entry.sender.send(serde_json::json!({
+                    "success": false,
+                    "error": error,
+                    "error_kind": "result_too_large"
+                }));
+            }
+            None
+        }
+    }
+}
+
+/// Replaces an envelope's `data` with the start of its JSON serialization, if that's
+/// longer than `max_size` bytes.
+///
+/// The result then holds `truncated: true` and the full size in `original_size`, so a
+/// huge result still returns something useful instead of failing. The cut never
+/// splits a UTF-8 character.
+pub fn truncate_data(envelope: &mut Value, max_size: usize) {
+    let Some(data) = envelope.get("data") else {
+        return;
+    };
+    // Measure first, so results under the limit aren't serialized just to check
+    let mut counter = ByteCounter(0);
+    if serde_json::to_writer(&mut counter, data).is_err() || counter.0 <= max_size {
+        return;
+    }
+
+    let serialized = data.to_string();
+    let mut end = max_size;
+    while !serialized.is_char_boundary(end) {
+        end -= 1;
+    }
+    envelope["data"] = Value::String(serialized[..end].to_string());
+    envelope["truncated"] = Value::Bool(true);
+    envelope["original_size"] = Value::from(serialized.len());
+}
+
+/// Counts the bytes written to it.
+struct ByteCounter(usize);
+
+impl std::io::Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0 += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Stores the progress a progress channel payload carries, if its execution is pending.
+///
+/// The execution's pending shard stays locked while storing, so a report can't
+/// outlive an execution that finished in the meantime.
+pub async fn record_progress(
+    pending: &PendingResults,
+    progress: &ProgressReports,
+    payload: &Map<String, Value>,
+) {
+    let Some(Value::String(exec_id)) = payload.get("exec_id") else {
+        return;
+    };
+    let pending = pending.lock(exec_id).await;
+    if pending.contains_key(exec_id) {
+        let data = payload.get("data").cloned().unwrap_or(Value::Null);
+        progress.lock().await.insert(exec_id.clone(), data);
+    }
+}
+
+/// Cancels an in-flight `execute_js` call.
+///
+/// The pending result is removed so the waiting call returns immediately with a
+/// "Script execution cancelled" error, and the webview is told not to emit the
+/// script's result.
+///
+/// Cancellation is best-effort: JavaScript that is already running synchronously
+/// can't be interrupted and runs to completion. Only its result is discarded.
+///
+/// # Arguments
+///
+/// * `window` - The calling window, used to look up the window the script runs in
+/// * `exec_id` - The execution ID passed to or returned by `execute_js`
+/// * `state` - Script executor state
+///
+/// # Returns
+///
+/// * `Ok(Value)` - `{ cancelled, exec_id }`, where `cancelled` is false if the
+///   execution had already finished or never existed
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const run = invoke('plugin:mcp-bridge|execute_js', {
+///   script: 'await new Promise(r => setTimeout(r, 60000))',
+///   timeoutMs: 120000,
+///   execId: 'long-wait'
+/// });
+/// await invoke('plugin:mcp-bridge|cancel_execution', { execId: 'long-wait' });
+/// console.log((await run).error); // "Script execution cancelled"
+/// ```
+#[command]
+pub async fn cancel_execution<R: Runtime>(
+    window: WebviewWindow<R>,
+    exec_id: String,
+    state: State<'_, ScriptExecutor>,
+) -> Result<Value, String> {
+    let pending = state.cancel(&exec_id).await;
+    let cancelled = pending.is_some();
+
+    if let Some(pending) = pending {
+        // Signal the window the script runs in, which may not be the caller's
+        let target = window
+            .app_handle()
+            .get_webview_window(&pending.window_label);
+        drop(pending);
+
+        let exec_id_js = serde_json::to_string(&exec_id).map_err(|e| e.to_string())?;
+        let signal = format!(
+            "(window.__MCP_CANCELLED_EXECUTIONS__ = window.__MCP_CANCELLED_EXECUTIONS__ || {{}})[{exec_id_js}] = true;"
+        );
+        // Best-effort: the result is discarded on the Rust side either way
+        if let Some(target) = target {
+            let _ = target.eval(&signal);
+        }
+    }
+
+    Ok(serde_json::json!({
+        "cancelled": cancelled,
+        "exec_id": exec_id
+    }))
+}
+
+/// Returns how busy the script executor is.
+///
+/// Clients can poll this to throttle themselves before hitting the concurrency limit.
+///
+/// # Arguments
+///
+/// * `state` - Script executor state
+///
+/// # Returns
+///
+/// * `Ok(Value)` - `{ in_flight, queued, limit, queue_when_busy }`, where `in_flight`
+///   counts running executions and `queued` counts those waiting for a free slot
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const metrics = await invoke('plugin:mcp-bridge|get_execution_metrics');
+/// if (metrics.in_flight >= metrics.limit) {
+///   // Back off before sending more scripts
+/// }
+/// ```
+#[command]
+pub async fn get_execution_metrics(state: State<'_, ScriptExecutor>) -> Result<Value, String> {
+    Ok(serde_json::json!({
+        "in_flight": state.in_flight(),
+        "queued": state.queued(),
+        "limit": state.max_concurrent,
+        "queue_when_busy": state.queue_when_busy
+    }))
+}
+
 /// Command to receive script execution results from JavaScript.
 ///
 /// This is called by JavaScript after script execution completes.
 #[command]
+#[allow(clippy::too_many_arguments)]
 pub async fn script_result<R: Runtime>(
     app: AppHandle<R>,
     exec_id: String,
     success: bool,
     data: Option<Value>,
     error: Option<String>,
+    stack: Option<String>,
+    result_type: Option<String>,
+    error_kind: Option<String>,
+    script_ms: Option<f64>,
 ) -> Result<(), String> {
     // Get the script executor from app state
     if let Some(executor) = app.try_state::<ScriptExecutor>() {
-        let mut pending = executor.pending_results.lock().await;
+        // Same shape as a result event payload
+        let payload = serde_json::json!({
+            "exec_id": exec_id,
+            "success": success,
+            "data": data,
+            "error": error,
+            "stack": stack,
+            "result_type": result_type,
+            "error_kind": error_kind,
+            "script_ms": script_ms
+        });
 
         // Find and complete the pending result
-        if let Some(sender) = pending.remove(&exec_id) {
-            let result = if success {
-                serde_json::json!({
-                    "success": true,
-                    "data": data.unwrap_or(Value::Null)
-                })
-            } else {
-                serde_json::json!({
-                    "success": false,
-                    "error": error.unwrap_or_else(|| "Unknown error".to_string())
-                })
-            };
-
-            // Send result through the channel (ignore if receiver dropped)
-            let _ = sender.send(result);
+        if let Value::Object(payload) = payload {
+            deliver_result(
+                &executor.pending_results,
+                &payload,
+                executor.max_result_size,
+            )
+            .await;
         }
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Splits `payload` the way the injected wrapper does.
+    fn split(exec_id: &str, payload: &str, size: usize) -> Vec<ResultChunk> {
+        let parts: Vec<String> = payload
+            .as_bytes()
+            .chunks(size)
+            .map(|c| String::from_utf8(c.to_vec()).unwrap())
+            .collect();
+        let total = parts.len();
+        parts
+            .into_iter()
+            .enumerate()
+            .map(|(index, chunk)| ResultChunk {
+                exec_id: exec_id.to_string(),
+                index,
+                total,
+                chunk,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_round_trips_large_payload() {
+        let data = "x".repeat(2 * 1024 * 1024);
+        let payload = serde_json::json!({
+            "exec_id": "big",
+            "success": true,
+            "data": data,
+        })
+        .to_string();
+
+        let mut assembler = ChunkAssembler::new(DEFAULT_CHUNK_THRESHOLD, DEFAULT_MAX_RESULT_SIZE);
+        let mut chunks = split("big", &payload, DEFAULT_CHUNK_THRESHOLD);
+        assert!(chunks.len() > 1);
+
+        // Events aren't guaranteed to arrive in order
+        chunks.reverse();
+        let last = chunks.pop().unwrap();
+        for chunk in chunks {
+            assert!(assembler.add_chunk(chunk).unwrap().is_none());
+        }
+        let joined = assembler
+            .add_chunk(last)
+            .unwrap()
+            .expect("payload should be complete");
+        assert!(assembler.is_empty());
+
+        let parsed: Map<String, Value> = serde_json::from_str(&joined).unwrap();
+        let envelope = result_envelope(&parsed);
+        assert_eq!(envelope["success"], true);
+        assert_eq!(envelope["data"].as_str().unwrap().len(), data.len());
+    }
+
+    #[test]
+    fn test_keeps_executions_separate() {
+        let mut assembler = ChunkAssembler::new(2, 16);
+        let mut a = split("a", "aaaa", 2);
+        let mut b = split("b", "bbbb", 2);
+
+        assert!(assembler.add_chunk(a.remove(0)).unwrap().is_none());
+        assert!(assembler.add_chunk(b.remove(0)).unwrap().is_none());
+        assert_eq!(assembler.len(), 2);
+        assert_eq!(
+            assembler.add_chunk(b.remove(0)).unwrap().as_deref(),
+            Some("bbbb")
+        );
+        assert_eq!(
+            assembler.add_chunk(a.remove(0)).unwrap().as_deref(),
+            Some("aaaa")
+        );
+        assert!(assembler.is_empty());
+    }
+
+    #[test]
+    fn test_rejects_out_of_range_chunks() {
+        let mut assembler = ChunkAssembler::new(2, 16);
+        let chunk = ResultChunk {
+            exec_id: "x".to_string(),
+            index: 3,
+            total: 2,
+            chunk: "oops".to_string(),
+        };
+        assert!(assembler.add_chunk(chunk).unwrap().is_none());
+        assert!(assembler.is_empty());
+    }
+
+    #[test]
+    fn test_rejects_results_over_the_size_limit() {
+        let mut assembler = ChunkAssembler::new(4, 16);
+
+        // Announcing more chunks than 16 bytes need fails before anything is allocated
+        let huge = ResultChunk {
+            exec_id: "huge".to_string(),
+            index: 0,
+            total: 1_000_000_000_000,
+            chunk: "a".to_string(),
+        };
+        let error = assembler.add_chunk(huge).unwrap_err();
+        assert!(error.contains("16 bytes"));
+        assert!(assembler.is_empty());
+
+        // Chunks larger than announced are dropped once they pass the limit
+        let mut chunks = split("long", &"x".repeat(20), 10);
+        assert!(assembler.add_chunk(chunks.remove(0)).unwrap().is_none());
+        assert!(assembler.add_chunk(chunks.remove(0)).is_err());
+        assert!(assembler.is_empty());
+    }
+
+    #[test]
+    fn test_error_envelope_includes_stack() {
+        let payload: Map<String, Value> = serde_json::from_value(serde_json::json!({
+            "exec_id": "e",
+            "success": false,
+            "error": "boom",
+            "stack": "Error: boom\n    at <anonymous>:1:7",
+        }))
+        .unwrap();
+        let envelope = result_envelope(&payload);
+        assert_eq!(envelope["error"], "boom");
+        assert_eq!(envelope["error_kind"], "runtime_error");
+        assert!(envelope["error_stack"]
+            .as_str()
+            .unwrap()
+            .starts_with("Error: boom"));
+    }
+
+    #[test]
+    fn test_envelope_includes_script_time_when_reported() {
+        let payload: Map<String, Value> = serde_json::from_value(serde_json::json!({
+            "exec_id": "e",
+            "success": true,
+            "data": 1,
+            "script_ms": 12.5
+        }))
+        .unwrap();
+        assert_eq!(result_envelope(&payload)["script_ms"], 12.5);
+
+        let payload: Map<String, Value> = serde_json::from_value(serde_json::json!({
+            "exec_id": "e",
+            "success": false,
+            "error": "Event API unavailable",
+            "error_kind": "injection_failed",
+            "script_ms": null
+        }))
+        .unwrap();
+        assert!(result_envelope(&payload).get("script_ms").is_none());
+    }
+
+    #[test]
+    fn test_success_envelope_includes_result_type() {
+        let payload: Map<String, Value> = serde_json::from_value(serde_json::json!({
+            "exec_id": "e",
+            "success": true,
+            "data": null,
+            "result_type": "undefined",
+        }))
+        .unwrap();
+        let envelope = result_envelope(&payload);
+        assert_eq!(envelope["data"], Value::Null);
+        assert_eq!(envelope["result_type"], "undefined");
+    }
+
+    #[test]
+    fn test_parses_double_encoded_payloads() {
+        let payload = serde_json::json!({ "exec_id": "twice", "success": true, "data": 1 });
+        let once = payload.to_string();
+        let twice = serde_json::to_string(&once).unwrap();
+
+        assert_eq!(
+            parse_payload(&once).unwrap(),
+            parse_payload(&twice).unwrap()
+        );
+        assert_eq!(parse_payload(&twice).unwrap()["exec_id"], "twice");
+        assert!(parse_payload("\"not an object\"").is_err());
+        assert!(parse_payload("[1, 2]").is_err());
+    }
+
+    #[test]
+    fn test_truncates_data_over_the_max_size() {
+        // `"abcd"` serializes to 6 bytes, quotes included
+        let mut at_limit = serde_json::json!({ "success": true, "data": "abcd" });
+        truncate_data(&mut at_limit, 6);
+        assert_eq!(at_limit["data"], "abcd");
+        assert!(at_limit.get("truncated").is_none());
+
+        let mut over_limit = serde_json::json!({ "success": true, "data": "abcde" });
+        truncate_data(&mut over_limit, 6);
+        assert_eq!(over_limit["data"], "\"abcde");
+        assert_eq!(over_limit["truncated"], true);
+        assert_eq!(over_limit["original_size"], 7);
+
+        // "é" takes 2 bytes, and isn't cut in half
+        let mut multibyte = serde_json::json!({ "success": true, "data": ["é"] });
+        truncate_data(&mut multibyte, 3);
+        assert_eq!(multibyte["data"], "[\"");
+        assert_eq!(multibyte["original_size"], 6);
+
+        let mut failed = serde_json::json!({ "success": false, "error": "boom" });
+        truncate_data(&mut failed, 1);
+        assert!(failed.get("truncated").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_delivers_double_encoded_results() {
+        let executor = ScriptExecutor::new();
+        let (tx, rx) = oneshot::channel();
+        executor
+            .pending_results
+            .insert(
+                "twice".into(),
+                PendingResult::new(tx, "main", std::time::Duration::from_secs(5)),
+            )
+            .await;
+
+        let payload = serde_json::json!({ "exec_id": "twice", "success": true, "data": 42 });
+        let raw = serde_json::to_string(&payload.to_string()).unwrap();
+        deliver_result(
+            &executor.pending_results,
+            &parse_payload(&raw).unwrap(),
+            DEFAULT_MAX_RESULT_SIZE,
+        )
+        .await;
+
+        let result = rx.await.unwrap();
+        assert_eq!(result["success"], true);
+        assert_eq!(result["data"], 42);
+    }
+
+    #[tokio::test]
+    async fn test_ignores_chunks_of_executions_no_longer_pending() {
+        let executor = ScriptExecutor::with_chunk_threshold(2);
+        let (tx, rx) = oneshot::channel();
+        executor
+            .pending_results
+            .insert(
+                "late".into(),
+                PendingResult::new(tx, "main", std::time::Duration::from_secs(5)),
+            )
+            .await;
+        let mut chunks = split("late", "aaaa", 2);
+
+        let first = chunks.remove(0);
+        assert!(
+            collect_chunk(&executor.pending_results, &executor.partial_results, first)
+                .await
+                .is_none()
+        );
+        let cancelled = executor.cancel("late").await;
+        assert_eq!(
+            cancelled.map(|pending| pending.window_label).as_deref(),
+            Some("main")
+        );
+        assert!(rx.await.is_err());
+
+        let last = chunks.remove(0);
+        assert!(
+            collect_chunk(&executor.pending_results, &executor.partial_results, last)
+                .await
+                .is_none()
+        );
+        assert!(executor.partial_results.lock().await.is_empty());
+
+        // Chunks of executions that never existed aren't buffered either
+        let stray = split("stray", "bbbb", 2).remove(0);
+        collect_chunk(&executor.pending_results, &executor.partial_results, stray).await;
+        assert!(executor.partial_results.lock().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_fails_executions_whose_result_is_too_large() {
+        let executor = ScriptExecutor::with_chunk_threshold(4).with_max_result_size(8);
+        let (tx, rx) = oneshot::channel();
+        executor
+            .pending_results
+            .insert(
+                "big".into(),
+                PendingResult::new(tx, "main", std::time::Duration::from_secs(5)),
+            )
+            .await;
+
+        let chunk = ResultChunk {
+            exec_id: "big".to_string(),
+            index: 0,
+            total: 1_000,
+            chunk: "a".to_string(),
+        };
+        collect_chunk(&executor.pending_results, &executor.partial_results, chunk).await;
+
+        let result = rx.await.unwrap();
+        assert_eq!(result["error_kind"], "result_too_large");
+        assert!(!executor.pending_results.contains_key("big").await);
+    }
+
+    #[tokio::test]
+    async fn test_truncates_chunked_results_over_the_max() {
+        let executor = ScriptExecutor::with_chunk_threshold(16).with_max_result_size(64);
+        let (tx, rx) = oneshot::channel();
+        executor
+            .pending_results
+            .insert(
+                "big".into(),
+                PendingResult::new(tx, "main", std::time::Duration::from_secs(5)),
+            )
+            .await;
+
+        let data = "x".repeat(100);
+        let payload = serde_json::json!({ "exec_id": "big", "success": true, "data": data });
+        let mut joined = None;
+        for chunk in split("big", &payload.to_string(), 16) {
+            joined =
+                collect_chunk(&executor.pending_results, &executor.partial_results, chunk).await;
+        }
+        let payload = parse_payload(&joined.unwrap()).unwrap();
+        deliver_result(
+            &executor.pending_results,
+            &payload,
+            executor.max_result_size,
+        )
+        .await;
+
+        let result = rx.await.unwrap();
+        assert_eq!(result["success"], true);
+        assert_eq!(result["truncated"], true);
+        assert_eq!(result["original_size"], 102);
+        assert_eq!(result["data"].as_str().unwrap().len(), 64);
+    }
+
+    #[tokio::test]
+    async fn test_rejects_executions_over_the_limit() {
+        let executor = ScriptExecutor::new().with_concurrency_limit(1, false);
+        let deadline = Instant::now() + std::time::Duration::from_secs(1);
+
+        let slot = executor.acquire_slot(deadline).await.unwrap();
+        assert_eq!(executor.in_flight(), 1);
+        let error = executor.acquire_slot(deadline).await.unwrap_err();
+        assert!(error.contains("Too many concurrent executions"));
+
+        drop(slot);
+        assert_eq!(executor.in_flight(), 0);
+        assert!(executor.acquire_slot(deadline).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_reaps_results_nobody_waits_for() {
+        let executor = ScriptExecutor::new();
+        let (waiting_tx, _waiting_rx) = oneshot::channel();
+        let (abandoned_tx, abandoned_rx) = oneshot::channel();
+        drop(abandoned_rx);
+        let timeout = std::time::Duration::from_secs(5);
+        let pending = &executor.pending_results;
+        pending
+            .insert(
+                "waiting".into(),
+                PendingResult::new(waiting_tx, "main", timeout),
+            )
+            .await;
+        pending
+            .insert(
+                "abandoned".into(),
+                PendingResult::new(abandoned_tx, "main", timeout),
+            )
+            .await;
+
+        assert_eq!(executor.reap_stale().await, 1);
+        assert!(pending.contains_key("waiting").await);
+        assert!(!pending.contains_key("abandoned").await);
+    }
+
+    #[tokio::test]
+    async fn test_fails_executions_of_a_navigated_window() {
+        let executor = ScriptExecutor::new();
+        let timeout = std::time::Duration::from_secs(5);
+        let (main_tx, main_rx) = oneshot::channel();
+        let (other_tx, _other_rx) = oneshot::channel();
+        let pending = &executor.pending_results;
+        pending
+            .insert(
+                "in-main".into(),
+                PendingResult::new(main_tx, "main", timeout),
+            )
+            .await;
+        pending
+            .insert(
+                "in-other".into(),
+                PendingResult::new(other_tx, "other", timeout),
+            )
+            .await;
+
+        assert_eq!(executor.fail_window("main", WindowGone::Navigated).await, 1);
+        let result = main_rx.await.unwrap();
+        assert_eq!(result["success"], false);
+        assert_eq!(result["error_kind"], "webview_navigated");
+        assert!(!pending.contains_key("in-main").await);
+        assert!(pending.contains_key("in-other").await);
+    }
+
+    #[tokio::test]
+    async fn test_keeps_latest_progress_of_pending_executions() {
+        let executor = ScriptExecutor::new();
+        let (tx, _rx) = oneshot::channel();
+        executor
+            .pending_results
+            .insert(
+                "running".into(),
+                PendingResult::new(tx, "main", std::time::Duration::from_secs(5)),
+            )
+            .await;
+
+        for (exec_id, step) in [("running", 1), ("running", 2), ("finished", 3)] {
+            let payload: Map<String, Value> = serde_json::from_value(serde_json::json!({
+                "exec_id": exec_id,
+                "data": { "step": step },
+            }))
+            .unwrap();
+            record_progress(&executor.pending_results, &executor.progress, &payload).await;
+        }
+
+        assert_eq!(executor.progress.lock().await.len(), 1);
+        let last = executor.take_progress("running").await.unwrap();
+        assert_eq!(last["step"], 2);
+        assert!(executor.take_progress("running").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_executors_on_different_channels_do_not_cross_talk() {
+        let first = ScriptExecutor::new();
+        let second = ScriptExecutor::new().with_result_channel("__script_result_second");
+
+        // Both wait for an execution with the same ID
+        let mut receivers = Vec::new();
+        for executor in [&first, &second] {
+            let (tx, rx) = oneshot::channel();
+            executor
+                .pending_results
+                .insert(
+                    "same".into(),
+                    PendingResult::new(tx, "main", Duration::from_secs(5)),
+                )
+                .await;
+            receivers.push(rx);
+        }
+        let second_rx = receivers.pop().unwrap();
+        let mut first_rx = receivers.pop().unwrap();
+
+        // Stands in for the app's event bus, which calls every listener of an event
+        let listeners: Vec<(String, EventHandler)> = first
+            .event_handlers()
+            .into_iter()
+            .chain(second.event_handlers())
+            .collect();
+        let emit = |event: &str, data: &str| {
+            let payload = serde_json::json!({ "exec_id": "same", "success": true, "data": data });
+            for (name, handler) in &listeners {
+                if name == event {
+                    handler(&payload.to_string());
+                }
+            }
+        };
+
+        emit("__script_result_second", "second");
+        let result = tokio::time::timeout(Duration::from_secs(5), second_rx)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(result["data"], "second");
+        assert!(first_rx.try_recv().is_err());
+
+        emit(DEFAULT_RESULT_CHANNEL, "first");
+        let result = tokio::time::timeout(Duration::from_secs(5), first_rx)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(result["data"], "first");
+        assert_eq!(second.chunk_channel(), "__script_result_second_chunk");
+        assert_eq!(second.progress_channel(), "__script_result_second_progress");
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_waits_for_pending_executions_then_fails_the_rest() {
+        let executor = ScriptExecutor::new();
+        let (finishing_tx, finishing_rx) = oneshot::channel();
+        let (stuck_tx, stuck_rx) = oneshot::channel();
+        for (exec_id, tx) in [("finishing", finishing_tx), ("stuck", stuck_tx)] {
+            executor
+                .pending_results
+                .insert(
+                    exec_id.into(),
+                    PendingResult::new(tx, "main", Duration::from_secs(5)),
+                )
+                .await;
+        }
+
+        let pending = executor.pending_results.clone();
+        let payload =
+            parse_payload(r#"{"exec_id": "finishing", "success": true, "data": 1}"#).unwrap();
+        let finish = async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            deliver_result(&pending, &payload, DEFAULT_MAX_RESULT_SIZE).await;
+        };
+
+        let (failed, ()) = tokio::join!(executor.shutdown(Duration::from_millis(300)), finish);
+        assert_eq!(failed, 1);
+        assert!(executor.is_shutting_down());
+        assert_eq!(finishing_rx.await.unwrap()["success"], true);
+        let stuck = stuck_rx.await.unwrap();
+        assert_eq!(stuck["success"], false);
+        assert_eq!(stuck["error_kind"], "shutting_down");
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 8)]
+    async fn test_hundreds_of_concurrent_executions_get_their_own_results() {
+        let executor = Arc::new(ScriptExecutor::new());
+        let mut waiters = Vec::new();
+        for i in 0..500 {
+            let executor = executor.clone();
+            waiters.push(tokio::spawn(async move {
+                let exec_id = format!("exec-{i}");
+                let (tx, rx) = oneshot::channel();
+                executor
+                    .pending_results
+                    .insert(
+                        exec_id.clone(),
+                        PendingResult::new(tx, "main", Duration::from_secs(5)),
+                    )
+                    .await;
+
+                // Progress and results for every execution arrive at the same time
+                let payload: Map<String, Value> = serde_json::from_value(serde_json::json!({
+                    "exec_id": exec_id,
+                    "success": true,
+                    "data": i
+                }))
+                .unwrap();
+                record_progress(&executor.pending_results, &executor.progress, &payload).await;
+                let pending = executor.pending_results.clone();
+                tokio::spawn(async move {
+                    deliver_result(&pending, &payload, DEFAULT_MAX_RESULT_SIZE).await;
+                });
+
+                let result = tokio::time::timeout(Duration::from_secs(5), rx)
+                    .await
+                    .unwrap()
+                    .unwrap();
+                assert_eq!(executor.take_progress(&exec_id).await, Some(Value::from(i)));
+                result["data"].as_i64()
+            }));
+        }
+
+        for (i, waiter) in waiters.into_iter().enumerate() {
+            assert_eq!(waiter.await.unwrap(), Some(i as i64));
+        }
+        assert!(executor.pending_results.is_empty().await);
+        assert!(executor.progress.lock().await.is_empty());
+    }
+}
@@ -1,7 +1,64 @@
 //! Script injection command for re-injecting registered scripts on page load.
 
+use crate::logging::mcp_log_error;
 use crate::script_registry::{ScriptEntry, ScriptType, SharedScriptRegistry};
-use tauri::{command, Runtime, State, WebviewWindow};
+use crate::{BRIDGE_PRESENT_CHECK, BRIDGE_SCRIPT};
+use tauri::{command, Manager, Runtime, State, Webview, WebviewWindow};
+
+/// Builds the script that hands `scripts` to the bridge's `__MCP_INJECT_SCRIPTS__`.
+///
+/// The bridge skips scripts that are already in the page, so running it twice is safe.
+fn injection_script(scripts: &[ScriptEntry]) -> String {
+    let scripts_json: Vec<serde_json::Value> = scripts
+        .iter()
+        .map(|entry| {
+            serde_json::json!({
+                "id": entry.id,
+                "type": match entry.script_type {
+                    ScriptType::Inline => "inline",
+                    ScriptType::Url => "url",
+                },
+                "content": entry.content
+            })
+        })
+        .collect();
+
+    format!(
+        "if (window.__MCP_INJECT_SCRIPTS__) {{ window.__MCP_INJECT_SCRIPTS__({}); }}",
+        serde_json::to_string(&scripts_json).unwrap_or_else(|_| "[]".to_string())
+    )
+}
+
+/// Restores the bridge and registered scripts after a page finishes loading.
+///
+/// Called from the plugin's page load hook. The bridge is normally injected as an init
+/// script, so it's only evaluated again if the page lost its console and error
+/// capture. Registered scripts are injected from here as well, since the bridge can
+/// only request them itself when the app enables `withGlobalTauri`.
+pub(crate) fn reinject_on_page_load<R: Runtime>(webview: &Webview<R>) {
+    let scripts: Vec<ScriptEntry> = match webview.try_state::<SharedScriptRegistry>() {
+        Some(registry) => match registry.lock() {
+            Ok(reg) => reg.get_all().into_iter().cloned().collect(),
+            Err(_) => Vec::new(),
+        },
+        None => Vec::new(),
+    };
+
+    let mut script = format!("if (!({BRIDGE_PRESENT_CHECK})) {{\n{BRIDGE_SCRIPT}\n}}\n");
+    if !scripts.is_empty() {
+        script.push_str(&injection_script(&scripts));
+    }
+    if let Some(mocks) = super::network_mocks::mocks_script(webview) {
+        script.push_str(&mocks);
+    }
+
+    if let Err(e) = webview.eval(&script) {
+        mcp_log_error(
+            "PLUGIN",
+            &format!("Failed to re-inject bridge into '{}': {e}", webview.label()),
+        );
+    }
+}
 
 /// Request script injection - called by bridge.js when a page loads.
 /// This command retrieves all registered scripts and injects them into the webview.
@@ -17,6 +74,13 @@ pub async fn request_script_injection<R: Runtime>(
         reg.get_all().iter().map(|e| (*e).clone()).collect()
     };
 
+    // Mock rules are restored even when no scripts are registered
+    if let Some(mocks) = super::network_mocks::mocks_script(&window) {
+        window
+            .eval(mocks)
+            .map_err(|e| format!("Failed to apply network mocks: {e}"))?;
+    }
+
     if scripts.is_empty() {
         return Ok(serde_json::json!({
             "injected": 0,
@@ -24,28 +88,8 @@ pub async fn request_script_injection<R: Runtime>(
         }));
     }
 
-    // Build the injection script
-    let scripts_json: Vec<serde_json::Value> = scripts
-        .iter()
-        .map(|entry| {
-            serde_json::json!({
-                "id": entry.id,
-                "type": match entry.script_type {
-                    ScriptType::Inline => "inline",
-                    ScriptType::Url => "url",
-                },
-                "content": entry.content
-            })
-        })
-        .collect();
-
-    let inject_script = format!(
-        "if (window.__MCP_INJECT_SCRIPTS__) {{ window.__MCP_INJECT_SCRIPTS__({}); }}",
-        serde_json::to_string(&scripts_json).unwrap_or_else(|_| "[]".to_string())
-    );
-
     window
-        .eval(&inject_script)
+        .eval(injection_script(&scripts))
         .map_err(|e| format!("Failed to inject scripts: {e}"))?;
 
     Ok(serde_json::json!({
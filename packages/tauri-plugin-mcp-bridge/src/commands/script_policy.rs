@@ -0,0 +1,155 @@
+//! Allow and deny rules for scripts sent by clients.
+
+use regex::Regex;
+use serde_json::Value;
+
+/// Rules deciding which client scripts `execute_js` may run.
+///
+/// A script is blocked if it matches any deny rule, or if there are allow rules and it
+/// matches none of them. With no rules (the default) every script runs. Rules are
+/// regular expressions searched anywhere in the script's source, so they're a guard
+/// against mistakes in semi-trusted setups rather than a sandbox: a determined client
+/// can always spell the same code differently.
+///
+/// Only scripts sent by clients are checked. The scripts the bridge runs for its own
+/// commands (clicking, querying elements, and so on) are not.
+#[derive(Debug, Clone, Default)]
+pub struct ScriptPolicy {
+    allow: Vec<Regex>,
+    deny: Vec<Regex>,
+}
+
+/// Why a script was blocked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BlockedScript {
+    /// The script matched this deny rule
+    Denied(String),
+    /// There are allow rules and the script matched none of them
+    NotAllowed,
+}
+
+impl ScriptPolicy {
+    /// Compiles the allow and deny rules.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(ScriptPolicy)` - The compiled policy
+    /// * `Err(String)` - Which pattern isn't a valid regular expression
+    pub fn new(allow: &[String], deny: &[String]) -> Result<Self, String> {
+        let compile = |patterns: &[String], kind: &str| {
+            patterns
+                .iter()
+                .map(|pattern| {
+                    Regex::new(pattern)
+                        .map_err(|e| format!("Invalid {kind} script pattern '{pattern}': {e}"))
+                })
+                .collect::<Result<Vec<_>, _>>()
+        };
+
+        Ok(Self {
+            allow: compile(allow, "allow")?,
+            deny: compile(deny, "deny")?,
+        })
+    }
+
+    /// Checks a script against the rules, deny rules first.
+    pub fn check(&self, script: &str) -> Result<(), BlockedScript> {
+        if let Some(rule) = self.deny.iter().find(|rule| rule.is_match(script)) {
+            return Err(BlockedScript::Denied(rule.as_str().to_string()));
+        }
+        if !self.allow.is_empty() && !self.allow.iter().any(|rule| rule.is_match(script)) {
+            return Err(BlockedScript::NotAllowed);
+        }
+        Ok(())
+    }
+}
+
+impl BlockedScript {
+    /// Builds the `execute_js` failure result for a blocked script.
+    ///
+    /// The result has `error_kind: "blocked"` and, for deny rules, the matched rule.
+    pub fn to_result(&self, exec_id: &str) -> Value {
+        match self {
+            BlockedScript::Denied(rule) => serde_json::json!({
+                "success": false,
+                "error": format!("Script blocked by deny rule '{rule}'"),
+                "error_kind": "blocked",
+                "rule": rule,
+                "exec_id": exec_id
+            }),
+            BlockedScript::NotAllowed => serde_json::json!({
+                "success": false,
+                "error": "Script blocked: it doesn't match any allow rule",
+                "error_kind": "blocked",
+                "rule": null,
+                "exec_id": exec_id
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn patterns(patterns: &[&str]) -> Vec<String> {
+        patterns.iter().map(|p| p.to_string()).collect()
+    }
+
+    #[test]
+    fn test_empty_policy_allows_everything() {
+        let policy = ScriptPolicy::default();
+        assert_eq!(policy.check("localStorage.clear()"), Ok(()));
+    }
+
+    #[test]
+    fn test_deny_rule_blocks_matching_scripts() {
+        let policy = ScriptPolicy::new(
+            &[],
+            &patterns(&[r"localStorage\.clear", r#"fetch\(\s*['`"]https?://"#]),
+        )
+        .unwrap();
+
+        assert_eq!(policy.check("document.title"), Ok(()));
+        assert_eq!(policy.check("fetch('/api/items')"), Ok(()));
+        assert_eq!(
+            policy.check("await fetch(\"https://example.com\")"),
+            Err(BlockedScript::Denied(
+                r#"fetch\(\s*['`"]https?://"#.to_string()
+            ))
+        );
+        assert_eq!(
+            policy.check("window.localStorage.clear()"),
+            Err(BlockedScript::Denied(r"localStorage\.clear".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_allow_rules_block_everything_else() {
+        let policy =
+            ScriptPolicy::new(&patterns(&[r"^document\.", r"^window\.scrollY$"]), &[]).unwrap();
+
+        assert_eq!(policy.check("document.title"), Ok(()));
+        assert_eq!(policy.check("window.scrollY"), Ok(()));
+        assert_eq!(
+            policy.check("window.location.href = '/'"),
+            Err(BlockedScript::NotAllowed)
+        );
+    }
+
+    #[test]
+    fn test_deny_rules_win_over_allow_rules() {
+        let policy = ScriptPolicy::new(&patterns(&["^document"]), &patterns(&["cookie"])).unwrap();
+        assert!(matches!(
+            policy.check("document.cookie"),
+            Err(BlockedScript::Denied(_))
+        ));
+    }
+
+    #[test]
+    fn test_invalid_pattern_is_rejected() {
+        let error = ScriptPolicy::new(&[], &patterns(&["fetch("])).unwrap_err();
+        assert!(error.contains("deny"));
+        assert!(error.contains("fetch("));
+    }
+}
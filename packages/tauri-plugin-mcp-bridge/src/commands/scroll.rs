@@ -0,0 +1,455 @@
+//! Scrolling the page or an element into view.
+
+use super::execute_js::run_script;
+use super::handles::ElementTarget;
+use super::list_windows::target_window;
+use super::script_executor::ScriptExecutor;
+use serde_json::Value;
+use tauri::{command, Runtime, State, WebviewWindow};
+
+/// Alignments accepted by `scrollIntoView` for `block` and `inline`.
+const SCROLL_ALIGNMENTS: &[&str] = &["start", "center", "end", "nearest"];
+
+/// Reports whether `el` overlaps the viewport. Shared by the scroll scripts.
+const IN_VIEWPORT_JS: &str = r#"
+function inViewport(el) {
+    const rect = el.getBoundingClientRect();
+    return rect.bottom > 0 && rect.right > 0
+        && rect.top < window.innerHeight && rect.left < window.innerWidth;
+}
+"#;
+
+/// Scrolls the element matching `selector` into view.
+///
+/// Expects `selector`, `block` and `inline` to be defined before it. Scrolling is
+/// instant so the position is final as soon as the script returns.
+const SCROLL_INTO_VIEW_SCRIPT: &str = r#"
+const el = document.querySelector(selector);
+if (el === null) {
+    throw new Error('No element matches selector ' + JSON.stringify(selector));
+}
+el.scrollIntoView({ behavior: 'instant', block: block, inline: inline });
+({ scrollX: window.scrollX, scrollY: window.scrollY, inViewport: inViewport(el) })
+"#;
+
+/// Scrolls the window to `x`/`y`, or to the top left of the element matching `selector`.
+///
+/// Expects `x`, `y` and `selector` to be defined before it. A null coordinate keeps the
+/// current scroll position on that axis.
+const SCROLL_TO_SCRIPT: &str = r#"
+let el = null;
+let left = x === null ? window.scrollX : x;
+let top = y === null ? window.scrollY : y;
+if (selector !== null) {
+    el = document.querySelector(selector);
+    if (el === null) {
+        throw new Error('No element matches selector ' + JSON.stringify(selector));
+    }
+    const rect = el.getBoundingClientRect();
+    left = rect.left + window.scrollX;
+    top = rect.top + window.scrollY;
+}
+window.scrollTo({ left: left, top: top, behavior: 'instant' });
+({
+    scrollX: window.scrollX,
+    scrollY: window.scrollY,
+    inViewport: el === null ? null : inViewport(el)
+})
+"#;
+
+/// Defines `scrollTarget()`, returning the element matching `selector` or the
+/// document's scrolling element when `selector` is null, and `scrollPosition(el)`.
+///
+/// Expects `selector` to be defined before it. Shared by the scroll position scripts.
+const SCROLL_TARGET_JS: &str = r#"
+function scrollTarget() {
+    if (selector === null) {
+        return document.scrollingElement || document.documentElement;
+    }
+    const el = document.querySelector(selector);
+    if (el === null) {
+        throw new Error('No element matches selector ' + JSON.stringify(selector));
+    }
+    return el;
+}
+function scrollPosition(el) {
+    return {
+        scrollTop: el.scrollTop,
+        scrollLeft: el.scrollLeft,
+        scrollHeight: el.scrollHeight,
+        scrollWidth: el.scrollWidth,
+        clientHeight: el.clientHeight,
+        clientWidth: el.clientWidth
+    };
+}
+"#;
+
+/// Sets the scroll position of the scroll target to `top`/`left` and waits for the
+/// `scroll` event, then evaluates to the position the browser clamped it to.
+///
+/// Expects `top` and `left` to be defined before it, along with [`SCROLL_TARGET_JS`]. A
+/// null coordinate keeps the current position on that axis. When the position doesn't
+/// change, no `scroll` event fires, so there's nothing to wait for; the wait is also
+/// capped, since browsers skip the event for elements that aren't rendered.
+const SET_SCROLL_SCRIPT: &str = r#"
+const el = scrollTarget();
+const before = [el.scrollTop, el.scrollLeft];
+// Scrolling the document fires the event on the document rather than the element
+const events = el === el.ownerDocument.scrollingElement ? el.ownerDocument : el;
+const options = { behavior: 'instant' };
+if (top !== null) options.top = top;
+if (left !== null) options.left = left;
+await new Promise(function(resolve) {
+    const timer = setTimeout(done, 500);
+    function done() {
+        clearTimeout(timer);
+        events.removeEventListener('scroll', done);
+        resolve();
+    }
+    events.addEventListener('scroll', done);
+    el.scrollTo(options);
+    if (el.scrollTop === before[0] && el.scrollLeft === before[1]) {
+        done();
+    }
+});
+scrollPosition(el)
+"#;
+
+/// Checks a `block` or `inline` alignment, falling back to `default` when omitted.
+fn alignment(name: &str, value: Option<String>, default: &str) -> Result<String, String> {
+    let value = value.unwrap_or_else(|| default.to_string());
+    if SCROLL_ALIGNMENTS.contains(&value.as_str()) {
+        Ok(value)
+    } else {
+        Err(format!(
+            "Invalid {name} '{value}': expected one of {}",
+            SCROLL_ALIGNMENTS.join(", ")
+        ))
+    }
+}
+
+/// Scrolls the element matching a CSS selector into view.
+///
+/// Uses the element's `scrollIntoView`, so every scrollable ancestor is scrolled as
+/// needed, not just the window. Scrolling is instant, ignoring smooth scrolling styles,
+/// so the element is in place when the command returns.
+///
+/// # Arguments
+///
+/// * `window` - The window to scroll in
+/// * `selector` - CSS selector of the element to bring into view
+/// * `handle` - Handle of the element from an earlier `execute_js` result, in place of
+///   `selector`
+/// * `by` - How `selector` locates the element: `"css"` (the default), `"xpath"`,
+///   `"text"`, `"id"` or `"name"`
+/// * `block` - Vertical alignment: `"start"` (the default), `"center"`, `"end"` or
+///   `"nearest"`
+/// * `inline` - Horizontal alignment: `"start"`, `"center"`, `"end"` or `"nearest"`
+///   (the default)
+/// * `window_label` - Label of the window to target (defaults to the calling window)
+/// * `executor` - Script executor state
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the element was scrolled into view
+///   - `data`: `{ scrollX, scrollY, inViewport }` with the window's scroll position
+///     afterwards and whether the element now overlaps the viewport
+///   - `error`: Error message (if nothing matched the selector)
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// await invoke('plugin:mcp-bridge|scroll_into_view', {
+///   selector: '#footer',
+///   block: 'center'
+/// });
+/// ```
+#[command]
+#[allow(clippy::too_many_arguments)]
+pub async fn scroll_into_view<R: Runtime>(
+    window: WebviewWindow<R>,
+    selector: Option<String>,
+    handle: Option<String>,
+    by: Option<String>,
+    block: Option<String>,
+    inline: Option<String>,
+    window_label: Option<String>,
+    executor: State<'_, ScriptExecutor>,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    let target = match ElementTarget::new(selector, handle, by) {
+        Ok(target) => target,
+        Err(error) => {
+            return Ok(serde_json::json!({
+                "success": false,
+                "error": error
+            }))
+        }
+    };
+
+    let block = alignment("block", block, "start")?;
+    let inline = alignment("inline", inline, "nearest")?;
+    let selector_js = serde_json::to_string(&target.selector()).map_err(|e| e.to_string())?;
+    let script = format!(
+        "const selector = {selector_js};\nconst block = '{block}';\nconst inline = '{inline}';\n{IN_VIEWPORT_JS}{SCROLL_INTO_VIEW_SCRIPT}"
+    );
+
+    run_script(
+        window,
+        target.wrap(script),
+        None,
+        None,
+        None,
+        None,
+        None,
+        executor,
+    )
+    .await
+}
+
+/// Scrolls the window to an absolute position or to an element.
+///
+/// Give either `x`/`y` page coordinates or a `selector`. With a selector, the window is
+/// scrolled so the element's top left corner is at the top left of the viewport, as
+/// far as the page can scroll. Scrolling is instant.
+///
+/// # Arguments
+///
+/// * `window` - The window to scroll
+/// * `x` - Horizontal scroll position in CSS pixels (keeps the current one if omitted)
+/// * `y` - Vertical scroll position in CSS pixels (keeps the current one if omitted)
+/// * `selector` - CSS selector of an element to scroll to, instead of `x`/`y`
+/// * `window_label` - Label of the window to target (defaults to the calling window)
+/// * `executor` - Script executor state
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the window was scrolled
+///   - `data`: `{ scrollX, scrollY, inViewport }` with the scroll position afterwards,
+///     which may differ from the one asked for at the edges of the page. `inViewport`
+///     is whether the element overlaps the viewport, or null without a selector.
+///   - `error`: Error message (if nothing matched the selector)
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// await invoke('plugin:mcp-bridge|scroll_to', { y: 0 });
+/// await invoke('plugin:mcp-bridge|scroll_to', { selector: '#results' });
+/// ```
+#[command]
+pub async fn scroll_to<R: Runtime>(
+    window: WebviewWindow<R>,
+    x: Option<f64>,
+    y: Option<f64>,
+    selector: Option<String>,
+    window_label: Option<String>,
+    executor: State<'_, ScriptExecutor>,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    if selector.is_some() && (x.is_some() || y.is_some()) {
+        return Err("Pass either x/y or selector, not both".to_string());
+    }
+    if selector.is_none() && x.is_none() && y.is_none() {
+        return Err("Missing x, y or selector argument".to_string());
+    }
+
+    let x_js = serde_json::to_string(&x).map_err(|e| e.to_string())?;
+    let y_js = serde_json::to_string(&y).map_err(|e| e.to_string())?;
+    let selector_js = serde_json::to_string(&selector).map_err(|e| e.to_string())?;
+    let script = format!(
+        "const x = {x_js};\nconst y = {y_js};\nconst selector = {selector_js};\n{IN_VIEWPORT_JS}{SCROLL_TO_SCRIPT}"
+    );
+
+    run_script(window, script, None, None, None, None, None, executor).await
+}
+
+/// Picks the scroll target from optional `selector`, `handle` and `by` arguments.
+///
+/// Without a selector or handle, the target is the document's scrolling element, and
+/// `None` is returned.
+fn scroll_target(
+    selector: Option<String>,
+    handle: Option<String>,
+    by: Option<String>,
+) -> Result<Option<ElementTarget>, String> {
+    if selector.is_none() && handle.is_none() {
+        if by.is_some() {
+            return Err("by only applies to selector".to_string());
+        }
+        return Ok(None);
+    }
+    ElementTarget::new(selector, handle, by).map(Some)
+}
+
+/// Builds a scroll position script for `target`, defining `selector` for it.
+fn scroll_position_script(
+    target: &Option<ElementTarget>,
+    script: String,
+) -> Result<String, String> {
+    let selector_js = serde_json::to_string(&target.as_ref().map(ElementTarget::selector))
+        .map_err(|e| e.to_string())?;
+    let script = format!("const selector = {selector_js};\n{SCROLL_TARGET_JS}{script}");
+    Ok(match target {
+        Some(target) => target.wrap(script),
+        None => script,
+    })
+}
+
+/// Returns the scroll position of the document or of a scrollable element.
+///
+/// Scroll containers like virtualized lists scroll independently of the window, so
+/// this reads `scrollTop` and `scrollLeft` of the given element, along with the sizes
+/// needed to tell how far it can scroll.
+///
+/// # Arguments
+///
+/// * `window` - The window to read the scroll position in
+/// * `selector` - Selector of the scrollable element (defaults to the document's
+///   scrolling element)
+/// * `handle` - Handle of the element from an earlier `execute_js` result, in place of
+///   `selector`
+/// * `by` - How `selector` locates the element: `"css"` (the default), `"xpath"`,
+///   `"text"`, `"id"` or `"name"`
+/// * `window_label` - Label of the window to target (defaults to the calling window)
+/// * `executor` - Script executor state
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the position was read
+///   - `data`: `{ scrollTop, scrollLeft, scrollHeight, scrollWidth, clientHeight,
+///     clientWidth }`. The element is scrolled to the end once `scrollTop` reaches
+///     `scrollHeight - clientHeight`.
+///   - `error`: Error message (if nothing matched the selector)
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const result = await invoke('plugin:mcp-bridge|get_scroll', {
+///   selector: '.message-list'
+/// });
+/// console.log(result.data.scrollTop);
+/// ```
+#[command]
+pub async fn get_scroll<R: Runtime>(
+    window: WebviewWindow<R>,
+    selector: Option<String>,
+    handle: Option<String>,
+    by: Option<String>,
+    window_label: Option<String>,
+    executor: State<'_, ScriptExecutor>,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    let target = match scroll_target(selector, handle, by) {
+        Ok(target) => target,
+        Err(error) => {
+            return Ok(serde_json::json!({
+                "success": false,
+                "error": error
+            }))
+        }
+    };
+    let script = scroll_position_script(&target, "scrollPosition(scrollTarget())".to_string())?;
+
+    run_script(window, script, None, None, None, None, None, executor).await
+}
+
+/// Sets the scroll position of the document or of a scrollable element.
+///
+/// Scrolling is instant, and the command returns once the `scroll` event has fired, so
+/// scroll handlers (like those loading more items into an infinite list) have been
+/// called. Positions past the edges are clamped by the browser, so the position
+/// returned is where the element actually ended up. Give a large `top` to scroll to
+/// the end.
+///
+/// # Arguments
+///
+/// * `window` - The window to scroll in
+/// * `top` - Vertical scroll position in CSS pixels (keeps the current one if omitted)
+/// * `left` - Horizontal scroll position in CSS pixels (keeps the current one if
+///   omitted)
+/// * `selector` - Selector of the scrollable element (defaults to the document's
+///   scrolling element)
+/// * `handle` - Handle of the element from an earlier `execute_js` result, in place of
+///   `selector`
+/// * `by` - How `selector` locates the element: `"css"` (the default), `"xpath"`,
+///   `"text"`, `"id"` or `"name"`
+/// * `window_label` - Label of the window to target (defaults to the calling window)
+/// * `executor` - Script executor state
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the element was scrolled
+///   - `data`: The position afterwards, like [`get_scroll`]
+///   - `error`: Error message (if nothing matched the selector)
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// await invoke('plugin:mcp-bridge|set_scroll', {
+///   selector: '.message-list',
+///   top: 1e9
+/// });
+/// ```
+#[command]
+#[allow(clippy::too_many_arguments)]
+pub async fn set_scroll<R: Runtime>(
+    window: WebviewWindow<R>,
+    top: Option<f64>,
+    left: Option<f64>,
+    selector: Option<String>,
+    handle: Option<String>,
+    by: Option<String>,
+    window_label: Option<String>,
+    executor: State<'_, ScriptExecutor>,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    if top.is_none() && left.is_none() {
+        return Err("Missing top or left argument".to_string());
+    }
+    let target = match scroll_target(selector, handle, by) {
+        Ok(target) => target,
+        Err(error) => {
+            return Ok(serde_json::json!({
+                "success": false,
+                "error": error
+            }))
+        }
+    };
+
+    let top_js = serde_json::to_string(&top).map_err(|e| e.to_string())?;
+    let left_js = serde_json::to_string(&left).map_err(|e| e.to_string())?;
+    let script = scroll_position_script(
+        &target,
+        format!("const top = {top_js};\nconst left = {left_js};\n{SET_SCROLL_SCRIPT}"),
+    )?;
+
+    run_script(window, script, None, None, None, None, None, executor).await
+}
@@ -0,0 +1,225 @@
+//! Reading and setting the text selection.
+
+use super::execute_js::run_script;
+use super::list_windows::target_window;
+use super::script_executor::ScriptExecutor;
+use serde_json::Value;
+use tauri::{command, Runtime, State, WebviewWindow};
+
+/// Describes the current selection.
+///
+/// The document selection doesn't cover the inside of text fields, so a focused
+/// `<input>` or `<textarea>` is read through its own selection instead.
+const GET_SELECTION_SCRIPT: &str = r#"
+function describeNode(node, offset) {
+    if (!node) return null;
+    const el = node.nodeType === Node.ELEMENT_NODE ? node : node.parentElement;
+    return {
+        node: node.nodeType === Node.TEXT_NODE ? '#text' : node.nodeName.toLowerCase(),
+        tag: el ? el.tagName.toLowerCase() : null,
+        id: el && el.id ? el.id : null,
+        classes: el ? Array.prototype.slice.call(el.classList) : [],
+        offset: offset
+    };
+}
+function toRect(rect) {
+    return { x: rect.x, y: rect.y, width: rect.width, height: rect.height };
+}
+const active = document.activeElement;
+let result;
+if (active && (active.tagName === 'INPUT' || active.tagName === 'TEXTAREA')
+        && typeof active.selectionStart === 'number') {
+    const start = active.selectionStart;
+    const end = active.selectionEnd;
+    const forward = active.selectionDirection !== 'backward';
+    result = {
+        source: 'input',
+        text: active.value.slice(start, end),
+        collapsed: start === end,
+        anchor: describeNode(active, forward ? start : end),
+        focus: describeNode(active, forward ? end : start),
+        rect: toRect(active.getBoundingClientRect())
+    };
+} else {
+    const selection = document.getSelection();
+    const hasRange = !!selection && selection.rangeCount > 0;
+    result = {
+        source: 'document',
+        text: selection ? selection.toString() : '',
+        collapsed: !selection || selection.isCollapsed,
+        anchor: hasRange ? describeNode(selection.anchorNode, selection.anchorOffset) : null,
+        focus: hasRange ? describeNode(selection.focusNode, selection.focusOffset) : null,
+        rect: hasRange ? toRect(selection.getRangeAt(0).getBoundingClientRect()) : null
+    };
+}
+result
+"#;
+
+/// Selects the characters from `start` to `end` of the element matching `selector`.
+///
+/// Expects `selector`, `start` and `end` (`null` for the end of the text) to be defined
+/// before it. Offsets count across all the text nodes inside the element.
+const SELECT_TEXT_SCRIPT: &str = r#"
+const el = document.querySelector(selector);
+if (!el) {
+    throw new Error('No element matches selector ' + JSON.stringify(selector));
+}
+let text;
+if (el.tagName === 'INPUT' || el.tagName === 'TEXTAREA') {
+    const length = el.value.length;
+    const to = end === null ? length : Math.min(end, length);
+    const from = Math.min(start, to);
+    el.focus();
+    el.setSelectionRange(from, to);
+    text = el.value.slice(from, to);
+} else {
+    const walker = document.createTreeWalker(el, NodeFilter.SHOW_TEXT);
+    const range = document.createRange();
+    const length = (el.textContent || '').length;
+    const to = end === null ? length : Math.min(end, length);
+    const from = Math.min(start, to);
+    range.setStart(el, 0);
+    range.setEnd(el, el.childNodes.length);
+    let seen = 0;
+    let startSet = false;
+    for (let node = walker.nextNode(); node; node = walker.nextNode()) {
+        const nodeLength = node.data.length;
+        if (!startSet && from <= seen + nodeLength) {
+            range.setStart(node, from - seen);
+            startSet = true;
+        }
+        if (to <= seen + nodeLength) {
+            range.setEnd(node, to - seen);
+            break;
+        }
+        seen += nodeLength;
+    }
+    const selection = document.getSelection();
+    selection.removeAllRanges();
+    selection.addRange(range);
+    text = selection.toString();
+}
+({ text: text })
+"#;
+
+/// Returns the current text selection.
+///
+/// When a text field has focus, its own selection is reported (`source: "input"`),
+/// since the page's selection doesn't see inside text fields. Otherwise the page's
+/// selection is (`source: "document"`). Nothing selected isn't an error: `text` is
+/// empty and `collapsed` is true.
+///
+/// # Arguments
+///
+/// * `window` - The window to read the selection of
+/// * `window_label` - Label of the window to target (defaults to the calling window)
+/// * `executor` - Script executor state
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the selection was read
+///   - `data`: `{ source, text, collapsed, anchor, focus, rect }`, where `anchor` and
+///     `focus` are `{ node, tag, id, classes, offset }` describing where the selection
+///     starts and ends (`node` is `"#text"` for text nodes, and the other fields
+///     describe the element holding it), and `rect` is the selection's bounding box
+///     in viewport coordinates. `anchor`, `focus` and `rect` are null with no selection.
+///   - `error`: Error message (if failed)
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const selection = await invoke('plugin:mcp-bridge|get_selection');
+/// console.log(selection.data.text);
+/// ```
+#[command]
+pub async fn get_selection<R: Runtime>(
+    window: WebviewWindow<R>,
+    window_label: Option<String>,
+    executor: State<'_, ScriptExecutor>,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    run_script(
+        window,
+        GET_SELECTION_SCRIPT.to_string(),
+        None,
+        None,
+        None,
+        None,
+        None,
+        executor,
+    )
+    .await
+}
+
+/// Selects text inside an element by character offsets.
+///
+/// In an `<input>` or `<textarea>` the field is focused and its value is selected.
+/// In any other element the offsets count the element's text content, across nested
+/// elements, and the page's selection is replaced. Offsets are in UTF-16 code units,
+/// like JavaScript string indices, and are clamped to the text's length.
+///
+/// # Arguments
+///
+/// * `window` - The window containing the element
+/// * `selector` - CSS selector of the element to select text in
+/// * `start` - Offset of the first selected character (defaults to 0)
+/// * `end` - Offset just past the last selected character (defaults to the end of the
+///   text)
+/// * `window_label` - Label of the window to target (defaults to the calling window)
+/// * `executor` - Script executor state
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the text was selected
+///   - `data`: `{ text }` with the selected text
+///   - `error`: Error message (if the element wasn't found or the offsets are invalid)
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// await invoke('plugin:mcp-bridge|select_text', {
+///   selector: '.editor p',
+///   start: 6,
+///   end: 11
+/// });
+/// ```
+#[command]
+pub async fn select_text<R: Runtime>(
+    window: WebviewWindow<R>,
+    selector: String,
+    start: Option<usize>,
+    end: Option<usize>,
+    window_label: Option<String>,
+    executor: State<'_, ScriptExecutor>,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    let start = start.unwrap_or(0);
+    if let Some(end) = end.filter(|&end| end < start) {
+        return Ok(serde_json::json!({
+            "success": false,
+            "error": format!("Invalid range: end ({end}) is before start ({start})")
+        }));
+    }
+
+    let selector_js = serde_json::to_string(&selector).map_err(|e| e.to_string())?;
+    let end_js = end.map_or_else(|| "null".to_string(), |end| end.to_string());
+    let script = format!(
+        "const selector = {selector_js};\nconst start = {start};\nconst end = {end_js};\n{SELECT_TEXT_SCRIPT}"
+    );
+
+    run_script(window, script, None, None, None, None, None, executor).await
+}
@@ -0,0 +1,39 @@
+//! Address of the bridge's WebSocket server.
+
+use serde_json::Value;
+use std::net::SocketAddr;
+use tauri::{command, State};
+
+/// The address the WebSocket server is actually bound to.
+///
+/// Managed as app state once the server has bound its port.
+pub struct ServerAddress(pub SocketAddr);
+
+/// Returns the address the bridge's WebSocket server is listening on.
+///
+/// This is the bound address rather than the configured one, so it reports the real
+/// port when the plugin scanned for a free port or was configured with port 0.
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `address`: The full `host:port` address
+///   - `host`: The bound IP address
+///   - `port`: The bound port
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const { port } = await invoke('plugin:mcp-bridge|get_server_address');
+/// ```
+#[command]
+pub async fn get_server_address(address: State<'_, ServerAddress>) -> Result<Value, String> {
+    let addr = address.0;
+    Ok(serde_json::json!({
+        "address": addr.to_string(),
+        "host": addr.ip().to_string(),
+        "port": addr.port()
+    }))
+}
@@ -0,0 +1,133 @@
+//! A map split into independently locked shards, for state touched by many concurrent
+//! executions.
+//!
+//! Each key lives in one shard, picked by its hash, so operations on keys in different
+//! shards don't wait for each other. Operations on the whole map, like
+//! [`ShardedMap::remove_where`], lock one shard at a time and are not atomic across
+//! shards.
+
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hash, Hasher};
+use tokio::sync::{Mutex, MutexGuard};
+
+/// Number of shards in a map created with [`ShardedMap::new`].
+pub const DEFAULT_SHARDS: usize = 16;
+
+/// A map from string keys to `V`, sharded to lower lock contention.
+pub struct ShardedMap<V> {
+    shards: Box<[Mutex<HashMap<String, V>>]>,
+    hasher: RandomState,
+}
+
+impl<V> ShardedMap<V> {
+    /// Creates an empty map with [`DEFAULT_SHARDS`] shards.
+    pub fn new() -> Self {
+        Self::with_shards(DEFAULT_SHARDS)
+    }
+
+    /// Creates an empty map with `shards` shards, at least one.
+    pub fn with_shards(shards: usize) -> Self {
+        Self {
+            shards: (0..shards.max(1))
+                .map(|_| Mutex::new(HashMap::new()))
+                .collect(),
+            hasher: RandomState::new(),
+        }
+    }
+
+    /// Locks the shard holding `key`, for operations that have to be atomic, like
+    /// checking for a key before inserting it.
+    ///
+    /// Only `key` (and the other keys of its shard) may be used through the guard.
+    pub async fn lock(&self, key: &str) -> MutexGuard<'_, HashMap<String, V>> {
+        let mut hasher = self.hasher.build_hasher();
+        key.hash(&mut hasher);
+        let index = (hasher.finish() % self.shards.len() as u64) as usize;
+        self.shards[index].lock().await
+    }
+
+    /// Inserts a value, returning the one it replaced.
+    pub async fn insert(&self, key: String, value: V) -> Option<V> {
+        self.lock(&key).await.insert(key, value)
+    }
+
+    /// Removes and returns the value of `key`.
+    pub async fn remove(&self, key: &str) -> Option<V> {
+        self.lock(key).await.remove(key)
+    }
+
+    /// Returns whether the map holds `key`.
+    pub async fn contains_key(&self, key: &str) -> bool {
+        self.lock(key).await.contains_key(key)
+    }
+
+    /// Returns the number of entries.
+    pub async fn len(&self) -> usize {
+        let mut len = 0;
+        for shard in self.shards.iter() {
+            len += shard.lock().await.len();
+        }
+        len
+    }
+
+    /// Returns whether the map has no entries.
+    pub async fn is_empty(&self) -> bool {
+        for shard in self.shards.iter() {
+            if !shard.lock().await.is_empty() {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Removes and returns the entries whose value `matches` picks.
+    pub async fn remove_where(&self, matches: impl Fn(&V) -> bool) -> Vec<(String, V)> {
+        let mut removed = Vec::new();
+        for shard in self.shards.iter() {
+            let mut shard = shard.lock().await;
+            let keys: Vec<String> = shard
+                .iter()
+                .filter(|(_, value)| matches(value))
+                .map(|(key, _)| key.clone())
+                .collect();
+            removed.extend(
+                keys.into_iter()
+                    .filter_map(|key| shard.remove(&key).map(|value| (key, value))),
+            );
+        }
+        removed
+    }
+}
+
+impl<V> Default for ShardedMap<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_entries_spread_over_shards_act_like_one_map() {
+        let map = ShardedMap::with_shards(4);
+        for i in 0..100 {
+            assert!(map.insert(format!("key-{i}"), i).await.is_none());
+        }
+        assert_eq!(map.len().await, 100);
+        assert_eq!(map.insert("key-7".to_string(), 700).await, Some(7));
+        assert_eq!(map.remove("key-7").await, Some(700));
+        assert!(!map.contains_key("key-7").await);
+
+        let mut even = map.remove_where(|value| value % 2 == 0).await;
+        even.sort_by_key(|(_, value)| *value);
+        assert_eq!(even.len(), 50);
+        assert_eq!(even[0], ("key-0".to_string(), 0));
+        assert_eq!(map.len().await, 49);
+
+        map.remove_where(|_| true).await;
+        assert!(map.is_empty().await);
+    }
+}
@@ -0,0 +1,196 @@
+//! Reading and seeding Web Storage (`localStorage` and `sessionStorage`).
+//!
+//! Web Storage only holds strings. Non-string values passed to `set_local_storage` are
+//! stored as their JSON text, and `get_local_storage` returns the raw strings unless
+//! asked to parse them as JSON.
+
+use super::execute_js::run_script;
+use super::list_windows::target_window;
+use super::script_executor::ScriptExecutor;
+use serde_json::Value;
+use tauri::{command, Runtime, State, WebviewWindow};
+
+/// Reads one key, or every key, from `storage`.
+///
+/// Expects `storage`, `key` and `parseJson` to be defined before it.
+const GET_STORAGE_SCRIPT: &str = r#"
+function decode(value) {
+    if (!parseJson || value === null) return value;
+    try {
+        return JSON.parse(value);
+    } catch (e) {
+        return value;
+    }
+}
+function readAll() {
+    const entries = {};
+    for (let i = 0; i < storage.length; i++) {
+        const name = storage.key(i);
+        entries[name] = decode(storage.getItem(name));
+    }
+    return { entries: entries, length: storage.length };
+}
+key !== null ? { key: key, value: decode(storage.getItem(key)) } : readAll()
+"#;
+
+/// Stores `value` under `key` in `storage`.
+///
+/// Expects `storage`, `key` and `value` to be defined before it.
+const SET_STORAGE_SCRIPT: &str = r#"
+storage.setItem(key, value);
+({ key: key, value: storage.getItem(key) })
+"#;
+
+/// Removes `key` from `storage`, or every key when `key` is null.
+///
+/// Expects `storage` and `key` to be defined before it.
+const CLEAR_STORAGE_SCRIPT: &str = r#"
+const cleared = key !== null ? (storage.getItem(key) !== null ? 1 : 0) : storage.length;
+if (key !== null) {
+    storage.removeItem(key);
+} else {
+    storage.clear();
+}
+({ cleared: cleared })
+"#;
+
+/// Maps the `storage` argument to the matching `window` property.
+fn storage_object(storage: Option<String>) -> Result<&'static str, String> {
+    match storage.as_deref().unwrap_or("local") {
+        "local" => Ok("window.localStorage"),
+        "session" => Ok("window.sessionStorage"),
+        other => Err(format!(
+            "Invalid storage '{other}': expected \"local\" or \"session\""
+        )),
+    }
+}
+
+/// Returns the entries of `localStorage` or `sessionStorage`.
+///
+/// # Arguments
+///
+/// * `window` - The window to read storage from
+/// * `key` - Only read this key (all keys are returned by default)
+/// * `storage` - `"local"` (the default) for `localStorage` or `"session"` for
+///   `sessionStorage`
+/// * `parse_json` - Parse values that are valid JSON, leaving other values as strings
+///   (defaults to false, returning the raw strings)
+/// * `window_label` - Label of the window to target (defaults to the calling window)
+/// * `executor` - Script executor state
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the storage was read
+///   - `data`: `{ key, value }` when `key` is given, where `value` is null if the key
+///     isn't set, or `{ entries, length }` with every key otherwise
+///   - `error`: Error message (if failed)
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const all = await invoke('plugin:mcp-bridge|get_local_storage');
+/// const settings = await invoke('plugin:mcp-bridge|get_local_storage', {
+///   key: 'settings',
+///   parseJson: true
+/// });
+/// ```
+#[command]
+pub async fn get_local_storage<R: Runtime>(
+    window: WebviewWindow<R>,
+    key: Option<String>,
+    storage: Option<String>,
+    parse_json: Option<bool>,
+    window_label: Option<String>,
+    executor: State<'_, ScriptExecutor>,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    let storage_js = storage_object(storage)?;
+    let key_js = serde_json::to_string(&key).map_err(|e| e.to_string())?;
+    let parse_json = parse_json.unwrap_or(false);
+    let script = format!(
+        "const storage = {storage_js};\nconst key = {key_js};\nconst parseJson = {parse_json};\n{GET_STORAGE_SCRIPT}"
+    );
+
+    run_script(window, script, None, None, None, None, None, executor).await
+}
+
+/// Sets a key in `localStorage` or `sessionStorage`, or clears keys from it.
+///
+/// String values are stored as-is. Any other JSON value (numbers, booleans, objects or
+/// arrays) is stored as its JSON text, so `{"theme":"dark"}` becomes the string
+/// `'{"theme":"dark"}'` and `42` becomes `'42'`.
+///
+/// # Arguments
+///
+/// * `window` - The window whose storage to change
+/// * `key` - Key to set, or to remove when `clear` is set
+/// * `value` - Value to store under `key` (required unless `clear` is set)
+/// * `clear` - Remove `key` instead of setting it, or every key when `key` is omitted
+/// * `storage` - `"local"` (the default) for `localStorage` or `"session"` for
+///   `sessionStorage`
+/// * `window_label` - Label of the window to target (defaults to the calling window)
+/// * `executor` - Script executor state
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the storage was changed
+///   - `data`: `{ key, value }` with the string that was stored, or `{ cleared }` with
+///     the number of keys removed
+///   - `error`: Error message (if failed, for example when the storage quota is full)
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// await invoke('plugin:mcp-bridge|set_local_storage', {
+///   key: 'settings',
+///   value: { theme: 'dark' }
+/// });
+/// await invoke('plugin:mcp-bridge|set_local_storage', { storage: 'session', clear: true });
+/// ```
+#[command]
+pub async fn set_local_storage<R: Runtime>(
+    window: WebviewWindow<R>,
+    key: Option<String>,
+    value: Option<Value>,
+    clear: Option<bool>,
+    storage: Option<String>,
+    window_label: Option<String>,
+    executor: State<'_, ScriptExecutor>,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    let storage_js = storage_object(storage)?;
+    let key_js = serde_json::to_string(&key).map_err(|e| e.to_string())?;
+
+    let script = if clear.unwrap_or(false) {
+        format!("const storage = {storage_js};\nconst key = {key_js};\n{CLEAR_STORAGE_SCRIPT}")
+    } else {
+        if key.is_none() {
+            return Err("Missing key argument".to_string());
+        }
+        let stored = match value {
+            Some(Value::String(text)) => text,
+            Some(other) => other.to_string(),
+            None => return Err("Missing value argument".to_string()),
+        };
+        let value_js = serde_json::to_string(&stored).map_err(|e| e.to_string())?;
+        format!(
+            "const storage = {storage_js};\nconst key = {key_js};\nconst value = {value_js};\n{SET_STORAGE_SCRIPT}"
+        )
+    };
+
+    run_script(window, script, None, None, None, None, None, executor).await
+}
@@ -0,0 +1,146 @@
+//! Checking scripts for syntax errors without running them.
+
+use super::execute_js::{
+    fast_path_body, is_sync_expression, prepare_script, run_script, uses_helpers,
+};
+use super::list_windows::target_window;
+use super::script_executor::ScriptExecutor;
+use super::script_policy::BlockedScript;
+use serde_json::Value;
+use tauri::{command, Runtime, State, WebviewWindow};
+
+/// Compiles `source` as the body of an async function without calling it, and evaluates
+/// to `{ valid, error, line, column }`.
+///
+/// `line` and `column` are whatever the engine attaches to the `SyntaxError` (WebKit
+/// does, Chromium doesn't), with `line` counted in the text the `Function` constructor
+/// builds, whose body starts on line 3. Expects `source` to be defined before it.
+const VALIDATE_SCRIPT: &str = r#"
+(function() {
+    const AsyncFunction = Object.getPrototypeOf(async function() {}).constructor;
+    try {
+        new AsyncFunction(source);
+        return { valid: true, error: null, line: null, column: null };
+    } catch (error) {
+        if (error && error.name === 'EvalError') {
+            throw new Error("The page's Content Security Policy doesn't allow compiling scripts");
+        }
+        if (!error || error.name !== 'SyntaxError') {
+            throw error;
+        }
+        const number = function(...values) {
+            return values.find(function(value) { return typeof value === 'number'; }) ?? null;
+        };
+        return {
+            valid: false,
+            error: 'SyntaxError: ' + error.message,
+            line: number(error.line, error.lineNumber),
+            column: number(error.column, error.columnNumber)
+        };
+    }
+})()
+"#;
+
+/// Lines the `Function` constructor puts before the body it's given.
+const FUNCTION_HEADER_LINES: i64 = 2;
+
+/// Checks a script for syntax errors without running it.
+///
+/// The script is prepared the way `execute_js` would prepare it, with `return` inserted
+/// in front of its last expression, and compiled in the page as the body of an async
+/// function that is never called. Nothing in the script runs, though compiling needs
+/// the page's CSP to allow `unsafe-eval`, like `execute_js`'s isolated mode. Also
+/// reports whether the script would take the fast path and whether the script policy
+/// would allow it.
+///
+/// # Arguments
+///
+/// * `window` - The window whose JavaScript engine compiles the script
+/// * `script` - JavaScript code to check
+/// * `window_label` - Label of the window to target (defaults to the calling window)
+/// * `executor` - Script executor state
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the check ran (an invalid script still succeeds)
+///   - `data`: Object with:
+///     - `valid`: Whether the script compiles
+///     - `error`: The syntax error message, or null
+///     - `line`: Line of the error in `script`, if the webview reports it
+///     - `column`: Column of the error, if the webview reports it. It's counted in the
+///       prepared script, so an inserted `return ` shifts it on the last statement
+///     - `prepared`: The function body that was compiled
+///     - `fast_path`: Whether `execute_js` would run the script on the fast path
+///     - `allowed`: Whether the script policy allows the script
+///     - `rule`: The deny rule that blocks the script, or null
+///   - `error`: Error message (if failed)
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const result = await invoke('plugin:mcp-bridge|validate_js', {
+///   script: 'const a = 1;\nconst a = 2;'
+/// });
+/// if (!result.data.valid) {
+///   console.log(result.data.error, result.data.line);
+/// }
+/// ```
+#[command]
+pub async fn validate_js<R: Runtime>(
+    window: WebviewWindow<R>,
+    script: String,
+    window_label: Option<String>,
+    executor: State<'_, ScriptExecutor>,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    let (allowed, rule) = match executor.script_policy.check(&script) {
+        Ok(()) => (true, None),
+        Err(BlockedScript::Denied(rule)) => (false, Some(rule)),
+        Err(BlockedScript::NotAllowed) => (false, None),
+    };
+
+    let fast_path = is_sync_expression(&script) && !uses_helpers(&script);
+    let (prepared, body_start_line) = if fast_path {
+        // The expression goes on the line after `return (`
+        (fast_path_body(&script), 1)
+    } else {
+        (prepare_script(&script), 0)
+    };
+    // Both preparations trim the script when they change it, dropping its leading lines
+    let trimmed_lines = if prepared == script {
+        0
+    } else {
+        script[..script.len() - script.trim_start().len()]
+            .matches('\n')
+            .count() as i64
+    };
+
+    let source = Value::String(prepared.clone());
+    let wrapped = format!("const source = {source};\n{VALIDATE_SCRIPT}");
+    let mut result = run_script(window, wrapped, None, None, None, None, None, executor).await?;
+    if result.get("success").and_then(|v| v.as_bool()) != Some(true) {
+        return Ok(result);
+    }
+
+    if let Some(data) = result.get_mut("data").and_then(|data| data.as_object_mut()) {
+        let line = data
+            .get("line")
+            .and_then(|line| line.as_i64())
+            .map(|line| line - FUNCTION_HEADER_LINES - body_start_line + trimmed_lines)
+            .filter(|&line| line >= 1);
+        data.insert("line".to_string(), Value::from(line));
+        data.insert("prepared".to_string(), Value::String(prepared));
+        data.insert("fast_path".to_string(), Value::Bool(fast_path));
+        data.insert("allowed".to_string(), Value::Bool(allowed));
+        data.insert("rule".to_string(), Value::from(rule));
+    }
+
+    Ok(result)
+}
@@ -0,0 +1,467 @@
+//! Waiting for elements to reach a given state, for arbitrary conditions to hold, or
+//! for the app to go idle.
+
+use super::execute_js::{in_frame, run_script, DEFAULT_TIMEOUT_MS};
+use super::handles::ElementTarget;
+use super::list_windows::target_window;
+use super::script_executor::ScriptExecutor;
+use serde_json::Value;
+use tauri::{command, Runtime, State, WebviewWindow};
+use uuid::Uuid;
+
+/// Extra time given to the script on top of its own timeout, so the page reports
+/// a timeout itself instead of `execute_js` giving up first.
+const SCRIPT_TIMEOUT_MARGIN_MS: u64 = 1000;
+
+/// Default time without requests or DOM changes after which the app counts as idle.
+pub const DEFAULT_QUIET_MS: u64 = 500;
+
+/// Waits for `selector` to reach `state`, re-checking on every DOM mutation.
+///
+/// Expects `selector`, `state` and `timeoutMs` to be defined before it. A short
+/// interval backs up the observer, since style changes from stylesheets (like a
+/// class toggled on an ancestor) don't always mutate the element itself.
+const WAIT_FOR_SELECTOR_SCRIPT: &str = r#"
+function isVisible(el) {
+    const style = window.getComputedStyle(el);
+    if (style.display === 'none' || style.visibility === 'hidden') return false;
+    const rect = el.getBoundingClientRect();
+    return rect.width > 0 && rect.height > 0;
+}
+function check() {
+    const el = document.querySelector(selector);
+    if (state === 'attached') return el !== null;
+    if (state === 'hidden') return el === null || !isVisible(el);
+    return el !== null && isVisible(el);
+}
+const started = performance.now();
+const found = check() || await new Promise(function(resolve) {
+    let observer, interval, timer;
+    function finish(result) {
+        observer.disconnect();
+        clearInterval(interval);
+        clearTimeout(timer);
+        resolve(result);
+    }
+    function recheck() {
+        if (check()) finish(true);
+    }
+    observer = new MutationObserver(recheck);
+    observer.observe(document.documentElement, { childList: true, subtree: true, attributes: true });
+    interval = setInterval(recheck, 100);
+    timer = setTimeout(function() { finish(false); }, timeoutMs);
+});
+({ found: found, elapsed_ms: Math.round(performance.now() - started) })
+"#;
+
+/// Evaluates `predicate` until its value is truthy, on animation frames at least
+/// `pollIntervalMs` apart.
+///
+/// Expects `predicate` (an async function), `timeoutMs` and `pollIntervalMs` to be
+/// defined before it. Hidden pages get no animation frames, so they fall back to
+/// timers. An error thrown by the predicate ends the wait.
+const WAIT_FOR_FUNCTION_SCRIPT: &str = r#"
+const started = performance.now();
+let value = await predicate();
+const satisfied = !!value || await new Promise(function(resolve, reject) {
+    let done = false;
+    let lastCheck = performance.now();
+    const timer = setTimeout(function() {
+        done = true;
+        resolve(false);
+    }, timeoutMs);
+    function schedule() {
+        if (document.hidden) {
+            setTimeout(check, Math.max(pollIntervalMs, 100));
+        } else {
+            requestAnimationFrame(check);
+        }
+    }
+    async function check() {
+        if (done) return;
+        if (performance.now() - lastCheck < pollIntervalMs) {
+            schedule();
+            return;
+        }
+        lastCheck = performance.now();
+        try {
+            value = await predicate();
+        } catch (error) {
+            done = true;
+            clearTimeout(timer);
+            reject(error);
+            return;
+        }
+        if (done) return;
+        if (value) {
+            done = true;
+            clearTimeout(timer);
+            resolve(true);
+        } else {
+            schedule();
+        }
+    }
+    schedule();
+});
+({ satisfied: satisfied, value: value === undefined ? null : value, elapsed_ms: Math.round(performance.now() - started) })
+"#;
+
+/// Waits until no request has been in flight and the DOM hasn't changed for `quietMs`.
+///
+/// Expects `quietMs` and `timeoutMs` to be defined before it. Requests are counted by
+/// the bridge's network capture in `__MCP_NETWORK_ACTIVITY__`, whose timestamps are
+/// from `Date.now()`. Changes to `style` attributes aren't significant, since
+/// JavaScript animations rewrite them on every frame.
+const WAIT_FOR_IDLE_SCRIPT: &str = r#"
+function pendingRequests() {
+    const activity = window.__MCP_NETWORK_ACTIVITY__;
+    return activity ? activity.pending : 0;
+}
+const started = performance.now();
+const idle = await new Promise(function(resolve) {
+    let lastActivity = started;
+    let observer, interval, timer;
+    function finish(result) {
+        observer.disconnect();
+        clearInterval(interval);
+        clearTimeout(timer);
+        resolve(result);
+    }
+    function check() {
+        const now = performance.now();
+        const activity = window.__MCP_NETWORK_ACTIVITY__;
+        if (activity && activity.pending > 0) {
+            lastActivity = now;
+        } else if (activity) {
+            lastActivity = Math.max(lastActivity, now - (Date.now() - activity.last_activity));
+        }
+        if (now - lastActivity >= quietMs) finish(true);
+    }
+    observer = new MutationObserver(function(mutations) {
+        const significant = mutations.some(function(mutation) {
+            return mutation.type !== 'attributes' || mutation.attributeName !== 'style';
+        });
+        if (significant) lastActivity = performance.now();
+    });
+    observer.observe(document.documentElement, {
+        childList: true, subtree: true, attributes: true, characterData: true
+    });
+    interval = setInterval(check, Math.min(50, quietMs));
+    timer = setTimeout(function() { finish(false); }, timeoutMs);
+    check();
+});
+({ idle: idle, elapsed_ms: Math.round(performance.now() - started), pending_requests: pendingRequests() })
+"#;
+
+/// Waits until an element matching a CSS selector reaches the requested state.
+///
+/// The check runs inside the webview and is re-evaluated whenever the DOM changes,
+/// so it resolves as soon as the condition holds instead of on a fixed poll.
+///
+/// # Arguments
+///
+/// * `window` - The window to wait in
+/// * `selector` - CSS selector of the element to wait for
+/// * `by` - How `selector` locates the element: `"css"` (the default), `"xpath"`,
+///   `"text"`, `"id"` or `"name"`
+/// * `timeout_ms` - How long to wait before giving up (defaults to 5000ms)
+/// * `state` - `"attached"` (in the DOM), `"visible"` (the default) or `"hidden"`
+///   (missing or not visible)
+/// * `frame_selector` - CSS selector of a same-origin `<iframe>` to look inside
+///   (defaults to the top-level page). The frame itself must already be loaded.
+/// * `window_label` - Label of the window to target (defaults to the calling window)
+/// * `executor` - Script executor state
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the element reached the state in time
+///   - `data`: `{ found, elapsed_ms }`
+///   - `error`: Error message (if timed out, or `state` or the selector is invalid)
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const result = await invoke('plugin:mcp-bridge|wait_for_selector', {
+///   selector: '#submit',
+///   state: 'visible',
+///   timeoutMs: 10000
+/// });
+/// ```
+#[command]
+#[allow(clippy::too_many_arguments)]
+pub async fn wait_for_selector<R: Runtime>(
+    window: WebviewWindow<R>,
+    selector: String,
+    by: Option<String>,
+    timeout_ms: Option<u64>,
+    state: Option<String>,
+    frame_selector: Option<String>,
+    window_label: Option<String>,
+    executor: State<'_, ScriptExecutor>,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    let timeout_ms = timeout_ms.unwrap_or(DEFAULT_TIMEOUT_MS);
+    let state = state.unwrap_or_else(|| "visible".to_string());
+    if !matches!(state.as_str(), "attached" | "visible" | "hidden") {
+        return Ok(serde_json::json!({
+            "success": false,
+            "error": format!(
+                "Invalid state '{state}': expected \"attached\", \"visible\" or \"hidden\""
+            )
+        }));
+    }
+
+    let target = match ElementTarget::new(Some(selector), None, by) {
+        Ok(target) => target,
+        Err(error) => {
+            return Ok(serde_json::json!({
+                "success": false,
+                "error": error
+            }))
+        }
+    };
+    let selector = target.selector();
+    let selector_js = serde_json::to_string(&selector).map_err(|e| e.to_string())?;
+    let state_js = serde_json::to_string(&state).map_err(|e| e.to_string())?;
+    let script = target.wrap(format!(
+        "const selector = {selector_js};\nconst state = {state_js};\nconst timeoutMs = {timeout_ms};\n{WAIT_FOR_SELECTOR_SCRIPT}"
+    ));
+    let script = match frame_selector {
+        Some(frame_selector) => in_frame(&frame_selector, &script),
+        None => script,
+    };
+
+    let result = run_script(
+        window,
+        script,
+        Some(timeout_ms.saturating_add(SCRIPT_TIMEOUT_MARGIN_MS)),
+        None,
+        None,
+        None,
+        None,
+        executor,
+    )
+    .await?;
+
+    if result.get("success").and_then(|v| v.as_bool()) != Some(true) {
+        return Ok(result);
+    }
+
+    let data = result.get("data").cloned().unwrap_or(Value::Null);
+    if data.get("found").and_then(|v| v.as_bool()) == Some(true) {
+        Ok(serde_json::json!({
+            "success": true,
+            "data": data
+        }))
+    } else {
+        Ok(serde_json::json!({
+            "success": false,
+            "data": data,
+            "error": format!(
+                "Timed out after {timeout_ms}ms waiting for '{selector}' to be {state}"
+            )
+        }))
+    }
+}
+
+/// Waits until a JavaScript expression evaluates to a truthy value.
+///
+/// For conditions a selector can't express, like `window.appReady === true` or a value
+/// in the app's store. The predicate is evaluated right away and then once per
+/// animation frame (or every `poll_interval_ms`), rather than on a `setInterval` timer,
+/// which browsers throttle heavily. It may `await`, and an error it throws ends the wait.
+/// Like `execute_js`, the predicate is checked against the app's script rules.
+///
+/// # Arguments
+///
+/// * `window` - The window to wait in
+/// * `predicate` - JavaScript expression to wait for, like `window.appReady === true`
+/// * `timeout_ms` - How long to wait before giving up (defaults to 5000ms)
+/// * `poll_interval_ms` - Minimum time between evaluations (defaults to every animation
+///   frame)
+/// * `window_label` - Label of the window to target (defaults to the calling window)
+/// * `executor` - Script executor state
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the predicate became truthy in time
+///   - `data`: `{ satisfied, value, elapsed_ms }` with the predicate's last value
+///   - `error`: Error message (if timed out, blocked, or the predicate threw)
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const result = await invoke('plugin:mcp-bridge|wait_for_function', {
+///   predicate: 'window.store?.getState().user?.id',
+///   timeoutMs: 10000
+/// });
+/// console.log(result.data.value); // The user ID
+/// ```
+#[command]
+pub async fn wait_for_function<R: Runtime>(
+    window: WebviewWindow<R>,
+    predicate: String,
+    timeout_ms: Option<u64>,
+    poll_interval_ms: Option<u64>,
+    window_label: Option<String>,
+    executor: State<'_, ScriptExecutor>,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    if let Err(blocked) = executor.script_policy.check(&predicate) {
+        return Ok(blocked.to_result(&Uuid::new_v4().to_string()));
+    }
+
+    let timeout_ms = timeout_ms.unwrap_or(DEFAULT_TIMEOUT_MS);
+    let poll_interval_ms = poll_interval_ms.unwrap_or(0);
+    let expression = predicate.trim().trim_end_matches(';');
+    let script = format!(
+        "const predicate = async () => (\n{expression}\n);\nconst timeoutMs = {timeout_ms};\nconst pollIntervalMs = {poll_interval_ms};\n{WAIT_FOR_FUNCTION_SCRIPT}"
+    );
+
+    let result = run_script(
+        window,
+        script,
+        Some(timeout_ms.saturating_add(SCRIPT_TIMEOUT_MARGIN_MS)),
+        None,
+        None,
+        None,
+        None,
+        executor,
+    )
+    .await?;
+
+    if result.get("success").and_then(|v| v.as_bool()) != Some(true) {
+        return Ok(result);
+    }
+
+    let data = result.get("data").cloned().unwrap_or(Value::Null);
+    if data.get("satisfied").and_then(|v| v.as_bool()) == Some(true) {
+        Ok(serde_json::json!({
+            "success": true,
+            "data": data
+        }))
+    } else {
+        Ok(serde_json::json!({
+            "success": false,
+            "data": data,
+            "error": format!("Timed out after {timeout_ms}ms waiting for the predicate to be truthy")
+        }))
+    }
+}
+
+/// Waits until the app is idle: no `fetch` or XHR request in flight and no significant
+/// DOM change for a quiet period.
+///
+/// More robust than a fixed sleep before checking the page, since it waits as long as
+/// the app needs and no longer. The quiet period starts over with every request that
+/// starts or ends and every DOM change, except `style` attribute changes, which
+/// JavaScript animations make constantly. Requests made before the bridge loaded and
+/// Tauri IPC calls aren't counted, and neither are timers, so work a timer starts
+/// after the quiet period isn't waited for.
+///
+/// # Arguments
+///
+/// * `window` - The window to wait in
+/// * `quiet_ms` - How long the app must stay quiet to count as idle (defaults to 500ms)
+/// * `timeout_ms` - How long to wait before giving up (defaults to 5000ms)
+/// * `window_label` - Label of the window to target (defaults to the calling window)
+/// * `executor` - Script executor state
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the app went idle in time
+///   - `data`: `{ idle, elapsed_ms, pending_requests }`, where `pending_requests` is
+///     the number of requests in flight when the wait ended
+///   - `error`: Error message (if timed out)
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// await invoke('plugin:mcp-bridge|click_element', { selector: '#load' });
+/// const result = await invoke('plugin:mcp-bridge|wait_for_idle', {
+///   quietMs: 300,
+///   timeoutMs: 10000
+/// });
+/// console.log(`Idle after ${result.data.elapsed_ms}ms`);
+/// ```
+#[command]
+pub async fn wait_for_idle<R: Runtime>(
+    window: WebviewWindow<R>,
+    quiet_ms: Option<u64>,
+    timeout_ms: Option<u64>,
+    window_label: Option<String>,
+    executor: State<'_, ScriptExecutor>,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    let quiet_ms = quiet_ms.unwrap_or(DEFAULT_QUIET_MS);
+    let timeout_ms = timeout_ms.unwrap_or(DEFAULT_TIMEOUT_MS);
+    if quiet_ms >= timeout_ms {
+        return Ok(serde_json::json!({
+            "success": false,
+            "error": format!(
+                "Invalid quiet_ms {quiet_ms}: must be shorter than timeout_ms ({timeout_ms})"
+            )
+        }));
+    }
+
+    let script = format!(
+        "const quietMs = {quiet_ms};\nconst timeoutMs = {timeout_ms};\n{WAIT_FOR_IDLE_SCRIPT}"
+    );
+
+    let result = run_script(
+        window,
+        script,
+        Some(timeout_ms.saturating_add(SCRIPT_TIMEOUT_MARGIN_MS)),
+        None,
+        None,
+        None,
+        None,
+        executor,
+    )
+    .await?;
+
+    if result.get("success").and_then(|v| v.as_bool()) != Some(true) {
+        return Ok(result);
+    }
+
+    let data = result.get("data").cloned().unwrap_or(Value::Null);
+    if data.get("idle").and_then(|v| v.as_bool()) == Some(true) {
+        Ok(serde_json::json!({
+            "success": true,
+            "data": data
+        }))
+    } else {
+        let pending = data
+            .get("pending_requests")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0);
+        Ok(serde_json::json!({
+            "success": false,
+            "data": data,
+            "error": format!(
+                "Timed out after {timeout_ms}ms waiting for the app to be idle ({pending} requests in flight)"
+            )
+        }))
+    }
+}
@@ -0,0 +1,151 @@
+//! Focusing, showing and hiding windows.
+
+use super::list_windows::target_window;
+use serde_json::Value;
+use tauri::{command, Runtime, WebviewWindow};
+
+/// Builds a command result from the window's state after an action.
+///
+/// The state is read back rather than assumed, since some platforms ignore the
+/// request (for example focus changes the window manager doesn't allow).
+fn visibility_result<R: Runtime>(
+    window: &WebviewWindow<R>,
+    action: tauri::Result<()>,
+    verb: &str,
+) -> Value {
+    if let Err(e) = action {
+        return serde_json::json!({
+            "success": false,
+            "error": format!("Failed to {verb} window '{}': {e}", window.label())
+        });
+    }
+
+    serde_json::json!({
+        "success": true,
+        "data": {
+            "window_label": window.label(),
+            "visible": window.is_visible().unwrap_or(false),
+            "focused": window.is_focused().unwrap_or(false),
+            "minimized": window.is_minimized().unwrap_or(false)
+        }
+    })
+}
+
+/// Brings a window to the front and gives it keyboard focus.
+///
+/// Hidden or minimized windows are shown and restored first, since most platforms
+/// can't focus them otherwise.
+///
+/// # Arguments
+///
+/// * `window` - The calling window
+/// * `window_label` - Label of the window to target (defaults to the calling window)
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the window was focused
+///   - `data`: `{ window_label, visible, focused, minimized }` after focusing
+///   - `error`: Error message (if failed or the window doesn't exist)
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// const result = await invoke('plugin:mcp-bridge|focus_window', {
+///   windowLabel: 'settings'
+/// });
+/// console.log(result.data.focused); // true
+/// ```
+#[command]
+pub async fn focus_window<R: Runtime>(
+    window: WebviewWindow<R>,
+    window_label: Option<String>,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    let action = (|| {
+        if !window.is_visible()? {
+            window.show()?;
+        }
+        if window.is_minimized()? {
+            window.unminimize()?;
+        }
+        window.set_focus()
+    })();
+    Ok(visibility_result(&window, action, "focus"))
+}
+
+/// Shows a hidden window.
+///
+/// # Arguments
+///
+/// * `window` - The calling window
+/// * `window_label` - Label of the window to target (defaults to the calling window)
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the window was shown
+///   - `data`: `{ window_label, visible, focused, minimized }` after showing
+///   - `error`: Error message (if failed or the window doesn't exist)
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// await invoke('plugin:mcp-bridge|show_window', { windowLabel: 'settings' });
+/// ```
+#[command]
+pub async fn show_window<R: Runtime>(
+    window: WebviewWindow<R>,
+    window_label: Option<String>,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    let action = window.show();
+    Ok(visibility_result(&window, action, "show"))
+}
+
+/// Hides a window without closing it.
+///
+/// # Arguments
+///
+/// * `window` - The calling window
+/// * `window_label` - Label of the window to target (defaults to the calling window)
+///
+/// # Returns
+///
+/// * `Ok(Value)` - JSON object containing:
+///   - `success`: Whether the window was hidden
+///   - `data`: `{ window_label, visible, focused, minimized }` after hiding
+///   - `error`: Error message (if failed or the window doesn't exist)
+///
+/// # Examples
+///
+/// ```typescript
+/// import { invoke } from '@tauri-apps/api/core';
+///
+/// await invoke('plugin:mcp-bridge|hide_window', { windowLabel: 'settings' });
+/// ```
+#[command]
+pub async fn hide_window<R: Runtime>(
+    window: WebviewWindow<R>,
+    window_label: Option<String>,
+) -> Result<Value, String> {
+    let window = match target_window(window, window_label) {
+        Ok(window) => window,
+        Err(error) => return Ok(error),
+    };
+
+    let action = window.hide();
+    Ok(visibility_result(&window, action, "hide"))
+}
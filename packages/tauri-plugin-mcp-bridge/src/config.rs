@@ -1,7 +1,22 @@
 //! Configuration for the MCP Bridge plugin.
 //!
 //! This module provides configuration options for customizing the plugin behavior,
-//! including the WebSocket server bind address.
+//! including the WebSocket server bind address and port.
+
+use crate::commands::introspect_commands::CommandInfo;
+use crate::commands::script_executor::{
+    DEFAULT_CHUNK_THRESHOLD, DEFAULT_MAX_CONCURRENT_EXECUTIONS, DEFAULT_MAX_FUNCTION_SOURCE,
+    DEFAULT_MAX_RESULT_SIZE, DEFAULT_RESULT_CHANNEL, DEFAULT_SHUTDOWN_GRACE,
+};
+use crate::commands::script_policy::ScriptPolicy;
+use crate::discovery::PORT_SCAN_RANGE;
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// Highest base port whose whole scan range fits in the valid port range.
+const MAX_BASE_PORT: u16 = u16::MAX - (PORT_SCAN_RANGE - 1);
 
 /// Configuration for the MCP Bridge plugin.
 #[derive(Clone, Debug)]
@@ -13,6 +28,82 @@ pub struct Config {
     /// The base port for the WebSocket server.
     /// Default: 9223. The plugin will scan up to 100 ports from this base.
     pub base_port: u16,
+    /// An exact port for the WebSocket server, which disables port scanning.
+    /// Default: None. Use `Some(0)` to let the OS pick a free port; the bound address
+    /// is logged and available from the `get_server_address` command.
+    pub port: Option<u16>,
+    /// Size in characters above which `execute_js` results are sent in chunks.
+    /// Default: 256KB. Larger results are split into events on the result channel
+    /// with `_chunk` appended, and reassembled before being returned.
+    pub chunk_threshold: usize,
+    /// Size in bytes above which the serialized data of an `execute_js` result is
+    /// truncated. Default: 50MB. Instead of failing, the result then holds the start of
+    /// the serialized data as a string, with `truncated: true` and `original_size`.
+    /// Results sent in chunks are truncated the same way once reassembled, and only
+    /// fail with `error_kind: "result_too_large"` past four times this size.
+    pub max_result_size: usize,
+    /// Length in characters after which the source of a function returned by
+    /// `execute_js` is cut off. Default: 10,000. Longer sources, such as functions from
+    /// minified bundles, come back with `truncated: true` and `original_length`.
+    pub max_function_source: usize,
+    /// Name of the event that `execute_js` results are sent through.
+    /// Default: "__script_result". Change it if the app uses that event name itself.
+    pub result_channel: String,
+    /// Whether a random suffix is added to `result_channel` every time the app starts.
+    /// Default: false. A random name can't collide with any event the app emits.
+    pub randomize_result_channel: bool,
+    /// How many `execute_js` calls may run at the same time.
+    /// Default: 32. Calls over the limit queue or fail, see `queue_executions`.
+    pub max_concurrent_executions: usize,
+    /// Whether `execute_js` calls over the concurrency limit wait for a free slot.
+    /// Default: true. When false, they fail right away with a "too many concurrent
+    /// executions" error instead.
+    pub queue_executions: bool,
+    /// How long running `execute_js` calls get to finish when the app exits.
+    /// Default: 5 seconds. New calls are refused during that time, and the calls still
+    /// running after it fail with `error_kind: "shutting_down"`.
+    pub shutdown_grace: Duration,
+    /// Whether failed commands get a screenshot of their window in the response.
+    /// Default: false. The screenshot adds the time of a capture, usually tens to
+    /// hundreds of milliseconds, to the failed response. At most one is taken every
+    /// 5 seconds, so a burst of failures doesn't slow down every response.
+    pub capture_on_failure: bool,
+    /// Directory that `execute_js_file` loads scripts from.
+    /// Default: None, which disables `execute_js_file`. Paths outside this directory
+    /// are rejected.
+    pub script_dir: Option<PathBuf>,
+    /// Directory that `set_file_input` reads files from.
+    /// Default: None, which disables `set_file_input`. Paths outside this directory
+    /// are rejected.
+    pub upload_dir: Option<PathBuf>,
+    /// The app's own commands, reported by `introspect_commands`.
+    /// Default: empty. Tauri can't list the commands in `invoke_handler` at runtime,
+    /// so the app registers them here.
+    pub commands: Vec<CommandInfo>,
+    /// Whether the bridge and registered scripts are restored after every page load.
+    /// Default: true. The bridge is injected as an init script, so this only adds a
+    /// check per page load, but it keeps console and error capture working if a page
+    /// loses it. Registered scripts are also re-injected from here.
+    pub reinject_on_load: bool,
+    /// Whether the bridge may run in release builds.
+    /// Default: false. The bridge gives full control over the app's webviews, so in a
+    /// release build the plugin does nothing unless this is explicitly enabled.
+    pub allow_in_release: bool,
+    /// Regular expressions a client script must match one of to run in `execute_js`.
+    /// Default: empty, which allows every script.
+    pub script_allowlist: Vec<String>,
+    /// Regular expressions that block a client script in `execute_js` when they match.
+    /// Default: empty. Checked before `script_allowlist`.
+    pub script_denylist: Vec<String>,
+    /// Maximum characters of each request and response body kept in the network log.
+    /// Default: None, which keeps no bodies. They may hold credentials or personal
+    /// data, and take up memory in the page.
+    pub network_body_limit: Option<usize>,
+    /// Whether CSS animations and transitions are disabled in every page as it loads.
+    /// Default: false. Same as calling `disable_animations` after each navigation, which
+    /// keeps screenshots and timings stable; `enable_animations` still turns them back
+    /// on until the next page load.
+    pub disable_animations: bool,
 }
 
 impl Default for Config {
@@ -20,6 +111,25 @@ impl Default for Config {
         Self {
             bind_address: "0.0.0.0".to_string(),
             base_port: 9223,
+            port: None,
+            chunk_threshold: DEFAULT_CHUNK_THRESHOLD,
+            max_result_size: DEFAULT_MAX_RESULT_SIZE,
+            max_function_source: DEFAULT_MAX_FUNCTION_SOURCE,
+            result_channel: DEFAULT_RESULT_CHANNEL.to_string(),
+            randomize_result_channel: false,
+            max_concurrent_executions: DEFAULT_MAX_CONCURRENT_EXECUTIONS,
+            queue_executions: true,
+            shutdown_grace: DEFAULT_SHUTDOWN_GRACE,
+            capture_on_failure: false,
+            script_dir: None,
+            upload_dir: None,
+            commands: Vec::new(),
+            reinject_on_load: true,
+            allow_in_release: false,
+            script_allowlist: Vec::new(),
+            script_denylist: Vec::new(),
+            network_body_limit: None,
+            disable_animations: false,
         }
     }
 }
@@ -29,7 +139,7 @@ impl Config {
     pub fn new(bind_address: &str) -> Self {
         Self {
             bind_address: bind_address.to_string(),
-            base_port: 9223,
+            ..Self::default()
         }
     }
 
@@ -37,8 +147,59 @@ impl Config {
     pub fn localhost_only() -> Self {
         Self {
             bind_address: "127.0.0.1".to_string(),
-            base_port: 9223,
+            ..Self::default()
+        }
+    }
+
+    /// Checks that the bind address, ports and script patterns can be used.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the configuration is valid
+    /// * `Err(String)` - What is wrong with it
+    pub fn validate(&self) -> Result<(), String> {
+        if self.bind_address.parse::<IpAddr>().is_err() {
+            return Err(format!(
+                "Invalid bind address '{}': expected an IP address like \"127.0.0.1\" or \"0.0.0.0\"",
+                self.bind_address
+            ));
+        }
+        if self.port.is_none() && (self.base_port == 0 || self.base_port > MAX_BASE_PORT) {
+            return Err(format!(
+                "Invalid base port {}: must be between 1 and {MAX_BASE_PORT} so that all {PORT_SCAN_RANGE} scanned ports are valid",
+                self.base_port
+            ));
         }
+        if self.max_result_size == 0 {
+            return Err("Invalid max_result_size 0: must be at least 1".to_string());
+        }
+        if self.max_concurrent_executions == 0 {
+            return Err("Invalid max_concurrent_executions 0: must be at least 1".to_string());
+        }
+        // Tauri rejects other characters in event names
+        let valid_char = |c: char| c.is_alphanumeric() || matches!(c, '-' | '/' | ':' | '_');
+        if self.result_channel.is_empty() || !self.result_channel.chars().all(valid_char) {
+            return Err(format!(
+                "Invalid result channel '{}': use only letters, digits, '-', '/', ':' and '_'",
+                self.result_channel
+            ));
+        }
+        self.script_policy()?;
+        Ok(())
+    }
+
+    /// Returns the name of the result event for this run of the app.
+    pub(crate) fn result_channel_name(&self) -> String {
+        if self.randomize_result_channel {
+            format!("{}_{}", self.result_channel, Uuid::new_v4().simple())
+        } else {
+            self.result_channel.clone()
+        }
+    }
+
+    /// Compiles the script allow and deny lists.
+    pub(crate) fn script_policy(&self) -> Result<ScriptPolicy, String> {
+        ScriptPolicy::new(&self.script_allowlist, &self.script_denylist)
     }
 }
 
@@ -113,8 +274,536 @@ impl Builder {
         self
     }
 
+    /// Sets an exact port for the WebSocket server instead of scanning from the base port.
+    ///
+    /// Initialization fails if the port is already in use.
+    ///
+    /// # Arguments
+    ///
+    /// * `port` - The port to bind, or 0 to let the OS pick a free one
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tauri_plugin_mcp_bridge::Builder;
+    ///
+    /// let builder = Builder::new().port(0);
+    /// ```
+    pub fn port(mut self, port: u16) -> Self {
+        self.config.port = Some(port);
+        self
+    }
+
+    /// Sets the size above which script results are sent in chunks.
+    ///
+    /// # Arguments
+    ///
+    /// * `chars` - Threshold in characters of the serialized result (default 256KB)
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tauri_plugin_mcp_bridge::Builder;
+    ///
+    /// let builder = Builder::new().chunk_threshold(64 * 1024);
+    /// ```
+    pub fn chunk_threshold(mut self, chars: usize) -> Self {
+        self.config.chunk_threshold = chars;
+        self
+    }
+
+    /// Sets the size above which the data of script results is truncated.
+    ///
+    /// Results this large are still returned, with the start of their serialized data
+    /// as a string, `truncated: true` and their full size in `original_size`. The whole
+    /// result is still sent from the webview, so this bounds what clients receive
+    /// rather than what the app serializes. Results large enough to be sent in chunks
+    /// (see [`Builder::chunk_threshold`]) are truncated once reassembled too, and are
+    /// only buffered up to four times this size, failing with
+    /// `error_kind: "result_too_large"` beyond it.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - Maximum size in bytes of the serialized data (default 50MB)
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tauri_plugin_mcp_bridge::Builder;
+    ///
+    /// let builder = Builder::new().max_result_size(5 * 1024 * 1024);
+    /// ```
+    pub fn max_result_size(mut self, bytes: usize) -> Self {
+        self.config.max_result_size = bytes;
+        self
+    }
+
+    /// Sets the length after which the source of functions returned by scripts is cut
+    /// off.
+    ///
+    /// A script that evaluates to a function returns its source, which for a function
+    /// from a minified bundle can be the whole bundle.
+    ///
+    /// # Arguments
+    ///
+    /// * `chars` - Maximum length in characters of the source (default 10,000)
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tauri_plugin_mcp_bridge::Builder;
+    ///
+    /// let builder = Builder::new().max_function_source(100_000);
+    /// ```
+    pub fn max_function_source(mut self, chars: usize) -> Self {
+        self.config.max_function_source = chars;
+        self
+    }
+
+    /// Sets the name of the event that script results are sent through.
+    ///
+    /// The default, `__script_result`, only needs changing when the app emits or
+    /// listens to an event of that name. Chunks of large results use the same name with
+    /// `_chunk` appended.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Event name, made of letters, digits, `-`, `/`, `:` and `_`
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tauri_plugin_mcp_bridge::Builder;
+    ///
+    /// let builder = Builder::new().result_channel("mcp-bridge:result");
+    /// ```
+    pub fn result_channel(mut self, name: &str) -> Self {
+        self.config.result_channel = name.to_string();
+        self
+    }
+
+    /// Sets whether the result event gets a random suffix every time the app starts.
+    ///
+    /// # Arguments
+    ///
+    /// * `randomize` - Add a random suffix to the result channel's name
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tauri_plugin_mcp_bridge::Builder;
+    ///
+    /// let builder = Builder::new().randomize_result_channel(true);
+    /// ```
+    pub fn randomize_result_channel(mut self, randomize: bool) -> Self {
+        self.config.randomize_result_channel = randomize;
+        self
+    }
+
+    /// Sets how many `execute_js` calls may run at the same time.
+    ///
+    /// # Arguments
+    ///
+    /// * `max` - Maximum number of concurrent executions (default 32)
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tauri_plugin_mcp_bridge::Builder;
+    ///
+    /// let builder = Builder::new().max_concurrent_executions(8);
+    /// ```
+    pub fn max_concurrent_executions(mut self, max: usize) -> Self {
+        self.config.max_concurrent_executions = max;
+        self
+    }
+
+    /// Sets whether calls over the concurrency limit wait for a free slot.
+    ///
+    /// # Arguments
+    ///
+    /// * `queue` - Wait for a slot (the default), or fail right away when false
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tauri_plugin_mcp_bridge::Builder;
+    ///
+    /// let builder = Builder::new().queue_executions(false);
+    /// ```
+    pub fn queue_executions(mut self, queue: bool) -> Self {
+        self.config.queue_executions = queue;
+        self
+    }
+
+    /// Sets how long running calls get to finish when the app exits.
+    ///
+    /// The exit waits until they finished or the grace period is over, so clients get
+    /// their results, or a `shutting_down` failure, instead of a dropped connection.
+    ///
+    /// # Arguments
+    ///
+    /// * `grace` - Longest time to delay the exit by (default 5 seconds)
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use tauri_plugin_mcp_bridge::Builder;
+    ///
+    /// let builder = Builder::new().shutdown_grace(Duration::from_secs(2));
+    /// ```
+    pub fn shutdown_grace(mut self, grace: Duration) -> Self {
+        self.config.shutdown_grace = grace;
+        self
+    }
+
+    /// Sets whether failed commands get a screenshot of their window.
+    ///
+    /// The base64 PNG is added to the failed response as `failure_screenshot`, which
+    /// shows the state the app was in when a command timed out or found no element.
+    /// Capturing delays the failed response, usually by tens to hundreds of
+    /// milliseconds, and at most one screenshot is taken every 5 seconds.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Capture a screenshot when a command fails (off by default)
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tauri_plugin_mcp_bridge::Builder;
+    ///
+    /// let builder = Builder::new().capture_on_failure(true);
+    /// ```
+    pub fn capture_on_failure(mut self, enabled: bool) -> Self {
+        self.config.capture_on_failure = enabled;
+        self
+    }
+
+    /// Sets the directory that `execute_js_file` may load scripts from.
+    ///
+    /// Without it, `execute_js_file` is disabled. Scripts outside this directory can't
+    /// be loaded, including through `..` or symlinks.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - Directory containing the scripts
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tauri_plugin_mcp_bridge::Builder;
+    ///
+    /// let builder = Builder::new().script_dir("tests/scripts");
+    /// ```
+    pub fn script_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.config.script_dir = Some(dir.into());
+        self
+    }
+
+    /// Sets the directory that `set_file_input` may read files from.
+    ///
+    /// Without it, `set_file_input` is disabled. Files outside this directory can't be
+    /// read, including through `..` or symlinks.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - Directory containing the files to upload
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tauri_plugin_mcp_bridge::Builder;
+    ///
+    /// let builder = Builder::new().upload_dir("tests/fixtures");
+    /// ```
+    pub fn upload_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.config.upload_dir = Some(dir.into());
+        self
+    }
+
+    /// Registers the names of the app's own commands for `introspect_commands`.
+    ///
+    /// List the commands passed to the app's `invoke_handler`. Names that are already
+    /// registered are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `names` - Command names
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tauri_plugin_mcp_bridge::Builder;
+    ///
+    /// let builder = Builder::new().with_commands(&["greet", "add_numbers", "get_config"]);
+    /// ```
+    pub fn with_commands(mut self, names: &[&str]) -> Self {
+        for name in names {
+            if !self.config.commands.iter().any(|info| info.name == *name) {
+                self.config.commands.push(CommandInfo::new(name));
+            }
+        }
+        self
+    }
+
+    /// Registers one of the app's own commands with metadata for `introspect_commands`.
+    ///
+    /// Replaces an earlier registration of the same name.
+    ///
+    /// # Arguments
+    ///
+    /// * `info` - The command's name, description and argument names
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tauri_plugin_mcp_bridge::{Builder, CommandInfo};
+    ///
+    /// let builder = Builder::new().command(
+    ///     CommandInfo::new("greet").description("Greets someone").arg("name"),
+    /// );
+    /// ```
+    pub fn command(mut self, info: CommandInfo) -> Self {
+        match self
+            .config
+            .commands
+            .iter_mut()
+            .find(|c| c.name == info.name)
+        {
+            Some(existing) => *existing = info,
+            None => self.config.commands.push(info),
+        }
+        self
+    }
+
+    /// Sets whether the bridge and registered scripts are restored after every page load.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Check and re-inject on every page load (the default), or rely on
+    ///   the init script alone when false
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tauri_plugin_mcp_bridge::Builder;
+    ///
+    /// let builder = Builder::new().reinject_on_load(false);
+    /// ```
+    pub fn reinject_on_load(mut self, enabled: bool) -> Self {
+        self.config.reinject_on_load = enabled;
+        self
+    }
+
+    /// Allows the bridge to run in release builds.
+    ///
+    /// Only enable this for builds that are tested end-to-end, never for builds that
+    /// are shipped to users: anyone who can reach the WebSocket server can run
+    /// JavaScript in the app.
+    ///
+    /// # Arguments
+    ///
+    /// * `allow` - Whether to start the bridge when `debug_assertions` are off
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tauri_plugin_mcp_bridge::Builder;
+    ///
+    /// let builder = Builder::new().allow_in_release(true);
+    /// ```
+    pub fn allow_in_release(mut self, allow: bool) -> Self {
+        self.config.allow_in_release = allow;
+        self
+    }
+
+    /// Allows only client scripts that match one of the allow patterns.
+    ///
+    /// Can be called several times to add patterns. Patterns are regular expressions
+    /// searched anywhere in the script. Without any, every script not denied runs.
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern` - Regular expression a script may match
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tauri_plugin_mcp_bridge::Builder;
+    ///
+    /// // Only read-only DOM queries
+    /// let builder = Builder::new().allow_script_pattern(r"^document\.querySelector");
+    /// ```
+    pub fn allow_script_pattern(mut self, pattern: &str) -> Self {
+        self.config.script_allowlist.push(pattern.to_string());
+        self
+    }
+
+    /// Blocks client scripts that match the pattern.
+    ///
+    /// Can be called several times to add patterns. Deny patterns are checked before
+    /// allow patterns. Blocked scripts fail with `error_kind: "blocked"` and the matched
+    /// pattern in `rule`. These are guard rails against mistakes, not a sandbox: the
+    /// same code can usually be written in a way a pattern doesn't catch.
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern` - Regular expression that blocks a script
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tauri_plugin_mcp_bridge::Builder;
+    ///
+    /// let builder = Builder::new()
+    ///     .deny_script_pattern(r"localStorage\.clear")
+    ///     .deny_script_pattern(r#"fetch\(\s*['"`]https?://"#);
+    /// ```
+    pub fn deny_script_pattern(mut self, pattern: &str) -> Self {
+        self.config.script_denylist.push(pattern.to_string());
+        self
+    }
+
+    /// Records request and response bodies in the network log.
+    ///
+    /// Bodies are off by default, since they may hold credentials or personal data.
+    /// Only text bodies are recorded.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_chars` - Characters kept of each body; longer bodies are cut off
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tauri_plugin_mcp_bridge::Builder;
+    ///
+    /// let builder = Builder::new().capture_network_bodies(4096);
+    /// ```
+    pub fn capture_network_bodies(mut self, max_chars: usize) -> Self {
+        self.config.network_body_limit = Some(max_chars);
+        self
+    }
+
+    /// Disables CSS animations and transitions in every page as it loads.
+    ///
+    /// # Arguments
+    ///
+    /// * `disabled` - Add the `disable_animations` stylesheet to every page, or leave
+    ///   animations alone (the default) when false
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tauri_plugin_mcp_bridge::Builder;
+    ///
+    /// let builder = Builder::new().disable_animations(true);
+    /// ```
+    pub fn disable_animations(mut self, disabled: bool) -> Self {
+        self.config.disable_animations = disabled;
+        self
+    }
+
     /// Builds the plugin with the configured options.
     pub fn build<R: tauri::Runtime>(self) -> tauri::plugin::TauriPlugin<R> {
         crate::init_with_config(self.config)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_config_is_valid() {
+        assert!(Config::default().validate().is_ok());
+        assert!(Config::localhost_only().validate().is_ok());
+    }
+
+    #[test]
+    fn test_invalid_bind_address_is_rejected() {
+        let error = Config::new("localhost:9223").validate().unwrap_err();
+        assert!(error.contains("localhost:9223"));
+    }
+
+    #[test]
+    fn test_base_port_range_is_checked() {
+        let mut config = Config {
+            base_port: u16::MAX,
+            ..Config::default()
+        };
+        assert!(config.validate().is_err());
+
+        config.base_port = MAX_BASE_PORT;
+        assert!(config.validate().is_ok());
+
+        // An exact port skips scanning, so the base port no longer matters
+        config.base_port = u16::MAX;
+        config.port = Some(0);
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_registered_commands_are_unique() {
+        let builder = Builder::new()
+            .with_commands(&["greet", "add_numbers"])
+            .command(CommandInfo::new("greet").arg("name"))
+            .with_commands(&["greet", "get_config"]);
+        let names: Vec<&str> = builder
+            .config
+            .commands
+            .iter()
+            .map(|info| info.name.as_str())
+            .collect();
+        assert_eq!(names, ["greet", "add_numbers", "get_config"]);
+        assert_eq!(builder.config.commands[0].args, ["name"]);
+    }
+
+    #[test]
+    fn test_invalid_script_pattern_is_rejected() {
+        let builder = Builder::new().deny_script_pattern("fetch(");
+        assert!(builder.config.validate().is_err());
+
+        let builder = Builder::new().deny_script_pattern(r"fetch\(");
+        assert!(builder.config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_zero_concurrency_is_rejected() {
+        let config = Config {
+            max_concurrent_executions: 0,
+            ..Config::default()
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_zero_max_result_size_is_rejected() {
+        let config = Config {
+            max_result_size: 0,
+            ..Config::default()
+        };
+        assert!(config.validate().is_err());
+        assert!(Builder::new().max_result_size(1).config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_result_channel_is_checked_and_randomized() {
+        let mut config = Config {
+            result_channel: "app results".to_string(),
+            ..Config::default()
+        };
+        assert!(config.validate().is_err());
+
+        config.result_channel = "mcp-bridge:result".to_string();
+        assert!(config.validate().is_ok());
+        assert_eq!(config.result_channel_name(), "mcp-bridge:result");
+
+        config.randomize_result_channel = true;
+        let name = config.result_channel_name();
+        assert!(name.starts_with("mcp-bridge:result_"));
+        assert_ne!(name, config.result_channel_name());
+    }
+}
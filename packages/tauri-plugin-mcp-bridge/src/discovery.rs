@@ -3,6 +3,9 @@
 //! This module provides a lightweight mechanism for multiple Tauri apps
 //! to coexist on the same machine by finding available ports dynamically.
 
+/// Number of ports scanned from the base port when no exact port is configured.
+pub const PORT_SCAN_RANGE: u16 = 100;
+
 /// Finds an available port for the WebSocket server.
 ///
 /// # Arguments
@@ -14,10 +17,10 @@
 ///
 /// An available port number in the range base_port to base_port+99, or base_port if none are available.
 pub fn find_available_port(bind_address: &str, base_port: u16) -> u16 {
-    let max_attempts = 100;
-
-    for offset in 0..max_attempts {
-        let port = base_port + offset;
+    for offset in 0..PORT_SCAN_RANGE {
+        let Some(port) = base_port.checked_add(offset) else {
+            break;
+        };
         if is_port_available(bind_address, port) {
             return port;
         }
@@ -28,6 +31,45 @@ pub fn find_available_port(bind_address: &str, base_port: u16) -> u16 {
     base_port
 }
 
+/// Binds the listener for the WebSocket server.
+///
+/// With an exact `port`, only that port is tried; port 0 lets the OS pick a free one.
+/// Otherwise ports are scanned from `base_port` and the first free one is used.
+/// The listener stays bound, so no other process can take the port between discovery
+/// and the server starting.
+///
+/// # Returns
+///
+/// * `Ok(TcpListener)` - The bound, non-blocking listener
+/// * `Err(String)` - Why no port could be bound
+pub fn bind_listener(
+    bind_address: &str,
+    base_port: u16,
+    port: Option<u16>,
+) -> Result<std::net::TcpListener, String> {
+    use std::net::TcpListener;
+
+    let listener = match port {
+        Some(port) => TcpListener::bind(format!("{bind_address}:{port}")).map_err(|e| {
+            format!("Failed to bind MCP Bridge WebSocket server to {bind_address}:{port}: {e}")
+        })?,
+        None => (0..PORT_SCAN_RANGE)
+            .filter_map(|offset| base_port.checked_add(offset))
+            .find_map(|port| TcpListener::bind(format!("{bind_address}:{port}")).ok())
+            .ok_or_else(|| {
+                format!(
+                    "Failed to bind MCP Bridge WebSocket server: no available port in {base_port}-{} on {bind_address}",
+                    base_port.saturating_add(PORT_SCAN_RANGE - 1)
+                )
+            })?,
+    };
+
+    listener
+        .set_nonblocking(true)
+        .map_err(|e| format!("Failed to configure MCP Bridge WebSocket listener: {e}"))?;
+    Ok(listener)
+}
+
 /// Checks if a port is available on the specified bind address.
 fn is_port_available(bind_address: &str, port: u16) -> bool {
     use std::net::TcpListener;
@@ -53,6 +95,21 @@ mod tests {
         assert!(port < 9323);
     }
 
+    #[test]
+    fn test_bind_listener_with_os_assigned_port() {
+        let listener = bind_listener("127.0.0.1", 9223, Some(0)).unwrap();
+        assert_ne!(listener.local_addr().unwrap().port(), 0);
+    }
+
+    #[test]
+    fn test_bind_listener_fails_on_taken_port() {
+        let taken = bind_listener("127.0.0.1", 9223, Some(0)).unwrap();
+        let port = taken.local_addr().unwrap().port();
+
+        let error = bind_listener("127.0.0.1", 9223, Some(port)).unwrap_err();
+        assert!(error.contains(&format!("127.0.0.1:{port}")));
+    }
+
     #[test]
     fn test_port_availability_custom_base() {
         let port = find_available_port("127.0.0.1", 9400);
@@ -79,15 +79,30 @@ pub mod screenshot;
 pub mod script_registry;
 pub mod websocket;
 
+pub use commands::CommandInfo;
 pub use config::{Builder, Config};
 
-use commands::ScriptExecutor;
-use discovery::find_available_port;
+use commands::{
+    CommandCatalog, DomSnapshots, EventSubscriptions, FailureCapture, MutationSubscriptions,
+    NetworkMocks, ScriptDir, ScriptExecutor, ServerAddress, StartTime, UploadDir, WindowGone,
+};
+use discovery::bind_listener;
 use logging::{mcp_log_error, mcp_log_info};
 use monitor::IPCMonitor;
 use script_registry::create_shared_registry;
 use std::sync::{Arc, Mutex};
-use tauri::{plugin::Builder as PluginBuilder, plugin::TauriPlugin, Manager, Runtime};
+use tauri::webview::PageLoadEvent;
+use tauri::{
+    plugin::Builder as PluginBuilder, plugin::TauriPlugin, Manager, RunEvent, Runtime, WindowEvent,
+};
+
+/// Script injected into every page to capture console output and errors and to run
+/// registered scripts.
+pub(crate) const BRIDGE_SCRIPT: &str = include_str!("bridge.js");
+
+/// Evaluates to whether the bridge's console and error capture is installed in a page.
+pub(crate) const BRIDGE_PRESENT_CHECK: &str =
+    "Array.isArray(window.__MCP_CONSOLE_LOGS__) && Array.isArray(window.__MCP_PAGE_ERRORS__)";
 
 /// Initializes the MCP Bridge plugin.
 ///
@@ -128,6 +143,9 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
 
 /// Initializes the MCP Bridge plugin with custom configuration.
 ///
+/// In release builds the returned plugin is inert (no commands, no WebSocket server)
+/// unless `config.allow_in_release` is set, so the bridge can't be shipped by accident.
+///
 /// # Arguments
 ///
 /// * `config` - The configuration options for the plugin
@@ -145,8 +163,43 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
 ///     .expect("error while running tauri application");
 /// ```
 pub fn init_with_config<R: Runtime>(config: Config) -> TauriPlugin<R> {
+    if !cfg!(debug_assertions) {
+        if !config.allow_in_release {
+            mcp_log_info(
+                "PLUGIN",
+                "MCP Bridge is disabled in release builds; use allow_in_release to enable it",
+            );
+            return PluginBuilder::<R>::new("mcp-bridge").build();
+        }
+        mcp_log_info(
+            "PLUGIN",
+            "MCP Bridge is enabled in a release build because allow_in_release is set",
+        );
+    }
+
     let bind_address = config.bind_address.clone();
     let base_port = config.base_port;
+    let port = config.port;
+    let chunk_threshold = config.chunk_threshold;
+    let max_result_size = config.max_result_size;
+    let max_function_source = config.max_function_source;
+    let max_concurrent_executions = config.max_concurrent_executions;
+    let queue_executions = config.queue_executions;
+    let shutdown_grace = config.shutdown_grace;
+    let capture_on_failure = config.capture_on_failure;
+    let script_dir = config.script_dir.clone();
+    let upload_dir = config.upload_dir.clone();
+    let app_commands = config.commands.clone();
+    let reinject_on_load = config.reinject_on_load;
+
+    // The network capture in the bridge script reads its body limit from the page
+    let mut init_script = match config.network_body_limit {
+        Some(limit) => format!("window.__MCP_NETWORK_BODY_LIMIT__ = {limit};\n{BRIDGE_SCRIPT}"),
+        None => BRIDGE_SCRIPT.to_string(),
+    };
+    if config.disable_animations {
+        init_script.push_str(commands::animations::DISABLE_ANIMATIONS_SCRIPT);
+    }
 
     PluginBuilder::<R>::new("mcp-bridge")
         .invoke_handler(tauri::generate_handler![
@@ -159,14 +212,135 @@ pub fn init_with_config<R: Runtime>(config: Config) -> TauriPlugin<R> {
             commands::ipc_monitor::get_ipc_events,
             commands::execute_js::execute_js,
             commands::script_executor::script_result,
+            commands::script_executor::cancel_execution,
             commands::screenshot::capture_native_screenshot,
+            commands::screenshot::take_screenshot,
             commands::list_windows::list_windows,
             commands::script_injection::request_script_injection,
+            commands::wait::wait_for_selector,
+            commands::interaction::click_element,
+            commands::interaction::set_input_value,
+            commands::dom_snapshot::get_dom_snapshot,
+            commands::navigate::navigate,
+            commands::server_address::get_server_address,
+            commands::console_logs::get_console_logs,
+            commands::console_logs::clear_console_logs,
+            commands::page_errors::get_page_errors,
+            commands::query_elements::query_elements,
+            commands::storage::get_local_storage,
+            commands::storage::set_local_storage,
+            commands::cookies::get_cookies,
+            commands::cookies::set_cookie,
+            commands::batch::batch_execute,
+            commands::scroll::scroll_into_view,
+            commands::scroll::scroll_to,
+            commands::page_metrics::get_page_metrics,
+            commands::script_executor::get_execution_metrics,
+            commands::execute_js_file::execute_js_file,
+            commands::computed_style::get_computed_style,
+            commands::interaction::type_text,
+            commands::interaction::press_key,
+            commands::introspect_commands::introspect_commands,
+            commands::invoke_command::invoke_command,
+            commands::app_config::get_app_config,
+            commands::navigate::reload,
+            commands::window_visibility::focus_window,
+            commands::window_visibility::show_window,
+            commands::window_visibility::hide_window,
+            commands::screenshot::take_element_screenshot,
+            commands::accessibility_tree::get_accessibility_tree,
+            commands::resize_window::get_window_size,
+            commands::resize_window::set_window_size,
+            commands::ping::ping,
+            commands::network_log::get_network_log,
+            commands::network_log::clear_network_log,
+            commands::network_mocks::mock_request,
+            commands::network_mocks::unmock_request,
+            commands::network_mocks::clear_mocks,
+            commands::interaction::hover_element,
+            commands::attributes::get_attribute,
+            commands::attributes::set_attribute,
+            commands::interaction::select_option,
+            commands::interaction::drag_and_drop,
+            commands::page_source::get_html,
+            commands::wait::wait_for_function,
+            commands::interaction::focus_element,
+            commands::interaction::blur_element,
+            commands::bridge_commands::list_bridge_commands,
+            commands::query_elements::element_state,
+            commands::selection::get_selection,
+            commands::selection::select_text,
+            commands::clipboard::read_clipboard,
+            commands::clipboard::write_clipboard,
+            commands::command_metrics::get_metrics,
+            commands::wait::wait_for_idle,
+            commands::page_title::get_title,
+            commands::page_title::set_title,
+            commands::navigate::get_url,
+            commands::navigate::go_back,
+            commands::navigate::go_forward,
+            commands::animations::disable_animations,
+            commands::animations::enable_animations,
+            commands::scroll::get_scroll,
+            commands::scroll::set_scroll,
+            commands::validate_js::validate_js,
+            commands::contexts::clear_context,
+            commands::page_info::get_page_info,
+            commands::file_input::set_file_input,
+            commands::dom_snapshot::diff_snapshots,
+            commands::query_elements::get_bounding_rects,
         ])
-        .js_init_script(include_str!("bridge.js").to_string())
+        .js_init_script(init_script)
+        .on_page_load(move |webview, payload| {
+            // Scripts still running on the old page can't report back anymore
+            if payload.event() == PageLoadEvent::Started {
+                ScriptExecutor::fail_window_later(
+                    webview.app_handle(),
+                    webview.label(),
+                    WindowGone::Navigated,
+                );
+            }
+            if reinject_on_load && payload.event() == PageLoadEvent::Finished {
+                commands::script_injection::reinject_on_page_load(webview);
+            }
+        })
+        .on_event(move |app, event| match event {
+            RunEvent::WindowEvent {
+                label,
+                event: WindowEvent::Destroyed,
+                ..
+            } => ScriptExecutor::fail_window_later(app, label, WindowGone::Closed),
+            // Let running scripts finish, so clients don't just see the connection drop
+            RunEvent::ExitRequested { code, api, .. }
+                if ScriptExecutor::drain_before_exit(app, *code, shutdown_grace) =>
+            {
+                api.prevent_exit();
+            }
+            RunEvent::Exit => ScriptExecutor::fail_on_exit(app),
+            _ => {}
+        })
         .setup(move |app, _api| {
-            // Initialize script executor state
-            app.manage(ScriptExecutor::new());
+            app.manage(StartTime(std::time::Instant::now()));
+
+            // Initialize script executor state and its result listeners
+            let executor = ScriptExecutor::with_chunk_threshold(chunk_threshold)
+                .with_max_result_size(max_result_size)
+                .with_max_function_source(max_function_source)
+                .with_concurrency_limit(max_concurrent_executions, queue_executions)
+                .with_script_policy(config.script_policy()?)
+                .with_result_channel(config.result_channel_name());
+            executor.listen(app);
+            app.manage(executor);
+            ScriptExecutor::spawn_sweeper(app);
+            app.manage(ScriptDir(script_dir));
+            app.manage(UploadDir(upload_dir));
+            app.manage(FailureCapture::new(capture_on_failure));
+            app.manage(CommandCatalog(app_commands));
+            app.manage(MutationSubscriptions::default());
+            MutationSubscriptions::listen(app);
+            app.manage(EventSubscriptions::default());
+            app.manage(NetworkMocks::default());
+            app.manage(DomSnapshots::default());
 
             // Initialize IPC monitor state
             let monitor = Arc::new(Mutex::new(IPCMonitor::new()));
@@ -176,8 +350,9 @@ pub fn init_with_config<R: Runtime>(config: Config) -> TauriPlugin<R> {
             let script_registry = create_shared_registry();
             app.manage(script_registry);
 
-            // Find an available port for WebSocket server
-            let port = find_available_port(&bind_address, base_port);
+            // Bind the WebSocket server's port now so a taken port fails initialization
+            config.validate()?;
+            let listener = bind_listener(&bind_address, base_port, port)?;
 
             // Log app information for debugging
             let app_name = app
@@ -191,7 +366,9 @@ pub fn init_with_config<R: Runtime>(config: Config) -> TauriPlugin<R> {
             // Start WebSocket server in background
             let app_handle = app.clone();
             let (ws_server, _event_rx) =
-                websocket::WebSocketServer::new(port, &bind_address, app_handle);
+                websocket::WebSocketServer::from_listener(listener, app_handle)?;
+            let address = ws_server.local_addr();
+            app.manage(ServerAddress(address));
 
             tauri::async_runtime::spawn(async move {
                 if let Err(e) = ws_server.start().await {
@@ -202,7 +379,7 @@ pub fn init_with_config<R: Runtime>(config: Config) -> TauriPlugin<R> {
             mcp_log_info(
                 "PLUGIN",
                 &format!(
-                    "MCP Bridge plugin initialized for '{app_name}' ({identifier}) on {bind_address}:{port}"
+                    "MCP Bridge plugin initialized for '{app_name}' ({identifier}) on {address}"
                 ),
             );
             Ok(())
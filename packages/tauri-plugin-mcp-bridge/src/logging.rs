@@ -1,7 +1,80 @@
+//! Plugin logging.
+//!
+//! With the `tracing` feature (on by default) messages are `tracing` events with the
+//! target `tauri_plugin_mcp_bridge` and a `scope` field, so the host app's subscriber
+//! decides what gets shown. Without it they're printed to stdout and stderr.
+
+use std::future::Future;
+
+/// Logs an informational message.
 pub fn mcp_log_info(scope: &str, msg: &str) {
+    #[cfg(feature = "tracing")]
+    tracing::info!(target: "tauri_plugin_mcp_bridge", scope, "{msg}");
+    #[cfg(not(feature = "tracing"))]
     println!("[MCP][{scope}][INFO] {msg}");
 }
 
+/// Logs a message about routine activity that's only interesting when debugging.
+pub fn mcp_log_debug(scope: &str, msg: &str) {
+    #[cfg(feature = "tracing")]
+    tracing::debug!(target: "tauri_plugin_mcp_bridge", scope, "{msg}");
+    #[cfg(not(feature = "tracing"))]
+    println!("[MCP][{scope}][DEBUG] {msg}");
+}
+
+/// Logs a problem the plugin recovered from.
+pub fn mcp_log_warn(scope: &str, msg: &str) {
+    #[cfg(feature = "tracing")]
+    tracing::warn!(target: "tauri_plugin_mcp_bridge", scope, "{msg}");
+    #[cfg(not(feature = "tracing"))]
+    eprintln!("[MCP][{scope}][WARN] {msg}");
+}
+
+/// Logs an error.
 pub fn mcp_log_error(scope: &str, msg: &str) {
+    #[cfg(feature = "tracing")]
+    tracing::error!(target: "tauri_plugin_mcp_bridge", scope, "{msg}");
+    #[cfg(not(feature = "tracing"))]
     eprintln!("[MCP][{scope}][ERROR] {msg}");
 }
+
+/// Runs a script execution inside an `execute_js` span carrying its `exec_id`.
+///
+/// Everything logged while the execution runs is attributed to it. Without the
+/// `tracing` feature the future is simply awaited.
+pub async fn in_execution_span<F: Future>(exec_id: &str, execution: F) -> F::Output {
+    #[cfg(feature = "tracing")]
+    {
+        use tracing::Instrument as _;
+        let span = tracing::debug_span!(target: "tauri_plugin_mcp_bridge", "execute_js", exec_id);
+        execution.instrument(span).await
+    }
+    #[cfg(not(feature = "tracing"))]
+    {
+        let _ = exec_id;
+        execution.await
+    }
+}
+
+/// Runs a WebSocket request inside a `ws_request` span carrying its `request_id`.
+///
+/// Script executions started by the request get their own `execute_js` span nested
+/// inside this one, so a single trace ties the request to the execution.
+pub async fn in_request_span<F: Future>(request_id: &str, command: &str, request: F) -> F::Output {
+    #[cfg(feature = "tracing")]
+    {
+        use tracing::Instrument as _;
+        let span = tracing::debug_span!(
+            target: "tauri_plugin_mcp_bridge",
+            "ws_request",
+            request_id,
+            command
+        );
+        request.instrument(span).await
+    }
+    #[cfg(not(feature = "tracing"))]
+    {
+        let _ = (request_id, command);
+        request.await
+    }
+}
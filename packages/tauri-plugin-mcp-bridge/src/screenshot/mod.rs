@@ -1,3 +1,4 @@
+use serde::Serialize;
 use tauri::{Runtime, WebviewWindow};
 
 // Platform-specific modules
@@ -35,10 +36,52 @@ pub enum ScreenshotError {
     #[error("Encoding failed: {0}")]
     EncodeFailed(String),
 
+    #[error("Decoding failed: {0}")]
+    DecodeFailed(String),
+
+    #[error("Crop region {0:?} is outside the {1}x{2} image")]
+    OutOfBounds(CropRect, u32, u32),
+
     #[error("Timeout exceeded")]
     Timeout,
 }
 
+/// A region of a screenshot, in image pixels
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct CropRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl CropRect {
+    /// Converts a rect in CSS pixels to the image pixels it covers, clipped to the image.
+    ///
+    /// `scale` is the number of image pixels per CSS pixel. Partially covered pixels
+    /// are included. Returns `None` if no part of the rect is inside the image.
+    pub fn from_css_rect(
+        (x, y, width, height): (f64, f64, f64, f64),
+        scale: f64,
+        (image_width, image_height): (u32, u32),
+    ) -> Option<Self> {
+        let left = (x * scale).floor().max(0.0);
+        let top = (y * scale).floor().max(0.0);
+        let right = ((x + width) * scale).ceil().min(f64::from(image_width));
+        let bottom = ((y + height) * scale).ceil().min(f64::from(image_height));
+        if right <= left || bottom <= top {
+            return None;
+        }
+
+        Some(Self {
+            x: left as u32,
+            y: top as u32,
+            width: (right - left) as u32,
+            height: (bottom - top) as u32,
+        })
+    }
+}
+
 /// Platform-specific screenshot implementation trait
 pub trait PlatformScreenshot {
     /// Capture a screenshot of the current viewport
@@ -47,27 +90,97 @@ pub trait PlatformScreenshot {
     ) -> Result<Screenshot, ScreenshotError>;
 }
 
-/// Capture a screenshot of the current viewport using platform-specific APIs
-pub async fn capture_viewport_screenshot<R: Runtime>(
+impl Screenshot {
+    /// Returns the `(width, height)` of the image, read from the PNG header.
+    pub fn png_dimensions(&self) -> Option<(u32, u32)> {
+        const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+        // The IHDR chunk always comes first: 4-byte length, "IHDR", then width and height
+        if self.data.len() < 24 || !self.data.starts_with(PNG_SIGNATURE) {
+            return None;
+        }
+        let width = u32::from_be_bytes(self.data[16..20].try_into().ok()?);
+        let height = u32::from_be_bytes(self.data[20..24].try_into().ok()?);
+        Some((width, height))
+    }
+
+    /// Returns a new screenshot containing only `rect` of this one.
+    ///
+    /// The image is decoded to 8 bits per channel and re-encoded as PNG.
+    pub fn crop(&self, rect: CropRect) -> Result<Screenshot, ScreenshotError> {
+        let decode_error = |e: png::DecodingError| ScreenshotError::DecodeFailed(e.to_string());
+        let encode_error = |e: png::EncodingError| ScreenshotError::EncodeFailed(e.to_string());
+
+        let mut decoder = png::Decoder::new(self.data.as_slice());
+        decoder.set_transformations(png::Transformations::normalize_to_color8());
+        let mut reader = decoder.read_info().map_err(decode_error)?;
+        let mut pixels = vec![0; reader.output_buffer_size()];
+        let frame = reader.next_frame(&mut pixels).map_err(decode_error)?;
+
+        let fits = |offset: u32, length: u32, total: u32| {
+            length > 0 && offset.checked_add(length).is_some_and(|end| end <= total)
+        };
+        if !fits(rect.x, rect.width, frame.width) || !fits(rect.y, rect.height, frame.height) {
+            return Err(ScreenshotError::OutOfBounds(
+                rect,
+                frame.width,
+                frame.height,
+            ));
+        }
+
+        let (color_type, _) = reader.output_color_type();
+        let pixel_size = color_type.samples();
+        let row_start = rect.x as usize * pixel_size;
+        let row_end = row_start + rect.width as usize * pixel_size;
+        let mut cropped = Vec::with_capacity((row_end - row_start) * rect.height as usize);
+        for row in pixels
+            .chunks(frame.line_size)
+            .skip(rect.y as usize)
+            .take(rect.height as usize)
+        {
+            cropped.extend_from_slice(&row[row_start..row_end]);
+        }
+
+        let mut data = Vec::new();
+        let mut encoder = png::Encoder::new(&mut data, rect.width, rect.height);
+        encoder.set_color(color_type);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().map_err(encode_error)?;
+        writer.write_image_data(&cropped).map_err(encode_error)?;
+        writer.finish().map_err(encode_error)?;
+
+        Ok(Screenshot { data })
+    }
+}
+
+/// Capture the current viewport as raw PNG bytes using platform-specific APIs
+pub fn capture_viewport_png<R: Runtime>(
     window: &WebviewWindow<R>,
-    format: &str,
-    _quality: u8,
-) -> Result<String, ScreenshotError> {
-    // Dispatch to platform-specific implementation
+) -> Result<Screenshot, ScreenshotError> {
     #[cfg(target_os = "macos")]
-    let screenshot = macos::capture_viewport(window)?;
+    {
+        macos::capture_viewport(window)
+    }
 
     #[cfg(target_os = "windows")]
-    let screenshot = windows::capture_viewport(window)?;
+    {
+        windows::capture_viewport(window)
+    }
 
     #[cfg(target_os = "linux")]
-    let screenshot = linux::capture_viewport(window)?;
+    {
+        linux::capture_viewport(window)
+    }
 
     #[cfg(target_os = "ios")]
-    let screenshot = ios::capture_viewport(window)?;
+    {
+        ios::capture_viewport(window)
+    }
 
     #[cfg(target_os = "android")]
-    let screenshot = android::capture_viewport(window)?;
+    {
+        android::capture_viewport(window)
+    }
 
     #[cfg(not(any(
         target_os = "macos",
@@ -76,7 +189,18 @@ pub async fn capture_viewport_screenshot<R: Runtime>(
         target_os = "ios",
         target_os = "android"
     )))]
-    return Err(ScreenshotError::PlatformUnsupported);
+    {
+        Err(ScreenshotError::PlatformUnsupported)
+    }
+}
+
+/// Capture a screenshot of the current viewport using platform-specific APIs
+pub async fn capture_viewport_screenshot<R: Runtime>(
+    window: &WebviewWindow<R>,
+    format: &str,
+    _quality: u8,
+) -> Result<String, ScreenshotError> {
+    let screenshot = capture_viewport_png(window)?;
 
     // Convert to base64 data URL
     let mime_type = if format == "jpeg" {
@@ -95,3 +219,77 @@ pub async fn capture_viewport_screenshot<R: Runtime>(
 
     Ok(data_url)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Encodes an RGBA image whose red channel is the pixel's x and green its y.
+    fn gradient(width: u32, height: u32) -> Screenshot {
+        let mut pixels = Vec::new();
+        for y in 0..height {
+            for x in 0..width {
+                pixels.extend_from_slice(&[x as u8, y as u8, 0, 255]);
+            }
+        }
+        let mut data = Vec::new();
+        let mut encoder = png::Encoder::new(&mut data, width, height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().unwrap();
+        writer.write_image_data(&pixels).unwrap();
+        writer.finish().unwrap();
+        Screenshot { data }
+    }
+
+    #[test]
+    fn test_crops_to_rect() {
+        let rect = CropRect {
+            x: 2,
+            y: 1,
+            width: 3,
+            height: 2,
+        };
+        let cropped = gradient(8, 6).crop(rect).unwrap();
+        assert_eq!(cropped.png_dimensions(), Some((3, 2)));
+
+        let decoder = png::Decoder::new(cropped.data.as_slice());
+        let mut reader = decoder.read_info().unwrap();
+        let mut pixels = vec![0; reader.output_buffer_size()];
+        reader.next_frame(&mut pixels).unwrap();
+        assert_eq!(&pixels[..4], &[2, 1, 0, 255]);
+        assert_eq!(&pixels[pixels.len() - 4..], &[4, 2, 0, 255]);
+    }
+
+    #[test]
+    fn test_rejects_rect_outside_image() {
+        let rect = CropRect {
+            x: 6,
+            y: 0,
+            width: 4,
+            height: 1,
+        };
+        assert!(matches!(
+            gradient(8, 6).crop(rect),
+            Err(ScreenshotError::OutOfBounds(..))
+        ));
+    }
+
+    #[test]
+    fn test_scales_and_clips_css_rect() {
+        let rect = CropRect::from_css_rect((-5.0, 10.25, 20.0, 10.0), 2.0, (100, 30));
+        assert_eq!(
+            rect,
+            Some(CropRect {
+                x: 0,
+                y: 20,
+                width: 30,
+                height: 10,
+            })
+        );
+        assert_eq!(
+            CropRect::from_css_rect((0.0, 40.0, 10.0, 10.0), 1.0, (100, 30)),
+            None
+        );
+    }
+}
@@ -5,7 +5,7 @@
 //! to all connected clients and can receive commands from them.
 
 use crate::commands::{resolve_window_with_context, WindowContext};
-use crate::logging::{mcp_log_error, mcp_log_info};
+use crate::logging::{in_request_span, mcp_log_debug, mcp_log_error, mcp_log_info, mcp_log_warn};
 use crate::script_registry::{ScriptEntry, ScriptType, SharedScriptRegistry};
 use futures_util::{SinkExt, StreamExt};
 use serde_json;
@@ -14,6 +14,7 @@ use tauri::{AppHandle, Manager, Runtime, WebviewWindow};
 use tokio::net::{TcpListener, TcpStream};
 use tokio::sync::{broadcast, mpsc};
 use tokio_tungstenite::{accept_async, tungstenite::Message};
+use uuid::Uuid;
 
 /// WebSocket server for real-time event streaming to MCP clients.
 ///
@@ -48,6 +49,7 @@ use tokio_tungstenite::{accept_async, tungstenite::Message};
 /// ```
 pub struct WebSocketServer<R: Runtime> {
     addr: SocketAddr,
+    listener: Option<std::net::TcpListener>,
     event_tx: broadcast::Sender<String>,
     app: AppHandle<R>,
 }
@@ -89,6 +91,7 @@ impl<R: Runtime> WebSocketServer<R> {
         (
             Self {
                 addr,
+                listener: None,
                 event_tx,
                 app,
             },
@@ -96,6 +99,43 @@ impl<R: Runtime> WebSocketServer<R> {
         )
     }
 
+    /// Creates a new WebSocket server that accepts connections on an already bound listener.
+    ///
+    /// Binding up front lets the caller report binding errors right away and learn the
+    /// actual address when port 0 was requested.
+    ///
+    /// # Arguments
+    ///
+    /// * `listener` - A bound, non-blocking listener
+    /// * `app` - The Tauri application handle
+    ///
+    /// # Returns
+    ///
+    /// * `Ok((WebSocketServer, Receiver))` - The server and a broadcast receiver for events
+    /// * `Err(std::io::Error)` - If the listener's address can't be read
+    pub fn from_listener(
+        listener: std::net::TcpListener,
+        app: AppHandle<R>,
+    ) -> std::io::Result<(Self, broadcast::Receiver<String>)> {
+        let addr = listener.local_addr()?;
+        let (event_tx, event_rx) = broadcast::channel(100);
+
+        Ok((
+            Self {
+                addr,
+                listener: Some(listener),
+                event_tx,
+                app,
+            },
+            event_rx,
+        ))
+    }
+
+    /// Returns the address the server listens on.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+
     /// Starts the WebSocket server and begins accepting connections.
     ///
     /// This method runs indefinitely, accepting new WebSocket connections and
@@ -125,7 +165,10 @@ impl<R: Runtime> WebSocketServer<R> {
     /// }
     /// ```
     pub async fn start(self) -> Result<(), Box<dyn std::error::Error>> {
-        let listener = TcpListener::bind(&self.addr).await?;
+        let listener = match self.listener {
+            Some(listener) => TcpListener::from_std(listener)?,
+            None => TcpListener::bind(&self.addr).await?,
+        };
         mcp_log_info(
             "WS_SERVER",
             &format!("WebSocket server listening on: {}", self.addr),
@@ -203,14 +246,14 @@ async fn handle_connection<R: Runtime>(
                 // Handle broadcast events
                 Ok(msg) = event_rx.recv() => {
                     if let Err(e) = ws_sender.send(Message::Text(msg.into())).await {
-                        eprintln!("Failed to send broadcast: {e}");
+                        mcp_log_warn("WS_SERVER", &format!("Failed to send broadcast: {e}"));
                         break;
                     }
                 }
                 // Handle responses to client requests
                 Some(response) = response_rx.recv() => {
                     if let Err(e) = ws_sender.send(Message::Text(response.into())).await {
-                        eprintln!("Failed to send response: {e}");
+                        mcp_log_warn("WS_SERVER", &format!("Failed to send response: {e}"));
                         break;
                     }
                 }
@@ -225,169 +268,301 @@ async fn handle_connection<R: Runtime>(
             Ok(Message::Text(text)) => {
                 // Parse incoming command and send response
                 if let Ok(command) = serde_json::from_str::<serde_json::Value>(&text) {
-                    let id = command.get("id").and_then(|v| v.as_str()).unwrap_or("");
-                    let cmd_name = command
-                        .get("command")
-                        .and_then(|v| v.as_str())
-                        .unwrap_or("unknown");
-
-                    // Handle commands
-                    let response = if cmd_name == "invoke_tauri" {
-                        // Handle Tauri IPC command invocation
-                        if let Some(args) = command.get("args") {
-                            if let Some(tauri_cmd) = args.get("command").and_then(|v| v.as_str()) {
-                                // Call the actual Tauri commands
-                                use crate::commands;
-
-                                // Get optional window_label from args for window targeting
-                                let window_label = args
-                                    .get("args")
-                                    .and_then(|a| a.get("windowLabel"))
-                                    .and_then(|v| v.as_str())
-                                    .map(|s| s.to_string());
-
-                                match tauri_cmd {
-                                    "plugin:mcp-bridge|get_window_info" => {
-                                        match commands::resolve_window(&app, window_label.clone()) {
-                                            Ok(window) => {
-                                                match commands::get_window_info(window).await {
-                                                    Ok(data) => serde_json::json!({
-                                                        "id": id,
-                                                        "success": true,
-                                                        "data": data
-                                                    }),
-                                                    Err(e) => serde_json::json!({
-                                                        "id": id,
-                                                        "success": false,
-                                                        "error": e
-                                                    }),
-                                                }
-                                            }
-                                            Err(e) => serde_json::json!({
-                                                "id": id,
-                                                "success": false,
-                                                "error": e
-                                            }),
-                                        }
-                                    }
-                                    "plugin:mcp-bridge|get_backend_state" => {
-                                        match commands::get_backend_state(app.clone()).await {
-                                            Ok(data) => serde_json::json!({
-                                                "id": id,
-                                                "success": true,
-                                                "data": data
-                                            }),
-                                            Err(e) => serde_json::json!({
-                                                "id": id,
-                                                "success": false,
-                                                "error": e
-                                            }),
-                                        }
-                                    }
-                                    "plugin:mcp-bridge|start_ipc_monitor" => {
-                                        match commands::start_ipc_monitor(app.state()).await {
-                                            Ok(data) => serde_json::json!({
-                                                "id": id,
-                                                "success": true,
-                                                "data": data
-                                            }),
-                                            Err(e) => serde_json::json!({
-                                                "id": id,
-                                                "success": false,
-                                                "error": e
-                                            }),
-                                        }
-                                    }
-                                    "plugin:mcp-bridge|stop_ipc_monitor" => {
-                                        match commands::stop_ipc_monitor(app.state()).await {
-                                            Ok(data) => serde_json::json!({
-                                                "id": id,
-                                                "success": true,
-                                                "data": data
-                                            }),
-                                            Err(e) => serde_json::json!({
-                                                "id": id,
-                                                "success": false,
-                                                "error": e
-                                            }),
-                                        }
-                                    }
-                                    "plugin:mcp-bridge|get_ipc_events" => {
-                                        match commands::get_ipc_events(app.state()).await {
-                                            Ok(data) => serde_json::json!({
-                                                "id": id,
-                                                "success": true,
-                                                "data": data
-                                            }),
-                                            Err(e) => serde_json::json!({
-                                                "id": id,
-                                                "success": false,
-                                                "error": e
-                                            }),
-                                        }
-                                    }
-                                    "plugin:mcp-bridge|emit_event" => {
-                                        if let Some(event_name) = args
-                                            .get("args")
-                                            .and_then(|a| a.get("eventName"))
-                                            .and_then(|v| v.as_str())
-                                        {
-                                            let payload = args
-                                                .get("args")
-                                                .and_then(|a| a.get("payload"))
-                                                .cloned()
-                                                .unwrap_or(serde_json::json!(null));
-                                            match commands::emit_event(
-                                                app.clone(),
-                                                event_name.to_string(),
-                                                payload,
-                                            )
-                                            .await
-                                            {
-                                                Ok(data) => serde_json::json!({
-                                                    "id": id,
-                                                    "success": true,
-                                                    "data": data
-                                                }),
-                                                Err(e) => serde_json::json!({
-                                                    "id": id,
-                                                    "success": false,
-                                                    "error": e
-                                                }),
-                                            }
-                                        } else {
-                                            serde_json::json!({
-                                                "id": id,
-                                                "success": false,
-                                                "error": "Missing eventName in args"
-                                            })
-                                        }
-                                    }
-                                    _ => {
-                                        serde_json::json!({
-                                            "id": id,
-                                            "success": false,
-                                            "error": format!("Unsupported Tauri command: {}", tauri_cmd)
-                                        })
-                                    }
-                                }
-                            } else {
-                                serde_json::json!({
+                    // Handle each request in its own task so slow commands (like a
+                    // long execute_js) don't block others, such as cancel_execution
+                    let app = app.clone();
+                    let response_tx = response_tx.clone();
+                    tokio::spawn(async move {
+                        let response = handle_request(&command, app, &response_tx).await;
+                        let _ = response_tx.send(response.to_string());
+                    });
+                } else {
+                    mcp_log_warn("WS_SERVER", &format!("Failed to parse command: {text}"));
+                }
+            }
+            Ok(Message::Close(_)) => {
+                mcp_log_debug("WS_SERVER", "Client disconnected");
+                break;
+            }
+            Err(e) => {
+                mcp_log_error("WS_SERVER", &format!("WebSocket error: {e}"));
+                break;
+            }
+            _ => {}
+        }
+    }
+
+    crate::commands::MutationSubscriptions::remove_client(&app, &response_tx);
+    crate::commands::EventSubscriptions::remove_client(&app, &response_tx);
+    send_task.abort();
+    Ok(())
+}
+
+/// Commands handled by `handle_command`.
+///
+/// Reported by `get_app_config`, so clients can check for a command before using it.
+pub const WEBSOCKET_COMMANDS: &[&str] = &[
+    "invoke_tauri",
+    "list_windows",
+    "get_window_info",
+    "execute_js",
+    "cancel_execution",
+    "clear_context",
+    "capture_native_screenshot",
+    "take_screenshot",
+    "wait_for_selector",
+    "click_element",
+    "hover_element",
+    "set_input_value",
+    "set_file_input",
+    "get_dom_snapshot",
+    "diff_snapshots",
+    "navigate",
+    "get_server_address",
+    "get_console_logs",
+    "clear_console_logs",
+    "get_page_errors",
+    "query_elements",
+    "get_bounding_rects",
+    "get_local_storage",
+    "set_local_storage",
+    "get_cookies",
+    "set_cookie",
+    "batch_execute",
+    "validate_js",
+    "scroll_into_view",
+    "scroll_to",
+    "get_scroll",
+    "set_scroll",
+    "get_page_metrics",
+    "get_execution_metrics",
+    "get_metrics",
+    "execute_js_file",
+    "get_computed_style",
+    "type_text",
+    "press_key",
+    "introspect_commands",
+    "invoke_command",
+    "focus_window",
+    "show_window",
+    "hide_window",
+    "take_element_screenshot",
+    "get_accessibility_tree",
+    "get_window_size",
+    "set_window_size",
+    "ping",
+    "subscribe_dom_mutations",
+    "unsubscribe_dom_mutations",
+    "subscribe_event",
+    "unsubscribe_event",
+    "get_network_log",
+    "clear_network_log",
+    "mock_request",
+    "unmock_request",
+    "clear_mocks",
+    "get_attribute",
+    "set_attribute",
+    "select_option",
+    "drag_and_drop",
+    "get_html",
+    "wait_for_function",
+    "wait_for_idle",
+    "focus_element",
+    "blur_element",
+    "list_bridge_commands",
+    "element_state",
+    "get_selection",
+    "select_text",
+    "get_title",
+    "set_title",
+    "get_url",
+    "get_page_info",
+    "go_back",
+    "go_forward",
+    "disable_animations",
+    "enable_animations",
+    "read_clipboard",
+    "write_clipboard",
+    "resize_window",
+    "register_script",
+    "remove_script",
+    "clear_scripts",
+    "get_scripts",
+    "get_app_config",
+    "reload",
+];
+
+/// Handles a request inside a span carrying its `id`, and logs failures.
+///
+/// Failed responses get a `correlationId` field joining the request `id` with the
+/// `execId` of the script execution it started (when the two differ), matching the
+/// IDs on the request and execution spans. With capture on failure enabled, they also
+/// get a `failure_screenshot` of the window, as base64 PNG data.
+async fn handle_request<R: Runtime>(
+    command: &serde_json::Value,
+    app: AppHandle<R>,
+    client: &mpsc::UnboundedSender<String>,
+) -> serde_json::Value {
+    let id = command.get("id").and_then(|v| v.as_str()).unwrap_or("");
+    let cmd_name = command
+        .get("command")
+        .and_then(|v| v.as_str())
+        .unwrap_or("unknown");
+
+    // Unknown names share one entry, so clients can't grow the metrics without bound
+    let metric_name = if WEBSOCKET_COMMANDS.contains(&cmd_name) {
+        cmd_name
+    } else {
+        "unknown"
+    };
+    let call = app
+        .state::<crate::commands::ScriptExecutor>()
+        .metrics
+        .start(metric_name);
+
+    let mut response =
+        in_request_span(id, cmd_name, handle_command(command, app.clone(), client)).await;
+    call.finish(&response);
+
+    if response.get("success").and_then(|v| v.as_bool()) == Some(false) {
+        let correlation_id = correlation_id(id, response.get("execId").and_then(|v| v.as_str()));
+        let error = response.get("error").and_then(|v| v.as_str()).unwrap_or("");
+        mcp_log_warn(
+            "WS_SERVER",
+            &format!("{cmd_name} [{correlation_id}] failed: {error}"),
+        );
+        response["correlationId"] = serde_json::Value::String(correlation_id);
+
+        // A screenshot of a failed screenshot would most likely fail the same way
+        if !cmd_name.contains("screenshot") {
+            let window_label = command
+                .get("args")
+                .and_then(|a| a.get("windowLabel"))
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string());
+            if let Some(image) = crate::commands::capture_failure(&app, window_label).await {
+                response["failure_screenshot"] = serde_json::Value::String(image);
+            }
+        }
+    }
+    response
+}
+
+/// Derives an execution ID from the request ID when the client didn't choose one.
+///
+/// Execution IDs are shared by every connection, while request IDs are only chosen by
+/// each client, often as plain counters. A random suffix keeps two clients sending the
+/// same `id` from colliding or cancelling each other's scripts. Requests without an
+/// `id` fall back to a generated execution ID.
+fn default_exec_id(request_id: &str) -> Option<String> {
+    (!request_id.is_empty()).then(|| format!("{request_id}-{}", Uuid::new_v4()))
+}
+
+/// Joins a request ID with the ID of the execution it started.
+fn correlation_id(request_id: &str, exec_id: Option<&str>) -> String {
+    match exec_id {
+        Some(exec_id) if exec_id != request_id => format!("{request_id}/{exec_id}"),
+        _ => request_id.to_string(),
+    }
+}
+
+/// Handles a single request from a WebSocket client and builds its response.
+///
+/// Every response echoes the request `id` so clients can match it to the request.
+/// Script executions default their `execId` to the request `id`. Subscriptions send
+/// their notifications through `client`.
+async fn handle_command<R: Runtime>(
+    command: &serde_json::Value,
+    app: AppHandle<R>,
+    client: &mpsc::UnboundedSender<String>,
+) -> serde_json::Value {
+    let id = command.get("id").and_then(|v| v.as_str()).unwrap_or("");
+    let cmd_name = command
+        .get("command")
+        .and_then(|v| v.as_str())
+        .unwrap_or("unknown");
+
+    // Handle commands
+    if cmd_name == "invoke_tauri" {
+        // Handle Tauri IPC command invocation
+        if let Some(args) = command.get("args") {
+            if let Some(tauri_cmd) = args.get("command").and_then(|v| v.as_str()) {
+                // Call the actual Tauri commands
+                use crate::commands;
+
+                // Get optional window_label from args for window targeting
+                let window_label = args
+                    .get("args")
+                    .and_then(|a| a.get("windowLabel"))
+                    .and_then(|v| v.as_str())
+                    .map(|s| s.to_string());
+
+                match tauri_cmd {
+                    "plugin:mcp-bridge|get_window_info" => {
+                        match commands::resolve_window(&app, window_label.clone()) {
+                            Ok(window) => match commands::get_window_info(window).await {
+                                Ok(data) => serde_json::json!({
+                                    "id": id,
+                                    "success": true,
+                                    "data": data
+                                }),
+                                Err(e) => serde_json::json!({
                                     "id": id,
                                     "success": false,
-                                    "error": "Missing command in args"
-                                })
-                            }
-                        } else {
-                            serde_json::json!({
+                                    "error": e
+                                }),
+                            },
+                            Err(e) => serde_json::json!({
                                 "id": id,
                                 "success": false,
-                                "error": "Missing args for invoke_tauri"
-                            })
+                                "error": e
+                            }),
+                        }
+                    }
+                    "plugin:mcp-bridge|get_backend_state" => {
+                        match commands::get_backend_state(app.clone()).await {
+                            Ok(data) => serde_json::json!({
+                                "id": id,
+                                "success": true,
+                                "data": data
+                            }),
+                            Err(e) => serde_json::json!({
+                                "id": id,
+                                "success": false,
+                                "error": e
+                            }),
+                        }
+                    }
+                    "plugin:mcp-bridge|start_ipc_monitor" => {
+                        match commands::start_ipc_monitor(app.state()).await {
+                            Ok(data) => serde_json::json!({
+                                "id": id,
+                                "success": true,
+                                "data": data
+                            }),
+                            Err(e) => serde_json::json!({
+                                "id": id,
+                                "success": false,
+                                "error": e
+                            }),
+                        }
+                    }
+                    "plugin:mcp-bridge|stop_ipc_monitor" => {
+                        match commands::stop_ipc_monitor(app.state()).await {
+                            Ok(data) => serde_json::json!({
+                                "id": id,
+                                "success": true,
+                                "data": data
+                            }),
+                            Err(e) => serde_json::json!({
+                                "id": id,
+                                "success": false,
+                                "error": e
+                            }),
                         }
-                    } else if cmd_name == "list_windows" {
-                        // Handle window listing
-                        match crate::commands::list_windows(app.clone()).await {
+                    }
+                    "plugin:mcp-bridge|get_ipc_events" => {
+                        match commands::get_ipc_events(app.state()).await {
                             Ok(data) => serde_json::json!({
                                 "id": id,
                                 "success": true,
@@ -399,16 +574,21 @@ async fn handle_connection<R: Runtime>(
                                 "error": e
                             }),
                         }
-                    } else if cmd_name == "get_window_info" {
-                        // Handle window info retrieval
-                        let window_id = command
+                    }
+                    "plugin:mcp-bridge|emit_event" => {
+                        if let Some(event_name) = args
                             .get("args")
-                            .and_then(|a| a.get("windowId"))
+                            .and_then(|a| a.get("eventName"))
                             .and_then(|v| v.as_str())
-                            .map(|s| s.to_string());
-
-                        match crate::commands::resolve_window(&app, window_id) {
-                            Ok(window) => match crate::commands::get_window_info(window).await {
+                        {
+                            let payload = args
+                                .get("args")
+                                .and_then(|a| a.get("payload"))
+                                .cloned()
+                                .unwrap_or(serde_json::json!(null));
+                            match commands::emit_event(app.clone(), event_name.to_string(), payload)
+                                .await
+                            {
                                 Ok(data) => serde_json::json!({
                                     "id": id,
                                     "success": true,
@@ -419,394 +599,3467 @@ async fn handle_connection<R: Runtime>(
                                     "success": false,
                                     "error": e
                                 }),
-                            },
-                            Err(e) => serde_json::json!({
-                                "id": id,
-                                "success": false,
-                                "error": e
-                            }),
-                        }
-                    } else if cmd_name == "execute_js" {
-                        if let Some(args) = command.get("args") {
-                            if let Some(script) = args.get("script").and_then(|v| v.as_str()) {
-                                // Get optional window_label, defaulting to "main"
-                                let window_label = args
-                                    .get("windowLabel")
-                                    .and_then(|v| v.as_str())
-                                    .map(|s| s.to_string());
-
-                                // Resolve the target window with context
-                                match crate::commands::resolve_window_with_context(
-                                    &app,
-                                    window_label,
-                                ) {
-                                    Ok(resolved) => {
-                                        // Get the script executor state and create State wrapper
-                                        let executor_state =
-                                            app.state::<crate::commands::ScriptExecutor>();
-                                        // Call the execute_js command with state
-                                        match crate::commands::execute_js(
-                                            resolved.window.clone(),
-                                            script.to_string(),
-                                            executor_state,
-                                        )
-                                        .await
-                                        {
-                                            Ok(result) => {
-                                                serde_json::json!({
-                                                    "id": id,
-                                                    "success": result.get("success").and_then(|v| v.as_bool()).unwrap_or(true),
-                                                    "data": result.get("data").cloned(),
-                                                    "error": result.get("error").and_then(|v| v.as_str()),
-                                                    "windowContext": resolved.context
-                                                })
-                                            }
-                                            Err(e) => {
-                                                serde_json::json!({
-                                                    "id": id,
-                                                    "success": false,
-                                                    "error": e,
-                                                    "windowContext": resolved.context
-                                                })
-                                            }
-                                        }
-                                    }
-                                    Err(e) => {
-                                        serde_json::json!({
-                                            "id": id,
-                                            "success": false,
-                                            "error": e
-                                        })
-                                    }
-                                }
-                            } else {
-                                serde_json::json!({
-                                    "id": id,
-                                    "success": false,
-                                    "error": "Missing script argument"
-                                })
                             }
                         } else {
                             serde_json::json!({
                                 "id": id,
                                 "success": false,
-                                "error": "Missing args"
+                                "error": "Missing eventName in args"
                             })
                         }
-                    } else if cmd_name == "capture_native_screenshot" {
-                        // Handle native screenshot capture
-                        let args = command.get("args");
-                        let format = args
-                            .and_then(|a| a.get("format"))
-                            .and_then(|v| v.as_str())
-                            .map(|s| s.to_string());
-                        let quality = args
-                            .and_then(|a| a.get("quality"))
-                            .and_then(|v| v.as_u64())
-                            .map(|q| q as u8);
-                        let window_label = args
-                            .and_then(|a| a.get("windowLabel"))
-                            .and_then(|v| v.as_str())
-                            .map(|s| s.to_string());
+                    }
+                    _ => {
+                        serde_json::json!({
+                            "id": id,
+                            "success": false,
+                            "error": format!("Unsupported Tauri command: {}", tauri_cmd)
+                        })
+                    }
+                }
+            } else {
+                serde_json::json!({
+                    "id": id,
+                    "success": false,
+                    "error": "Missing command in args"
+                })
+            }
+        } else {
+            serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": "Missing args for invoke_tauri"
+            })
+        }
+    } else if cmd_name == "list_windows" {
+        // Handle window listing
+        match crate::commands::list_windows(app.clone()).await {
+            Ok(data) => serde_json::json!({
+                "id": id,
+                "success": true,
+                "data": data
+            }),
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "get_window_info" {
+        // Handle window info retrieval
+        let window_id = command
+            .get("args")
+            .and_then(|a| a.get("windowId"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
 
-                        // Resolve the target window with context
-                        match crate::commands::resolve_window_with_context(&app, window_label) {
-                            Ok(resolved) => {
-                                match crate::commands::capture_native_screenshot(
-                                    resolved.window,
-                                    format,
-                                    quality,
-                                )
-                                .await
-                                {
-                                    Ok(data_url) => {
-                                        serde_json::json!({
-                                            "id": id,
-                                            "success": true,
-                                            "data": data_url,
-                                            "windowContext": resolved.context
-                                        })
-                                    }
-                                    Err(e) => {
-                                        serde_json::json!({
-                                            "id": id,
-                                            "success": false,
-                                            "error": e,
-                                            "windowContext": resolved.context
-                                        })
-                                    }
-                                }
-                            }
+        match crate::commands::resolve_window(&app, window_id) {
+            Ok(window) => match crate::commands::get_window_info(window).await {
+                Ok(data) => serde_json::json!({
+                    "id": id,
+                    "success": true,
+                    "data": data
+                }),
+                Err(e) => serde_json::json!({
+                    "id": id,
+                    "success": false,
+                    "error": e
+                }),
+            },
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "execute_js" {
+        if let Some(args) = command.get("args") {
+            if let Some(script) = args.get("script").and_then(|v| v.as_str()) {
+                // Get optional window_label, defaulting to "main"
+                let window_label = args
+                    .get("windowLabel")
+                    .and_then(|v| v.as_str())
+                    .map(|s| s.to_string());
+                let script_args = args.get("args").filter(|v| !v.is_null()).cloned();
+                let context = args
+                    .get("context")
+                    .and_then(|v| v.as_str())
+                    .map(|s| s.to_string());
+                let timeout_ms = args.get("timeoutMs").and_then(|v| v.as_u64());
+                let exec_id = args
+                    .get("execId")
+                    .and_then(|v| v.as_str())
+                    .map(|s| s.to_string())
+                    .or_else(|| default_exec_id(id));
+                let isolated = args.get("isolated").and_then(|v| v.as_bool());
+                let decode_binary = args.get("decodeBinary").and_then(|v| v.as_bool());
+                let frame_selector = args
+                    .get("frameSelector")
+                    .and_then(|v| v.as_str())
+                    .map(|s| s.to_string());
+                let result_path = args
+                    .get("resultPath")
+                    .and_then(|v| v.as_str())
+                    .map(|s| s.to_string());
+                let retry = match args.get("retry").filter(|v| !v.is_null()) {
+                    Some(retry) => {
+                        match serde_json::from_value::<crate::commands::RetryPolicy>(retry.clone())
+                        {
+                            Ok(retry) => Some(retry),
                             Err(e) => {
-                                serde_json::json!({
+                                return serde_json::json!({
                                     "id": id,
                                     "success": false,
-                                    "error": e
-                                })
+                                    "error": format!("Invalid retry argument: {e}")
+                                });
                             }
                         }
-                    } else if cmd_name == "resize_window" {
-                        // Handle window resize
-                        if let Some(args) = command.get("args") {
-                            let width =
-                                args.get("width").and_then(|v| v.as_u64()).map(|w| w as u32);
-                            let height = args
-                                .get("height")
-                                .and_then(|v| v.as_u64())
-                                .map(|h| h as u32);
-                            let window_id = args
-                                .get("windowId")
-                                .and_then(|v| v.as_str())
-                                .map(|s| s.to_string());
-                            let logical = args
-                                .get("logical")
-                                .and_then(|v| v.as_bool())
-                                .unwrap_or(true);
-
-                            match (width, height) {
-                                (Some(w), Some(h)) => {
-                                    let params = crate::commands::ResizeWindowParams {
-                                        width: w,
-                                        height: h,
-                                        window_id,
-                                        logical,
-                                    };
-
-                                    match crate::commands::resize_window(app.clone(), params).await
-                                    {
-                                        Ok(result) => serde_json::json!({
-                                            "id": id,
-                                            "success": result.success,
-                                            "data": result,
-                                            "error": result.error
-                                        }),
-                                        Err(e) => serde_json::json!({
-                                            "id": id,
-                                            "success": false,
-                                            "error": e
-                                        }),
-                                    }
-                                }
-                                _ => serde_json::json!({
+                    }
+                    None => None,
+                };
+                let wait_for_result = args.get("waitForResult").and_then(|v| v.as_bool());
+                let wait_ready_state = args
+                    .get("waitReadyState")
+                    .and_then(|v| v.as_str())
+                    .map(|s| s.to_string());
+                let capture_console = args.get("captureConsole").and_then(|v| v.as_bool());
+                let encoding = args
+                    .get("encoding")
+                    .and_then(|v| v.as_str())
+                    .map(|s| s.to_string());
+
+                // Resolve the target window with context
+                match crate::commands::resolve_window_with_context(&app, window_label) {
+                    Ok(resolved) => {
+                        // Get the script executor state and create State wrapper
+                        let executor_state = app.state::<crate::commands::ScriptExecutor>();
+                        // Call the execute_js command with state
+                        match crate::commands::execute_js(
+                            resolved.window.clone(),
+                            script.to_string(),
+                            script_args,
+                            context,
+                            timeout_ms,
+                            exec_id,
+                            isolated,
+                            decode_binary,
+                            frame_selector,
+                            result_path,
+                            retry,
+                            wait_for_result,
+                            wait_ready_state,
+                            capture_console,
+                            encoding,
+                            None,
+                            executor_state,
+                        )
+                        .await
+                        {
+                            Ok(result) => {
+                                let mut response = serde_json::json!({
                                     "id": id,
-                                    "success": false,
-                                    "error": "Missing width or height argument"
-                                }),
-                            }
-                        } else {
-                            serde_json::json!({
-                                "id": id,
-                                "success": false,
-                                "error": "Missing args for resize_window"
-                            })
-                        }
-                    } else if cmd_name == "register_script" {
-                        // Handle script registration
-                        if let Some(args) = command.get("args") {
-                            let script_id = args.get("id").and_then(|v| v.as_str());
-                            let script_type_str = args.get("type").and_then(|v| v.as_str());
-                            let content = args.get("content").and_then(|v| v.as_str());
-
-                            match (script_id, script_type_str, content) {
-                                (Some(id_str), Some(type_str), Some(content_str)) => {
-                                    let script_type = match type_str {
-                                        "url" => ScriptType::Url,
-                                        _ => ScriptType::Inline,
-                                    };
-
-                                    let entry = ScriptEntry {
-                                        id: id_str.to_string(),
-                                        script_type,
-                                        content: content_str.to_string(),
-                                    };
-
-                                    // Add to registry
-                                    let registry: tauri::State<'_, SharedScriptRegistry> =
-                                        app.state();
-                                    {
-                                        let mut reg = registry.lock().unwrap();
-                                        reg.add(entry.clone());
-                                    }
-
-                                    // Inject the script into the webview
-                                    let window_label = args
-                                        .get("windowLabel")
-                                        .and_then(|v| v.as_str())
-                                        .map(|s| s.to_string());
-
-                                    match inject_script_to_webview(&app, &entry, window_label) {
-                                        Ok(result) => serde_json::json!({
-                                            "id": id,
-                                            "success": true,
-                                            "data": { "registered": true, "scriptId": id_str },
-                                            "windowContext": {
-                                                "windowLabel": result.window_context.window_label,
-                                                "totalWindows": result.window_context.total_windows,
-                                                "warning": result.window_context.warning
-                                            }
-                                        }),
-                                        Err(e) => serde_json::json!({
-                                            "id": id,
-                                            "success": false,
-                                            "error": e
-                                        }),
-                                    }
+                                    "success": result.get("success").and_then(|v| v.as_bool()).unwrap_or(true),
+                                    "data": result.get("data").cloned(),
+                                    "error": result.get("error").and_then(|v| v.as_str()),
+                                    "windowContext": resolved.context
+                                });
+                                if let Some(stack) = result.get("error_stack") {
+                                    response["errorStack"] = stack.clone();
                                 }
-                                _ => serde_json::json!({
-                                    "id": id,
-                                    "success": false,
-                                    "error": "Missing required args: id, type, content"
-                                }),
-                            }
-                        } else {
-                            serde_json::json!({
-                                "id": id,
-                                "success": false,
-                                "error": "Missing args for register_script"
-                            })
-                        }
-                    } else if cmd_name == "remove_script" {
-                        // Handle script removal
-                        if let Some(args) = command.get("args") {
-                            if let Some(script_id) = args.get("id").and_then(|v| v.as_str()) {
-                                let registry: tauri::State<'_, SharedScriptRegistry> = app.state();
-                                let removed = {
-                                    let mut reg = registry.lock().unwrap();
-                                    reg.remove(script_id).is_some()
-                                };
-
-                                // Remove from DOM
-                                let window_label = args
-                                    .get("windowLabel")
-                                    .and_then(|v| v.as_str())
-                                    .map(|s| s.to_string());
-
-                                match remove_script_from_webview(&app, script_id, window_label) {
-                                    Ok(result) => serde_json::json!({
-                                        "id": id,
-                                        "success": true,
-                                        "data": { "removed": removed, "scriptId": script_id },
-                                        "windowContext": {
-                                            "windowLabel": result.window_context.window_label,
-                                            "totalWindows": result.window_context.total_windows,
-                                            "warning": result.window_context.warning
-                                        }
-                                    }),
-                                    Err(e) => {
-                                        eprintln!("Failed to remove script from DOM: {e}");
-                                        serde_json::json!({
-                                            "id": id,
-                                            "success": true,
-                                            "data": { "removed": removed, "scriptId": script_id },
-                                            "error": format!("Script removed from registry but DOM removal failed: {e}")
-                                        })
-                                    }
+                                if let Some(error_kind) = result.get("error_kind") {
+                                    response["errorKind"] = error_kind.clone();
+                                }
+                                if let Some(result_type) = result.get("result_type") {
+                                    response["resultType"] = result_type.clone();
+                                }
+                                if let Some(exec_id) = result.get("exec_id") {
+                                    response["execId"] = exec_id.clone();
+                                }
+                                if let Some(last_progress) = result.get("last_progress") {
+                                    response["lastProgress"] = last_progress.clone();
+                                }
+                                if let Some(duration_ms) = result.get("duration_ms") {
+                                    response["durationMs"] = duration_ms.clone();
+                                }
+                                if let Some(script_ms) = result.get("script_ms") {
+                                    response["scriptMs"] = script_ms.clone();
+                                }
+                                if let Some(path_matched) = result.get("path_matched") {
+                                    response["pathMatched"] = path_matched.clone();
+                                }
+                                if let Some(attempts_made) = result.get("attempts_made") {
+                                    response["attemptsMade"] = attempts_made.clone();
+                                }
+                                if let Some(attempt_exec_ids) = result.get("attempt_exec_ids") {
+                                    response["attemptExecIds"] = attempt_exec_ids.clone();
+                                }
+                                if let Some(console) = result.get("console") {
+                                    response["console"] = console.clone();
+                                }
+                                if let Some(truncated) = result.get("truncated") {
+                                    response["truncated"] = truncated.clone();
+                                }
+                                if let Some(original_size) = result.get("original_size") {
+                                    response["originalSize"] = original_size.clone();
                                 }
-                            } else {
+                                response
+                            }
+                            Err(e) => {
                                 serde_json::json!({
                                     "id": id,
                                     "success": false,
-                                    "error": "Missing script id"
+                                    "error": e,
+                                    "windowContext": resolved.context
                                 })
                             }
-                        } else {
-                            serde_json::json!({
+                        }
+                    }
+                    Err(e) => {
+                        serde_json::json!({
+                            "id": id,
+                            "success": false,
+                            "error": e
+                        })
+                    }
+                }
+            } else {
+                serde_json::json!({
+                    "id": id,
+                    "success": false,
+                    "error": "Missing script argument"
+                })
+            }
+        } else {
+            serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": "Missing args"
+            })
+        }
+    } else if cmd_name == "clear_context" {
+        let args = command.get("args");
+        let context = args
+            .and_then(|a| a.get("context"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match context {
+            Some(context) => {
+                match crate::commands::resolve_window_with_context(&app, window_label) {
+                    Ok(resolved) => {
+                        let executor_state = app.state::<crate::commands::ScriptExecutor>();
+                        match crate::commands::clear_context(
+                            resolved.window,
+                            context,
+                            None,
+                            executor_state,
+                        )
+                        .await
+                        {
+                            Ok(result) => serde_json::json!({
+                                "id": id,
+                                "success": result.get("success").and_then(|v| v.as_bool()).unwrap_or(false),
+                                "data": result.get("data").cloned(),
+                                "error": result.get("error").and_then(|v| v.as_str()),
+                                "windowContext": resolved.context
+                            }),
+                            Err(e) => serde_json::json!({
                                 "id": id,
                                 "success": false,
-                                "error": "Missing args for remove_script"
-                            })
+                                "error": e,
+                                "windowContext": resolved.context
+                            }),
                         }
-                    } else if cmd_name == "clear_scripts" {
-                        // Handle clearing all scripts
-                        let registry: tauri::State<'_, SharedScriptRegistry> = app.state();
-                        let count = {
-                            let mut reg = registry.lock().unwrap();
-                            let count = reg.len();
-                            reg.clear();
-                            count
-                        };
-
-                        // Clear from DOM
-                        let window_label = command
-                            .get("args")
-                            .and_then(|a| a.get("windowLabel"))
-                            .and_then(|v| v.as_str())
-                            .map(|s| s.to_string());
+                    }
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e
+                    }),
+                }
+            }
+            None => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": "Missing context argument"
+            }),
+        }
+    } else if cmd_name == "cancel_execution" {
+        let args = command.get("args");
+        let exec_id = args
+            .and_then(|a| a.get("execId"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
 
-                        match clear_scripts_from_webview(&app, window_label) {
+        match exec_id {
+            Some(exec_id) => {
+                match crate::commands::resolve_window_with_context(&app, window_label) {
+                    Ok(resolved) => {
+                        let executor_state = app.state::<crate::commands::ScriptExecutor>();
+                        match crate::commands::cancel_execution(
+                            resolved.window,
+                            exec_id,
+                            executor_state,
+                        )
+                        .await
+                        {
                             Ok(result) => serde_json::json!({
                                 "id": id,
                                 "success": true,
-                                "data": { "cleared": count },
-                                "windowContext": {
-                                    "windowLabel": result.window_context.window_label,
-                                    "totalWindows": result.window_context.total_windows,
-                                    "warning": result.window_context.warning
-                                }
+                                "data": result,
+                                "windowContext": resolved.context
+                            }),
+                            Err(e) => serde_json::json!({
+                                "id": id,
+                                "success": false,
+                                "error": e,
+                                "windowContext": resolved.context
                             }),
-                            Err(e) => {
-                                eprintln!("Failed to clear scripts from DOM: {e}");
-                                serde_json::json!({
-                                    "id": id,
-                                    "success": true,
-                                    "data": { "cleared": count },
-                                    "error": format!("Scripts cleared from registry but DOM clear failed: {e}")
-                                })
-                            }
                         }
-                    } else if cmd_name == "get_scripts" {
-                        // Handle getting all registered scripts
-                        let registry: tauri::State<'_, SharedScriptRegistry> = app.state();
-                        let scripts: Vec<serde_json::Value> = {
-                            let reg = registry.lock().unwrap();
-                            reg.get_all()
-                                .iter()
-                                .map(|entry| {
-                                    serde_json::json!({
-                                        "id": entry.id,
-                                        "type": match entry.script_type {
-                                            ScriptType::Inline => "inline",
-                                            ScriptType::Url => "url",
-                                        },
-                                        "content": entry.content
-                                    })
-                                })
-                                .collect()
-                        };
+                    }
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e
+                    }),
+                }
+            }
+            None => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": "Missing execId argument"
+            }),
+        }
+    } else if cmd_name == "capture_native_screenshot" {
+        // Handle native screenshot capture
+        let args = command.get("args");
+        let format = args
+            .and_then(|a| a.get("format"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let quality = args
+            .and_then(|a| a.get("quality"))
+            .and_then(|v| v.as_u64())
+            .map(|q| q as u8);
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
 
+        // Resolve the target window with context
+        match crate::commands::resolve_window_with_context(&app, window_label) {
+            Ok(resolved) => {
+                match crate::commands::capture_native_screenshot(
+                    resolved.window,
+                    format,
+                    quality,
+                    None,
+                )
+                .await
+                {
+                    Ok(data_url) => {
                         serde_json::json!({
                             "id": id,
                             "success": true,
-                            "data": { "scripts": scripts }
+                            "data": data_url,
+                            "windowContext": resolved.context
                         })
-                    } else {
-                        // Unknown command
+                    }
+                    Err(e) => {
                         serde_json::json!({
                             "id": id,
                             "success": false,
-                            "error": format!("Unknown command: {}", cmd_name)
+                            "error": e,
+                            "windowContext": resolved.context
                         })
-                    };
-
-                    let _ = response_tx.send(response.to_string());
-                } else {
-                    eprintln!("Failed to parse command: {text}");
+                    }
                 }
             }
-            Ok(Message::Close(_)) => {
-                println!("Client disconnected");
-                break;
-            }
             Err(e) => {
-                eprintln!("WebSocket error: {e}");
-                break;
+                serde_json::json!({
+                    "id": id,
+                    "success": false,
+                    "error": e
+                })
             }
-            _ => {}
         }
-    }
+    } else if cmd_name == "take_screenshot" {
+        let window_label = command
+            .get("args")
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
 
-    send_task.abort();
-    Ok(())
+        match crate::commands::resolve_window_with_context(&app, window_label) {
+            Ok(resolved) => {
+                let executor_state = app.state::<crate::commands::ScriptExecutor>();
+                match crate::commands::take_screenshot(resolved.window, None, executor_state).await
+                {
+                    Ok(result) => serde_json::json!({
+                        "id": id,
+                        "success": result.get("success").and_then(|v| v.as_bool()).unwrap_or(false),
+                        "data": result.get("data").cloned(),
+                        "error": result.get("error").and_then(|v| v.as_str()),
+                        "windowContext": resolved.context
+                    }),
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e,
+                        "windowContext": resolved.context
+                    }),
+                }
+            }
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "wait_for_selector" {
+        let args = command.get("args");
+        let selector = args
+            .and_then(|a| a.get("selector"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let by = args
+            .and_then(|a| a.get("by"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let timeout_ms = args
+            .and_then(|a| a.get("timeoutMs"))
+            .and_then(|v| v.as_u64());
+        let state = args
+            .and_then(|a| a.get("state"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let frame_selector = args
+            .and_then(|a| a.get("frameSelector"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match selector {
+            Some(selector) => {
+                match crate::commands::resolve_window_with_context(&app, window_label) {
+                    Ok(resolved) => {
+                        let executor_state = app.state::<crate::commands::ScriptExecutor>();
+                        match crate::commands::wait_for_selector(
+                            resolved.window,
+                            selector,
+                            by,
+                            timeout_ms,
+                            state,
+                            frame_selector,
+                            None,
+                            executor_state,
+                        )
+                        .await
+                        {
+                            Ok(result) => serde_json::json!({
+                                "id": id,
+                                "success": result.get("success").and_then(|v| v.as_bool()).unwrap_or(false),
+                                "data": result.get("data").cloned(),
+                                "error": result.get("error").and_then(|v| v.as_str()),
+                                "windowContext": resolved.context
+                            }),
+                            Err(e) => serde_json::json!({
+                                "id": id,
+                                "success": false,
+                                "error": e,
+                                "windowContext": resolved.context
+                            }),
+                        }
+                    }
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e
+                    }),
+                }
+            }
+            None => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": "Missing selector argument"
+            }),
+        }
+    } else if cmd_name == "click_element" || cmd_name == "hover_element" {
+        let args = command.get("args");
+        let selector = args
+            .and_then(|a| a.get("selector"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let handle = args
+            .and_then(|a| a.get("handle"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let by = args
+            .and_then(|a| a.get("by"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let nth = args
+            .and_then(|a| a.get("nth"))
+            .and_then(|v| v.as_u64())
+            .map(|n| n as usize);
+        let strict = args.and_then(|a| a.get("strict")).and_then(|v| v.as_bool());
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match crate::commands::resolve_window_with_context(&app, window_label) {
+            Ok(resolved) => {
+                let executor_state = app.state::<crate::commands::ScriptExecutor>();
+                let result = if cmd_name == "click_element" {
+                    crate::commands::click_element(
+                        resolved.window,
+                        selector,
+                        handle,
+                        by,
+                        nth,
+                        strict,
+                        None,
+                        executor_state,
+                    )
+                    .await
+                } else {
+                    crate::commands::hover_element(
+                        resolved.window,
+                        selector,
+                        handle,
+                        by,
+                        nth,
+                        strict,
+                        None,
+                        executor_state,
+                    )
+                    .await
+                };
+
+                match result {
+                    Ok(result) => serde_json::json!({
+                        "id": id,
+                        "success": result.get("success").and_then(|v| v.as_bool()).unwrap_or(false),
+                        "data": result.get("data").cloned(),
+                        "error": result.get("error").and_then(|v| v.as_str()),
+                        "errorKind": result.get("error_kind").cloned(),
+                        "windowContext": resolved.context
+                    }),
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e,
+                        "windowContext": resolved.context
+                    }),
+                }
+            }
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "set_input_value" {
+        let args = command.get("args");
+        let selector = args
+            .and_then(|a| a.get("selector"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let handle = args
+            .and_then(|a| a.get("handle"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let by = args
+            .and_then(|a| a.get("by"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let value = args
+            .and_then(|a| a.get("value"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let nth = args
+            .and_then(|a| a.get("nth"))
+            .and_then(|v| v.as_u64())
+            .map(|n| n as usize);
+        let strict = args.and_then(|a| a.get("strict")).and_then(|v| v.as_bool());
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match value {
+            Some(value) => match crate::commands::resolve_window_with_context(&app, window_label) {
+                Ok(resolved) => {
+                    let executor_state = app.state::<crate::commands::ScriptExecutor>();
+                    match crate::commands::set_input_value(
+                        resolved.window,
+                        selector,
+                        handle,
+                        by,
+                        value,
+                        nth,
+                        strict,
+                        None,
+                        executor_state,
+                    )
+                    .await
+                    {
+                        Ok(result) => serde_json::json!({
+                            "id": id,
+                            "success": result.get("success").and_then(|v| v.as_bool()).unwrap_or(false),
+                            "data": result.get("data").cloned(),
+                            "error": result.get("error").and_then(|v| v.as_str()),
+                            "errorKind": result.get("error_kind").cloned(),
+                            "windowContext": resolved.context
+                        }),
+                        Err(e) => serde_json::json!({
+                            "id": id,
+                            "success": false,
+                            "error": e,
+                            "windowContext": resolved.context
+                        }),
+                    }
+                }
+                Err(e) => serde_json::json!({
+                    "id": id,
+                    "success": false,
+                    "error": e
+                }),
+            },
+            None => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": "Missing value argument"
+            }),
+        }
+    } else if cmd_name == "set_file_input" {
+        let args = command.get("args");
+        let selector = args
+            .and_then(|a| a.get("selector"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let handle = args
+            .and_then(|a| a.get("handle"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let by = args
+            .and_then(|a| a.get("by"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let paths = args
+            .and_then(|a| a.get("paths"))
+            .and_then(|v| v.as_array())
+            .map(|paths| {
+                paths
+                    .iter()
+                    .filter_map(|p| p.as_str().map(|p| p.to_string()))
+                    .collect::<Vec<_>>()
+            });
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match paths {
+            Some(paths) => match crate::commands::resolve_window_with_context(&app, window_label) {
+                Ok(resolved) => {
+                    let upload_dir = app.state::<crate::commands::UploadDir>();
+                    let executor_state = app.state::<crate::commands::ScriptExecutor>();
+                    match crate::commands::set_file_input(
+                        resolved.window,
+                        selector,
+                        handle,
+                        by,
+                        paths,
+                        None,
+                        upload_dir,
+                        executor_state,
+                    )
+                    .await
+                    {
+                        Ok(result) => serde_json::json!({
+                            "id": id,
+                            "success": result.get("success").and_then(|v| v.as_bool()).unwrap_or(false),
+                            "data": result.get("data").cloned(),
+                            "error": result.get("error").and_then(|v| v.as_str()),
+                            "windowContext": resolved.context
+                        }),
+                        Err(e) => serde_json::json!({
+                            "id": id,
+                            "success": false,
+                            "error": e,
+                            "windowContext": resolved.context
+                        }),
+                    }
+                }
+                Err(e) => serde_json::json!({
+                    "id": id,
+                    "success": false,
+                    "error": e
+                }),
+            },
+            None => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": "Missing paths argument"
+            }),
+        }
+    } else if cmd_name == "get_dom_snapshot" {
+        let args = command.get("args");
+        let selector = args
+            .and_then(|a| a.get("selector"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let max_depth = args
+            .and_then(|a| a.get("maxDepth"))
+            .and_then(|v| v.as_u64())
+            .map(|d| d as u32);
+        let include_scripts = args
+            .and_then(|a| a.get("includeScripts"))
+            .and_then(|v| v.as_bool());
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match crate::commands::resolve_window_with_context(&app, window_label) {
+            Ok(resolved) => {
+                let snapshots = app.state::<crate::commands::DomSnapshots>();
+                let executor_state = app.state::<crate::commands::ScriptExecutor>();
+                match crate::commands::get_dom_snapshot(
+                    resolved.window,
+                    selector,
+                    max_depth,
+                    include_scripts,
+                    None,
+                    snapshots,
+                    executor_state,
+                )
+                .await
+                {
+                    Ok(result) => serde_json::json!({
+                        "id": id,
+                        "success": result.get("success").and_then(|v| v.as_bool()).unwrap_or(false),
+                        "data": result.get("data").cloned(),
+                        "error": result.get("error").and_then(|v| v.as_str()),
+                        "windowContext": resolved.context
+                    }),
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e,
+                        "windowContext": resolved.context
+                    }),
+                }
+            }
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "diff_snapshots" {
+        let args = command.get("args");
+        let before = args
+            .and_then(|a| a.get("before"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let after = args
+            .and_then(|a| a.get("after"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match (before, after) {
+            (Some(before), Some(after)) => {
+                let snapshots = app.state::<crate::commands::DomSnapshots>();
+                match crate::commands::diff_snapshots(before, after, snapshots).await {
+                    Ok(result) => serde_json::json!({
+                        "id": id,
+                        "success": result.get("success").and_then(|v| v.as_bool()).unwrap_or(false),
+                        "data": result.get("data").cloned(),
+                        "error": result.get("error").and_then(|v| v.as_str())
+                    }),
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e
+                    }),
+                }
+            }
+            _ => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": "Missing before or after argument"
+            }),
+        }
+    } else if cmd_name == "navigate" {
+        let args = command.get("args");
+        let url = args
+            .and_then(|a| a.get("url"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let wait_until = args
+            .and_then(|a| a.get("waitUntil"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let timeout_ms = args
+            .and_then(|a| a.get("timeoutMs"))
+            .and_then(|v| v.as_u64());
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match url {
+            Some(url) => match crate::commands::resolve_window_with_context(&app, window_label) {
+                Ok(resolved) => {
+                    let executor_state = app.state::<crate::commands::ScriptExecutor>();
+                    match crate::commands::navigate(
+                        resolved.window,
+                        url,
+                        wait_until,
+                        timeout_ms,
+                        None,
+                        executor_state,
+                    )
+                    .await
+                    {
+                        Ok(result) => serde_json::json!({
+                            "id": id,
+                            "success": result.get("success").and_then(|v| v.as_bool()).unwrap_or(false),
+                            "data": result.get("data").cloned(),
+                            "error": result.get("error").and_then(|v| v.as_str()),
+                            "windowContext": resolved.context
+                        }),
+                        Err(e) => serde_json::json!({
+                            "id": id,
+                            "success": false,
+                            "error": e,
+                            "windowContext": resolved.context
+                        }),
+                    }
+                }
+                Err(e) => serde_json::json!({
+                    "id": id,
+                    "success": false,
+                    "error": e
+                }),
+            },
+            None => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": "Missing url argument"
+            }),
+        }
+    } else if cmd_name == "get_server_address" {
+        let address = app.state::<crate::commands::ServerAddress>();
+        match crate::commands::get_server_address(address).await {
+            Ok(data) => serde_json::json!({
+                "id": id,
+                "success": true,
+                "data": data
+            }),
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "get_console_logs" || cmd_name == "clear_console_logs" {
+        let args = command.get("args");
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match crate::commands::resolve_window_with_context(&app, window_label) {
+            Ok(resolved) => {
+                let executor_state = app.state::<crate::commands::ScriptExecutor>();
+                let result = if cmd_name == "get_console_logs" {
+                    let level = args
+                        .and_then(|a| a.get("level"))
+                        .and_then(|v| v.as_str())
+                        .map(|s| s.to_string());
+                    let since = args.and_then(|a| a.get("since")).and_then(|v| v.as_u64());
+                    crate::commands::get_console_logs(
+                        resolved.window,
+                        level,
+                        since,
+                        None,
+                        executor_state,
+                    )
+                    .await
+                } else {
+                    crate::commands::clear_console_logs(resolved.window, None, executor_state).await
+                };
+
+                match result {
+                    Ok(result) => serde_json::json!({
+                        "id": id,
+                        "success": result.get("success").and_then(|v| v.as_bool()).unwrap_or(false),
+                        "data": result.get("data").cloned(),
+                        "error": result.get("error").and_then(|v| v.as_str()),
+                        "windowContext": resolved.context
+                    }),
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e,
+                        "windowContext": resolved.context
+                    }),
+                }
+            }
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "get_page_errors" {
+        let args = command.get("args");
+        let since = args.and_then(|a| a.get("since")).and_then(|v| v.as_u64());
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match crate::commands::resolve_window_with_context(&app, window_label) {
+            Ok(resolved) => {
+                let executor_state = app.state::<crate::commands::ScriptExecutor>();
+                match crate::commands::get_page_errors(resolved.window, since, None, executor_state)
+                    .await
+                {
+                    Ok(result) => serde_json::json!({
+                        "id": id,
+                        "success": result.get("success").and_then(|v| v.as_bool()).unwrap_or(false),
+                        "data": result.get("data").cloned(),
+                        "error": result.get("error").and_then(|v| v.as_str()),
+                        "windowContext": resolved.context
+                    }),
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e,
+                        "windowContext": resolved.context
+                    }),
+                }
+            }
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "query_elements" {
+        let args = command.get("args");
+        let selector = args
+            .and_then(|a| a.get("selector"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let by = args
+            .and_then(|a| a.get("by"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let limit = args
+            .and_then(|a| a.get("limit"))
+            .and_then(|v| v.as_u64())
+            .map(|n| n as usize);
+        let frame_selector = args
+            .and_then(|a| a.get("frameSelector"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match selector {
+            Some(selector) => {
+                match crate::commands::resolve_window_with_context(&app, window_label) {
+                    Ok(resolved) => {
+                        let executor_state = app.state::<crate::commands::ScriptExecutor>();
+                        match crate::commands::query_elements(
+                            resolved.window,
+                            selector,
+                            by,
+                            limit,
+                            frame_selector,
+                            None,
+                            executor_state,
+                        )
+                        .await
+                        {
+                            Ok(result) => serde_json::json!({
+                                "id": id,
+                                "success": result.get("success").and_then(|v| v.as_bool()).unwrap_or(false),
+                                "data": result.get("data").cloned(),
+                                "error": result.get("error").and_then(|v| v.as_str()),
+                                "windowContext": resolved.context
+                            }),
+                            Err(e) => serde_json::json!({
+                                "id": id,
+                                "success": false,
+                                "error": e,
+                                "windowContext": resolved.context
+                            }),
+                        }
+                    }
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e
+                    }),
+                }
+            }
+            None => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": "Missing selector argument"
+            }),
+        }
+    } else if cmd_name == "get_bounding_rects" {
+        let args = command.get("args");
+        let selectors = args
+            .and_then(|a| a.get("selectors"))
+            .and_then(|v| v.as_array())
+            .map(|selectors| {
+                selectors
+                    .iter()
+                    .filter_map(|s| s.as_str().map(|s| s.to_string()))
+                    .collect::<Vec<_>>()
+            });
+        let limit = args
+            .and_then(|a| a.get("limit"))
+            .and_then(|v| v.as_u64())
+            .map(|n| n as usize);
+        let frame_selector = args
+            .and_then(|a| a.get("frameSelector"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match selectors {
+            Some(selectors) => {
+                match crate::commands::resolve_window_with_context(&app, window_label) {
+                    Ok(resolved) => {
+                        let executor_state = app.state::<crate::commands::ScriptExecutor>();
+                        match crate::commands::get_bounding_rects(
+                            resolved.window,
+                            selectors,
+                            limit,
+                            frame_selector,
+                            None,
+                            executor_state,
+                        )
+                        .await
+                        {
+                            Ok(result) => serde_json::json!({
+                                "id": id,
+                                "success": result.get("success").and_then(|v| v.as_bool()).unwrap_or(false),
+                                "data": result.get("data").cloned(),
+                                "error": result.get("error").and_then(|v| v.as_str()),
+                                "windowContext": resolved.context
+                            }),
+                            Err(e) => serde_json::json!({
+                                "id": id,
+                                "success": false,
+                                "error": e,
+                                "windowContext": resolved.context
+                            }),
+                        }
+                    }
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e
+                    }),
+                }
+            }
+            None => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": "Missing selectors argument"
+            }),
+        }
+    } else if cmd_name == "get_local_storage" || cmd_name == "set_local_storage" {
+        let args = command.get("args");
+        let key = args
+            .and_then(|a| a.get("key"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let storage = args
+            .and_then(|a| a.get("storage"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match crate::commands::resolve_window_with_context(&app, window_label) {
+            Ok(resolved) => {
+                let executor_state = app.state::<crate::commands::ScriptExecutor>();
+                let result = if cmd_name == "get_local_storage" {
+                    let parse_json = args
+                        .and_then(|a| a.get("parseJson"))
+                        .and_then(|v| v.as_bool());
+                    crate::commands::get_local_storage(
+                        resolved.window,
+                        key,
+                        storage,
+                        parse_json,
+                        None,
+                        executor_state,
+                    )
+                    .await
+                } else {
+                    let value = args.and_then(|a| a.get("value")).cloned();
+                    let clear = args.and_then(|a| a.get("clear")).and_then(|v| v.as_bool());
+                    crate::commands::set_local_storage(
+                        resolved.window,
+                        key,
+                        value,
+                        clear,
+                        storage,
+                        None,
+                        executor_state,
+                    )
+                    .await
+                };
+
+                match result {
+                    Ok(result) => serde_json::json!({
+                        "id": id,
+                        "success": result.get("success").and_then(|v| v.as_bool()).unwrap_or(false),
+                        "data": result.get("data").cloned(),
+                        "error": result.get("error").and_then(|v| v.as_str()),
+                        "windowContext": resolved.context
+                    }),
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e,
+                        "windowContext": resolved.context
+                    }),
+                }
+            }
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "get_cookies" || cmd_name == "set_cookie" {
+        let args = command.get("args");
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match crate::commands::resolve_window_with_context(&app, window_label) {
+            Ok(resolved) => {
+                let executor_state = app.state::<crate::commands::ScriptExecutor>();
+                let result = if cmd_name == "get_cookies" {
+                    crate::commands::get_cookies(resolved.window, None, executor_state).await
+                } else {
+                    match args.and_then(|a| a.get("cookie")).cloned() {
+                        Some(cookie) => match serde_json::from_value(cookie) {
+                            Ok(cookie) => {
+                                crate::commands::set_cookie(
+                                    resolved.window,
+                                    cookie,
+                                    None,
+                                    executor_state,
+                                )
+                                .await
+                            }
+                            Err(e) => Err(format!("Invalid cookie argument: {e}")),
+                        },
+                        None => Err("Missing cookie argument".to_string()),
+                    }
+                };
+
+                match result {
+                    Ok(result) => serde_json::json!({
+                        "id": id,
+                        "success": result.get("success").and_then(|v| v.as_bool()).unwrap_or(false),
+                        "data": result.get("data").cloned(),
+                        "error": result.get("error").and_then(|v| v.as_str()),
+                        "windowContext": resolved.context
+                    }),
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e,
+                        "windowContext": resolved.context
+                    }),
+                }
+            }
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "batch_execute" {
+        let args = command.get("args");
+        let scripts = args
+            .and_then(|a| a.get("scripts"))
+            .and_then(|v| v.as_array())
+            .map(|scripts| {
+                scripts
+                    .iter()
+                    .filter_map(|s| s.as_str().map(|s| s.to_string()))
+                    .collect::<Vec<_>>()
+            });
+        let stop_on_error = args
+            .and_then(|a| a.get("stopOnError"))
+            .and_then(|v| v.as_bool());
+        let timeout_ms = args
+            .and_then(|a| a.get("timeoutMs"))
+            .and_then(|v| v.as_u64());
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match scripts {
+            Some(scripts) => {
+                match crate::commands::resolve_window_with_context(&app, window_label) {
+                    Ok(resolved) => {
+                        let executor_state = app.state::<crate::commands::ScriptExecutor>();
+                        match crate::commands::batch_execute(
+                            resolved.window,
+                            scripts,
+                            stop_on_error,
+                            timeout_ms,
+                            None,
+                            executor_state,
+                        )
+                        .await
+                        {
+                            Ok(result) => {
+                                let mut response = serde_json::json!({
+                                    "id": id,
+                                    "success": result.get("success").and_then(|v| v.as_bool()).unwrap_or(false),
+                                    "data": result.get("data").cloned(),
+                                    "error": result.get("error").and_then(|v| v.as_str()),
+                                    "windowContext": resolved.context
+                                });
+                                if let Some(error_kind) = result.get("error_kind") {
+                                    response["errorKind"] = error_kind.clone();
+                                }
+                                if let Some(exec_id) = result.get("exec_id") {
+                                    response["execId"] = exec_id.clone();
+                                }
+                                response
+                            }
+                            Err(e) => serde_json::json!({
+                                "id": id,
+                                "success": false,
+                                "error": e,
+                                "windowContext": resolved.context
+                            }),
+                        }
+                    }
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e
+                    }),
+                }
+            }
+            None => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": "Missing scripts argument"
+            }),
+        }
+    } else if cmd_name == "validate_js" {
+        let args = command.get("args");
+        let script = args
+            .and_then(|a| a.get("script"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match script {
+            Some(script) => {
+                match crate::commands::resolve_window_with_context(&app, window_label) {
+                    Ok(resolved) => {
+                        let executor_state = app.state::<crate::commands::ScriptExecutor>();
+                        match crate::commands::validate_js(
+                            resolved.window,
+                            script,
+                            None,
+                            executor_state,
+                        )
+                        .await
+                        {
+                            Ok(result) => serde_json::json!({
+                                "id": id,
+                                "success": result.get("success").and_then(|v| v.as_bool()).unwrap_or(false),
+                                "data": result.get("data").cloned(),
+                                "error": result.get("error").and_then(|v| v.as_str()),
+                                "windowContext": resolved.context
+                            }),
+                            Err(e) => serde_json::json!({
+                                "id": id,
+                                "success": false,
+                                "error": e,
+                                "windowContext": resolved.context
+                            }),
+                        }
+                    }
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e
+                    }),
+                }
+            }
+            None => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": "Missing script argument"
+            }),
+        }
+    } else if cmd_name == "scroll_into_view"
+        || cmd_name == "scroll_to"
+        || cmd_name == "get_scroll"
+        || cmd_name == "set_scroll"
+    {
+        let args = command.get("args");
+        let selector = args
+            .and_then(|a| a.get("selector"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let handle = args
+            .and_then(|a| a.get("handle"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let by = args
+            .and_then(|a| a.get("by"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match crate::commands::resolve_window_with_context(&app, window_label) {
+            Ok(resolved) => {
+                let executor_state = app.state::<crate::commands::ScriptExecutor>();
+                let result = if cmd_name == "scroll_into_view" {
+                    let block = args
+                        .and_then(|a| a.get("block"))
+                        .and_then(|v| v.as_str())
+                        .map(|s| s.to_string());
+                    let inline = args
+                        .and_then(|a| a.get("inline"))
+                        .and_then(|v| v.as_str())
+                        .map(|s| s.to_string());
+                    crate::commands::scroll_into_view(
+                        resolved.window,
+                        selector,
+                        handle,
+                        by,
+                        block,
+                        inline,
+                        None,
+                        executor_state,
+                    )
+                    .await
+                } else if cmd_name == "scroll_to" {
+                    let x = args.and_then(|a| a.get("x")).and_then(|v| v.as_f64());
+                    let y = args.and_then(|a| a.get("y")).and_then(|v| v.as_f64());
+                    crate::commands::scroll_to(
+                        resolved.window,
+                        x,
+                        y,
+                        selector,
+                        None,
+                        executor_state,
+                    )
+                    .await
+                } else if cmd_name == "get_scroll" {
+                    crate::commands::get_scroll(
+                        resolved.window,
+                        selector,
+                        handle,
+                        by,
+                        None,
+                        executor_state,
+                    )
+                    .await
+                } else {
+                    let top = args.and_then(|a| a.get("top")).and_then(|v| v.as_f64());
+                    let left = args.and_then(|a| a.get("left")).and_then(|v| v.as_f64());
+                    crate::commands::set_scroll(
+                        resolved.window,
+                        top,
+                        left,
+                        selector,
+                        handle,
+                        by,
+                        None,
+                        executor_state,
+                    )
+                    .await
+                };
+
+                match result {
+                    Ok(result) => serde_json::json!({
+                        "id": id,
+                        "success": result.get("success").and_then(|v| v.as_bool()).unwrap_or(false),
+                        "data": result.get("data").cloned(),
+                        "error": result.get("error").and_then(|v| v.as_str()),
+                        "windowContext": resolved.context
+                    }),
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e,
+                        "windowContext": resolved.context
+                    }),
+                }
+            }
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "get_page_metrics" {
+        let window_label = command
+            .get("args")
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match crate::commands::resolve_window_with_context(&app, window_label) {
+            Ok(resolved) => {
+                let executor_state = app.state::<crate::commands::ScriptExecutor>();
+                match crate::commands::get_page_metrics(resolved.window, None, executor_state).await
+                {
+                    Ok(result) => serde_json::json!({
+                        "id": id,
+                        "success": result.get("success").and_then(|v| v.as_bool()).unwrap_or(false),
+                        "data": result.get("data").cloned(),
+                        "error": result.get("error").and_then(|v| v.as_str()),
+                        "windowContext": resolved.context
+                    }),
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e,
+                        "windowContext": resolved.context
+                    }),
+                }
+            }
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "get_execution_metrics" {
+        let executor_state = app.state::<crate::commands::ScriptExecutor>();
+        match crate::commands::get_execution_metrics(executor_state).await {
+            Ok(data) => serde_json::json!({
+                "id": id,
+                "success": true,
+                "data": data
+            }),
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "get_metrics" {
+        let executor_state = app.state::<crate::commands::ScriptExecutor>();
+        match crate::commands::get_metrics(executor_state).await {
+            Ok(data) => serde_json::json!({
+                "id": id,
+                "success": true,
+                "data": data
+            }),
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "execute_js_file" {
+        let args = command.get("args");
+        let path = args
+            .and_then(|a| a.get("path"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let script_args = args.and_then(|a| a.get("args")).cloned();
+        let timeout_ms = args
+            .and_then(|a| a.get("timeoutMs"))
+            .and_then(|v| v.as_u64());
+        let exec_id = args
+            .and_then(|a| a.get("execId"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string())
+            .or_else(|| default_exec_id(id));
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match path {
+            Some(path) => match crate::commands::resolve_window_with_context(&app, window_label) {
+                Ok(resolved) => {
+                    let script_dir = app.state::<crate::commands::ScriptDir>();
+                    let executor_state = app.state::<crate::commands::ScriptExecutor>();
+                    match crate::commands::execute_js_file(
+                        resolved.window,
+                        path,
+                        script_args,
+                        timeout_ms,
+                        exec_id,
+                        None,
+                        script_dir,
+                        executor_state,
+                    )
+                    .await
+                    {
+                        Ok(result) => {
+                            let mut response = serde_json::json!({
+                                "id": id,
+                                "success": result.get("success").and_then(|v| v.as_bool()).unwrap_or(false),
+                                "data": result.get("data").cloned(),
+                                "error": result.get("error").and_then(|v| v.as_str()),
+                                "windowContext": resolved.context
+                            });
+                            if let Some(stack) = result.get("error_stack") {
+                                response["errorStack"] = stack.clone();
+                            }
+                            if let Some(error_kind) = result.get("error_kind") {
+                                response["errorKind"] = error_kind.clone();
+                            }
+                            if let Some(result_type) = result.get("result_type") {
+                                response["resultType"] = result_type.clone();
+                            }
+                            if let Some(exec_id) = result.get("exec_id") {
+                                response["execId"] = exec_id.clone();
+                            }
+                            if let Some(last_progress) = result.get("last_progress") {
+                                response["lastProgress"] = last_progress.clone();
+                            }
+                            if let Some(duration_ms) = result.get("duration_ms") {
+                                response["durationMs"] = duration_ms.clone();
+                            }
+                            if let Some(script_ms) = result.get("script_ms") {
+                                response["scriptMs"] = script_ms.clone();
+                            }
+                            response
+                        }
+                        Err(e) => serde_json::json!({
+                            "id": id,
+                            "success": false,
+                            "error": e,
+                            "windowContext": resolved.context
+                        }),
+                    }
+                }
+                Err(e) => serde_json::json!({
+                    "id": id,
+                    "success": false,
+                    "error": e
+                }),
+            },
+            None => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": "Missing path argument"
+            }),
+        }
+    } else if cmd_name == "get_computed_style" {
+        let args = command.get("args");
+        let selector = args
+            .and_then(|a| a.get("selector"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let properties = args
+            .and_then(|a| a.get("properties"))
+            .filter(|v| !v.is_null())
+            .map(|v| serde_json::from_value::<Vec<String>>(v.clone()))
+            .transpose();
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match (selector, properties) {
+            (Some(selector), Ok(properties)) => {
+                match crate::commands::resolve_window_with_context(&app, window_label) {
+                    Ok(resolved) => {
+                        let executor_state = app.state::<crate::commands::ScriptExecutor>();
+                        match crate::commands::get_computed_style(
+                            resolved.window,
+                            selector,
+                            properties,
+                            None,
+                            executor_state,
+                        )
+                        .await
+                        {
+                            Ok(result) => serde_json::json!({
+                                "id": id,
+                                "success": result.get("success").and_then(|v| v.as_bool()).unwrap_or(false),
+                                "data": result.get("data").cloned(),
+                                "error": result.get("error").and_then(|v| v.as_str()),
+                                "windowContext": resolved.context
+                            }),
+                            Err(e) => serde_json::json!({
+                                "id": id,
+                                "success": false,
+                                "error": e,
+                                "windowContext": resolved.context
+                            }),
+                        }
+                    }
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e
+                    }),
+                }
+            }
+            (None, _) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": "Missing selector argument"
+            }),
+            (_, Err(e)) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": format!("Invalid properties argument: {e}")
+            }),
+        }
+    } else if cmd_name == "type_text" || cmd_name == "press_key" {
+        let args = command.get("args");
+        let selector = args
+            .and_then(|a| a.get("selector"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match crate::commands::resolve_window_with_context(&app, window_label) {
+            Ok(resolved) => {
+                let executor_state = app.state::<crate::commands::ScriptExecutor>();
+                let result = if cmd_name == "type_text" {
+                    let text = args
+                        .and_then(|a| a.get("text"))
+                        .and_then(|v| v.as_str())
+                        .map(|s| s.to_string());
+                    let clear = args.and_then(|a| a.get("clear")).and_then(|v| v.as_bool());
+                    let delay_ms = args.and_then(|a| a.get("delayMs")).and_then(|v| v.as_u64());
+                    let nth = args
+                        .and_then(|a| a.get("nth"))
+                        .and_then(|v| v.as_u64())
+                        .map(|n| n as usize);
+                    let strict = args.and_then(|a| a.get("strict")).and_then(|v| v.as_bool());
+                    match (selector, text) {
+                        (Some(selector), Some(text)) => {
+                            crate::commands::type_text(
+                                resolved.window,
+                                selector,
+                                text,
+                                clear,
+                                delay_ms,
+                                nth,
+                                strict,
+                                None,
+                                executor_state,
+                            )
+                            .await
+                        }
+                        _ => Err("Missing selector or text argument".to_string()),
+                    }
+                } else {
+                    let key = args
+                        .and_then(|a| a.get("key"))
+                        .and_then(|v| v.as_str())
+                        .map(|s| s.to_string());
+                    let modifiers = args
+                        .and_then(|a| a.get("modifiers"))
+                        .filter(|v| !v.is_null())
+                        .map(|v| serde_json::from_value::<Vec<String>>(v.clone()))
+                        .transpose()
+                        .map_err(|e| format!("Invalid modifiers argument: {e}"));
+                    match (key, modifiers) {
+                        (Some(key), Ok(modifiers)) => {
+                            crate::commands::press_key(
+                                resolved.window,
+                                key,
+                                modifiers,
+                                selector,
+                                None,
+                                executor_state,
+                            )
+                            .await
+                        }
+                        (None, _) => Err("Missing key argument".to_string()),
+                        (_, Err(e)) => Err(e),
+                    }
+                };
+
+                match result {
+                    Ok(result) => serde_json::json!({
+                        "id": id,
+                        "success": result.get("success").and_then(|v| v.as_bool()).unwrap_or(false),
+                        "data": result.get("data").cloned(),
+                        "error": result.get("error").and_then(|v| v.as_str()),
+                        "errorKind": result.get("error_kind").cloned(),
+                        "windowContext": resolved.context
+                    }),
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e,
+                        "windowContext": resolved.context
+                    }),
+                }
+            }
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "introspect_commands" {
+        let catalog = app.state::<crate::commands::CommandCatalog>();
+        match crate::commands::introspect_commands(catalog).await {
+            Ok(data) => serde_json::json!({
+                "id": id,
+                "success": true,
+                "data": data
+            }),
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "invoke_command" {
+        let args = command.get("args");
+        let tauri_cmd = args
+            .and_then(|a| a.get("command"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let command_args = args.and_then(|a| a.get("args")).cloned();
+        let timeout_ms = args
+            .and_then(|a| a.get("timeoutMs"))
+            .and_then(|v| v.as_u64());
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match tauri_cmd {
+            Some(tauri_cmd) => {
+                match crate::commands::resolve_window_with_context(&app, window_label) {
+                    Ok(resolved) => {
+                        let executor_state = app.state::<crate::commands::ScriptExecutor>();
+                        match crate::commands::invoke_command(
+                            resolved.window,
+                            tauri_cmd,
+                            command_args,
+                            timeout_ms,
+                            None,
+                            executor_state,
+                        )
+                        .await
+                        {
+                            Ok(result) => serde_json::json!({
+                                "id": id,
+                                "success": result.get("success").and_then(|v| v.as_bool()).unwrap_or(false),
+                                "data": result.get("data").cloned(),
+                                "error": result.get("error").and_then(|v| v.as_str()),
+                                "windowContext": resolved.context
+                            }),
+                            Err(e) => serde_json::json!({
+                                "id": id,
+                                "success": false,
+                                "error": e,
+                                "windowContext": resolved.context
+                            }),
+                        }
+                    }
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e
+                    }),
+                }
+            }
+            None => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": "Missing command argument"
+            }),
+        }
+    } else if cmd_name == "get_app_config" {
+        let catalog = app.state::<crate::commands::CommandCatalog>();
+        match crate::commands::get_app_config(app.clone(), catalog).await {
+            Ok(data) => serde_json::json!({
+                "id": id,
+                "success": true,
+                "data": data
+            }),
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "reload" {
+        let args = command.get("args");
+        let ignore_cache = args
+            .and_then(|a| a.get("ignoreCache"))
+            .and_then(|v| v.as_bool());
+        let wait_until = args
+            .and_then(|a| a.get("waitUntil"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let timeout_ms = args
+            .and_then(|a| a.get("timeoutMs"))
+            .and_then(|v| v.as_u64());
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match crate::commands::resolve_window_with_context(&app, window_label) {
+            Ok(resolved) => {
+                let executor_state = app.state::<crate::commands::ScriptExecutor>();
+                match crate::commands::reload(
+                    resolved.window,
+                    ignore_cache,
+                    wait_until,
+                    timeout_ms,
+                    None,
+                    executor_state,
+                )
+                .await
+                {
+                    Ok(result) => serde_json::json!({
+                        "id": id,
+                        "success": result.get("success").and_then(|v| v.as_bool()).unwrap_or(false),
+                        "data": result.get("data").cloned(),
+                        "error": result.get("error").and_then(|v| v.as_str()),
+                        "windowContext": resolved.context
+                    }),
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e,
+                        "windowContext": resolved.context
+                    }),
+                }
+            }
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "focus_window" || cmd_name == "show_window" || cmd_name == "hide_window" {
+        let window_label = command
+            .get("args")
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match crate::commands::resolve_window_with_context(&app, window_label) {
+            Ok(resolved) => {
+                let result = match cmd_name {
+                    "focus_window" => crate::commands::focus_window(resolved.window, None).await,
+                    "show_window" => crate::commands::show_window(resolved.window, None).await,
+                    _ => crate::commands::hide_window(resolved.window, None).await,
+                };
+
+                match result {
+                    Ok(result) => serde_json::json!({
+                        "id": id,
+                        "success": result.get("success").and_then(|v| v.as_bool()).unwrap_or(false),
+                        "data": result.get("data").cloned(),
+                        "error": result.get("error").and_then(|v| v.as_str()),
+                        "windowContext": resolved.context
+                    }),
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e,
+                        "windowContext": resolved.context
+                    }),
+                }
+            }
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "take_element_screenshot" {
+        let args = command.get("args");
+        let selector = args
+            .and_then(|a| a.get("selector"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match selector {
+            Some(selector) => {
+                match crate::commands::resolve_window_with_context(&app, window_label) {
+                    Ok(resolved) => {
+                        let executor_state = app.state::<crate::commands::ScriptExecutor>();
+                        match crate::commands::take_element_screenshot(
+                            resolved.window,
+                            selector,
+                            None,
+                            executor_state,
+                        )
+                        .await
+                        {
+                            Ok(result) => serde_json::json!({
+                                "id": id,
+                                "success": result.get("success").and_then(|v| v.as_bool()).unwrap_or(false),
+                                "data": result.get("data").cloned(),
+                                "error": result.get("error").and_then(|v| v.as_str()),
+                                "windowContext": resolved.context
+                            }),
+                            Err(e) => serde_json::json!({
+                                "id": id,
+                                "success": false,
+                                "error": e,
+                                "windowContext": resolved.context
+                            }),
+                        }
+                    }
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e
+                    }),
+                }
+            }
+            None => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": "Missing selector argument"
+            }),
+        }
+    } else if cmd_name == "get_accessibility_tree" {
+        let args = command.get("args");
+        let selector = args
+            .and_then(|a| a.get("selector"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let max_depth = args
+            .and_then(|a| a.get("maxDepth"))
+            .and_then(|v| v.as_u64())
+            .map(|d| d as u32);
+        let max_nodes = args
+            .and_then(|a| a.get("maxNodes"))
+            .and_then(|v| v.as_u64())
+            .map(|n| n.min(u64::from(u32::MAX)) as u32);
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match crate::commands::resolve_window_with_context(&app, window_label) {
+            Ok(resolved) => {
+                let executor_state = app.state::<crate::commands::ScriptExecutor>();
+                match crate::commands::get_accessibility_tree(
+                    resolved.window,
+                    selector,
+                    max_depth,
+                    max_nodes,
+                    None,
+                    executor_state,
+                )
+                .await
+                {
+                    Ok(result) => serde_json::json!({
+                        "id": id,
+                        "success": result.get("success").and_then(|v| v.as_bool()).unwrap_or(false),
+                        "data": result.get("data").cloned(),
+                        "error": result.get("error").and_then(|v| v.as_str()),
+                        "windowContext": resolved.context
+                    }),
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e,
+                        "windowContext": resolved.context
+                    }),
+                }
+            }
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "get_window_size" || cmd_name == "set_window_size" {
+        let args = command.get("args");
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match crate::commands::resolve_window_with_context(&app, window_label) {
+            Ok(resolved) => {
+                let result = if cmd_name == "get_window_size" {
+                    crate::commands::get_window_size(resolved.window, None).await
+                } else {
+                    let width = args
+                        .and_then(|a| a.get("width"))
+                        .and_then(|v| v.as_u64())
+                        .map(|w| w as u32);
+                    let height = args
+                        .and_then(|a| a.get("height"))
+                        .and_then(|v| v.as_u64())
+                        .map(|h| h as u32);
+                    let logical = args
+                        .and_then(|a| a.get("logical"))
+                        .and_then(|v| v.as_bool());
+                    let timeout_ms = args
+                        .and_then(|a| a.get("timeoutMs"))
+                        .and_then(|v| v.as_u64());
+                    match (width, height) {
+                        (Some(width), Some(height)) => {
+                            crate::commands::set_window_size(
+                                resolved.window,
+                                width,
+                                height,
+                                logical,
+                                timeout_ms,
+                                None,
+                            )
+                            .await
+                        }
+                        _ => Err("Missing width or height argument".to_string()),
+                    }
+                };
+
+                match result {
+                    Ok(result) => serde_json::json!({
+                        "id": id,
+                        "success": result.get("success").and_then(|v| v.as_bool()).unwrap_or(false),
+                        "data": result.get("data").cloned(),
+                        "error": result.get("error").and_then(|v| v.as_str()),
+                        "windowContext": resolved.context
+                    }),
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e,
+                        "windowContext": resolved.context
+                    }),
+                }
+            }
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "ping" {
+        // Answered without touching any webview, so it works mid-navigation
+        let start_time = app.state::<crate::commands::StartTime>();
+        match crate::commands::ping(start_time).await {
+            Ok(data) => serde_json::json!({
+                "id": id,
+                "success": true,
+                "data": data
+            }),
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "subscribe_dom_mutations" {
+        let args = command.get("args");
+        let selector = args
+            .and_then(|a| a.get("selector"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let batch_interval_ms = args
+            .and_then(|a| a.get("batchIntervalMs"))
+            .and_then(|v| v.as_u64());
+        let max_records = args
+            .and_then(|a| a.get("maxRecords"))
+            .and_then(|v| v.as_u64())
+            .map(|n| n as usize);
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match crate::commands::resolve_window_with_context(&app, window_label) {
+            Ok(resolved) => {
+                let subscriptions = app.state::<crate::commands::MutationSubscriptions>();
+                let executor_state = app.state::<crate::commands::ScriptExecutor>();
+                match crate::commands::subscribe_dom_mutations(
+                    resolved.window,
+                    selector,
+                    batch_interval_ms,
+                    max_records,
+                    client.clone(),
+                    &subscriptions,
+                    executor_state,
+                )
+                .await
+                {
+                    Ok(result) => serde_json::json!({
+                        "id": id,
+                        "success": result.get("success").and_then(|v| v.as_bool()).unwrap_or(false),
+                        "data": result.get("data"),
+                        "error": result.get("error").and_then(|v| v.as_str()),
+                        "windowContext": resolved.context
+                    }),
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e,
+                        "windowContext": resolved.context
+                    }),
+                }
+            }
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "unsubscribe_dom_mutations" {
+        let subscription_id = command
+            .get("args")
+            .and_then(|a| a.get("subscriptionId"))
+            .and_then(|v| v.as_str());
+
+        match subscription_id {
+            Some(subscription_id) => {
+                let subscriptions = app.state::<crate::commands::MutationSubscriptions>();
+                match crate::commands::unsubscribe_dom_mutations(
+                    &app,
+                    subscription_id,
+                    &subscriptions,
+                ) {
+                    Ok(result) => serde_json::json!({
+                        "id": id,
+                        "success": result.get("success").and_then(|v| v.as_bool()).unwrap_or(false),
+                        "data": result.get("data"),
+                        "error": result.get("error").and_then(|v| v.as_str())
+                    }),
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e
+                    }),
+                }
+            }
+            None => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": "Missing subscriptionId argument"
+            }),
+        }
+    } else if cmd_name == "subscribe_event" {
+        let event = command
+            .get("args")
+            .and_then(|a| a.get("event"))
+            .and_then(|v| v.as_str());
+
+        match event {
+            Some(event) => {
+                let subscriptions = app.state::<crate::commands::EventSubscriptions>();
+                match crate::commands::subscribe_event(&app, event, client.clone(), &subscriptions)
+                {
+                    Ok(result) => serde_json::json!({
+                        "id": id,
+                        "success": result.get("success").and_then(|v| v.as_bool()).unwrap_or(false),
+                        "data": result.get("data"),
+                        "error": result.get("error").and_then(|v| v.as_str())
+                    }),
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e
+                    }),
+                }
+            }
+            None => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": "Missing event argument"
+            }),
+        }
+    } else if cmd_name == "unsubscribe_event" {
+        let subscription_id = command
+            .get("args")
+            .and_then(|a| a.get("subscriptionId"))
+            .and_then(|v| v.as_str());
+
+        match subscription_id {
+            Some(subscription_id) => {
+                let subscriptions = app.state::<crate::commands::EventSubscriptions>();
+                match crate::commands::unsubscribe_event(&app, subscription_id, &subscriptions) {
+                    Ok(result) => serde_json::json!({
+                        "id": id,
+                        "success": result.get("success").and_then(|v| v.as_bool()).unwrap_or(false),
+                        "data": result.get("data"),
+                        "error": result.get("error").and_then(|v| v.as_str())
+                    }),
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e
+                    }),
+                }
+            }
+            None => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": "Missing subscriptionId argument"
+            }),
+        }
+    } else if cmd_name == "get_network_log" || cmd_name == "clear_network_log" {
+        let args = command.get("args");
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match crate::commands::resolve_window_with_context(&app, window_label) {
+            Ok(resolved) => {
+                let executor_state = app.state::<crate::commands::ScriptExecutor>();
+                let result = if cmd_name == "get_network_log" {
+                    let since = args.and_then(|a| a.get("since")).and_then(|v| v.as_u64());
+                    crate::commands::get_network_log(resolved.window, since, None, executor_state)
+                        .await
+                } else {
+                    crate::commands::clear_network_log(resolved.window, None, executor_state).await
+                };
+
+                match result {
+                    Ok(result) => serde_json::json!({
+                        "id": id,
+                        "success": result.get("success").and_then(|v| v.as_bool()).unwrap_or(false),
+                        "data": result.get("data").cloned(),
+                        "error": result.get("error").and_then(|v| v.as_str()),
+                        "windowContext": resolved.context
+                    }),
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e,
+                        "windowContext": resolved.context
+                    }),
+                }
+            }
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "mock_request" {
+        let args = command.get("args");
+        let arg_str = |name: &str| {
+            args.and_then(|a| a.get(name))
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string())
+        };
+
+        match arg_str("url") {
+            Some(url) => {
+                let status = args
+                    .and_then(|a| a.get("status"))
+                    .and_then(|v| v.as_u64())
+                    .map(|s| u16::try_from(s).unwrap_or(u16::MAX));
+                let headers = args
+                    .and_then(|a| a.get("headers"))
+                    .and_then(|v| serde_json::from_value(v.clone()).ok());
+                let body = args.and_then(|a| a.get("body")).cloned();
+                let mocks = app.state::<crate::commands::NetworkMocks>();
+
+                match crate::commands::mock_request(
+                    app.clone(),
+                    url,
+                    arg_str("urlMatch"),
+                    arg_str("method"),
+                    status,
+                    headers,
+                    body,
+                    mocks,
+                )
+                .await
+                {
+                    Ok(result) => serde_json::json!({
+                        "id": id,
+                        "success": result.get("success").and_then(|v| v.as_bool()).unwrap_or(false),
+                        "data": result.get("data"),
+                        "error": result.get("error").and_then(|v| v.as_str())
+                    }),
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e
+                    }),
+                }
+            }
+            None => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": "Missing url argument"
+            }),
+        }
+    } else if cmd_name == "unmock_request" || cmd_name == "clear_mocks" {
+        let mocks = app.state::<crate::commands::NetworkMocks>();
+        let result = if cmd_name == "clear_mocks" {
+            Some(crate::commands::clear_mocks(app.clone(), mocks).await)
+        } else {
+            let mock_id = command
+                .get("args")
+                .and_then(|a| a.get("mockId"))
+                .and_then(|v| v.as_str());
+            match mock_id {
+                Some(mock_id) => Some(
+                    crate::commands::unmock_request(app.clone(), mock_id.to_string(), mocks).await,
+                ),
+                None => None,
+            }
+        };
+
+        match result {
+            Some(Ok(result)) => serde_json::json!({
+                "id": id,
+                "success": result.get("success").and_then(|v| v.as_bool()).unwrap_or(false),
+                "data": result.get("data"),
+                "error": result.get("error").and_then(|v| v.as_str())
+            }),
+            Some(Err(e)) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+            None => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": "Missing mockId argument"
+            }),
+        }
+    } else if cmd_name == "get_attribute" || cmd_name == "set_attribute" {
+        let args = command.get("args");
+        let arg_str = |name: &str| {
+            args.and_then(|a| a.get(name))
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string())
+        };
+        let window_label = arg_str("windowLabel");
+
+        let selector = arg_str("selector");
+        let handle = arg_str("handle");
+        let by = arg_str("by");
+
+        match arg_str("name") {
+            Some(name) => match crate::commands::resolve_window_with_context(&app, window_label) {
+                Ok(resolved) => {
+                    let executor_state = app.state::<crate::commands::ScriptExecutor>();
+                    let result = if cmd_name == "get_attribute" {
+                        crate::commands::get_attribute(
+                            resolved.window,
+                            selector,
+                            handle,
+                            by,
+                            name,
+                            None,
+                            executor_state,
+                        )
+                        .await
+                    } else {
+                        let value = args.and_then(|a| a.get("value")).cloned();
+                        let remove = args.and_then(|a| a.get("remove")).and_then(|v| v.as_bool());
+                        crate::commands::set_attribute(
+                            resolved.window,
+                            selector,
+                            handle,
+                            by,
+                            name,
+                            value,
+                            remove,
+                            None,
+                            executor_state,
+                        )
+                        .await
+                    };
+
+                    match result {
+                        Ok(result) => serde_json::json!({
+                            "id": id,
+                            "success": result.get("success").and_then(|v| v.as_bool()).unwrap_or(false),
+                            "data": result.get("data").cloned(),
+                            "error": result.get("error").and_then(|v| v.as_str()),
+                            "windowContext": resolved.context
+                        }),
+                        Err(e) => serde_json::json!({
+                            "id": id,
+                            "success": false,
+                            "error": e,
+                            "windowContext": resolved.context
+                        }),
+                    }
+                }
+                Err(e) => serde_json::json!({
+                    "id": id,
+                    "success": false,
+                    "error": e
+                }),
+            },
+            None => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": "Missing name argument"
+            }),
+        }
+    } else if cmd_name == "select_option" {
+        let args = command.get("args");
+        let selector = args
+            .and_then(|a| a.get("selector"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let arg = |name: &str| args.and_then(|a| a.get(name)).cloned();
+        let nth = args
+            .and_then(|a| a.get("nth"))
+            .and_then(|v| v.as_u64())
+            .map(|n| n as usize);
+        let strict = args.and_then(|a| a.get("strict")).and_then(|v| v.as_bool());
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match selector {
+            Some(selector) => {
+                match crate::commands::resolve_window_with_context(&app, window_label) {
+                    Ok(resolved) => {
+                        let executor_state = app.state::<crate::commands::ScriptExecutor>();
+                        match crate::commands::select_option(
+                            resolved.window,
+                            selector,
+                            arg("value"),
+                            arg("label"),
+                            arg("index"),
+                            nth,
+                            strict,
+                            None,
+                            executor_state,
+                        )
+                        .await
+                        {
+                            Ok(result) => serde_json::json!({
+                                "id": id,
+                                "success": result.get("success").and_then(|v| v.as_bool()).unwrap_or(false),
+                                "data": result.get("data").cloned(),
+                                "error": result.get("error").and_then(|v| v.as_str()),
+                                "errorKind": result.get("error_kind").cloned(),
+                                "windowContext": resolved.context
+                            }),
+                            Err(e) => serde_json::json!({
+                                "id": id,
+                                "success": false,
+                                "error": e,
+                                "windowContext": resolved.context
+                            }),
+                        }
+                    }
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e
+                    }),
+                }
+            }
+            None => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": "Missing selector argument"
+            }),
+        }
+    } else if cmd_name == "drag_and_drop" {
+        let args = command.get("args");
+        let arg_str = |name: &str| {
+            args.and_then(|a| a.get(name))
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string())
+        };
+        let arg_nth = |name: &str| {
+            args.and_then(|a| a.get(name))
+                .and_then(|v| v.as_u64())
+                .map(|n| n as usize)
+        };
+        let arg_position = |name: &str| {
+            args.and_then(|a| a.get(name))
+                .filter(|v| !v.is_null())
+                .map(|v| serde_json::from_value::<crate::commands::DragPosition>(v.clone()))
+                .transpose()
+                .map_err(|e| format!("Invalid {name} argument: {e}"))
+        };
+
+        match (
+            arg_position("sourcePosition"),
+            arg_position("targetPosition"),
+        ) {
+            (Ok(source_position), Ok(target_position)) => {
+                match crate::commands::resolve_window_with_context(&app, arg_str("windowLabel")) {
+                    Ok(resolved) => {
+                        let executor_state = app.state::<crate::commands::ScriptExecutor>();
+                        match crate::commands::drag_and_drop(
+                            resolved.window,
+                            arg_str("sourceSelector"),
+                            arg_str("targetSelector"),
+                            source_position,
+                            target_position,
+                            arg_str("kind"),
+                            arg_nth("sourceNth"),
+                            arg_nth("targetNth"),
+                            args.and_then(|a| a.get("strict")).and_then(|v| v.as_bool()),
+                            None,
+                            executor_state,
+                        )
+                        .await
+                        {
+                            Ok(result) => serde_json::json!({
+                                "id": id,
+                                "success": result.get("success").and_then(|v| v.as_bool()).unwrap_or(false),
+                                "data": result.get("data").cloned(),
+                                "error": result.get("error").and_then(|v| v.as_str()),
+                                "errorKind": result.get("error_kind").cloned(),
+                                "windowContext": resolved.context
+                            }),
+                            Err(e) => serde_json::json!({
+                                "id": id,
+                                "success": false,
+                                "error": e,
+                                "windowContext": resolved.context
+                            }),
+                        }
+                    }
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e
+                    }),
+                }
+            }
+            (Err(e), _) | (_, Err(e)) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "get_html" {
+        let args = command.get("args");
+        let pretty = args.and_then(|a| a.get("pretty")).and_then(|v| v.as_bool());
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match crate::commands::resolve_window_with_context(&app, window_label) {
+            Ok(resolved) => {
+                let executor_state = app.state::<crate::commands::ScriptExecutor>();
+                match crate::commands::get_html(resolved.window, pretty, None, executor_state).await
+                {
+                    Ok(result) => serde_json::json!({
+                        "id": id,
+                        "success": result.get("success").and_then(|v| v.as_bool()).unwrap_or(false),
+                        "data": result.get("data").cloned(),
+                        "error": result.get("error").and_then(|v| v.as_str()),
+                        "windowContext": resolved.context
+                    }),
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e,
+                        "windowContext": resolved.context
+                    }),
+                }
+            }
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "wait_for_function" {
+        let args = command.get("args");
+        let predicate = args
+            .and_then(|a| a.get("predicate"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let timeout_ms = args
+            .and_then(|a| a.get("timeoutMs"))
+            .and_then(|v| v.as_u64());
+        let poll_interval_ms = args
+            .and_then(|a| a.get("pollIntervalMs"))
+            .and_then(|v| v.as_u64());
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match predicate {
+            Some(predicate) => {
+                match crate::commands::resolve_window_with_context(&app, window_label) {
+                    Ok(resolved) => {
+                        let executor_state = app.state::<crate::commands::ScriptExecutor>();
+                        match crate::commands::wait_for_function(
+                            resolved.window,
+                            predicate,
+                            timeout_ms,
+                            poll_interval_ms,
+                            None,
+                            executor_state,
+                        )
+                        .await
+                        {
+                            Ok(result) => serde_json::json!({
+                                "id": id,
+                                "success": result.get("success").and_then(|v| v.as_bool()).unwrap_or(false),
+                                "data": result.get("data").cloned(),
+                                "error": result.get("error").and_then(|v| v.as_str()),
+                                "errorKind": result.get("error_kind").cloned(),
+                                "windowContext": resolved.context
+                            }),
+                            Err(e) => serde_json::json!({
+                                "id": id,
+                                "success": false,
+                                "error": e,
+                                "windowContext": resolved.context
+                            }),
+                        }
+                    }
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e
+                    }),
+                }
+            }
+            None => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": "Missing predicate argument"
+            }),
+        }
+    } else if cmd_name == "wait_for_idle" {
+        let args = command.get("args");
+        let quiet_ms = args.and_then(|a| a.get("quietMs")).and_then(|v| v.as_u64());
+        let timeout_ms = args
+            .and_then(|a| a.get("timeoutMs"))
+            .and_then(|v| v.as_u64());
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match crate::commands::resolve_window_with_context(&app, window_label) {
+            Ok(resolved) => {
+                let executor_state = app.state::<crate::commands::ScriptExecutor>();
+                match crate::commands::wait_for_idle(
+                    resolved.window,
+                    quiet_ms,
+                    timeout_ms,
+                    None,
+                    executor_state,
+                )
+                .await
+                {
+                    Ok(result) => serde_json::json!({
+                        "id": id,
+                        "success": result.get("success").and_then(|v| v.as_bool()).unwrap_or(false),
+                        "data": result.get("data").cloned(),
+                        "error": result.get("error").and_then(|v| v.as_str()),
+                        "errorKind": result.get("error_kind").cloned(),
+                        "windowContext": resolved.context
+                    }),
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e,
+                        "windowContext": resolved.context
+                    }),
+                }
+            }
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "focus_element" || cmd_name == "blur_element" {
+        let args = command.get("args");
+        let selector = args
+            .and_then(|a| a.get("selector"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let handle = args
+            .and_then(|a| a.get("handle"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let by = args
+            .and_then(|a| a.get("by"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let prevent_scroll = args
+            .and_then(|a| a.get("preventScroll"))
+            .and_then(|v| v.as_bool());
+        let nth = args
+            .and_then(|a| a.get("nth"))
+            .and_then(|v| v.as_u64())
+            .map(|n| n as usize);
+        let strict = args.and_then(|a| a.get("strict")).and_then(|v| v.as_bool());
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match crate::commands::resolve_window_with_context(&app, window_label) {
+            Ok(resolved) => {
+                let executor_state = app.state::<crate::commands::ScriptExecutor>();
+                let result = if cmd_name == "focus_element" {
+                    crate::commands::focus_element(
+                        resolved.window,
+                        selector,
+                        handle,
+                        by,
+                        prevent_scroll,
+                        nth,
+                        strict,
+                        None,
+                        executor_state,
+                    )
+                    .await
+                } else {
+                    crate::commands::blur_element(resolved.window, selector, None, executor_state)
+                        .await
+                };
+
+                match result {
+                    Ok(result) => serde_json::json!({
+                        "id": id,
+                        "success": result.get("success").and_then(|v| v.as_bool()).unwrap_or(false),
+                        "data": result.get("data").cloned(),
+                        "error": result.get("error").and_then(|v| v.as_str()),
+                        "errorKind": result.get("error_kind").cloned(),
+                        "windowContext": resolved.context
+                    }),
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e,
+                        "windowContext": resolved.context
+                    }),
+                }
+            }
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "list_bridge_commands" {
+        match crate::commands::list_bridge_commands().await {
+            Ok(data) => serde_json::json!({
+                "id": id,
+                "success": true,
+                "data": data
+            }),
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "element_state" {
+        let args = command.get("args");
+        let selector = args
+            .and_then(|a| a.get("selector"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let handle = args
+            .and_then(|a| a.get("handle"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let by = args
+            .and_then(|a| a.get("by"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let frame_selector = args
+            .and_then(|a| a.get("frameSelector"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match crate::commands::resolve_window_with_context(&app, window_label) {
+            Ok(resolved) => {
+                let executor_state = app.state::<crate::commands::ScriptExecutor>();
+                match crate::commands::element_state(
+                    resolved.window,
+                    selector,
+                    handle,
+                    by,
+                    frame_selector,
+                    None,
+                    executor_state,
+                )
+                .await
+                {
+                    Ok(result) => serde_json::json!({
+                        "id": id,
+                        "success": result.get("success").and_then(|v| v.as_bool()).unwrap_or(false),
+                        "data": result.get("data").cloned(),
+                        "error": result.get("error").and_then(|v| v.as_str()),
+                        "windowContext": resolved.context
+                    }),
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e,
+                        "windowContext": resolved.context
+                    }),
+                }
+            }
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "get_selection" || cmd_name == "select_text" {
+        let args = command.get("args");
+        let selector = args
+            .and_then(|a| a.get("selector"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let start = args
+            .and_then(|a| a.get("start"))
+            .and_then(|v| v.as_u64())
+            .map(|n| n as usize);
+        let end = args
+            .and_then(|a| a.get("end"))
+            .and_then(|v| v.as_u64())
+            .map(|n| n as usize);
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match crate::commands::resolve_window_with_context(&app, window_label) {
+            Ok(resolved) => {
+                let executor_state = app.state::<crate::commands::ScriptExecutor>();
+                let result = if cmd_name == "get_selection" {
+                    crate::commands::get_selection(resolved.window, None, executor_state).await
+                } else {
+                    match selector {
+                        Some(selector) => {
+                            crate::commands::select_text(
+                                resolved.window,
+                                selector,
+                                start,
+                                end,
+                                None,
+                                executor_state,
+                            )
+                            .await
+                        }
+                        None => Err("Missing selector argument".to_string()),
+                    }
+                };
+
+                match result {
+                    Ok(result) => serde_json::json!({
+                        "id": id,
+                        "success": result.get("success").and_then(|v| v.as_bool()).unwrap_or(false),
+                        "data": result.get("data").cloned(),
+                        "error": result.get("error").and_then(|v| v.as_str()),
+                        "windowContext": resolved.context
+                    }),
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e,
+                        "windowContext": resolved.context
+                    }),
+                }
+            }
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "read_clipboard" || cmd_name == "write_clipboard" {
+        let args = command.get("args");
+        let text = args
+            .and_then(|a| a.get("text"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let mime_type = args
+            .and_then(|a| a.get("mimeType"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match crate::commands::resolve_window_with_context(&app, window_label) {
+            Ok(resolved) => {
+                let executor_state = app.state::<crate::commands::ScriptExecutor>();
+                let result = if cmd_name == "read_clipboard" {
+                    crate::commands::read_clipboard(resolved.window, None, executor_state).await
+                } else {
+                    match text {
+                        Some(text) => {
+                            crate::commands::write_clipboard(
+                                resolved.window,
+                                text,
+                                mime_type,
+                                None,
+                                executor_state,
+                            )
+                            .await
+                        }
+                        None => Err("Missing text argument".to_string()),
+                    }
+                };
+
+                match result {
+                    Ok(result) => serde_json::json!({
+                        "id": id,
+                        "success": result.get("success").and_then(|v| v.as_bool()).unwrap_or(false),
+                        "data": result.get("data").cloned(),
+                        "error": result.get("error").and_then(|v| v.as_str()),
+                        "errorKind": result.get("error_kind").cloned(),
+                        "windowContext": resolved.context
+                    }),
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e,
+                        "windowContext": resolved.context
+                    }),
+                }
+            }
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "get_title" || cmd_name == "set_title" {
+        let args = command.get("args");
+        let title = args
+            .and_then(|a| a.get("title"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let sync_window = args
+            .and_then(|a| a.get("syncWindow"))
+            .and_then(|v| v.as_bool());
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match crate::commands::resolve_window_with_context(&app, window_label) {
+            Ok(resolved) => {
+                let executor_state = app.state::<crate::commands::ScriptExecutor>();
+                let result = if cmd_name == "get_title" {
+                    crate::commands::get_title(resolved.window, None, executor_state).await
+                } else {
+                    match title {
+                        Some(title) => {
+                            crate::commands::set_title(
+                                resolved.window,
+                                title,
+                                sync_window,
+                                None,
+                                executor_state,
+                            )
+                            .await
+                        }
+                        None => Err("Missing title argument".to_string()),
+                    }
+                };
+
+                match result {
+                    Ok(result) => serde_json::json!({
+                        "id": id,
+                        "success": result.get("success").and_then(|v| v.as_bool()).unwrap_or(false),
+                        "data": result.get("data").cloned(),
+                        "error": result.get("error").and_then(|v| v.as_str()),
+                        "windowContext": resolved.context
+                    }),
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e,
+                        "windowContext": resolved.context
+                    }),
+                }
+            }
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "get_url" {
+        let window_label = command
+            .get("args")
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match crate::commands::resolve_window_with_context(&app, window_label) {
+            Ok(resolved) => {
+                let executor_state = app.state::<crate::commands::ScriptExecutor>();
+                match crate::commands::get_url(resolved.window, None, executor_state).await {
+                    Ok(result) => serde_json::json!({
+                        "id": id,
+                        "success": result.get("success").and_then(|v| v.as_bool()).unwrap_or(false),
+                        "data": result.get("data").cloned(),
+                        "error": result.get("error").and_then(|v| v.as_str()),
+                        "windowContext": resolved.context
+                    }),
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e,
+                        "windowContext": resolved.context
+                    }),
+                }
+            }
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "get_page_info" {
+        let window_label = command
+            .get("args")
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match crate::commands::resolve_window_with_context(&app, window_label) {
+            Ok(resolved) => {
+                let executor_state = app.state::<crate::commands::ScriptExecutor>();
+                match crate::commands::get_page_info(resolved.window, None, executor_state).await {
+                    Ok(result) => serde_json::json!({
+                        "id": id,
+                        "success": result.get("success").and_then(|v| v.as_bool()).unwrap_or(false),
+                        "data": result.get("data").cloned(),
+                        "error": result.get("error").and_then(|v| v.as_str()),
+                        "windowContext": resolved.context
+                    }),
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e,
+                        "windowContext": resolved.context
+                    }),
+                }
+            }
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "go_back" || cmd_name == "go_forward" {
+        let args = command.get("args");
+        let wait_until = args
+            .and_then(|a| a.get("waitUntil"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let timeout_ms = args
+            .and_then(|a| a.get("timeoutMs"))
+            .and_then(|v| v.as_u64());
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match crate::commands::resolve_window_with_context(&app, window_label) {
+            Ok(resolved) => {
+                let executor_state = app.state::<crate::commands::ScriptExecutor>();
+                let result = if cmd_name == "go_back" {
+                    crate::commands::go_back(
+                        resolved.window,
+                        wait_until,
+                        timeout_ms,
+                        None,
+                        executor_state,
+                    )
+                    .await
+                } else {
+                    crate::commands::go_forward(
+                        resolved.window,
+                        wait_until,
+                        timeout_ms,
+                        None,
+                        executor_state,
+                    )
+                    .await
+                };
+
+                match result {
+                    Ok(result) => serde_json::json!({
+                        "id": id,
+                        "success": result.get("success").and_then(|v| v.as_bool()).unwrap_or(false),
+                        "data": result.get("data").cloned(),
+                        "error": result.get("error").and_then(|v| v.as_str()),
+                        "errorKind": result.get("error_kind").cloned(),
+                        "windowContext": resolved.context
+                    }),
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e,
+                        "windowContext": resolved.context
+                    }),
+                }
+            }
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "disable_animations" || cmd_name == "enable_animations" {
+        let window_label = command
+            .get("args")
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match crate::commands::resolve_window_with_context(&app, window_label) {
+            Ok(resolved) => {
+                let executor_state = app.state::<crate::commands::ScriptExecutor>();
+                let result = if cmd_name == "disable_animations" {
+                    crate::commands::disable_animations(resolved.window, None, executor_state).await
+                } else {
+                    crate::commands::enable_animations(resolved.window, None, executor_state).await
+                };
+
+                match result {
+                    Ok(result) => serde_json::json!({
+                        "id": id,
+                        "success": result.get("success").and_then(|v| v.as_bool()).unwrap_or(false),
+                        "data": result.get("data").cloned(),
+                        "error": result.get("error").and_then(|v| v.as_str()),
+                        "windowContext": resolved.context
+                    }),
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e,
+                        "windowContext": resolved.context
+                    }),
+                }
+            }
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "resize_window" {
+        // Handle window resize
+        if let Some(args) = command.get("args") {
+            let width = args.get("width").and_then(|v| v.as_u64()).map(|w| w as u32);
+            let height = args
+                .get("height")
+                .and_then(|v| v.as_u64())
+                .map(|h| h as u32);
+            let window_id = args
+                .get("windowId")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string());
+            let logical = args
+                .get("logical")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(true);
+
+            match (width, height) {
+                (Some(w), Some(h)) => {
+                    let params = crate::commands::ResizeWindowParams {
+                        width: w,
+                        height: h,
+                        window_id,
+                        logical,
+                    };
+
+                    match crate::commands::resize_window(app.clone(), params).await {
+                        Ok(result) => serde_json::json!({
+                            "id": id,
+                            "success": result.success,
+                            "data": result,
+                            "error": result.error
+                        }),
+                        Err(e) => serde_json::json!({
+                            "id": id,
+                            "success": false,
+                            "error": e
+                        }),
+                    }
+                }
+                _ => serde_json::json!({
+                    "id": id,
+                    "success": false,
+                    "error": "Missing width or height argument"
+                }),
+            }
+        } else {
+            serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": "Missing args for resize_window"
+            })
+        }
+    } else if cmd_name == "register_script" {
+        // Handle script registration
+        if let Some(args) = command.get("args") {
+            let script_id = args.get("id").and_then(|v| v.as_str());
+            let script_type_str = args.get("type").and_then(|v| v.as_str());
+            let content = args.get("content").and_then(|v| v.as_str());
+
+            match (script_id, script_type_str, content) {
+                (Some(id_str), Some(type_str), Some(content_str)) => {
+                    let script_type = match type_str {
+                        "url" => ScriptType::Url,
+                        _ => ScriptType::Inline,
+                    };
+
+                    let entry = ScriptEntry {
+                        id: id_str.to_string(),
+                        script_type,
+                        content: content_str.to_string(),
+                    };
+
+                    // Add to registry
+                    let registry: tauri::State<'_, SharedScriptRegistry> = app.state();
+                    {
+                        let mut reg = registry.lock().unwrap();
+                        reg.add(entry.clone());
+                    }
+
+                    // Inject the script into the webview
+                    let window_label = args
+                        .get("windowLabel")
+                        .and_then(|v| v.as_str())
+                        .map(|s| s.to_string());
+
+                    match inject_script_to_webview(&app, &entry, window_label) {
+                        Ok(result) => serde_json::json!({
+                            "id": id,
+                            "success": true,
+                            "data": { "registered": true, "scriptId": id_str },
+                            "windowContext": {
+                                "windowLabel": result.window_context.window_label,
+                                "totalWindows": result.window_context.total_windows,
+                                "warning": result.window_context.warning
+                            }
+                        }),
+                        Err(e) => serde_json::json!({
+                            "id": id,
+                            "success": false,
+                            "error": e
+                        }),
+                    }
+                }
+                _ => serde_json::json!({
+                    "id": id,
+                    "success": false,
+                    "error": "Missing required args: id, type, content"
+                }),
+            }
+        } else {
+            serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": "Missing args for register_script"
+            })
+        }
+    } else if cmd_name == "remove_script" {
+        // Handle script removal
+        if let Some(args) = command.get("args") {
+            if let Some(script_id) = args.get("id").and_then(|v| v.as_str()) {
+                let registry: tauri::State<'_, SharedScriptRegistry> = app.state();
+                let removed = {
+                    let mut reg = registry.lock().unwrap();
+                    reg.remove(script_id).is_some()
+                };
+
+                // Remove from DOM
+                let window_label = args
+                    .get("windowLabel")
+                    .and_then(|v| v.as_str())
+                    .map(|s| s.to_string());
+
+                match remove_script_from_webview(&app, script_id, window_label) {
+                    Ok(result) => serde_json::json!({
+                        "id": id,
+                        "success": true,
+                        "data": { "removed": removed, "scriptId": script_id },
+                        "windowContext": {
+                            "windowLabel": result.window_context.window_label,
+                            "totalWindows": result.window_context.total_windows,
+                            "warning": result.window_context.warning
+                        }
+                    }),
+                    Err(e) => {
+                        mcp_log_warn(
+                            "WS_SERVER",
+                            &format!("Failed to remove script from DOM: {e}"),
+                        );
+                        serde_json::json!({
+                            "id": id,
+                            "success": true,
+                            "data": { "removed": removed, "scriptId": script_id },
+                            "error": format!("Script removed from registry but DOM removal failed: {e}")
+                        })
+                    }
+                }
+            } else {
+                serde_json::json!({
+                    "id": id,
+                    "success": false,
+                    "error": "Missing script id"
+                })
+            }
+        } else {
+            serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": "Missing args for remove_script"
+            })
+        }
+    } else if cmd_name == "clear_scripts" {
+        // Handle clearing all scripts
+        let registry: tauri::State<'_, SharedScriptRegistry> = app.state();
+        let count = {
+            let mut reg = registry.lock().unwrap();
+            let count = reg.len();
+            reg.clear();
+            count
+        };
+
+        // Clear from DOM
+        let window_label = command
+            .get("args")
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match clear_scripts_from_webview(&app, window_label) {
+            Ok(result) => serde_json::json!({
+                "id": id,
+                "success": true,
+                "data": { "cleared": count },
+                "windowContext": {
+                    "windowLabel": result.window_context.window_label,
+                    "totalWindows": result.window_context.total_windows,
+                    "warning": result.window_context.warning
+                }
+            }),
+            Err(e) => {
+                mcp_log_warn(
+                    "WS_SERVER",
+                    &format!("Failed to clear scripts from DOM: {e}"),
+                );
+                serde_json::json!({
+                    "id": id,
+                    "success": true,
+                    "data": { "cleared": count },
+                    "error": format!("Scripts cleared from registry but DOM clear failed: {e}")
+                })
+            }
+        }
+    } else if cmd_name == "get_scripts" {
+        // Handle getting all registered scripts
+        let registry: tauri::State<'_, SharedScriptRegistry> = app.state();
+        let scripts: Vec<serde_json::Value> = {
+            let reg = registry.lock().unwrap();
+            reg.get_all()
+                .iter()
+                .map(|entry| {
+                    serde_json::json!({
+                        "id": entry.id,
+                        "type": match entry.script_type {
+                            ScriptType::Inline => "inline",
+                            ScriptType::Url => "url",
+                        },
+                        "content": entry.content
+                    })
+                })
+                .collect()
+        };
+
+        serde_json::json!({
+            "id": id,
+            "success": true,
+            "data": { "scripts": scripts }
+        })
+    } else {
+        // Unknown command
+        serde_json::json!({
+            "id": id,
+            "success": false,
+            "error": format!("Unknown command: {}", cmd_name)
+        })
+    }
 }
 
 /// Result of a script operation with window context.
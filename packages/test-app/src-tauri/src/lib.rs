@@ -31,6 +31,18 @@ pub fn run() {
         builder = builder.plugin(
             tauri_plugin_mcp_bridge::Builder::new()
                 .base_port(9300)
+                .command(
+                    tauri_plugin_mcp_bridge::CommandInfo::new("greet")
+                        .description("Greets someone by name")
+                        .arg("name"),
+                )
+                .command(
+                    tauri_plugin_mcp_bridge::CommandInfo::new("add_numbers")
+                        .description("Adds two numbers")
+                        .arg("a")
+                        .arg("b"),
+                )
+                .with_commands(&["get_config"])
                 .build(),
         );
     }
@@ -0,0 +1,109 @@
+#!/usr/bin/env node
+
+/* eslint-disable no-undef */
+
+/**
+ * Compares `execute_js` round trips on the fast path and on the event path
+ * Usage: node scripts/benchmark-execution-paths.js [runs] [port]
+ *
+ * Connects to a running app with the plugin over its WebSocket server (port 9223
+ * unless given) and runs, one after the other, scripts reading the same values
+ * through each path: a single expression takes the fast path, and the same value
+ * computed over two statements takes the event path. Reports the median and 95th
+ * percentile round trip as seen by the client, which includes the WebSocket hop.
+ */
+
+import WebSocket from 'ws';
+
+const runs = Number(process.argv[2] ?? 200);
+
+const port = Number(process.argv[3] ?? 9223);
+
+const pairs = {
+   'document.title': {
+      fast: 'document.title',
+      event: 'const title = document.title; title',
+   },
+   'element count': {
+      fast: 'document.querySelectorAll(\'*\').length',
+      event: 'const all = document.querySelectorAll(\'*\'); all.length',
+   },
+   'records (1,000 small objects)': {
+      fast: 'Array.from({ length: 1000 }, (_, i) => ({ id: i, name: `User ${i}` }))',
+      event: 'const count = 1000; Array.from({ length: count }, (_, i) => ({ id: i, name: `User ${i}` }))',
+   },
+};
+
+const socket = new WebSocket(`ws://127.0.0.1:${port}`);
+
+await new Promise((resolve, reject) => {
+   socket.once('open', resolve);
+   socket.once('error', reject);
+});
+
+const waiting = new Map();
+
+socket.on('message', (raw) => {
+   const message = JSON.parse(raw.toString());
+
+   const resolve = waiting.get(message.id);
+
+   if (resolve) {
+      waiting.delete(message.id);
+      resolve(message);
+   }
+});
+
+let nextId = 0;
+
+function executeJs(script) {
+   const id = `bench-${nextId++}`;
+
+   return new Promise((resolve) => {
+      waiting.set(id, resolve);
+      socket.send(JSON.stringify({ id, command: 'execute_js', args: { script } }));
+   });
+}
+
+async function measure(script) {
+   // Warm up, and fail early on a script the app can't run
+   const first = await executeJs(script);
+
+   if (!first.success) {
+      throw new Error(`"${script}" failed: ${first.error}`);
+   }
+
+   const times = [];
+
+   for (let i = 0; i < runs; i++) {
+      const start = performance.now();
+
+      await executeJs(script);
+      times.push(performance.now() - start);
+   }
+   times.sort((a, b) => { return a - b; });
+   return {
+      median: times[Math.floor(times.length / 2)],
+      p95: times[Math.floor(times.length * 0.95)],
+   };
+}
+
+const rows = [];
+
+for (const [ name, { fast, event } ] of Object.entries(pairs)) {
+   const fastTimes = await measure(fast);
+
+   const eventTimes = await measure(event);
+
+   rows.push({
+      script: name,
+      'fast median ms': fastTimes.median.toFixed(2),
+      'fast p95 ms': fastTimes.p95.toFixed(2),
+      'event median ms': eventTimes.median.toFixed(2),
+      'event p95 ms': eventTimes.p95.toFixed(2),
+      speedup: `${(eventTimes.median / fastTimes.median).toFixed(1)}x`,
+   });
+}
+
+socket.close();
+console.table(rows);
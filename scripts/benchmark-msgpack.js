@@ -0,0 +1,89 @@
+#!/usr/bin/env node
+
+/* eslint-disable no-undef */
+
+/**
+ * Compares the plugin's `encoding: "msgpack"` result encoder with JSON.stringify
+ * Usage: node scripts/benchmark-msgpack.js [runs]
+ *
+ * Runs the encoder from packages/tauri-plugin-mcp-bridge/src/commands/msgpack.rs as the
+ * webview would, on a few representative payloads, and reports the size sent over the
+ * event channel (the base64'd MessagePack or the JSON) and the median encoding time.
+ * Node runs the same V8 as Chromium-based webviews, so the times are indicative only
+ * for WebKit.
+ */
+
+import { readFileSync } from 'fs';
+import { join, dirname } from 'path';
+import { fileURLToPath } from 'url';
+
+const __filename = fileURLToPath(import.meta.url);
+
+const __dirname = dirname(__filename);
+
+const source = readFileSync(join(__dirname, '../packages/tauri-plugin-mcp-bridge/src/commands/msgpack.rs'), 'utf8');
+
+const match = source.match(/pub\(crate\) const MSGPACK_SCRIPT: &str = r#"([\s\S]*?)"#;/);
+
+if (!match) {
+   throw new Error('MSGPACK_SCRIPT not found in msgpack.rs');
+}
+
+// The wrapper returns the script's last expression, the encoded marker object
+const AsyncFunction = Object.getPrototypeOf(async function() { /* empty */ }).constructor;
+
+const encodeMsgpack = new AsyncFunction('__mcpSource', match[1].replace('\n(function(root) {', '\nreturn (function(root) {'));
+
+const runs = Number(process.argv[2] ?? 20);
+
+function records(count) {
+   return Array.from({ length: count }, (_, i) => {
+      return {
+         id: i,
+         name: `User ${i}`,
+         email: `user${i}@example.com`,
+         active: i % 3 !== 0,
+         score: (i * 7919) % 1000 / 7,
+         tags: [ 'alpha', 'beta' ].slice(0, i % 3),
+      };
+   });
+}
+
+const payloads = {
+   'records (10,000 small objects)': records(10000),
+   'numbers (100,000 floats)': Array.from({ length: 100000 }, (_, i) => { return Math.sin(i) * 1000; }),
+   'text (1,000 paragraphs)': Array.from({ length: 1000 }, (_, i) => { return `Paragraph ${i}: ${'Lorem ipsum dolor sit amet. '.repeat(20)}`; }),
+   'binary (64KB nested in an object)': { name: 'blob', bytes: new Uint8Array(65536).map((_, i) => { return i % 251; }) },
+};
+
+async function median(fn) {
+   const times = [];
+
+   for (let i = 0; i < runs; i++) {
+      const start = performance.now();
+
+      await fn();
+      times.push(performance.now() - start);
+   }
+   times.sort((a, b) => { return a - b; });
+   return times[Math.floor(times.length / 2)];
+}
+
+const rows = [];
+
+for (const [ name, payload ] of Object.entries(payloads)) {
+   const json = JSON.stringify(payload);
+
+   const packed = await encodeMsgpack(async () => { return payload; });
+
+   rows.push({
+      payload: name,
+      'JSON bytes': Buffer.byteLength(json),
+      'msgpack bytes (base64)': packed.data.length,
+      'size change': `${Math.round((packed.data.length / Buffer.byteLength(json) - 1) * 100)}%`,
+      'JSON ms': (await median(() => { return JSON.stringify(payload); })).toFixed(1),
+      'msgpack ms': (await median(() => { return encodeMsgpack(async () => { return payload; }); })).toFixed(1),
+   });
+}
+
+console.table(rows);